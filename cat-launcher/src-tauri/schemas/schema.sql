@@ -171,3 +171,90 @@ CREATE TABLE IF NOT EXISTS github_commit_mod_activity (
     FOREIGN KEY (mod_id, game_variant) REFERENCES mods (id, game_variant) ON DELETE CASCADE
 );
 
+
+-- This table stores whether older installations of a game variant are pruned
+-- after a new release is installed, or kept side by side.
+CREATE TABLE IF NOT EXISTS release_retention_policy (
+    game_variant TEXT PRIMARY KEY,
+    policy TEXT NOT NULL,
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- This table stores the last successful response to each GitHub API request
+-- along with its validators, so that later requests can be made conditionally
+-- with If-None-Match/If-Modified-Since and a 304 can be served from here.
+CREATE TABLE IF NOT EXISTS github_api_cache (
+    url TEXT PRIMARY KEY,
+    etag TEXT,
+    last_modified TEXT,
+    link TEXT,
+    body TEXT NOT NULL
+);
+
+-- This table stores the optional GitHub token used to authenticate requests to
+-- the GitHub API, which raises the rate limit.
+CREATE TABLE IF NOT EXISTS github_token (
+    _id INTEGER PRIMARY KEY DEFAULT 1 CHECK(_id = 1),
+    token TEXT NOT NULL
+);
+
+-- This table stores whether a game variant is checked for newer releases of a
+-- release channel, and whether those are only announced or also installed.
+CREATE TABLE IF NOT EXISTS release_update_policy (
+    game_variant TEXT PRIMARY KEY,
+    mode TEXT NOT NULL,
+    channel TEXT NOT NULL,
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- This table stores whether offline mode is enabled. While it is, the launcher
+-- makes no network requests and only uses cached or installed content.
+CREATE TABLE IF NOT EXISTS offline_mode (
+    _id INTEGER PRIMARY KEY DEFAULT 1 CHECK(_id = 1),
+    enabled INTEGER NOT NULL DEFAULT 0
+);
+
+-- This table stores the descriptors of user-registered game variants as JSON.
+-- Built-in variants are described by the launcher itself.
+CREATE TABLE IF NOT EXISTS variant_descriptors (
+    game_variant TEXT PRIMARY KEY,
+    descriptor TEXT NOT NULL,
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- This table stores releases that were installed from local archives, e.g.
+-- builds shared on a LAN drive or never published on GitHub, so that they
+-- are listed alongside downloaded releases.
+CREATE TABLE IF NOT EXISTS sideloaded_releases (
+    game_variant TEXT NOT NULL,
+    version TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (game_variant, version),
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- This table stores the build flavor (e.g. tiles or curses) selected for each
+-- game variant. Variants without a row use the default flavor.
+CREATE TABLE IF NOT EXISTS build_flavors (
+    game_variant TEXT PRIMARY KEY,
+    flavor TEXT NOT NULL,
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- This table stores whether builds for another architecture may be used when
+-- a release has no native build, e.g. x64 builds on an ARM64 machine with an
+-- emulation layer.
+CREATE TABLE IF NOT EXISTS emulated_builds (
+    _id INTEGER PRIMARY KEY DEFAULT 1 CHECK(_id = 1),
+    allowed INTEGER NOT NULL DEFAULT 0
+);
+
+-- This table stores the mirrors release assets can be downloaded from, as URL
+-- templates, along with when a download from each last succeeded and failed,
+-- so that dead mirrors can be skipped.
+CREATE TABLE IF NOT EXISTS download_mirrors (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url_template TEXT NOT NULL UNIQUE,
+    last_succeeded_at TEXT,
+    last_failed_at TEXT
+);
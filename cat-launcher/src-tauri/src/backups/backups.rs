@@ -1,11 +1,25 @@
+use std::io;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 
+use tokio_util::sync::CancellationToken;
+
+use crate::constants::ORPHAN_MIN_AGE;
 use crate::filesystem::paths::{
-  GetAutomaticBackupArchivePathError, GetUserGameDataDirError,
+  GetAutomaticBackupArchivePathError, GetBackupStoreDirError,
+  GetUserGameDataDirError,
   get_or_create_automatic_backup_archive_filepath,
-  get_or_create_user_game_data_dir,
+  get_or_create_automatic_backup_manifest_filepath,
+  get_or_create_backup_store_dir, get_or_create_user_game_data_dir,
+};
+use crate::infra::archive::{
+  ExtractionError, ExtractionPolicy, extract_archive,
+};
+use crate::infra::installation_progress_monitor::noop_reporter::NoopReporter;
+use crate::infra::snapshot_store::{
+  PrunedBlob, SnapshotError, prune_snapshot_store, restore_snapshot,
 };
-use crate::infra::archive::{ExtractionError, extract_archive};
 use crate::infra::utils::OS;
 use crate::launch_game::repository::{
   BackupRepository, BackupRepositoryError,
@@ -41,7 +55,7 @@ pub enum DeleteBackupError {
   #[error("failed to get backup entry: {0}")]
   Get(#[from] BackupRepositoryError),
 
-  /// Failed to construct the file path to the backup archive.
+  /// Failed to construct the file path to the backup manifest or archive.
   #[error("failed to get backup archive path: {0}")]
   BackupArchivePath(#[from] GetAutomaticBackupArchivePathError),
 
@@ -54,7 +68,8 @@ pub enum DeleteBackupError {
   Reinsert(BackupRepositoryError),
 }
 
-/// Deletes a backup from both the database and the filesystem.
+/// Deletes a backup from both the database and the filesystem, and prunes
+/// the content no other backup refers to from the backup store.
 ///
 /// If the file removal fails, it attempts to re-insert the backup entry back into the database.
 pub async fn delete_backup(
@@ -63,7 +78,7 @@ pub async fn delete_backup(
   backup_repository: &impl BackupRepository,
 ) -> Result<(), DeleteBackupError> {
   let backup = backup_repository.get_backup_entry(id).await?;
-  let path = get_or_create_automatic_backup_archive_filepath(
+  let archive_path = get_or_create_automatic_backup_archive_filepath(
     &backup.game_variant,
     backup.id,
     &backup.release_version,
@@ -71,11 +86,20 @@ pub async fn delete_backup(
     data_dir,
   )
   .await?;
+  let manifest_path =
+    get_or_create_automatic_backup_manifest_filepath(
+      &backup.game_variant,
+      backup.id,
+      &backup.release_version,
+      backup.timestamp,
+      data_dir,
+    )
+    .await?;
 
   backup_repository.delete_backup_entry(id).await?;
 
-  if let Err(e) = tokio::fs::remove_file(path).await
-    && e.kind() != std::io::ErrorKind::NotFound
+  if let Err(e) =
+    remove_backup_files(&[&manifest_path, &archive_path]).await
   {
     // If we fail to delete the file for reasons other than NotFound,
     // re-insert the backup entry back into the database using its
@@ -94,9 +118,92 @@ pub async fn delete_backup(
     return Err(DeleteBackupError::RemoveBackupFile(e));
   }
 
+  // Garbage collection prunes the store too, so a failure here only
+  // delays it.
+  if let Err(e) = prune_backup_store(
+    &backup.game_variant,
+    data_dir,
+    backup_repository,
+    ORPHAN_MIN_AGE,
+  )
+  .await
+  {
+    eprintln!("Failed to prune backup store: {}", e);
+  }
+
   Ok(())
 }
 
+/// Removes the files of an automatic backup, skipping those that don't
+/// exist. A backup has either a manifest or, if it was made before the
+/// backup store existed, an archive.
+pub async fn remove_backup_files(paths: &[&Path]) -> io::Result<()> {
+  for path in paths {
+    match tokio::fs::remove_file(path).await {
+      Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+      _ => {}
+    }
+  }
+
+  Ok(())
+}
+
+/// Errors that can occur when pruning the backup store.
+#[derive(thiserror::Error, Debug)]
+pub enum PruneBackupStoreError {
+  /// Failed to retrieve the backup entries.
+  #[error("failed to get backup entries: {0}")]
+  Get(#[from] BackupRepositoryError),
+
+  /// Failed to construct the file path to a backup manifest.
+  #[error("failed to get backup manifest path: {0}")]
+  ManifestPath(#[from] GetAutomaticBackupArchivePathError),
+
+  /// Failed to locate or create the backup store.
+  #[error("failed to get backup store directory: {0}")]
+  StoreDir(#[from] GetBackupStoreDirError),
+
+  /// Failed to prune the backup store.
+  #[error("failed to prune backup store: {0}")]
+  Prune(#[from] SnapshotError),
+}
+
+/// Removes the content of a variant's backup store that none of its
+/// automatic backups refer to anymore, and returns what was removed.
+///
+/// Content stored less than `min_age` ago is kept, since it may belong to
+/// a backup in progress.
+pub async fn prune_backup_store(
+  variant: &GameVariant,
+  data_dir: &Path,
+  backup_repository: &impl BackupRepository,
+  min_age: Duration,
+) -> Result<Vec<PrunedBlob>, PruneBackupStoreError> {
+  let mut manifest_paths = Vec::new();
+  for backup in backup_repository
+    .get_backups_sorted_by_timestamp(variant)
+    .await?
+  {
+    manifest_paths.push(
+      get_or_create_automatic_backup_manifest_filepath(
+        variant,
+        backup.id,
+        &backup.release_version,
+        backup.timestamp,
+        data_dir,
+      )
+      .await?,
+    );
+  }
+
+  let store_dir =
+    get_or_create_backup_store_dir(variant, data_dir).await?;
+  Ok(
+    prune_snapshot_store(&store_dir, &manifest_paths, min_age)
+      .await?,
+  )
+}
+
 /// Errors that can occur when restoring a backup.
 #[derive(thiserror::Error, Debug)]
 pub enum RestoreBackupError {
@@ -104,10 +211,14 @@ pub enum RestoreBackupError {
   #[error("failed to get backup entry: {0}")]
   Get(#[from] BackupRepositoryError),
 
-  /// Failed to construct the file path to the backup archive.
+  /// Failed to construct the file path to the backup manifest or archive.
   #[error("failed to get backup archive path: {0}")]
   BackupArchivePath(#[from] GetAutomaticBackupArchivePathError),
 
+  /// Failed to locate or create the backup store.
+  #[error("failed to get backup store directory: {0}")]
+  StoreDir(#[from] GetBackupStoreDirError),
+
   /// Failed to determine the user game data directory.
   #[error("failed to get user game data directory: {0}")]
   UserGameDataDir(#[from] GetUserGameDataDirError),
@@ -116,7 +227,11 @@ pub enum RestoreBackupError {
   #[error("failed to extract archive: {0}")]
   Extract(#[from] ExtractionError),
 
-  /// The backup archive file does not exist.
+  /// Failed to restore the backup from the backup store.
+  #[error("failed to restore snapshot: {0}")]
+  Snapshot(#[from] SnapshotError),
+
+  /// Neither the backup manifest nor the backup archive file exists.
   #[error("backup archive file does not exist")]
   ArchiveFileMissing,
 
@@ -125,7 +240,8 @@ pub enum RestoreBackupError {
   Delete(BackupRepositoryError),
 }
 
-/// Restores a backup by extracting its archive into the user's game data directory.
+/// Restores a backup into the user's game data directory, from the backup
+/// store or, for backups made before it existed, by extracting its archive.
 pub async fn restore_backup(
   id: i64,
   data_dir: &Path,
@@ -133,6 +249,15 @@ pub async fn restore_backup(
   os: &OS,
 ) -> Result<(), RestoreBackupError> {
   let backup = backup_repository.get_backup_entry(id).await?;
+  let manifest_path =
+    get_or_create_automatic_backup_manifest_filepath(
+      &backup.game_variant,
+      backup.id,
+      &backup.release_version,
+      backup.timestamp,
+      data_dir,
+    )
+    .await?;
   let archive_path = get_or_create_automatic_backup_archive_filepath(
     &backup.game_variant,
     backup.id,
@@ -142,8 +267,11 @@ pub async fn restore_backup(
   )
   .await?;
 
-  if let Err(e) = tokio::fs::metadata(&archive_path).await
-    && e.kind() == std::io::ErrorKind::NotFound
+  let is_missing = |result: io::Result<_>| matches!(result, Err(e) if e.kind() == io::ErrorKind::NotFound);
+  let has_manifest =
+    !is_missing(tokio::fs::metadata(&manifest_path).await);
+  if !has_manifest
+    && is_missing(tokio::fs::metadata(&archive_path).await)
   {
     // The archive file is missing, so the backup can never be restored.
     // Delete the entry to avoid leaving an orphaned record behind.
@@ -158,7 +286,24 @@ pub async fn restore_backup(
     get_or_create_user_game_data_dir(&backup.game_variant, data_dir)
       .await?;
 
-  extract_archive(&archive_path, &user_data_dir, os).await?;
+  if has_manifest {
+    let store_dir =
+      get_or_create_backup_store_dir(&backup.game_variant, data_dir)
+        .await?;
+    restore_snapshot(&manifest_path, &store_dir, &user_data_dir)
+      .await?;
+    return Ok(());
+  }
+
+  extract_archive(
+    &archive_path,
+    &user_data_dir,
+    os,
+    ExtractionPolicy::TRUSTED,
+    Arc::new(NoopReporter),
+    &CancellationToken::new(),
+  )
+  .await?;
 
   Ok(())
 }
@@ -180,6 +325,7 @@ mod tests {
   use tempfile::TempDir;
 
   use crate::infra::testing::test_zip::create_test_zip;
+  use crate::launch_game::utils::backup_save_files;
 
   type TestResult<T = ()> =
     std::result::Result<T, Box<dyn std::error::Error>>;
@@ -401,6 +547,38 @@ mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  async fn test_restore_backup_from_snapshot() -> TestResult {
+    let (_db, repo, temp_data) = setup_backup_test().await?;
+    let variant = GameVariant::DarkDaysAhead;
+    let data_dir = temp_data.path();
+
+    let user_data =
+      get_or_create_user_game_data_dir(&variant, data_dir).await?;
+    let world_file = user_data.join("save").join("world.json");
+    tokio::fs::create_dir_all(user_data.join("save")).await?;
+    tokio::fs::write(&world_file, b"{\"name\":\"World1\"}").await?;
+
+    let id = repo.add_backup_entry(&variant, "v1.0.0", 1000).await?;
+    backup_save_files(&variant, id, "v1.0.0", 1000, data_dir).await?;
+    tokio::fs::write(&world_file, b"{\"name\":\"World2\"}").await?;
+
+    restore_backup(id, data_dir, &repo, &OS::Linux).await?;
+
+    let content = tokio::fs::read_to_string(&world_file).await?;
+    assert_eq!(content, "{\"name\":\"World1\"}");
+
+    delete_backup(id, data_dir, &repo).await?;
+    let manifest_path =
+      get_or_create_automatic_backup_manifest_filepath(
+        &variant, id, "v1.0.0", 1000, data_dir,
+      )
+      .await?;
+    assert!(!manifest_path.exists());
+
+    Ok(())
+  }
+
   #[tokio::test]
   async fn test_restore_backup_missing_archive_returns_error()
   -> TestResult {
@@ -1,4 +1,26 @@
 use std::num::{NonZeroU16, NonZeroUsize};
+use std::time::Duration;
 
-pub const MAX_BACKUPS: NonZeroUsize = NonZeroUsize::new(5).unwrap();
+/// How many automatic backups are kept per variant. Backups share
+/// unchanged content, so keeping many of them is cheap.
+pub const MAX_BACKUPS: NonZeroUsize = NonZeroUsize::new(30).unwrap();
 pub const PARALLEL_REQUESTS: NonZeroU16 = NonZeroU16::new(4).unwrap();
+/// How often variants are checked for newer releases in the background.
+pub const RELEASE_UPDATE_CHECK_INTERVAL: Duration =
+  Duration::from_secs(6 * 60 * 60);
+/// How long a download mirror is skipped after a download from it failed.
+pub const DEAD_MIRROR_RETRY_INTERVAL: Duration =
+  Duration::from_secs(24 * 60 * 60);
+/// How long quitting waits for cancelled installations to clean up.
+pub const INSTALL_CANCELLATION_TIMEOUT: Duration =
+  Duration::from_secs(10);
+/// How old leftovers of failed installations and backups must be before
+/// they're removed, so that those in progress are left alone.
+pub const ORPHAN_MIN_AGE: Duration = Duration::from_secs(60 * 60);
+/// The most entries a third-party archive may have.
+pub const MAX_UNTRUSTED_ARCHIVE_ENTRIES: u64 = 100_000;
+/// The most bytes the content of a third-party archive may take up.
+pub const MAX_UNTRUSTED_ARCHIVE_SIZE: u64 = 8 * 1024 * 1024 * 1024;
+/// The size of the chunks that files in backups are split into. Most save
+/// files are much smaller and are stored whole.
+pub const SNAPSHOT_CHUNK_SIZE: u64 = 1024 * 1024;
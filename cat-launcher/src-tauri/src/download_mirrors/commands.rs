@@ -0,0 +1,48 @@
+use tauri::{State, command};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::download_mirrors::download_mirrors::{
+  DownloadMirror, DownloadMirrorError,
+  add_download_mirror as add_download_mirror_impl,
+  list_download_mirrors as list_download_mirrors_impl,
+  remove_download_mirror as remove_download_mirror_impl,
+};
+use crate::download_mirrors::repository::sqlite_download_mirrors_repository::SqliteDownloadMirrorsRepository;
+
+/// Errors that can occur when managing download mirrors via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum DownloadMirrorCommandError {
+  /// Failed to access or update the download mirrors.
+  #[error("failed to manage download mirrors: {0}")]
+  DownloadMirror(#[from] DownloadMirrorError),
+}
+
+/// Returns the mirrors release assets are downloaded from, along with when
+/// downloads from them last succeeded and failed.
+#[command]
+pub async fn list_download_mirrors(
+  repository: State<'_, SqliteDownloadMirrorsRepository>,
+) -> Result<Vec<DownloadMirror>, DownloadMirrorCommandError> {
+  Ok(list_download_mirrors_impl(&*repository).await?)
+}
+
+/// Adds a mirror to download release assets from. The URL template may
+/// contain `{repo}`, `{tag}` and `{asset}`, and must contain `{asset}`.
+#[command]
+pub async fn add_download_mirror(
+  url_template: String,
+  repository: State<'_, SqliteDownloadMirrorsRepository>,
+) -> Result<DownloadMirror, DownloadMirrorCommandError> {
+  Ok(add_download_mirror_impl(&url_template, &*repository).await?)
+}
+
+/// Removes a download mirror.
+#[command]
+pub async fn remove_download_mirror(
+  id: i64,
+  repository: State<'_, SqliteDownloadMirrorsRepository>,
+) -> Result<(), DownloadMirrorCommandError> {
+  remove_download_mirror_impl(id, &*repository).await?;
+  Ok(())
+}
@@ -0,0 +1,309 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use ts_rs::TS;
+use url::Url;
+
+use crate::constants::DEAD_MIRROR_RETRY_INTERVAL;
+use crate::download_mirrors::repository::{
+  DownloadMirrorsRepository, DownloadMirrorsRepositoryError,
+};
+use crate::infra::download::DownloadAttempt;
+
+/// A mirror that release assets can be downloaded from instead of GitHub.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct DownloadMirror {
+  pub id: i64,
+  /// The URL of a release asset on the mirror, in which `{repo}`, `{tag}`
+  /// and `{asset}` stand for the GitHub repository (`owner/name`), the
+  /// release tag and the asset name, e.g.
+  /// `https://mirror.example/github/{repo}/{tag}/{asset}`.
+  pub url_template: String,
+  /// When a download from the mirror last succeeded.
+  #[ts(type = "string | null")]
+  pub last_succeeded_at: Option<DateTime<Utc>>,
+  /// When a download from the mirror last failed.
+  #[ts(type = "string | null")]
+  pub last_failed_at: Option<DateTime<Utc>>,
+}
+
+impl DownloadMirror {
+  /// Returns the URL of the given release asset on this mirror.
+  pub fn asset_url(
+    &self,
+    repo: &str,
+    tag: &str,
+    asset: &str,
+  ) -> String {
+    render_url_template(&self.url_template, repo, tag, asset)
+  }
+
+  /// Returns whether the last download from this mirror failed recently
+  /// enough that it should be skipped.
+  pub fn is_dead(&self, now: DateTime<Utc>) -> bool {
+    let Some(failed_at) = self.last_failed_at else {
+      return false;
+    };
+
+    let failed_last =
+      self.last_succeeded_at.is_none_or(|s| failed_at > s);
+
+    failed_last
+      && now
+        .signed_duration_since(failed_at)
+        .to_std()
+        .ok()
+        .is_none_or(|elapsed| elapsed < DEAD_MIRROR_RETRY_INTERVAL)
+  }
+}
+
+/// Fills in the placeholders of a URL template. The values are encoded as
+/// path segments, except for the `/` between the owner and name of the
+/// repository.
+fn render_url_template(
+  template: &str,
+  repo: &str,
+  tag: &str,
+  asset: &str,
+) -> String {
+  let repo = repo
+    .split('/')
+    .map(|segment| urlencoding::encode(segment))
+    .collect::<Vec<_>>()
+    .join("/");
+
+  template
+    .replace("{repo}", &repo)
+    .replace("{tag}", &urlencoding::encode(tag))
+    .replace("{asset}", &urlencoding::encode(asset))
+}
+
+/// The URL of a release asset on a download mirror.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MirrorUrl {
+  /// The ID of the mirror.
+  pub mirror_id: i64,
+  /// The URL of the asset on the mirror.
+  pub url: String,
+}
+
+/// Errors that can occur when managing download mirrors.
+#[derive(thiserror::Error, Debug)]
+pub enum DownloadMirrorError {
+  /// The URL template doesn't contain `{asset}` or doesn't result in an
+  /// HTTP(S) URL.
+  #[error("invalid mirror URL template: {0}")]
+  InvalidTemplate(String),
+
+  /// An error occurred in the download mirrors repository.
+  #[error("failed to access download mirrors: {0}")]
+  Repository(#[from] DownloadMirrorsRepositoryError),
+}
+
+fn validate_url_template(
+  template: &str,
+) -> Result<(), DownloadMirrorError> {
+  let invalid =
+    || DownloadMirrorError::InvalidTemplate(template.to_string());
+
+  if !template.contains("{asset}") {
+    return Err(invalid());
+  }
+
+  let url =
+    render_url_template(template, "owner/name", "v1.0.0", "game.zip");
+  match Url::parse(&url) {
+    Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+    _ => Err(invalid()),
+  }
+}
+
+/// Returns all download mirrors.
+pub async fn list_download_mirrors(
+  repository: &impl DownloadMirrorsRepository,
+) -> Result<Vec<DownloadMirror>, DownloadMirrorError> {
+  Ok(repository.get_download_mirrors().await?)
+}
+
+/// Adds a download mirror with the given URL template.
+pub async fn add_download_mirror(
+  url_template: &str,
+  repository: &impl DownloadMirrorsRepository,
+) -> Result<DownloadMirror, DownloadMirrorError> {
+  let url_template = url_template.trim();
+  validate_url_template(url_template)?;
+
+  Ok(repository.add_download_mirror(url_template).await?)
+}
+
+/// Removes the download mirror with the given ID.
+pub async fn remove_download_mirror(
+  id: i64,
+  repository: &impl DownloadMirrorsRepository,
+) -> Result<(), DownloadMirrorError> {
+  repository.remove_download_mirror(id).await?;
+  Ok(())
+}
+
+/// Returns the URLs of the given release asset on all mirrors that aren't
+/// dead.
+pub async fn get_mirror_urls(
+  repo: &str,
+  tag: &str,
+  asset: &str,
+  now: DateTime<Utc>,
+  repository: &impl DownloadMirrorsRepository,
+) -> Result<Vec<MirrorUrl>, DownloadMirrorError> {
+  let mirrors = repository.get_download_mirrors().await?;
+
+  Ok(
+    mirrors
+      .iter()
+      .filter(|mirror| !mirror.is_dead(now))
+      .map(|mirror| MirrorUrl {
+        mirror_id: mirror.id,
+        url: mirror.asset_url(repo, tag, asset),
+      })
+      .collect(),
+  )
+}
+
+/// Records which of the given mirrors a download succeeded or failed from,
+/// so that dead mirrors are skipped on later downloads.
+pub async fn record_mirror_attempts(
+  mirror_urls: &[MirrorUrl],
+  attempts: &[DownloadAttempt],
+  now: DateTime<Utc>,
+  repository: &impl DownloadMirrorsRepository,
+) -> Result<(), DownloadMirrorError> {
+  for attempt in attempts {
+    let Some(mirror_url) =
+      mirror_urls.iter().find(|m| m.url == attempt.url)
+    else {
+      continue;
+    };
+
+    repository
+      .record_download_result(
+        mirror_url.mirror_id,
+        attempt.succeeded,
+        now,
+      )
+      .await?;
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::download_mirrors::repository::sqlite_download_mirrors_repository::SqliteDownloadMirrorsRepository;
+  use crate::infra::testing::test_database::TestDatabase;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_add_download_mirror_validates_template() -> TestResult
+  {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteDownloadMirrorsRepository::new(db.pool().clone());
+
+    for template in [
+      "https://mirror.example/{repo}/{tag}",
+      "ftp://mirror.example/{asset}",
+      "not a url {asset}",
+    ] {
+      assert!(matches!(
+        add_download_mirror(template, &repo).await,
+        Err(DownloadMirrorError::InvalidTemplate(_))
+      ));
+    }
+
+    let mirror = add_download_mirror(
+      " https://mirror.example/{repo}/{tag}/{asset} ",
+      &repo,
+    )
+    .await?;
+    assert_eq!(
+      mirror.asset_url(
+        "CleverRaven/Cataclysm-DDA",
+        "0.H",
+        "game.zip"
+      ),
+      "https://mirror.example/CleverRaven/Cataclysm-DDA/0.H/game.zip"
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_render_url_template_encodes_path_segments() {
+    assert_eq!(
+      render_url_template(
+        "https://mirror.example/{repo}/{tag}/{asset}",
+        "owner/name",
+        "0.H #1",
+        "game?x=1/../a.zip"
+      ),
+      "https://mirror.example/owner/name/0.H%20%231/game%3Fx%3D1%2F..%2Fa.zip"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_dead_mirrors_are_skipped() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteDownloadMirrorsRepository::new(db.pool().clone());
+
+    add_download_mirror("https://a.example/{asset}", &repo).await?;
+    add_download_mirror("https://b.example/{asset}", &repo).await?;
+
+    let now = Utc::now();
+    let mirror_urls =
+      get_mirror_urls("owner/name", "v1", "game.zip", now, &repo)
+        .await?;
+    assert_eq!(mirror_urls.len(), 2);
+
+    let attempts = [
+      DownloadAttempt {
+        url: "https://a.example/game.zip".to_string(),
+        succeeded: false,
+      },
+      DownloadAttempt {
+        url: "https://b.example/game.zip".to_string(),
+        succeeded: true,
+      },
+    ];
+    record_mirror_attempts(&mirror_urls, &attempts, now, &repo)
+      .await?;
+
+    let urls =
+      get_mirror_urls("owner/name", "v2", "game.zip", now, &repo)
+        .await?
+        .into_iter()
+        .map(|m| m.url)
+        .collect::<Vec<_>>();
+    assert_eq!(urls, vec!["https://b.example/game.zip"]);
+
+    // A dead mirror is tried again once the retry interval has passed.
+    let later = now
+      + chrono::Duration::from_std(DEAD_MIRROR_RETRY_INTERVAL)?
+      + chrono::Duration::seconds(1);
+    let mirror_urls =
+      get_mirror_urls("owner/name", "v2", "game.zip", later, &repo)
+        .await?;
+    assert_eq!(mirror_urls.len(), 2);
+
+    Ok(())
+  }
+}
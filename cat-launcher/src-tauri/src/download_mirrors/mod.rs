@@ -0,0 +1,4 @@
+pub mod commands;
+#[allow(clippy::module_inception)]
+pub mod download_mirrors;
+pub mod repository;
@@ -0,0 +1,60 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::download_mirrors::download_mirrors::DownloadMirror;
+
+/// Errors that can occur when interacting with the download mirrors repository.
+#[derive(thiserror::Error, Debug)]
+pub enum DownloadMirrorsRepositoryError {
+  /// An error occurred while retrieving the download mirrors.
+  #[error("failed to get download mirrors: {0}")]
+  Get(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while adding a download mirror.
+  #[error("failed to add download mirror: {0}")]
+  Add(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while removing a download mirror.
+  #[error("failed to remove download mirror: {0}")]
+  Remove(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while recording the outcome of a download.
+  #[error("failed to record download mirror result: {0}")]
+  Record(Box<dyn Error + Send + Sync>),
+
+  /// No mirror with the given ID exists.
+  #[error("download mirror with id {0} not found")]
+  NotFound(i64),
+}
+
+/// A repository for the mirrors release assets are downloaded from.
+#[async_trait]
+pub trait DownloadMirrorsRepository: Send + Sync {
+  /// Retrieves all download mirrors, in the order they were added.
+  async fn get_download_mirrors(
+    &self,
+  ) -> Result<Vec<DownloadMirror>, DownloadMirrorsRepositoryError>;
+
+  /// Adds a download mirror with the given URL template and returns it.
+  async fn add_download_mirror(
+    &self,
+    url_template: &str,
+  ) -> Result<DownloadMirror, DownloadMirrorsRepositoryError>;
+
+  /// Removes the download mirror with the given ID.
+  async fn remove_download_mirror(
+    &self,
+    id: i64,
+  ) -> Result<(), DownloadMirrorsRepositoryError>;
+
+  /// Records whether a download from the mirror with the given ID
+  /// succeeded at the given time.
+  async fn record_download_result(
+    &self,
+    id: i64,
+    succeeded: bool,
+    at: DateTime<Utc>,
+  ) -> Result<(), DownloadMirrorsRepositoryError>;
+}
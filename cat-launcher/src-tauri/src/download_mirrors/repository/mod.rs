@@ -0,0 +1,6 @@
+pub mod download_mirrors_repository;
+pub mod sqlite_download_mirrors_repository;
+
+pub use download_mirrors_repository::{
+  DownloadMirrorsRepository, DownloadMirrorsRepositoryError,
+};
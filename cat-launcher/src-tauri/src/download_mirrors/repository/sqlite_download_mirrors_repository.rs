@@ -0,0 +1,192 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use r2d2_sqlite::SqliteConnectionManager;
+
+use crate::download_mirrors::download_mirrors::DownloadMirror;
+use crate::download_mirrors::repository::{
+  DownloadMirrorsRepository, DownloadMirrorsRepositoryError,
+};
+use crate::infra::repository::db_helper::run_db;
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+type MirrorRow = (i64, String, Option<String>, Option<String>);
+
+/// A SQLite-backed implementation of the `DownloadMirrorsRepository`.
+#[derive(Clone)]
+pub struct SqliteDownloadMirrorsRepository {
+  pool: Pool,
+}
+
+impl SqliteDownloadMirrorsRepository {
+  /// Creates a new `SqliteDownloadMirrorsRepository` with the given connection pool.
+  pub fn new(pool: Pool) -> Self {
+    Self { pool }
+  }
+}
+
+fn parse_timestamp(
+  timestamp: Option<String>,
+) -> Result<Option<DateTime<Utc>>, DownloadMirrorsRepositoryError> {
+  timestamp
+    .map(|t| t.parse::<DateTime<Utc>>())
+    .transpose()
+    .map_err(|e| DownloadMirrorsRepositoryError::Get(Box::new(e)))
+}
+
+#[async_trait]
+impl DownloadMirrorsRepository for SqliteDownloadMirrorsRepository {
+  async fn get_download_mirrors(
+    &self,
+  ) -> Result<Vec<DownloadMirror>, DownloadMirrorsRepositoryError> {
+    let pool = self.pool.clone();
+
+    let rows: Vec<MirrorRow> = run_db(pool, move |conn| {
+      let mut stmt = conn.prepare(
+        "SELECT id, url_template, last_succeeded_at, last_failed_at
+         FROM download_mirrors ORDER BY id",
+      )?;
+      let rows = stmt
+        .query_map([], |row| {
+          Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+      Ok::<_, rusqlite::Error>(rows)
+    })
+    .await
+    .map_err(DownloadMirrorsRepositoryError::Get)?;
+
+    rows
+      .into_iter()
+      .map(|(id, url_template, last_succeeded_at, last_failed_at)| {
+        Ok(DownloadMirror {
+          id,
+          url_template,
+          last_succeeded_at: parse_timestamp(last_succeeded_at)?,
+          last_failed_at: parse_timestamp(last_failed_at)?,
+        })
+      })
+      .collect()
+  }
+
+  async fn add_download_mirror(
+    &self,
+    url_template: &str,
+  ) -> Result<DownloadMirror, DownloadMirrorsRepositoryError> {
+    let pool = self.pool.clone();
+    let template = url_template.to_string();
+
+    let id = run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT INTO download_mirrors (url_template) VALUES (?1)",
+        [template],
+      )?;
+      Ok::<_, rusqlite::Error>(conn.last_insert_rowid())
+    })
+    .await
+    .map_err(DownloadMirrorsRepositoryError::Add)?;
+
+    Ok(DownloadMirror {
+      id,
+      url_template: url_template.to_string(),
+      last_succeeded_at: None,
+      last_failed_at: None,
+    })
+  }
+
+  async fn remove_download_mirror(
+    &self,
+    id: i64,
+  ) -> Result<(), DownloadMirrorsRepositoryError> {
+    let pool = self.pool.clone();
+
+    let removed = run_db(pool, move |conn| {
+      conn.execute("DELETE FROM download_mirrors WHERE id = ?1", [id])
+    })
+    .await
+    .map_err(DownloadMirrorsRepositoryError::Remove)?;
+
+    if removed == 0 {
+      return Err(DownloadMirrorsRepositoryError::NotFound(id));
+    }
+
+    Ok(())
+  }
+
+  async fn record_download_result(
+    &self,
+    id: i64,
+    succeeded: bool,
+    at: DateTime<Utc>,
+  ) -> Result<(), DownloadMirrorsRepositoryError> {
+    let pool = self.pool.clone();
+    let at = at.to_rfc3339();
+
+    let sql = if succeeded {
+      "UPDATE download_mirrors SET last_succeeded_at = ?1 WHERE id = ?2"
+    } else {
+      "UPDATE download_mirrors SET last_failed_at = ?1 WHERE id = ?2"
+    };
+
+    run_db(pool, move |conn| {
+      conn.execute(sql, rusqlite::params![at, id])
+    })
+    .await
+    .map_err(DownloadMirrorsRepositoryError::Record)?;
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::infra::testing::test_database::TestDatabase;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_add_record_and_remove_download_mirror() -> TestResult
+  {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteDownloadMirrorsRepository::new(db.pool().clone());
+
+    assert!(repo.get_download_mirrors().await?.is_empty());
+
+    let template = "https://mirror.example/{repo}/{tag}/{asset}";
+    let mirror = repo.add_download_mirror(template).await?;
+    assert!(repo.add_download_mirror(template).await.is_err());
+
+    let failed_at = Utc::now();
+    repo
+      .record_download_result(mirror.id, false, failed_at)
+      .await?;
+
+    let mirrors = repo.get_download_mirrors().await?;
+    assert_eq!(
+      mirrors,
+      vec![DownloadMirror {
+        last_failed_at: Some(failed_at),
+        ..mirror.clone()
+      }]
+    );
+
+    repo.remove_download_mirror(mirror.id).await?;
+    assert!(repo.get_download_mirrors().await?.is_empty());
+    assert!(matches!(
+      repo.remove_download_mirror(mirror.id).await,
+      Err(DownloadMirrorsRepositoryError::NotFound(_))
+    ));
+
+    Ok(())
+  }
+}
@@ -0,0 +1,63 @@
+use tauri::{State, command};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::infra::download::Downloader;
+use crate::infra::download_queue::{
+  DownloadPriority, DownloadQueueError, QueuedDownload,
+};
+
+/// Errors that can occur when controlling a download via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum DownloadQueueCommandError {
+  /// The download has finished, was cancelled or never existed.
+  #[error("failed to control download: {0}")]
+  Queue(#[from] DownloadQueueError),
+}
+
+/// Returns the game, mod, tileset and soundpack downloads that are queued,
+/// running or paused, in the order they start in.
+#[command]
+pub fn list_downloads(
+  downloader: State<'_, Downloader>,
+) -> Vec<QueuedDownload> {
+  downloader.queue().list()
+}
+
+/// Pauses a download. It continues where it left off once resumed.
+#[command]
+pub fn pause_download(
+  id: u64,
+  downloader: State<'_, Downloader>,
+) -> Result<(), DownloadQueueCommandError> {
+  Ok(downloader.queue().pause(id)?)
+}
+
+/// Puts a paused download back into the queue.
+#[command]
+pub fn resume_download(
+  id: u64,
+  downloader: State<'_, Downloader>,
+) -> Result<(), DownloadQueueCommandError> {
+  Ok(downloader.queue().resume(id)?)
+}
+
+/// Cancels a download and removes what was downloaded so far. The
+/// installation it belongs to fails.
+#[command]
+pub fn cancel_download(
+  id: u64,
+  downloader: State<'_, Downloader>,
+) -> Result<(), DownloadQueueCommandError> {
+  Ok(downloader.queue().cancel(id)?)
+}
+
+/// Changes the priority of a download that is waiting for a free slot.
+#[command]
+pub fn set_download_priority(
+  id: u64,
+  priority: DownloadPriority,
+  downloader: State<'_, Downloader>,
+) -> Result<(), DownloadQueueCommandError> {
+  Ok(downloader.queue().set_priority(id, priority)?)
+}
@@ -0,0 +1,40 @@
+use tauri::{State, command};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::emulated_builds::emulated_builds::{
+  EmulatedBuildsError, EmulatedBuildsSetting,
+  allow_emulated_builds,
+};
+use crate::emulated_builds::repository::sqlite_emulated_builds_repository::SqliteEmulatedBuildsRepository;
+
+/// Returns whether builds that run through an emulation layer may be used
+/// for releases without a native build.
+#[command]
+pub fn get_emulated_builds_allowed(
+  setting: State<'_, EmulatedBuildsSetting>,
+) -> bool {
+  setting.is_allowed()
+}
+
+/// Errors that can occur when setting whether emulated builds are allowed
+/// via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum SetEmulatedBuildsAllowedCommandError {
+  /// Failed to store the emulated builds setting.
+  #[error("failed to set emulated builds setting: {0}")]
+  EmulatedBuilds(#[from] EmulatedBuildsError),
+}
+
+/// Allows or disallows builds that run through an emulation layer, e.g. x64
+/// builds on an ARM64 machine, for releases without a native build.
+#[command]
+pub async fn set_emulated_builds_allowed(
+  allowed: bool,
+  setting: State<'_, EmulatedBuildsSetting>,
+  repository: State<'_, SqliteEmulatedBuildsRepository>,
+) -> Result<(), SetEmulatedBuildsAllowedCommandError> {
+  allow_emulated_builds(allowed, &setting, &*repository).await?;
+
+  Ok(())
+}
@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::emulated_builds::repository::{
+  EmulatedBuildsRepository, EmulatedBuildsRepositoryError,
+};
+
+/// Whether builds that can only run through an emulation layer, e.g. x64
+/// builds on an ARM64 machine, may be used for releases without a native
+/// build. They aren't used unless the user opts in.
+///
+/// Commands read it when they start and pass it on to asset selection.
+#[derive(Default)]
+pub struct EmulatedBuildsSetting {
+  allowed: AtomicBool,
+}
+
+impl EmulatedBuildsSetting {
+  /// Returns whether emulated builds are allowed.
+  pub fn is_allowed(&self) -> bool {
+    self.allowed.load(Ordering::Relaxed)
+  }
+
+  /// Allows or disallows emulated builds.
+  pub fn set_allowed(&self, allowed: bool) {
+    self.allowed.store(allowed, Ordering::Relaxed);
+  }
+}
+
+/// Errors that can occur when accessing the emulated builds setting.
+#[derive(thiserror::Error, Debug)]
+pub enum EmulatedBuildsError {
+  /// An error occurred in the emulated builds repository.
+  #[error("failed to access emulated builds setting: {0}")]
+  Repository(#[from] EmulatedBuildsRepositoryError),
+}
+
+/// Allows or disallows emulated builds and stores the choice, so that it
+/// persists across restarts.
+pub async fn allow_emulated_builds(
+  allowed: bool,
+  setting: &EmulatedBuildsSetting,
+  repository: &impl EmulatedBuildsRepository,
+) -> Result<(), EmulatedBuildsError> {
+  repository.set_emulated_builds_allowed(allowed).await?;
+  setting.set_allowed(allowed);
+
+  Ok(())
+}
+
+/// Applies the emulated builds setting stored in the repository.
+pub async fn load_emulated_builds_setting(
+  setting: &EmulatedBuildsSetting,
+  repository: &impl EmulatedBuildsRepository,
+) -> Result<(), EmulatedBuildsError> {
+  setting
+    .set_allowed(repository.get_emulated_builds_allowed().await?);
+
+  Ok(())
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::emulated_builds::repository::sqlite_emulated_builds_repository::SqliteEmulatedBuildsRepository;
+  use crate::infra::testing::test_database::TestDatabase;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_allow_and_load_emulated_builds() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo = SqliteEmulatedBuildsRepository::new(db.pool().clone());
+    let setting = EmulatedBuildsSetting::default();
+
+    assert!(!setting.is_allowed());
+
+    allow_emulated_builds(true, &setting, &repo).await?;
+    assert!(setting.is_allowed());
+
+    let loaded_setting = EmulatedBuildsSetting::default();
+    load_emulated_builds_setting(&loaded_setting, &repo).await?;
+    assert!(loaded_setting.is_allowed());
+
+    Ok(())
+  }
+}
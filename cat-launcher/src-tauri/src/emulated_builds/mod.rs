@@ -0,0 +1,4 @@
+pub mod commands;
+#[allow(clippy::module_inception)]
+pub mod emulated_builds;
+pub mod repository;
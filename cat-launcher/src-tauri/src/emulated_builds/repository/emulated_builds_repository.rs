@@ -0,0 +1,30 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+/// Errors that can occur when interacting with the emulated builds repository.
+#[derive(thiserror::Error, Debug)]
+pub enum EmulatedBuildsRepositoryError {
+  /// An error occurred while retrieving the emulated builds setting.
+  #[error("failed to get emulated builds setting: {0}")]
+  Get(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while storing the emulated builds setting.
+  #[error("failed to set emulated builds setting: {0}")]
+  Set(Box<dyn Error + Send + Sync>),
+}
+
+/// A repository for whether emulated builds are allowed.
+#[async_trait]
+pub trait EmulatedBuildsRepository: Send + Sync {
+  /// Retrieves whether emulated builds are allowed. Defaults to `false`.
+  async fn get_emulated_builds_allowed(
+    &self,
+  ) -> Result<bool, EmulatedBuildsRepositoryError>;
+
+  /// Stores whether emulated builds are allowed.
+  async fn set_emulated_builds_allowed(
+    &self,
+    allowed: bool,
+  ) -> Result<(), EmulatedBuildsRepositoryError>;
+}
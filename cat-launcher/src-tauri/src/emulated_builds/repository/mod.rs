@@ -0,0 +1,6 @@
+pub mod emulated_builds_repository;
+pub mod sqlite_emulated_builds_repository;
+
+pub use emulated_builds_repository::{
+  EmulatedBuildsRepository, EmulatedBuildsRepositoryError,
+};
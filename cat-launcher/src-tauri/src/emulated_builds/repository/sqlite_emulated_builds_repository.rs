@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::OptionalExtension;
+
+use crate::emulated_builds::repository::{
+  EmulatedBuildsRepository, EmulatedBuildsRepositoryError,
+};
+use crate::infra::repository::db_helper::run_db;
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+/// A SQLite-backed implementation of the `EmulatedBuildsRepository`.
+#[derive(Clone)]
+pub struct SqliteEmulatedBuildsRepository {
+  pool: Pool,
+}
+
+impl SqliteEmulatedBuildsRepository {
+  /// Creates a new `SqliteEmulatedBuildsRepository` with the given connection pool.
+  pub fn new(pool: Pool) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl EmulatedBuildsRepository for SqliteEmulatedBuildsRepository {
+  async fn get_emulated_builds_allowed(
+    &self,
+  ) -> Result<bool, EmulatedBuildsRepositoryError> {
+    let pool = self.pool.clone();
+
+    let allowed: Option<bool> = run_db(pool, move |conn| {
+      conn
+        .query_row(
+          "SELECT allowed FROM emulated_builds WHERE _id = 1",
+          [],
+          |row| row.get(0),
+        )
+        .optional()
+    })
+    .await
+    .map_err(EmulatedBuildsRepositoryError::Get)?;
+
+    Ok(allowed.unwrap_or_default())
+  }
+
+  async fn set_emulated_builds_allowed(
+    &self,
+    allowed: bool,
+  ) -> Result<(), EmulatedBuildsRepositoryError> {
+    let pool = self.pool.clone();
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO emulated_builds (_id, allowed) VALUES (1, ?1)",
+        [allowed],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(EmulatedBuildsRepositoryError::Set)
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::infra::testing::test_database::TestDatabase;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_set_and_get_emulated_builds_allowed() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo = SqliteEmulatedBuildsRepository::new(db.pool().clone());
+
+    assert!(!repo.get_emulated_builds_allowed().await?);
+
+    repo.set_emulated_builds_allowed(true).await?;
+    assert!(repo.get_emulated_builds_allowed().await?);
+
+    repo.set_emulated_builds_allowed(false).await?;
+    assert!(!repo.get_emulated_builds_allowed().await?);
+
+    Ok(())
+  }
+}
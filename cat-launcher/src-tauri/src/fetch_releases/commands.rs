@@ -4,13 +4,21 @@ use tauri::{AppHandle, Emitter, Manager, State, command};
 
 use cat_macros::CommandErrorSerialize;
 
+use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::emulated_builds::emulated_builds::EmulatedBuildsSetting;
 use crate::fetch_releases::fetch_releases::{
   FetchReleaseNotesError, FetchReleasesError, ReleasesUpdatePayload,
+  ReleasesUpdateStatus,
 };
 use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
-use crate::infra::http_client::ReqwestHttpClient;
+use crate::infra::github::repository::sqlite_github_api_cache_repository::SqliteGitHubApiCacheRepository;
+use crate::infra::http_client::{ReqwestHttpClient, is_offline_error};
 use crate::infra::utils::{HostSystem, HostSystemError};
+use crate::sideloaded_releases::repository::sqlite_sideloaded_releases_repository::SqliteSideloadedReleasesRepository;
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavorSelection;
+use crate::variants::game_variant::UnknownGameVariantError;
+use crate::variants::variant_descriptor::VariantRegistry;
 
 #[derive(thiserror::Error, Debug, CommandErrorSerialize)]
 pub enum FetchReleasesCommandError {
@@ -22,17 +30,91 @@ pub enum FetchReleasesCommandError {
 
   #[error("failed to determine host system: {0}")]
   HostSystem(#[from] HostSystemError),
+
+  /// The game variant is not registered.
+  #[error("unknown game variant: {0}")]
+  UnknownVariant(#[from] UnknownGameVariantError),
 }
 
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn fetch_releases_for_variant(
   app_handle: AppHandle,
   variant: GameVariant,
   releases_repository: State<'_, SqliteReleasesRepository>,
+  github_api_cache_repository: State<
+    '_,
+    SqliteGitHubApiCacheRepository,
+  >,
+  active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+  sideloaded_releases_repository: State<
+    '_,
+    SqliteSideloadedReleasesRepository,
+  >,
+  emulated_builds: State<'_, EmulatedBuildsSetting>,
+  build_flavors: State<'_, BuildFlavorSelection>,
   client: State<'_, ReqwestHttpClient>,
+  variant_registry: State<'_, VariantRegistry>,
 ) -> Result<(), FetchReleasesCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
   let resources_dir = app_handle.path().resource_dir()?;
   let host_system = HostSystem::current(OS, ARCH)?;
+  let flavor = build_flavors.get(&variant);
+  let descriptor = variant_registry.get(&variant)?;
+
+  // The active and installed releases are kept in the list even if
+  // they're older than anything fetched. It's okay if they can't be
+  // determined; they're then only shown if they're cached.
+  let mut pinned_versions = Vec::new();
+  if let Ok(Some(active_release)) = variant
+    .get_active_release(&*active_release_repository)
+    .await
+  {
+    pinned_versions.push(active_release);
+  }
+  if let Ok(installed_releases) = variant
+    .list_installed_releases(
+      &descriptor,
+      &host_system.os,
+      flavor,
+      &data_dir,
+      &resources_dir,
+      &*releases_repository,
+      &*sideloaded_releases_repository,
+      &*active_release_repository,
+    )
+    .await
+  {
+    pinned_versions.extend(
+      installed_releases
+        .into_iter()
+        .filter(|release| !release.is_active)
+        .map(|release| release.version),
+    );
+  }
+
+  // Sideloaded releases aren't on GitHub, so they're emitted separately.
+  // It's okay if they can't be read; they're then just not listed.
+  if let Ok(sideloaded_releases) = variant
+    .get_sideloaded_releases(
+      &descriptor,
+      &host_system.os,
+      flavor,
+      &data_dir,
+      &*sideloaded_releases_repository,
+    )
+    .await
+    && !sideloaded_releases.is_empty()
+  {
+    app_handle.emit(
+      "releases-update",
+      ReleasesUpdatePayload {
+        variant,
+        releases: sideloaded_releases,
+        status: ReleasesUpdateStatus::Fetching,
+      },
+    )?;
+  }
 
   let on_releases = move |payload: ReleasesUpdatePayload| {
     app_handle.emit("releases-update", payload)?;
@@ -41,11 +123,16 @@ pub async fn fetch_releases_for_variant(
 
   variant
     .fetch_releases(
+      &descriptor,
       client.inner(),
       &resources_dir,
       &*releases_repository,
+      &*github_api_cache_repository,
+      &pinned_versions,
       on_releases,
       &host_system,
+      emulated_builds.is_allowed(),
+      flavor,
     )
     .await?;
 
@@ -54,8 +141,25 @@ pub async fn fetch_releases_for_variant(
 
 #[derive(thiserror::Error, Debug, CommandErrorSerialize)]
 pub enum FetchReleaseNotesCommandError {
+  #[error("offline mode is enabled")]
+  Offline,
+
   #[error("failed to fetch release notes: {0}")]
-  Fetch(#[from] FetchReleaseNotesError),
+  Fetch(FetchReleaseNotesError),
+
+  /// The game variant is not registered.
+  #[error("unknown game variant: {0}")]
+  UnknownVariant(#[from] UnknownGameVariantError),
+}
+
+impl From<FetchReleaseNotesError> for FetchReleaseNotesCommandError {
+  fn from(e: FetchReleaseNotesError) -> Self {
+    if is_offline_error(&e) {
+      Self::Offline
+    } else {
+      Self::Fetch(e)
+    }
+  }
 }
 
 #[command]
@@ -63,13 +167,21 @@ pub async fn fetch_release_notes(
   variant: GameVariant,
   release_id: String,
   releases_repository: State<'_, SqliteReleasesRepository>,
+  github_api_cache_repository: State<
+    '_,
+    SqliteGitHubApiCacheRepository,
+  >,
   client: State<'_, ReqwestHttpClient>,
+  variant_registry: State<'_, VariantRegistry>,
 ) -> Result<Option<String>, FetchReleaseNotesCommandError> {
+  let descriptor = variant_registry.get(&variant)?;
   let notes = variant
     .fetch_release_notes(
+      &descriptor,
       &release_id,
       client.inner(),
       &*releases_repository,
+      &*github_api_cache_repository,
     )
     .await?;
 
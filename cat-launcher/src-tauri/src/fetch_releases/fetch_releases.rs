@@ -1,6 +1,8 @@
+use std::collections::HashSet;
 use std::error::Error;
 use std::path::Path;
 
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use ts_rs::TS;
 
@@ -11,13 +13,17 @@ use crate::fetch_releases::utils::{
   get_default_releases, get_releases_payload,
 };
 use crate::game_release::game_release::GameRelease;
+use crate::infra::github::repository::GitHubApiCacheRepository;
 use crate::infra::github::utils::{
-  FetchGitHubReleaseByTagError, GitHubReleaseFetchError,
-  fetch_github_release_by_tag, fetch_github_releases,
+  FetchGitHubReleaseByTagError, GitHubApiRequestError,
+  GitHubReleaseFetchError, fetch_github_release_by_tag,
+  fetch_github_releases_until,
 };
-use crate::infra::http_client::HttpClient;
-use crate::infra::utils::{HostSystem, get_github_repo_for_variant};
+use crate::infra::http_client::{HttpClient, HttpClientError};
+use crate::infra::utils::HostSystem;
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::variant_descriptor::VariantDescriptor;
 
 #[derive(thiserror::Error, Debug)]
 pub enum FetchReleasesError<E: Error> {
@@ -45,6 +51,15 @@ pub enum ReleasesUpdateStatus {
   Fetching,
   Success,
   Error,
+  /// GitHub rate limited the request, so only cached and default releases
+  /// were emitted. Fresh releases can be fetched after `reset_at`.
+  RateLimited {
+    #[ts(type = "string")]
+    reset_at: DateTime<Utc>,
+  },
+  /// Offline mode is enabled, so only cached and default releases were
+  /// emitted.
+  Offline,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -57,13 +72,26 @@ pub enum FetchReleaseNotesError {
 }
 
 impl GameVariant {
+  /// Emits cached releases, then syncs new releases from GitHub, then emits
+  /// the bundled default releases.
+  ///
+  /// Only releases newer than the newest cached one are fetched. Each of
+  /// `pinned_versions` (e.g. the active and installed releases) that is
+  /// still unknown afterwards is fetched by its tag, so that it stays in the
+  /// list however old it is.
+  #[allow(clippy::too_many_arguments)]
   pub async fn fetch_releases<E, F>(
     &self,
+    descriptor: &VariantDescriptor,
     client: &impl HttpClient,
     resources_dir: &Path,
     releases_repository: &impl ReleasesRepository,
+    github_api_cache_repository: &impl GitHubApiCacheRepository,
+    pinned_versions: &[String],
     on_releases: F,
     host_system: &HostSystem,
+    allow_emulated: bool,
+    flavor: BuildFlavor,
   ) -> Result<(), FetchReleasesError<E>>
   where
     E: Error,
@@ -74,41 +102,143 @@ impl GameVariant {
       releases_repository.get_cached_releases(self).await?;
     let payload = get_releases_payload(
       self,
+      descriptor,
       &cached_releases,
       ReleasesUpdateStatus::Fetching,
       host_system,
+      allow_emulated,
+      flavor,
     );
     on_releases(payload).map_err(FetchReleasesError::Send)?;
 
-    // 2. Fetch and emit releases from GitHub.
-    // Fetching 100 releases makes it likely that we have the last played release.
-    // TODO: Fetch the last played release separately.
-    let repo = get_github_repo_for_variant(self);
-    let fetched_releases =
-      fetch_github_releases(client, repo, Some(100)).await?;
+    // 2. Fetch and emit releases from GitHub up to the first page with a
+    // cached release. The cached releases on the pages fetched are
+    // refreshed as well.
+    // Neither being rate limited nor being offline is fatal: cached
+    // releases were already emitted, and default releases are still
+    // emitted below.
+    let repo = descriptor.repo.as_str();
+    let known_release_ids: HashSet<u64> =
+      cached_releases.iter().map(|r| r.id).collect();
+    // Without a cache to stop at, only fetch the latest page.
+    let num_releases = if known_release_ids.is_empty() {
+      Some(100)
+    } else {
+      None
+    };
+
+    let mut final_status = ReleasesUpdateStatus::Success;
+    let fetched_releases = match fetch_github_releases_until(
+      client,
+      repo,
+      num_releases,
+      github_api_cache_repository,
+      |release| known_release_ids.contains(&release.id),
+    )
+    .await
+    {
+      Ok(fetched_releases) => fetched_releases,
+      Err(GitHubReleaseFetchError::Fetch(
+        GitHubApiRequestError::RateLimited { reset_at },
+      )) => {
+        final_status = ReleasesUpdateStatus::RateLimited { reset_at };
+        Vec::new()
+      }
+      Err(GitHubReleaseFetchError::Fetch(
+        GitHubApiRequestError::Fetch(HttpClientError::Offline),
+      )) => {
+        final_status = ReleasesUpdateStatus::Offline;
+        Vec::new()
+      }
+      Err(e) => return Err(e.into()),
+    };
 
-    releases_repository
-      .update_cached_releases(self, &fetched_releases)
-      .await?;
+    if final_status == ReleasesUpdateStatus::Success {
+      releases_repository
+        .update_cached_releases(self, &fetched_releases)
+        .await?;
 
-    let payload = get_releases_payload(
-      self,
-      &fetched_releases,
-      ReleasesUpdateStatus::Fetching,
-      host_system,
-    );
-    on_releases(payload).map_err(FetchReleasesError::Send)?;
+      let payload = get_releases_payload(
+        self,
+        descriptor,
+        &fetched_releases,
+        ReleasesUpdateStatus::Fetching,
+        host_system,
+        allow_emulated,
+        flavor,
+      );
+      on_releases(payload).map_err(FetchReleasesError::Send)?;
+    }
 
-    // 3. Fetch and emit default releases.
-    // These are only fetched and emitted at the end so that GitHub releases
-    // are displayed first on first launch.
     let default_releases =
       get_default_releases(self, resources_dir).await;
+
+    // 3. Fetch and emit pinned releases that are still missing.
+    if final_status == ReleasesUpdateStatus::Success {
+      let known_tags: HashSet<&str> = cached_releases
+        .iter()
+        .chain(&fetched_releases)
+        .chain(&default_releases)
+        .map(|r| r.tag_name.as_str())
+        .collect();
+
+      let mut pinned_releases = Vec::new();
+      for version in pinned_versions {
+        if known_tags.contains(version.as_str()) {
+          continue;
+        }
+
+        match fetch_github_release_by_tag(
+          client,
+          repo,
+          version,
+          github_api_cache_repository,
+        )
+        .await
+        {
+          Ok(release) => pinned_releases.push(release),
+          Err(FetchGitHubReleaseByTagError::Fetch(
+            GitHubApiRequestError::RateLimited { reset_at },
+          )) => {
+            final_status =
+              ReleasesUpdateStatus::RateLimited { reset_at };
+            break;
+          }
+          // It's okay if a pinned release can't be fetched, e.g. because
+          // it was deleted from GitHub.
+          Err(_) => {}
+        }
+      }
+
+      if !pinned_releases.is_empty() {
+        releases_repository
+          .update_cached_releases(self, &pinned_releases)
+          .await?;
+
+        let payload = get_releases_payload(
+          self,
+          descriptor,
+          &pinned_releases,
+          ReleasesUpdateStatus::Fetching,
+          host_system,
+          allow_emulated,
+          flavor,
+        );
+        on_releases(payload).map_err(FetchReleasesError::Send)?;
+      }
+    }
+
+    // 4. Emit default releases.
+    // These are only emitted at the end so that GitHub releases are
+    // displayed first on first launch.
     let payload = get_releases_payload(
       self,
+      descriptor,
       &default_releases,
-      ReleasesUpdateStatus::Success,
+      final_status,
       host_system,
+      allow_emulated,
+      flavor,
     );
     on_releases(payload).map_err(FetchReleasesError::Send)?;
 
@@ -117,9 +247,11 @@ impl GameVariant {
 
   pub async fn fetch_release_notes(
     &self,
+    descriptor: &VariantDescriptor,
     release_id: &str,
     client: &impl HttpClient,
     releases_repository: &impl ReleasesRepository,
+    github_api_cache_repository: &impl GitHubApiCacheRepository,
   ) -> Result<Option<String>, FetchReleaseNotesError> {
     let cached_release = releases_repository
       .get_cached_release_by_tag(self, release_id)
@@ -132,9 +264,14 @@ impl GameVariant {
     }
 
     // If not found or body is missing, fetch from GitHub
-    let repo = get_github_repo_for_variant(self);
-    let github_release =
-      fetch_github_release_by_tag(client, repo, release_id).await?;
+    let repo = descriptor.repo.as_str();
+    let github_release = fetch_github_release_by_tag(
+      client,
+      repo,
+      release_id,
+      github_api_cache_repository,
+    )
+    .await?;
 
     // Update cache
     releases_repository
@@ -160,11 +297,15 @@ impl GameVariant {
 mod tests {
   use super::*;
   use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
+  use crate::game_release::game_release::GameReleaseStatus;
+  use crate::infra::github::asset::GitHubAsset;
+  use crate::infra::github::repository::sqlite_github_api_cache_repository::SqliteGitHubApiCacheRepository;
   use crate::infra::github::release::GitHubRelease;
   use crate::infra::testing::http_client::TestHttpClient;
   use crate::infra::testing::test_database::TestDatabase;
   use crate::infra::utils::{Arch, HostSystem, OS};
   use crate::variants::GameVariant;
+  use crate::variants::variant_descriptor::VariantRegistry;
   use chrono::Utc;
   use github_mock_api::{MockServer, Release as MockRelease};
   use std::collections::HashMap;
@@ -192,10 +333,66 @@ mod tests {
     Ok((db, server, client))
   }
 
+  #[test]
+  fn test_releases_without_native_asset_are_not_available() {
+    let variant = GameVariant::BrightNights;
+    let descriptor = VariantRegistry::default()
+      .get(&variant)
+      .expect("built-in variants should be registered");
+    let gh_releases = [GitHubRelease {
+      id: 1,
+      tag_name: "v1.0.0".to_string(),
+      prerelease: false,
+      body: None,
+      assets: vec![GitHubAsset {
+        id: 2,
+        browser_download_url: "https://example.com/asset".to_string(),
+        name: "cbn-linux-tiles-x64-v1.0.0.tar.gz".to_string(),
+        digest: None,
+      }],
+      created_at: Utc::now(),
+    }];
+
+    let statuses = |arch, allow_emulated| {
+      get_releases_payload(
+        &variant,
+        &descriptor,
+        &gh_releases,
+        ReleasesUpdateStatus::Success,
+        &HostSystem {
+          os: OS::Linux,
+          arch,
+        },
+        allow_emulated,
+        BuildFlavor::default(),
+      )
+      .releases
+      .into_iter()
+      .map(|r| r.status)
+      .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+      statuses(Arch::X64, false),
+      vec![GameReleaseStatus::Unknown]
+    );
+    assert_eq!(
+      statuses(Arch::ARM64, false),
+      vec![GameReleaseStatus::NotAvailable]
+    );
+    // The x64 build runs through an emulation layer if that's allowed.
+    assert_eq!(
+      statuses(Arch::ARM64, true),
+      vec![GameReleaseStatus::Unknown]
+    );
+  }
+
   #[tokio::test]
   async fn test_fetch_release_notes_cache_hit() -> TestResult {
     let (db, _server, client) = setup().await?;
     let repo = SqliteReleasesRepository::new(db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(db.pool().clone());
     let variant = GameVariant::DarkDaysAhead;
     let tag = "v1.0.0";
     let body = "cached notes";
@@ -215,8 +412,15 @@ mod tests {
       )
       .await?;
 
-    let result =
-      variant.fetch_release_notes(tag, &client, &repo).await?;
+    let result = variant
+      .fetch_release_notes(
+        &*VariantRegistry::default().get(&variant)?,
+        tag,
+        &client,
+        &repo,
+        &api_cache,
+      )
+      .await?;
 
     if result != Some(body.to_string()) {
       return Err(
@@ -242,6 +446,8 @@ mod tests {
   -> TestResult {
     let (db, server, client) = setup().await?;
     let repo = SqliteReleasesRepository::new(db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(db.pool().clone());
     let variant = GameVariant::DarkDaysAhead;
     let tag = "v1.0.0";
     let body = "github notes";
@@ -271,8 +477,15 @@ mod tests {
       )
       .await;
 
-    let result =
-      variant.fetch_release_notes(tag, &client, &repo).await?;
+    let result = variant
+      .fetch_release_notes(
+        &*VariantRegistry::default().get(&variant)?,
+        tag,
+        &client,
+        &repo,
+        &api_cache,
+      )
+      .await?;
 
     if result != Some(body.to_string()) {
       return Err(
@@ -313,6 +526,8 @@ mod tests {
   async fn test_fetch_release_notes_cache_miss() -> TestResult {
     let (db, server, client) = setup().await?;
     let repo = SqliteReleasesRepository::new(db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(db.pool().clone());
     let variant = GameVariant::BrightNights;
     let tag = "bn-1.0";
     let body = "bn notes";
@@ -327,8 +542,15 @@ mod tests {
       )
       .await;
 
-    let result =
-      variant.fetch_release_notes(tag, &client, &repo).await?;
+    let result = variant
+      .fetch_release_notes(
+        &*VariantRegistry::default().get(&variant)?,
+        tag,
+        &client,
+        &repo,
+        &api_cache,
+      )
+      .await?;
 
     if result != Some(body.to_string()) {
       return Err(
@@ -378,12 +600,21 @@ mod tests {
   async fn test_fetch_release_notes_github_404() -> TestResult {
     let (db, _server, client) = setup().await?;
     let repo = SqliteReleasesRepository::new(db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(db.pool().clone());
     let variant = GameVariant::TheLastGeneration;
     let tag = "missing-tag";
 
     // GitHub is empty, should 404
-    let result =
-      variant.fetch_release_notes(tag, &client, &repo).await;
+    let result = variant
+      .fetch_release_notes(
+        &*VariantRegistry::default().get(&variant)?,
+        tag,
+        &client,
+        &repo,
+        &api_cache,
+      )
+      .await;
 
     match result {
       Err(FetchReleaseNotesError::Fetch(_)) => Ok(()),
@@ -400,6 +631,8 @@ mod tests {
   async fn test_fetch_release_notes_github_500() -> TestResult {
     let (db, server, client) = setup().await?;
     let repo = SqliteReleasesRepository::new(db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(db.pool().clone());
     let variant = GameVariant::DarkDaysAhead;
     let tag = "v1.0.0";
 
@@ -412,8 +645,15 @@ mod tests {
       )
       .await?;
 
-    let result =
-      variant.fetch_release_notes(tag, &client, &repo).await;
+    let result = variant
+      .fetch_release_notes(
+        &*VariantRegistry::default().get(&variant)?,
+        tag,
+        &client,
+        &repo,
+        &api_cache,
+      )
+      .await;
 
     match result {
       Err(FetchReleaseNotesError::Fetch(_)) => Ok(()),
@@ -431,6 +671,8 @@ mod tests {
   -> TestResult {
     let (db, server, client) = setup().await?;
     let repo = SqliteReleasesRepository::new(db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(db.pool().clone());
     let variant = GameVariant::DarkDaysAhead;
     let tag = "v1.0.0+test";
     let body = "special notes";
@@ -445,8 +687,15 @@ mod tests {
       )
       .await;
 
-    let result =
-      variant.fetch_release_notes(tag, &client, &repo).await?;
+    let result = variant
+      .fetch_release_notes(
+        &*VariantRegistry::default().get(&variant)?,
+        tag,
+        &client,
+        &repo,
+        &api_cache,
+      )
+      .await?;
 
     if result != Some(body.to_string()) {
       return Err(
@@ -461,6 +710,8 @@ mod tests {
   -> TestResult {
     let (db, server, client) = setup().await?;
     let repo = SqliteReleasesRepository::new(db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(db.pool().clone());
     let tag = "v1.0.0";
 
     let variants = [
@@ -484,16 +735,25 @@ mod tests {
       ),
     ];
 
-    for (variant, owner, repo_name, body) in variants {
+    for (variant_index, (variant, owner, repo_name, body)) in
+      variants.into_iter().enumerate()
+    {
       let mut mock_release =
         MockRelease::new(owner, repo_name, tag).body(body);
       // Manually set ID to a value that fits in i64 to avoid Sqlite conversion error
-      mock_release.id = 12345 + (variant as u64);
+      mock_release.id = 12345 + variant_index as u64;
 
       server.add_release(owner, repo_name, mock_release).await;
 
-      let result =
-        variant.fetch_release_notes(tag, &client, &repo).await?;
+      let result = variant
+        .fetch_release_notes(
+          &*VariantRegistry::default().get(&variant)?,
+          tag,
+          &client,
+          &repo,
+          &api_cache,
+        )
+        .await?;
 
       if result != Some(body.to_string()) {
         return Err(
@@ -571,6 +831,8 @@ mod tests {
   -> Result<(), Box<dyn std::error::Error>> {
     let (server, client, test_db) = setup_test_context().await?;
     let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
     let variant = GameVariant::BrightNights;
     let resources_dir =
       std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -598,9 +860,12 @@ mod tests {
 
     variant
       .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
         &client,
         &resources_dir,
         &repo,
+        &api_cache,
+        &[],
         |payload| {
           payloads_clone
             .lock()
@@ -617,6 +882,8 @@ mod tests {
           os: OS::Windows,
           arch: Arch::X64,
         },
+        false,
+        BuildFlavor::default(),
       )
       .await?;
 
@@ -646,6 +913,8 @@ mod tests {
   -> Result<(), Box<dyn std::error::Error>> {
     let (_server, client, test_db) = setup_test_context().await?;
     let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
     let variant = GameVariant::BrightNights;
     let resources_dir =
       std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -665,9 +934,12 @@ mod tests {
     // We don't care if it fails after the first emission for this test
     let _ = variant
       .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
         &client,
         &resources_dir,
         &repo,
+        &api_cache,
+        &[],
         |payload| {
           payloads_clone
             .lock()
@@ -684,6 +956,8 @@ mod tests {
           os: OS::Windows,
           arch: Arch::X64,
         },
+        false,
+        BuildFlavor::default(),
       )
       .await;
 
@@ -702,6 +976,8 @@ mod tests {
   -> Result<(), Box<dyn std::error::Error>> {
     let (server, client, test_db) = setup_test_context().await?;
     let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
     let variant = GameVariant::BrightNights;
     let resources_dir =
       std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -718,9 +994,12 @@ mod tests {
 
     variant
       .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
         &client,
         &resources_dir,
         &repo,
+        &api_cache,
+        &[],
         |payload| {
           payloads_clone
             .lock()
@@ -737,6 +1016,8 @@ mod tests {
           os: OS::Windows,
           arch: Arch::X64,
         },
+        false,
+        BuildFlavor::default(),
       )
       .await?;
 
@@ -755,6 +1036,8 @@ mod tests {
   -> Result<(), Box<dyn std::error::Error>> {
     let (server, client, test_db) = setup_test_context().await?;
     let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
     let variant = GameVariant::BrightNights;
     let resources_dir =
       std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -768,14 +1051,19 @@ mod tests {
 
     variant
       .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
         &client,
         &resources_dir,
         &repo,
+        &api_cache,
+        &[],
         |_| Ok::<(), std::io::Error>(()),
         &HostSystem {
           os: OS::Windows,
           arch: Arch::X64,
         },
+        false,
+        BuildFlavor::default(),
       )
       .await?;
 
@@ -790,6 +1078,8 @@ mod tests {
   -> Result<(), Box<dyn std::error::Error>> {
     let (_server, client, test_db) = setup_test_context().await?;
     let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
     let variant = GameVariant::BrightNights;
     let resources_dir =
       std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -799,9 +1089,12 @@ mod tests {
 
     variant
       .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
         &client,
         &resources_dir,
         &repo,
+        &api_cache,
+        &[],
         |payload| {
           payloads_clone
             .lock()
@@ -818,6 +1111,8 @@ mod tests {
           os: OS::Windows,
           arch: Arch::X64,
         },
+        false,
+        BuildFlavor::default(),
       )
       .await?;
 
@@ -836,6 +1131,8 @@ mod tests {
   -> Result<(), Box<dyn std::error::Error>> {
     let (server, client, test_db) = setup_test_context().await?;
     let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
     let variant = GameVariant::BrightNights;
     let resources_dir =
       std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -852,9 +1149,12 @@ mod tests {
 
     variant
       .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
         &client,
         &resources_dir,
         &repo,
+        &api_cache,
+        &[],
         |payload| {
           payloads_clone
             .lock()
@@ -871,6 +1171,8 @@ mod tests {
           os: OS::Windows,
           arch: Arch::X64,
         },
+        false,
+        BuildFlavor::default(),
       )
       .await?;
 
@@ -893,6 +1195,8 @@ mod tests {
   -> Result<(), Box<dyn std::error::Error>> {
     let (server, client, test_db) = setup_test_context().await?;
     let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
     let variant = GameVariant::BrightNights;
     let resources_dir =
       std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -922,9 +1226,12 @@ mod tests {
 
     variant
       .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
         &client,
         &resources_dir,
         &repo,
+        &api_cache,
+        &[],
         |payload| {
           payloads_clone
             .lock()
@@ -941,6 +1248,8 @@ mod tests {
           os: OS::Windows,
           arch: Arch::X64,
         },
+        false,
+        BuildFlavor::default(),
       )
       .await?;
 
@@ -952,11 +1261,234 @@ mod tests {
     Ok(())
   }
 
+  async fn add_dated_bn_releases(
+    server: &MockServer,
+    releases: &[(u64, &str, &str)],
+  ) {
+    for (id, tag, created_at) in releases {
+      let mut release =
+        MockRelease::new("cataclysmbnteam", "Cataclysm-BN", tag)
+          .created_at(*created_at)
+          .body(format!("Notes for {tag}"));
+      release.id = *id;
+      server
+        .add_release("cataclysmbnteam", "Cataclysm-BN", release)
+        .await;
+    }
+  }
+
+  /// Adds `v1` followed by a full page of newer releases, `v2` to `v101`,
+  /// so that `v1` is on the second page.
+  async fn add_two_pages_of_bn_releases(server: &MockServer) {
+    let releases: Vec<(u64, String, String)> = (1..=101)
+      .map(|id| {
+        let created_at =
+          format!("2024-02-01T{:02}:{:02}:00Z", id / 60, id % 60);
+        (id, format!("v{id}"), created_at)
+      })
+      .collect();
+    let releases: Vec<(u64, &str, &str)> = releases
+      .iter()
+      .map(|(id, tag, created_at)| {
+        (*id, tag.as_str(), created_at.as_str())
+      })
+      .collect();
+    add_dated_bn_releases(server, &releases).await;
+  }
+
+  #[tokio::test]
+  async fn test_fetch_releases_refreshes_cached_releases_on_first_page()
+  -> Result<(), Box<dyn std::error::Error>> {
+    let (server, client, test_db) = setup_test_context().await?;
+    let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
+    let variant = GameVariant::BrightNights;
+    let resources_dir =
+      std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    add_dated_bn_releases(
+      &server,
+      &[
+        (1, "v1", "2024-01-01T00:00:00Z"),
+        (2, "v2", "2024-02-01T00:00:00Z"),
+        (3, "v3", "2024-03-01T00:00:00Z"),
+      ],
+    )
+    .await;
+    repo
+      .update_cached_releases(
+        &variant,
+        &[create_github_release_with_assets(3, "v3", vec![])],
+      )
+      .await?;
+
+    variant
+      .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
+        &client,
+        &resources_dir,
+        &repo,
+        &api_cache,
+        &[],
+        |_| Ok::<(), std::io::Error>(()),
+        &HostSystem {
+          os: OS::Windows,
+          arch: Arch::X64,
+        },
+        false,
+        BuildFlavor::default(),
+      )
+      .await?;
+
+    let mut cached_tags: Vec<String> = repo
+      .get_cached_releases(&variant)
+      .await?
+      .into_iter()
+      .map(|r| r.tag_name)
+      .collect();
+    cached_tags.sort();
+    assert_eq!(cached_tags, vec!["v1", "v2", "v3"]);
+
+    // The newest release was already cached, but is refreshed anyway.
+    let refreshed = repo
+      .get_cached_release_by_tag(&variant, "v3")
+      .await?
+      .ok_or("v3 should be cached")?;
+    assert_eq!(refreshed.body.as_deref(), Some("Notes for v3"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_fetch_releases_stops_after_page_with_cached_release()
+  -> Result<(), Box<dyn std::error::Error>> {
+    let (server, client, test_db) = setup_test_context().await?;
+    let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
+    let variant = GameVariant::BrightNights;
+    let resources_dir =
+      std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    add_two_pages_of_bn_releases(&server).await;
+    repo
+      .update_cached_releases(
+        &variant,
+        &[create_github_release_with_assets(101, "v101", vec![])],
+      )
+      .await?;
+
+    variant
+      .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
+        &client,
+        &resources_dir,
+        &repo,
+        &api_cache,
+        &[],
+        |_| Ok::<(), std::io::Error>(()),
+        &HostSystem {
+          os: OS::Windows,
+          arch: Arch::X64,
+        },
+        false,
+        BuildFlavor::default(),
+      )
+      .await?;
+
+    assert!(
+      repo
+        .get_cached_release_by_tag(&variant, "v2")
+        .await?
+        .is_some()
+    );
+    assert!(
+      repo
+        .get_cached_release_by_tag(&variant, "v1")
+        .await?
+        .is_none()
+    );
+    assert_eq!(client.request_count(), 1);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_fetch_releases_fetches_missing_pinned_releases()
+  -> Result<(), Box<dyn std::error::Error>> {
+    let (server, client, test_db) = setup_test_context().await?;
+    let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
+    let variant = GameVariant::BrightNights;
+    let resources_dir =
+      std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    add_two_pages_of_bn_releases(&server).await;
+    repo
+      .update_cached_releases(
+        &variant,
+        &[create_github_release_with_assets(101, "v101", vec![])],
+      )
+      .await?;
+
+    let received_payloads = Arc::new(Mutex::new(Vec::new()));
+    let payloads_clone = received_payloads.clone();
+
+    variant
+      .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
+        &client,
+        &resources_dir,
+        &repo,
+        &api_cache,
+        &["v1".to_string(), "v101".to_string()],
+        |payload| {
+          payloads_clone
+            .lock()
+            .map_err(|e| {
+              std::io::Error::new(
+                std::io::ErrorKind::Other,
+                e.to_string(),
+              )
+            })?
+            .push(payload);
+          Ok::<(), std::io::Error>(())
+        },
+        &HostSystem {
+          os: OS::Windows,
+          arch: Arch::X64,
+        },
+        false,
+        BuildFlavor::default(),
+      )
+      .await?;
+
+    // Cached, GitHub, pinned and default releases.
+    let num_payloads =
+      received_payloads.lock().map_err(|e| e.to_string())?.len();
+    assert_eq!(num_payloads, 4);
+
+    assert!(
+      repo
+        .get_cached_release_by_tag(&variant, "v1")
+        .await?
+        .is_some()
+    );
+    // Only v1 was unknown, so only one release is fetched by tag.
+    assert_eq!(client.request_count(), 2);
+
+    Ok(())
+  }
+
   #[tokio::test]
   async fn test_fetch_releases_github_api_error_handling()
   -> Result<(), Box<dyn std::error::Error>> {
     let (server, client, test_db) = setup_test_context().await?;
     let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
     let variant = GameVariant::BrightNights;
     let resources_dir =
       std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -968,14 +1500,19 @@ mod tests {
 
     let result = variant
       .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
         &client,
         &resources_dir,
         &repo,
+        &api_cache,
+        &[],
         |_| Ok::<(), std::io::Error>(()),
         &HostSystem {
           os: OS::Windows,
           arch: Arch::X64,
         },
+        false,
+        BuildFlavor::default(),
       )
       .await;
 
@@ -986,6 +1523,150 @@ mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  async fn test_fetch_releases_rate_limited_falls_back_to_cache()
+  -> Result<(), Box<dyn std::error::Error>> {
+    let (server, client, test_db) = setup_test_context().await?;
+    let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
+    let variant = GameVariant::BrightNights;
+    let resources_dir =
+      std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    let cached_release = create_github_release_with_assets(
+      1,
+      "v1-cached",
+      vec!["cbn-windows-tiles-x64-msvc-2026-06-05.zip"],
+    );
+    repo
+      .update_cached_releases(&variant, &[cached_release])
+      .await?;
+
+    let behavior = github_mock_api::MockBehavior::builder()
+      .error(github_mock_api::MockError::RateLimitExceeded)
+      .build();
+    server.add_mock_behavior(behavior).await?;
+
+    let received_payloads = Arc::new(Mutex::new(Vec::new()));
+    let payloads_clone = received_payloads.clone();
+
+    variant
+      .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
+        &client,
+        &resources_dir,
+        &repo,
+        &api_cache,
+        &[],
+        |payload| {
+          payloads_clone
+            .lock()
+            .map_err(|e| {
+              std::io::Error::new(
+                std::io::ErrorKind::Other,
+                e.to_string(),
+              )
+            })?
+            .push(payload);
+          Ok::<(), std::io::Error>(())
+        },
+        &HostSystem {
+          os: OS::Windows,
+          arch: Arch::X64,
+        },
+        false,
+        BuildFlavor::default(),
+      )
+      .await?;
+
+    let payloads =
+      received_payloads.lock().map_err(|e| e.to_string())?;
+    assert_eq!(payloads.len(), 2);
+
+    let p0 = payloads.get(0).ok_or("Missing payload 0")?;
+    assert_eq!(p0.status, ReleasesUpdateStatus::Fetching);
+    assert!(p0.releases.iter().any(|r| r.version == "v1-cached"));
+
+    let p1 = payloads.get(1).ok_or("Missing payload 1")?;
+    assert!(matches!(
+      p1.status,
+      ReleasesUpdateStatus::RateLimited { reset_at } if reset_at > Utc::now()
+    ));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_fetch_releases_offline_uses_cache()
+  -> Result<(), Box<dyn std::error::Error>> {
+    use crate::infra::http_client::ReqwestHttpClient;
+
+    let test_db = TestDatabase::builder().build()?;
+    let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
+    let variant = GameVariant::BrightNights;
+    let resources_dir =
+      std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    let cached_release = create_github_release_with_assets(
+      1,
+      "v1-cached",
+      vec!["cbn-windows-tiles-x64-msvc-2026-06-05.zip"],
+    );
+    repo
+      .update_cached_releases(&variant, &[cached_release])
+      .await?;
+
+    let client = ReqwestHttpClient::new(reqwest::Client::new());
+    client.set_offline_mode(true);
+
+    let received_payloads = Arc::new(Mutex::new(Vec::new()));
+    let payloads_clone = received_payloads.clone();
+
+    variant
+      .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
+        &client,
+        &resources_dir,
+        &repo,
+        &api_cache,
+        &["v0-pinned".to_string()],
+        |payload| {
+          payloads_clone
+            .lock()
+            .map_err(|e| {
+              std::io::Error::new(
+                std::io::ErrorKind::Other,
+                e.to_string(),
+              )
+            })?
+            .push(payload);
+          Ok::<(), std::io::Error>(())
+        },
+        &HostSystem {
+          os: OS::Windows,
+          arch: Arch::X64,
+        },
+        false,
+        BuildFlavor::default(),
+      )
+      .await?;
+
+    let payloads =
+      received_payloads.lock().map_err(|e| e.to_string())?;
+    assert_eq!(payloads.len(), 2);
+
+    let p0 = payloads.get(0).ok_or("Missing payload 0")?;
+    assert!(p0.releases.iter().any(|r| r.version == "v1-cached"));
+
+    let p1 = payloads.get(1).ok_or("Missing payload 1")?;
+    assert_eq!(p1.status, ReleasesUpdateStatus::Offline);
+
+    Ok(())
+  }
+
   #[tokio::test]
   async fn test_fetch_releases_repository_error_handling()
   -> Result<(), Box<dyn std::error::Error>> {
@@ -993,6 +1674,8 @@ mod tests {
 
     // Use the normal test database with proper schema initialization
     let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
     let variant = GameVariant::BrightNights;
     let resources_dir =
       std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -1001,14 +1684,19 @@ mod tests {
     // and that the fetch_releases function handles the data flow correctly
     let result = variant
       .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
         &client,
         &resources_dir,
         &repo,
+        &api_cache,
+        &[],
         |_| Ok::<(), std::io::Error>(()),
         &HostSystem {
           os: OS::Windows,
           arch: Arch::X64,
         },
+        false,
+        BuildFlavor::default(),
       )
       .await;
 
@@ -1023,15 +1711,20 @@ mod tests {
   -> Result<(), Box<dyn std::error::Error>> {
     let (_server, client, test_db) = setup_test_context().await?;
     let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
     let variant = GameVariant::BrightNights;
     let resources_dir =
       std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
 
     let result = variant
       .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
         &client,
         &resources_dir,
         &repo,
+        &api_cache,
+        &[],
         |_| {
           Err(std::io::Error::new(
             std::io::ErrorKind::Other,
@@ -1042,6 +1735,8 @@ mod tests {
           os: OS::Windows,
           arch: Arch::X64,
         },
+        false,
+        BuildFlavor::default(),
       )
       .await;
 
@@ -1057,6 +1752,8 @@ mod tests {
   -> Result<(), Box<dyn std::error::Error>> {
     let (server, client, test_db) = setup_test_context().await?;
     let repo = SqliteReleasesRepository::new(test_db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(test_db.pool().clone());
     let variant = GameVariant::DarkDaysAhead;
     let resources_dir =
       std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -1082,9 +1779,12 @@ mod tests {
 
     variant
       .fetch_releases(
+        &*VariantRegistry::default().get(&variant)?,
         &client,
         &resources_dir,
         &repo,
+        &api_cache,
+        &[],
         |payload| {
           payloads_clone
             .lock()
@@ -1101,6 +1801,8 @@ mod tests {
           os: OS::Windows,
           arch: Arch::X64,
         },
+        false,
+        BuildFlavor::default(),
       )
       .await?;
 
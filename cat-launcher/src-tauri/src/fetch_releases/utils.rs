@@ -6,14 +6,18 @@ use crate::fetch_releases::fetch_releases::{
 };
 use crate::fetch_releases::repository::ReleasesRepository;
 use crate::filesystem::paths::get_default_releases_file_path;
-use crate::game_release::game_release::GameRelease;
+use crate::game_release::game_release::{
+  GameRelease, GameReleaseStatus,
+};
 use crate::game_release::utils::{
-  get_platform_asset_substrs, gh_release_to_game_release,
+  find_platform_asset, gh_release_to_game_release,
 };
 use crate::infra::github::asset::GitHubAsset;
 use crate::infra::github::release::GitHubRelease;
 use crate::infra::utils::{HostSystem, read_from_file};
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::variant_descriptor::VariantDescriptor;
 
 pub async fn get_default_releases(
   variant: &GameVariant,
@@ -92,34 +96,48 @@ pub async fn get_assets(
 }
 
 pub fn is_installable(
-  variant: &GameVariant,
+  descriptor: &VariantDescriptor,
   release: &GitHubRelease,
   host_system: &HostSystem,
+  allow_emulated: bool,
+  flavor: BuildFlavor,
 ) -> bool {
-  let asset_substrs =
-    get_platform_asset_substrs(variant, host_system);
-  release.assets.iter().any(|asset| {
-    asset_substrs
-      .iter()
-      .any(|substr| asset.name.contains(substr))
-  })
+  find_platform_asset(
+    descriptor,
+    host_system,
+    allow_emulated,
+    flavor,
+    &release.assets,
+  )
+  .is_some()
 }
 
+/// Builds the payload of a releases update. Releases without an asset the
+/// host system can run are included, but marked as not available.
 pub fn get_releases_payload(
   variant: &GameVariant,
+  descriptor: &VariantDescriptor,
   gh_releases: &[GitHubRelease],
   status: ReleasesUpdateStatus,
   host_system: &HostSystem,
+  allow_emulated: bool,
+  flavor: BuildFlavor,
 ) -> ReleasesUpdatePayload {
   let releases: Vec<GameRelease> = gh_releases
     .iter()
-    .filter_map(|r| {
-      if !is_installable(variant, r, host_system) {
-        return None;
+    .map(|r| {
+      let mut release =
+        gh_release_to_game_release(r, variant, descriptor);
+      if !is_installable(
+        descriptor,
+        r,
+        host_system,
+        allow_emulated,
+        flavor,
+      ) {
+        release.status = GameReleaseStatus::NotAvailable;
       }
-
-      let release = gh_release_to_game_release(r, variant);
-      Some(release)
+      release
     })
     .collect();
 
@@ -0,0 +1,75 @@
+use std::path::Path;
+
+/// There isn't enough free space on a filesystem for what is about to be
+/// written to it.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error(
+  "not enough disk space: {required} bytes required, {available} bytes available"
+)]
+pub struct InsufficientSpace {
+  /// The estimated number of bytes that will be written.
+  pub required: u64,
+  /// The number of bytes that are free.
+  pub available: u64,
+}
+
+/// Checks that `required` bytes can be written to the filesystem that
+/// `path` is, or would be, on.
+///
+/// `path` doesn't have to exist yet. If the free space can't be
+/// determined, the check passes, so that the write itself decides.
+pub fn ensure_available_space(
+  path: &Path,
+  required: u64,
+) -> Result<(), InsufficientSpace> {
+  let Some(existing) = path.ancestors().find(|p| p.exists()) else {
+    return Ok(());
+  };
+  let Ok(available) = fs4::available_space(existing) else {
+    return Ok(());
+  };
+
+  if required > available {
+    return Err(InsufficientSpace {
+      required,
+      available,
+    });
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[test]
+  fn test_ensure_available_space() -> TestResult {
+    let dir = TempDir::new()?;
+    let missing = dir.path().join("not").join("created");
+
+    assert_eq!(ensure_available_space(&missing, 0), Ok(()));
+
+    assert!(matches!(
+      ensure_available_space(&missing, u64::MAX),
+      Err(InsufficientSpace {
+        required: u64::MAX,
+        ..
+      })
+    ));
+
+    Ok(())
+  }
+}
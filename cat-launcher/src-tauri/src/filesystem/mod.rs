@@ -1,2 +1,3 @@
+pub mod disk_space;
 pub mod paths;
 pub mod utils;
@@ -1,11 +1,14 @@
 use std::io;
 use std::path::{Path, PathBuf};
 
+use strum::IntoEnumIterator;
 use tokio::fs::{create_dir_all, read_dir};
 
 use crate::filesystem::utils::get_safe_filename;
 use crate::infra::utils::OS;
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::variant_descriptor::VariantDescriptor;
 
 /// Returns the path to the application database file.
 pub fn get_db_path(data_dir: &Path) -> PathBuf {
@@ -54,11 +57,20 @@ pub enum AssetDownloadDirError {
   CreateDirectory(#[from] io::Error),
 }
 
+/// Returns the directory the releases of a variant are downloaded and
+/// installed into, without creating it.
+pub fn get_asset_download_dir(
+  variant: &GameVariant,
+  data_dir: &Path,
+) -> PathBuf {
+  data_dir.join("Assets").join(variant.id())
+}
+
 pub async fn get_or_create_asset_download_dir(
   variant: &GameVariant,
   data_dir: &Path,
 ) -> Result<PathBuf, AssetDownloadDirError> {
-  let dir = data_dir.join("Assets").join(variant.id());
+  let dir = get_asset_download_dir(variant, data_dir);
 
   create_dir_all(&dir).await?;
 
@@ -71,10 +83,16 @@ pub enum GetAutomaticBackupsDirError {
   DirFailed(#[from] io::Error),
 }
 
+/// Returns the directory automatic backups are stored in, without
+/// creating it.
+pub fn get_automatic_backups_dir(data_dir: &Path) -> PathBuf {
+  data_dir.join("Backups").join("Automatic")
+}
+
 pub async fn get_or_create_automatic_backups_dir(
   data_dir: &Path,
 ) -> Result<PathBuf, GetAutomaticBackupsDirError> {
-  let dir = data_dir.join("Backups").join("Automatic");
+  let dir = get_automatic_backups_dir(data_dir);
   create_dir_all(&dir).await?;
 
   Ok(dir)
@@ -86,16 +104,111 @@ pub enum AssetExtractionDirError {
   CreateDirectory(#[from] io::Error),
 }
 
+/// Returns the installation directory of a release in the given build
+/// flavor, without creating it.
+pub fn get_asset_installation_dir(
+  variant: &GameVariant,
+  release_version: &str,
+  flavor: BuildFlavor,
+  data_dir: &Path,
+) -> PathBuf {
+  get_asset_download_dir(variant, data_dir)
+    .join(get_installation_dir_name(release_version, flavor))
+}
+
+/// Returns the directory holding the releases of a variant that are
+/// being extracted, without creating it.
+///
+/// It's kept apart from the installations so that a partial extraction
+/// is never mistaken for one, but within the data directory so that
+/// moving it into place is a rename.
+pub fn get_staging_dir(
+  variant: &GameVariant,
+  data_dir: &Path,
+) -> PathBuf {
+  data_dir.join("Staging").join(variant.id())
+}
+
+/// Returns the directory a release is extracted into before it's moved
+/// to its installation directory, without creating it.
+pub fn get_asset_staging_dir(
+  variant: &GameVariant,
+  release_version: &str,
+  flavor: BuildFlavor,
+  data_dir: &Path,
+) -> PathBuf {
+  get_staging_dir(variant, data_dir)
+    .join(get_installation_dir_name(release_version, flavor))
+}
+
+/// Returns the path of the file that marks the last download of a release
+/// as corrupted, next to its installation directory. Garbage collection
+/// only removes release archives from there, so the marker is kept.
+pub fn get_corrupted_download_marker_path(
+  variant: &GameVariant,
+  release_version: &str,
+  flavor: BuildFlavor,
+  data_dir: &Path,
+) -> PathBuf {
+  let mut path = get_asset_installation_dir(
+    variant,
+    release_version,
+    flavor,
+    data_dir,
+  )
+  .into_os_string();
+  path.push(".corrupted");
+  PathBuf::from(path)
+}
+
+/// Returns the name of the installation directory of a release in the
+/// given build flavor.
+pub fn get_installation_dir_name(
+  release_version: &str,
+  flavor: BuildFlavor,
+) -> String {
+  let dir_name = match flavor.installation_dir_suffix() {
+    Some(suffix) => format!("{}-{}", release_version, suffix),
+    None => release_version.to_string(),
+  };
+  get_safe_filename(&dir_name)
+}
+
+/// Returns the version of the release installed in the directory with the
+/// given name, or `None` if it's an installation in another build flavor.
+///
+/// Installation directory names are made safe, so the version may differ
+/// from that of the release, but it names the same directory.
+pub fn parse_installation_dir_name(
+  dir_name: &str,
+  flavor: BuildFlavor,
+) -> Option<&str> {
+  let strip_suffix =
+    |suffix: &str| dir_name.strip_suffix(suffix)?.strip_suffix('_');
+
+  match flavor.installation_dir_suffix() {
+    Some(suffix) => strip_suffix(suffix),
+    None => {
+      let is_other_flavor = BuildFlavor::iter()
+        .filter_map(|flavor| flavor.installation_dir_suffix())
+        .any(|suffix| strip_suffix(suffix).is_some());
+      (!is_other_flavor).then_some(dir_name)
+    }
+  }
+}
+
 pub async fn get_or_create_asset_installation_dir(
   variant: &GameVariant,
   release_version: &str,
+  flavor: BuildFlavor,
   data_dir: &Path,
 ) -> Result<PathBuf, AssetExtractionDirError> {
-  let safe_dir_name = get_safe_filename(release_version);
-  let dir = data_dir
-    .join("Assets")
-    .join(variant.id())
-    .join(&safe_dir_name);
+  let dir = get_asset_installation_dir(
+    variant,
+    release_version,
+    flavor,
+    data_dir,
+  );
 
   create_dir_all(&dir).await?;
 
@@ -120,16 +233,27 @@ pub enum GetGameExecutableDirError {
 pub async fn get_game_executable_dir(
   variant: &GameVariant,
   release_version: &str,
+  flavor: BuildFlavor,
   data_dir: &Path,
   os: &OS,
 ) -> Result<PathBuf, GetGameExecutableDirError> {
   let installation_dir = get_or_create_asset_installation_dir(
     variant,
     release_version,
+    flavor,
     data_dir,
   )
   .await?;
 
+  get_executable_dir_in(installation_dir, os).await
+}
+
+/// Returns the directory holding the game executable within the given
+/// installation directory.
+async fn get_executable_dir_in(
+  installation_dir: PathBuf,
+  os: &OS,
+) -> Result<PathBuf, GetGameExecutableDirError> {
   if os == &OS::Windows {
     return Ok(installation_dir);
   }
@@ -164,6 +288,7 @@ pub async fn get_game_executable_dir(
 pub async fn get_game_resources_dir(
   variant: &GameVariant,
   release_version: &str,
+  flavor: BuildFlavor,
   data_dir: &Path,
   os: &OS,
 ) -> Result<PathBuf, GetGameExecutableDirError> {
@@ -172,6 +297,7 @@ pub async fn get_game_resources_dir(
       let installation_dir = get_or_create_asset_installation_dir(
         variant,
         release_version,
+        flavor,
         data_dir,
       )
       .await?;
@@ -182,28 +308,23 @@ pub async fn get_game_resources_dir(
       Ok(resources_dir)
     }
     _ => {
-      get_game_executable_dir(variant, release_version, data_dir, os)
-        .await
+      get_game_executable_dir(
+        variant,
+        release_version,
+        flavor,
+        data_dir,
+        os,
+      )
+      .await
     }
   }
 }
 
-pub fn get_game_executable_filenames(
-  variant: &GameVariant,
+pub fn get_game_executable_filenames<'a>(
+  descriptor: &'a VariantDescriptor,
   os: &OS,
-) -> &'static [&'static str] {
-  match (variant, os) {
-    (g, OS::Windows) => match g {
-      GameVariant::BrightNights => &["cataclysm-bn-tiles.exe"],
-      GameVariant::DarkDaysAhead => &["cataclysm-tiles.exe"],
-      GameVariant::TheLastGeneration => {
-        &["cataclysm-tlg-tiles.exe", "cataclysm-tiles.exe"]
-      }
-    },
-
-    (_, OS::Linux) => &["cataclysm-launcher"],
-    (_, OS::Mac) => &["Cataclysm.sh"],
-  }
+) -> Vec<&'a str> {
+  descriptor.executable_filenames(os)
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -217,28 +338,45 @@ pub enum GetExecutablePathError {
 
 pub async fn get_game_executable_filepath(
   variant: &GameVariant,
+  descriptor: &VariantDescriptor,
   release_version: &str,
+  flavor: BuildFlavor,
   data_dir: &Path,
   os: &OS,
 ) -> Result<PathBuf, GetExecutablePathError> {
-  let dir = match get_game_executable_dir(
+  let installation_dir = get_or_create_asset_installation_dir(
     variant,
     release_version,
+    flavor,
     data_dir,
-    os,
   )
   .await
-  {
-    Ok(dir) => dir,
-    Err(GetGameExecutableDirError::NoInstallation) => {
-      return Err(GetExecutablePathError::DoesNotExist);
-    }
-    Err(err) => {
-      return Err(GetExecutablePathError::LauncherDirectory(err));
-    }
-  };
+  .map_err(GetGameExecutableDirError::from)?;
 
-  let filenames = get_game_executable_filenames(variant, os);
+  find_game_executable(&installation_dir, descriptor, os).await
+}
+
+/// Returns the path to the game executable within the given
+/// installation directory, wherever that directory is.
+pub async fn find_game_executable(
+  installation_dir: &Path,
+  descriptor: &VariantDescriptor,
+  os: &OS,
+) -> Result<PathBuf, GetExecutablePathError> {
+  let dir =
+    match get_executable_dir_in(installation_dir.to_path_buf(), os)
+      .await
+    {
+      Ok(dir) => dir,
+      Err(GetGameExecutableDirError::NoInstallation) => {
+        return Err(GetExecutablePathError::DoesNotExist);
+      }
+      Err(err) => {
+        return Err(GetExecutablePathError::LauncherDirectory(err));
+      }
+    };
+
+  let filenames = get_game_executable_filenames(descriptor, os);
 
   for filename in filenames {
     let filepath = dir.join(filename);
@@ -271,20 +409,27 @@ pub enum GetVersionExecutableDirError {
 pub async fn get_game_save_dirs(
   variant: &GameVariant,
   release_version: &str,
+  flavor: BuildFlavor,
   data_dir: &Path,
   os: &OS,
 ) -> Result<Vec<PathBuf>, GetGameExecutableDirError> {
   let dirs = &["save"];
 
-  let executable_dir =
-    get_game_executable_dir(variant, release_version, data_dir, os)
-      .await?;
+  let executable_dir = get_game_executable_dir(
+    variant,
+    release_version,
+    flavor,
+    data_dir,
+    os,
+  )
+  .await?;
   Ok(dirs.iter().map(|d| executable_dir.join(d)).collect())
 }
 
 pub async fn get_game_save_and_config_dirs(
   variant: &GameVariant,
   release_version: &str,
+  flavor: BuildFlavor,
   data_dir: &Path,
   os: &OS,
 ) -> Result<Vec<PathBuf>, GetGameExecutableDirError> {
@@ -297,9 +442,14 @@ pub async fn get_game_save_and_config_dirs(
     "templates",
   ];
 
-  let executable_dir =
-    get_game_executable_dir(variant, release_version, data_dir, os)
-      .await?;
+  let executable_dir = get_game_executable_dir(
+    variant,
+    release_version,
+    flavor,
+    data_dir,
+    os,
+  )
+  .await?;
   Ok(dirs.iter().map(|d| executable_dir.join(d)).collect())
 }
 
@@ -311,30 +461,28 @@ pub enum GetTipFilePathsError {
 
 pub async fn get_tip_file_paths(
   variant: &GameVariant,
+  descriptor: &VariantDescriptor,
   release_version: &str,
+  flavor: BuildFlavor,
   data_dir: &Path,
   os: &OS,
 ) -> Result<Vec<PathBuf>, GetTipFilePathsError> {
-  let resources_dir =
-    get_game_resources_dir(variant, release_version, data_dir, os)
-      .await?;
+  let resources_dir = get_game_resources_dir(
+    variant,
+    release_version,
+    flavor,
+    data_dir,
+    os,
+  )
+  .await?;
 
-  let hints_path = resources_dir
-    .join("data")
-    .join("json")
-    .join("npcs")
-    .join("hints.json");
+  let paths = descriptor
+    .tips_paths
+    .iter()
+    .map(|path| resources_dir.join(path))
+    .collect();
 
-  let tips_path = match variant {
-    GameVariant::BrightNights => {
-      resources_dir.join("data").join("raw").join("tips.json")
-    }
-    GameVariant::DarkDaysAhead | GameVariant::TheLastGeneration => {
-      resources_dir.join("data").join("core").join("tips.json")
-    }
-  };
-
-  Ok(vec![tips_path, hints_path])
+  Ok(paths)
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -343,11 +491,20 @@ pub enum GetUserGameDataDirError {
   DirFailed(#[from] io::Error),
 }
 
+/// Returns the directory holding the user data of a variant, without
+/// creating it.
+pub fn get_user_game_data_dir(
+  variant: &GameVariant,
+  data_dir: &Path,
+) -> PathBuf {
+  data_dir.join("UserData").join(variant.id())
+}
+
 pub async fn get_or_create_user_game_data_dir(
   variant: &GameVariant,
   data_dir: &Path,
 ) -> Result<PathBuf, GetUserGameDataDirError> {
-  let dir = data_dir.join("UserData").join(variant.id());
+  let dir = get_user_game_data_dir(variant, data_dir);
   create_dir_all(&dir).await?;
 
   Ok(dir)
@@ -359,10 +516,16 @@ pub enum GetManualBackupsDirError {
   DirFailed(#[from] io::Error),
 }
 
+/// Returns the directory manual backups are stored in, without creating
+/// it.
+pub fn get_manual_backups_dir(data_dir: &Path) -> PathBuf {
+  data_dir.join("Backups").join("Manual")
+}
+
 pub async fn get_or_create_manual_backups_dir(
   data_dir: &Path,
 ) -> Result<PathBuf, GetManualBackupsDirError> {
-  let dir = data_dir.join("Backups").join("Manual");
+  let dir = get_manual_backups_dir(data_dir);
   create_dir_all(&dir).await?;
 
   Ok(dir)
@@ -374,23 +537,95 @@ pub enum GetAutomaticBackupArchivePathError {
   DirFailed(#[from] GetAutomaticBackupsDirError),
 }
 
-pub async fn get_or_create_automatic_backup_archive_filepath(
+/// Returns the path of the archive of an automatic backup, without
+/// creating its directory.
+pub fn get_automatic_backup_archive_filepath(
   variant: &GameVariant,
   id: i64,
   version: &str,
   timestamp: u64,
   data_dir: &Path,
-) -> Result<PathBuf, GetAutomaticBackupArchivePathError> {
-  let backup_dir =
-    get_or_create_automatic_backups_dir(data_dir).await?;
-
-  Ok(backup_dir.join(format!(
+) -> PathBuf {
+  get_automatic_backups_dir(data_dir).join(format!(
     "{}_{}_{}_{}.zip",
     id,
     variant.id(),
     get_safe_filename(version),
     timestamp
-  )))
+  ))
+}
+
+pub async fn get_or_create_automatic_backup_archive_filepath(
+  variant: &GameVariant,
+  id: i64,
+  version: &str,
+  timestamp: u64,
+  data_dir: &Path,
+) -> Result<PathBuf, GetAutomaticBackupArchivePathError> {
+  get_or_create_automatic_backups_dir(data_dir).await?;
+
+  Ok(get_automatic_backup_archive_filepath(
+    variant, id, version, timestamp, data_dir,
+  ))
+}
+
+/// Returns the path of the manifest of an automatic backup, which refers
+/// to its content in the variant's backup store, without creating its
+/// directory. Backups made before the store existed are archives at
+/// [`get_automatic_backup_archive_filepath`] instead.
+pub fn get_automatic_backup_manifest_filepath(
+  variant: &GameVariant,
+  id: i64,
+  version: &str,
+  timestamp: u64,
+  data_dir: &Path,
+) -> PathBuf {
+  get_automatic_backup_archive_filepath(
+    variant, id, version, timestamp, data_dir,
+  )
+  .with_extension("json")
+}
+
+pub async fn get_or_create_automatic_backup_manifest_filepath(
+  variant: &GameVariant,
+  id: i64,
+  version: &str,
+  timestamp: u64,
+  data_dir: &Path,
+) -> Result<PathBuf, GetAutomaticBackupArchivePathError> {
+  let archive_path = get_or_create_automatic_backup_archive_filepath(
+    variant, id, version, timestamp, data_dir,
+  )
+  .await?;
+
+  Ok(archive_path.with_extension("json"))
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetBackupStoreDirError {
+  #[error("failed to create backup store directory: {0}")]
+  DirFailed(#[from] io::Error),
+}
+
+/// Returns the directory of the store holding the content of a variant's
+/// automatic backups, without creating it.
+pub fn get_backup_store_dir(
+  variant: &GameVariant,
+  data_dir: &Path,
+) -> PathBuf {
+  data_dir.join("Backups").join("Store").join(variant.id())
+}
+
+/// Returns the directory of the store holding the content of a variant's
+/// automatic backups.
+pub async fn get_or_create_backup_store_dir(
+  variant: &GameVariant,
+  data_dir: &Path,
+) -> Result<PathBuf, GetBackupStoreDirError> {
+  let dir = get_backup_store_dir(variant, data_dir);
+  create_dir_all(&dir).await?;
+
+  Ok(dir)
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -399,18 +634,28 @@ pub enum GetManualBackupArchivePathError {
   DirFailed(#[from] GetManualBackupsDirError),
 }
 
-pub async fn get_or_create_manual_backup_archive_filepath(
+/// Returns the path of the archive of a manual backup, without creating
+/// its directory.
+pub fn get_manual_backup_archive_filepath(
   id: i64,
   name: &str,
   data_dir: &Path,
-) -> Result<PathBuf, GetManualBackupArchivePathError> {
-  let backup_dir = get_or_create_manual_backups_dir(data_dir).await?;
-
-  Ok(backup_dir.join(format!(
+) -> PathBuf {
+  get_manual_backups_dir(data_dir).join(format!(
     "{}_{}.zip",
     id,
     get_safe_filename(name)
-  )))
+  ))
+}
+
+pub async fn get_or_create_manual_backup_archive_filepath(
+  id: i64,
+  name: &str,
+  data_dir: &Path,
+) -> Result<PathBuf, GetManualBackupArchivePathError> {
+  get_or_create_manual_backups_dir(data_dir).await?;
+
+  Ok(get_manual_backup_archive_filepath(id, name, data_dir))
 }
 
 #[derive(thiserror::Error, Debug)]
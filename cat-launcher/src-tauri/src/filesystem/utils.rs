@@ -1,6 +1,7 @@
 use std::io;
 use std::path::Path;
 
+use sha2::{Digest, Sha256};
 use tokio::fs::{create_dir_all, read_dir};
 use tokio::process::Command;
 
@@ -65,3 +66,78 @@ pub async fn copy_dir_all(
 
   Ok(())
 }
+
+/// Errors that can occur when computing the size of a directory.
+#[derive(thiserror::Error, Debug)]
+pub enum DirSizeError {
+  /// Failed to walk the directory tree.
+  #[error("failed to walk directory: {0}")]
+  Walk(#[from] walkdir::Error),
+
+  /// The blocking task computing the size failed.
+  #[error("failed to join size computation task: {0}")]
+  Join(#[from] tokio::task::JoinError),
+}
+
+/// Returns the total size in bytes of all files under `dir`.
+///
+/// Symbolic links are not followed.
+pub async fn get_dir_size(dir: &Path) -> Result<u64, DirSizeError> {
+  let dir = dir.to_path_buf();
+  tokio::task::spawn_blocking(move || {
+    let mut size = 0u64;
+    for entry in walkdir::WalkDir::new(&dir) {
+      let entry = entry?;
+      if entry.file_type().is_file() {
+        size = size.saturating_add(entry.metadata()?.len());
+      }
+    }
+    Ok::<u64, DirSizeError>(size)
+  })
+  .await?
+}
+
+/// Errors that can occur when hashing a file.
+#[derive(thiserror::Error, Debug)]
+pub enum FileHashError {
+  /// Failed to read the file.
+  #[error("failed to read file: {0}")]
+  Io(#[from] io::Error),
+
+  /// The blocking task computing the hash failed.
+  #[error("failed to join hashing task: {0}")]
+  Join(#[from] tokio::task::JoinError),
+}
+
+/// Returns the lowercase hex-encoded SHA-256 digest of `data`.
+pub fn get_sha256(data: &[u8]) -> String {
+  encode_hex(&Sha256::digest(data))
+}
+
+/// Returns the lowercase hex-encoded SHA-256 digest of the file at `path`.
+pub async fn get_file_sha256(
+  path: &Path,
+) -> Result<String, FileHashError> {
+  use std::io::Read;
+
+  let path = path.to_path_buf();
+  tokio::task::spawn_blocking(move || {
+    let mut file = std::fs::File::open(&path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+      let read = file.read(&mut buffer)?;
+      if read == 0 {
+        break;
+      }
+      hasher.update(buffer.get(..read).unwrap_or_default());
+    }
+
+    Ok::<String, FileHashError>(encode_hex(&hasher.finalize()))
+  })
+  .await?
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -1,18 +1,33 @@
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString, IntoStaticStr};
 use ts_rs::TS;
 
 use crate::fetch_releases::repository::ReleasesRepository;
 use crate::fetch_releases::utils::get_assets;
-use crate::game_release::utils::get_platform_asset_substrs;
+use crate::game_release::utils::find_platform_asset;
 use crate::infra::github::asset::GitHubAsset;
 use crate::infra::utils::HostSystem;
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::variant_descriptor::VariantDescriptor;
 
 #[derive(
-  Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, TS,
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Hash,
+  Deserialize,
+  Serialize,
+  TS,
+  Display,
+  EnumString,
+  IntoStaticStr,
 )]
+#[strum(ascii_case_insensitive)]
 #[ts(export)]
 /// The type of a game release.
 pub enum ReleaseType {
@@ -66,23 +81,28 @@ pub enum GameReleaseStatus {
 }
 
 impl GameRelease {
-  /// Attempts to find a compatible GitHub asset for this release based on the host system.
+  /// Attempts to find a compatible GitHub asset for this release based on the host system
+  /// in the given build flavor. Emulated builds are only considered if `allow_emulated` is
+  /// set.
   pub async fn get_asset(
     &self,
+    descriptor: &VariantDescriptor,
     host_system: &HostSystem,
+    allow_emulated: bool,
+    flavor: BuildFlavor,
     resources_dir: &Path,
     releases_repository: &impl ReleasesRepository,
   ) -> Option<GitHubAsset> {
     let assets =
       get_assets(self, resources_dir, releases_repository).await;
-    let substrings =
-      get_platform_asset_substrs(&self.variant, host_system);
 
-    substrings
-      .iter()
-      .find_map(|substr| {
-        assets.iter().find(|a| a.name.contains(substr))
-      })
-      .cloned()
+    find_platform_asset(
+      descriptor,
+      host_system,
+      allow_emulated,
+      flavor,
+      &assets,
+    )
+    .cloned()
   }
 }
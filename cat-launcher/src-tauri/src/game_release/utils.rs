@@ -6,49 +6,37 @@ use crate::fetch_releases::utils::{
 };
 use crate::game_release::GameRelease;
 use crate::game_release::game_release::GameReleaseStatus;
+use crate::infra::github::asset::GitHubAsset;
 use crate::infra::github::release::GitHubRelease;
-use crate::infra::utils::{Arch, HostSystem, OS};
+use crate::infra::utils::{HostSystem, OS};
 use crate::install_release::installation_status::status::GetInstallationStatusError;
+use crate::sideloaded_releases::repository::SideloadedReleasesRepository;
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::variant_descriptor::VariantDescriptor;
 
-/// Returns a list of substrings that identify the correct game asset for the given platform and architecture.
-pub fn get_platform_asset_substrs(
-  variant: &GameVariant,
+/// Finds the game asset for the given platform and architecture among the assets of a release,
+/// in the given build flavor.
+///
+/// Native builds are preferred. Builds that run through an emulation layer are only used if
+/// `allow_emulated` is set and the release has no native build.
+pub fn find_platform_asset<'a>(
+  descriptor: &VariantDescriptor,
   host_system: &HostSystem,
-) -> Vec<&'static str> {
-  let HostSystem { os, arch } = host_system;
-  match (variant, os, arch) {
-    (GameVariant::DarkDaysAhead, OS::Windows, _) => {
-      vec!["windows-with-graphics-and-sounds"]
-    }
-    (GameVariant::DarkDaysAhead, OS::Mac, _) => {
-      vec!["osx-with-graphics", "osx-terminal-only"]
-    }
-    (GameVariant::DarkDaysAhead, OS::Linux, _) => {
-      vec!["linux-with-graphics-and-sounds"]
-    }
-
-    (GameVariant::BrightNights, OS::Windows, _) => {
-      vec!["windows-tiles"]
-    }
-    (GameVariant::BrightNights, OS::Mac, Arch::ARM64) => {
-      vec!["osx-tiles-arm"]
-    }
-    (GameVariant::BrightNights, OS::Mac, Arch::X64) => {
-      vec!["osx-tiles-x64"]
-    }
-    (GameVariant::BrightNights, OS::Linux, _) => vec!["linux-tiles"],
-
-    (GameVariant::TheLastGeneration, OS::Windows, _) => {
-      vec!["windows-tiles-sounds-x64-msvc"]
-    }
-    (GameVariant::TheLastGeneration, OS::Mac, _) => {
-      vec!["osx-tiles-universal"]
-    }
-    (GameVariant::TheLastGeneration, OS::Linux, _) => {
-      vec!["linux-tiles-sounds"]
-    }
-  }
+  allow_emulated: bool,
+  flavor: BuildFlavor,
+  assets: &'a [GitHubAsset],
+) -> Option<&'a GitHubAsset> {
+  descriptor
+    .asset_patterns(host_system, flavor, allow_emulated)
+    .find_map(|pattern| {
+      pattern.substrs.iter().find_map(|substr| {
+        assets.iter().find(|asset| {
+          asset.name.contains(substr.as_str())
+            && !pattern.excludes_asset(&asset.name)
+        })
+      })
+    })
 }
 
 /// Errors that can occur when retrieving a specific game release.
@@ -67,15 +55,14 @@ pub enum GetReleaseError {
 pub fn gh_release_to_game_release(
   gh_release: &GitHubRelease,
   variant: &GameVariant,
+  descriptor: &VariantDescriptor,
 ) -> GameRelease {
   GameRelease {
     variant: *variant,
     version: gh_release.tag_name.clone(),
     body: gh_release.body.clone(),
-    release_type: variant.determine_release_type(
-      &gh_release.tag_name,
-      gh_release.prerelease,
-    ),
+    release_type: descriptor
+      .release_type(&gh_release.tag_name, gh_release.prerelease),
     status: GameReleaseStatus::Unknown,
     created_at: gh_release.created_at,
   }
@@ -84,14 +71,19 @@ pub fn gh_release_to_game_release(
 /// Retrieves a specific game release by its ID (tag name).
 ///
 /// This function merges cached and default releases to find the requested release
-/// and populates its current installation status.
+/// and populates its current installation status. Releases that were only
+/// sideloaded from a local archive are found as well.
+#[allow(clippy::too_many_arguments)]
 pub async fn get_release_by_id(
   variant: &GameVariant,
+  descriptor: &VariantDescriptor,
   release_id: &str,
   os: &OS,
+  flavor: BuildFlavor,
   data_dir: &Path,
   resources_dir: &Path,
   releases_repository: &impl ReleasesRepository,
+  sideloaded_releases_repository: &impl SideloadedReleasesRepository,
 ) -> Result<GameRelease, GetReleaseError> {
   let cached_releases = releases_repository
     .get_cached_releases(variant)
@@ -105,17 +97,105 @@ pub async fn get_release_by_id(
   let gh_releases =
     merge_releases(&cached_releases, &default_releases);
 
-  let gh_release = match gh_releases
-    .iter()
-    .find(|r| r.tag_name == release_id)
-  {
-    Some(r) => r,
-    None => return Err(GetReleaseError::NotFound(release_id.into())),
-  };
+  let gh_release =
+    gh_releases.iter().find(|r| r.tag_name == release_id);
 
-  let mut release = gh_release_to_game_release(gh_release, variant);
-  release.status =
-    release.get_installation_status(os, data_dir).await?;
+  let mut release = match gh_release {
+    Some(gh_release) => {
+      gh_release_to_game_release(gh_release, variant, descriptor)
+    }
+    None => sideloaded_releases_repository
+      .get_sideloaded_releases(variant)
+      .await
+      .unwrap_or_default() // It's okay if sideloaded releases couldn't be read.
+      .into_iter()
+      .find(|r| r.version == release_id)
+      .map(|r| r.to_game_release(variant, descriptor))
+      .ok_or_else(|| GetReleaseError::NotFound(release_id.into()))?,
+  };
+  release.status = release
+    .get_installation_status(descriptor, os, flavor, data_dir)
+    .await?;
 
   Ok(release)
 }
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::infra::utils::Arch;
+  use crate::variants::variant_descriptor::builtin_descriptors;
+
+  fn create_assets(names: &[&str]) -> Vec<GitHubAsset> {
+    names
+      .iter()
+      .map(|name| GitHubAsset {
+        id: 1,
+        browser_download_url: "url".to_string(),
+        name: name.to_string(),
+        digest: None,
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_find_platform_asset_picks_architecture() {
+    let dda = &builtin_descriptors()[0];
+    let assets = create_assets(&[
+      "cdda-linux-with-graphics-and-sounds-arm64-2025.tar.gz",
+      "cdda-linux-with-graphics-and-sounds-x64-2025.tar.gz",
+    ]);
+    let find = |arch, allow_emulated| {
+      let host_system = HostSystem {
+        os: OS::Linux,
+        arch,
+      };
+      find_platform_asset(
+        dda,
+        &host_system,
+        allow_emulated,
+        BuildFlavor::TilesWithSounds,
+        &assets,
+      )
+      .map(|asset| asset.name.as_str())
+    };
+
+    assert_eq!(
+      find(Arch::X64, false),
+      Some("cdda-linux-with-graphics-and-sounds-x64-2025.tar.gz")
+    );
+    assert_eq!(
+      find(Arch::ARM64, true),
+      Some("cdda-linux-with-graphics-and-sounds-arm64-2025.tar.gz")
+    );
+  }
+
+  #[test]
+  fn test_find_platform_asset_skips_arm64_builds_on_x64() {
+    let bn = &builtin_descriptors()[1];
+    let assets =
+      create_assets(&["cbn-linux-tiles-arm64-2025.tar.gz"]);
+    let host_system = HostSystem {
+      os: OS::Linux,
+      arch: Arch::X64,
+    };
+
+    assert!(
+      find_platform_asset(
+        bn,
+        &host_system,
+        false,
+        BuildFlavor::TilesWithSounds,
+        &assets,
+      )
+      .is_none()
+    );
+  }
+}
@@ -6,8 +6,11 @@ use crate::game_tips::lib::get_all_tips_for_variant;
 use crate::game_tips::lib::GetAllTipsForVariantError;
 use crate::infra::utils::{get_os_enum, OSNotSupportedError};
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavorSelection;
 use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::variants::game_variant::UnknownGameVariantError;
+use crate::variants::variant_descriptor::VariantRegistry;
 
 /// Errors that can occur when executing the get tips command.
 #[derive(thiserror::Error, Debug, CommandErrorSerialize)]
@@ -23,6 +26,10 @@ pub enum GetTipsCommandError {
   /// Failed to retrieve tips for the given game variant.
   #[error("failed to get tips for variant: {0}")]
   GetForVariant(#[from] GetAllTipsForVariantError),
+
+  /// The game variant is not registered.
+  #[error("unknown game variant: {0}")]
+  UnknownVariant(#[from] UnknownGameVariantError),
 }
 
 /// Tauri command to retrieve game tips for a specified game variant.
@@ -32,12 +39,17 @@ pub async fn get_tips(
   variant: GameVariant,
   active_release_repository: State<'_, SqliteActiveReleaseRepository>,
   releases_repository: State<'_, SqliteReleasesRepository>,
+  build_flavors: State<'_, BuildFlavorSelection>,
+  variant_registry: State<'_, VariantRegistry>,
 ) -> Result<Vec<String>, GetTipsCommandError> {
   let data_dir = app_handle.path().app_local_data_dir()?;
   let os = get_os_enum(std::env::consts::OS)?;
 
+  let descriptor = variant_registry.get(&variant)?;
   let tips = get_all_tips_for_variant(
     &variant,
+    &descriptor,
+    build_flavors.get(&variant),
     &data_dir,
     &os,
     &*active_release_repository,
@@ -17,6 +17,8 @@ use crate::game_tips::types::Tip;
 use crate::infra::utils::OS;
 use crate::install_release::installation_status::status::GetInstallationStatusError;
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::variant_descriptor::VariantDescriptor;
 
 /// Errors that can occur when retrieving game tips for a variant.
 #[derive(Debug, Error)]
@@ -49,12 +51,16 @@ pub enum GetAllTipsForVariantError {
 /// Reads all tip files associated with a specific game version and collects the text.
 async fn get_tips_from_version(
   variant: &GameVariant,
+  descriptor: &VariantDescriptor,
   version: &str,
+  flavor: BuildFlavor,
   data_dir: &std::path::Path,
   os: &OS,
 ) -> Result<Vec<String>, GetAllTipsForVariantError> {
-  let tip_file_paths =
-    get_tip_file_paths(variant, version, data_dir, os).await?;
+  let tip_file_paths = get_tip_file_paths(
+    variant, descriptor, version, flavor, data_dir, os,
+  )
+  .await?;
   let mut all_tips: Vec<String> = Vec::new();
 
   for path in tip_file_paths {
@@ -76,9 +82,12 @@ async fn get_tips_from_version(
   Ok(all_tips)
 }
 
-/// Retrieves all game tips for the currently active or installed game release variant.
+/// Retrieves all game tips for the currently active or installed game release variant,
+/// in the given build flavor.
 pub async fn get_all_tips_for_variant(
   variant: &GameVariant,
+  descriptor: &VariantDescriptor,
+  flavor: BuildFlavor,
   data_dir: &std::path::Path,
   os: &OS,
   active_release_repository: &impl ActiveReleaseRepository,
@@ -88,9 +97,15 @@ pub async fn get_all_tips_for_variant(
     .get_active_release(variant)
     .await?
   {
-    let tips =
-      get_tips_from_version(variant, &active_release, data_dir, os)
-        .await?;
+    let tips = get_tips_from_version(
+      variant,
+      descriptor,
+      &active_release,
+      flavor,
+      data_dir,
+      os,
+    )
+    .await?;
     return Ok(tips);
   }
 
@@ -98,16 +113,20 @@ pub async fn get_all_tips_for_variant(
     releases_repository.get_cached_releases(variant).await?;
   let releases: Vec<GameRelease> = gh_releases
     .iter()
-    .map(|r| gh_release_to_game_release(r, variant))
+    .map(|r| gh_release_to_game_release(r, variant, descriptor))
     .collect();
 
   for release in releases {
-    if release.get_installation_status(os, data_dir).await?
+    if release
+      .get_installation_status(descriptor, os, flavor, data_dir)
+      .await?
       == GameReleaseStatus::ReadyToPlay
     {
       let tips = get_tips_from_version(
         variant,
+        descriptor,
         &release.version,
+        flavor,
         data_dir,
         os,
       )
@@ -139,6 +158,7 @@ mod tests {
   use crate::infra::github::release::GitHubRelease;
   use crate::infra::testing::test_database::TestDatabase;
   use crate::infra::utils::get_os_enum;
+  use crate::variants::variant_descriptor::VariantRegistry;
   use chrono::Utc;
   use tempfile::TempDir;
 
@@ -152,14 +172,23 @@ mod tests {
     os: &OS,
   ) -> TestResult {
     let install_dir = get_or_create_asset_installation_dir(
-      variant, version, data_dir,
+      variant,
+      version,
+      BuildFlavor::default(),
+      data_dir,
     )
     .await?;
     if os == &OS::Linux {
       get_or_create_directory(&install_dir, "cataclysm-dda").await?;
     }
-    let resources_dir =
-      get_game_resources_dir(variant, version, data_dir, os).await?;
+    let resources_dir = get_game_resources_dir(
+      variant,
+      version,
+      BuildFlavor::default(),
+      data_dir,
+      os,
+    )
+    .await?;
     tokio::fs::create_dir_all(&resources_dir).await?;
     Ok(())
   }
@@ -171,10 +200,18 @@ mod tests {
     os: &OS,
   ) -> TestResult {
     setup_game_resources_dir(variant, version, data_dir, os).await?;
-    let exec_dir =
-      get_game_executable_dir(variant, version, data_dir, os).await?;
+    let exec_dir = get_game_executable_dir(
+      variant,
+      version,
+      BuildFlavor::default(),
+      data_dir,
+      os,
+    )
+    .await?;
     tokio::fs::create_dir_all(&exec_dir).await?;
-    let exec_filename = get_game_executable_filenames(variant, os)[0];
+    let descriptor = VariantRegistry::default().get(variant)?;
+    let exec_filename =
+      get_game_executable_filenames(&descriptor, os)[0];
     tokio::fs::write(exec_dir.join(exec_filename), b"dummy exec")
       .await?;
     Ok(())
@@ -187,8 +224,15 @@ mod tests {
     os: &OS,
     tips_json: &str,
   ) -> TestResult {
-    let tip_paths =
-      get_tip_file_paths(variant, version, data_dir, os).await?;
+    let tip_paths = get_tip_file_paths(
+      variant,
+      &*VariantRegistry::default().get(variant)?,
+      version,
+      BuildFlavor::default(),
+      data_dir,
+      os,
+    )
+    .await?;
     let tips_file_path = &tip_paths[0];
     if let Some(parent) = tips_file_path.parent() {
       tokio::fs::create_dir_all(parent).await?;
@@ -213,6 +257,8 @@ mod tests {
     ] {
       let tips = get_all_tips_for_variant(
         &variant,
+        &*VariantRegistry::default().get(&variant)?,
+        BuildFlavor::default(),
         temp_data.path(),
         &OS::Linux,
         &active_repo,
@@ -266,6 +312,8 @@ mod tests {
 
       let tips = get_all_tips_for_variant(
         &variant,
+        &*VariantRegistry::default().get(&variant)?,
+        BuildFlavor::default(),
         temp_data.path(),
         &current_os,
         &active_repo,
@@ -311,6 +359,8 @@ mod tests {
 
       let tips = get_all_tips_for_variant(
         &variant,
+        &*VariantRegistry::default().get(&variant)?,
+        BuildFlavor::default(),
         temp_data.path(),
         &current_os,
         &active_repo,
@@ -378,6 +428,8 @@ mod tests {
 
       let tips = get_all_tips_for_variant(
         &variant,
+        &*VariantRegistry::default().get(&variant)?,
+        BuildFlavor::default(),
         temp_data.path(),
         &current_os,
         &active_repo,
@@ -434,6 +486,8 @@ mod tests {
 
       let tips = get_all_tips_for_variant(
         &variant,
+        &*VariantRegistry::default().get(&variant)?,
+        BuildFlavor::default(),
         temp_data.path(),
         &current_os,
         &active_repo,
@@ -0,0 +1,62 @@
+use tauri::{AppHandle, Manager, State, command};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::constants::ORPHAN_MIN_AGE;
+use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
+use crate::garbage_collection::garbage_collection::{
+  CollectGarbageError, GarbageCollectionReport, collect_garbage,
+};
+use crate::infra::download::Downloader;
+use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+use crate::manual_backups::repository::sqlite_manual_backup_repository::SqliteManualBackupRepository;
+use crate::variants::variant_descriptor::VariantRegistry;
+
+/// Errors that can occur when collecting garbage via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum CollectGarbageCommandError {
+  /// The system's local data or cache directory could not be found.
+  #[error("system directory not found: {0}")]
+  SystemDir(#[from] tauri::Error),
+
+  /// Failed to collect garbage.
+  #[error("failed to collect garbage: {0}")]
+  Collect(#[from] CollectGarbageError),
+}
+
+/// Removes what failed installations and backups left behind, and reports
+/// what was removed.
+#[command]
+pub async fn run_garbage_collection(
+  app_handle: AppHandle,
+  downloader: State<'_, Downloader>,
+  releases_repository: State<'_, SqliteReleasesRepository>,
+  backup_repository: State<'_, SqliteBackupRepository>,
+  manual_backup_repository: State<'_, SqliteManualBackupRepository>,
+  variant_registry: State<'_, VariantRegistry>,
+) -> Result<GarbageCollectionReport, CollectGarbageCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let temp_dir = app_handle.path().app_cache_dir()?;
+  let resource_dir = app_handle.path().resource_dir()?;
+  let queued_downloads = downloader
+    .queue()
+    .list()
+    .into_iter()
+    .map(|download| download.file_name)
+    .collect::<Vec<_>>();
+
+  let report = collect_garbage(
+    &variant_registry.variants(),
+    &data_dir,
+    &temp_dir,
+    &resource_dir,
+    &queued_downloads,
+    ORPHAN_MIN_AGE,
+    &*releases_repository,
+    &*backup_repository,
+    &*manual_backup_repository,
+  )
+  .await?;
+
+  Ok(report)
+}
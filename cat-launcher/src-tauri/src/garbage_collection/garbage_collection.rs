@@ -0,0 +1,668 @@
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use ts_rs::TS;
+
+use crate::backups::backups::{
+  PruneBackupStoreError, prune_backup_store,
+};
+use crate::fetch_releases::repository::ReleasesRepository;
+use crate::fetch_releases::utils::get_default_releases;
+use crate::filesystem::paths::{
+  GetAutomaticBackupArchivePathError, GetAutomaticBackupsDirError,
+  GetManualBackupArchivePathError, GetManualBackupsDirError,
+  get_asset_download_dir,
+  get_or_create_automatic_backup_archive_filepath,
+  get_or_create_automatic_backup_manifest_filepath,
+  get_or_create_automatic_backups_dir,
+  get_or_create_manual_backup_archive_filepath,
+  get_or_create_manual_backups_dir, get_staging_dir,
+};
+use crate::filesystem::utils::get_dir_size;
+use crate::launch_game::repository::{
+  BackupRepository, BackupRepositoryError,
+};
+use crate::manual_backups::repository::manual_backup_repository::{
+  ManualBackupRepository, ManualBackupRepositoryError,
+};
+use crate::variants::GameVariant;
+
+/// The kinds of leftovers that garbage collection removes.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub enum GarbageKind {
+  /// A release archive left behind by a failed installation.
+  DownloadArchive,
+  /// A partial extraction, or an installation it replaced, left behind by
+  /// a failed release installation.
+  StagingDir,
+  /// A temporary directory left behind by a failed mod, tileset or
+  /// soundpack installation.
+  TempInstallDir,
+  /// A backup record whose manifest and archive are missing.
+  BackupRecord,
+  /// A backup archive or manifest that no backup record refers to.
+  BackupArchive,
+  /// Content in the backup store that no automatic backup refers to.
+  BackupBlob,
+}
+
+/// Something that garbage collection removed.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ReclaimedItem {
+  /// What was removed.
+  pub kind: GarbageKind,
+  /// The path of the removed file or directory. For a backup record, the
+  /// path of its missing manifest.
+  pub path: String,
+  /// The disk space that was freed, in bytes.
+  pub size_in_bytes: u64,
+}
+
+/// What garbage collection removed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GarbageCollectionReport {
+  /// The removed items.
+  pub items: Vec<ReclaimedItem>,
+  /// The total disk space that was freed, in bytes.
+  pub reclaimed_bytes: u64,
+}
+
+impl GarbageCollectionReport {
+  fn add(
+    &mut self,
+    kind: GarbageKind,
+    path: &Path,
+    size_in_bytes: u64,
+  ) {
+    self.reclaimed_bytes += size_in_bytes;
+    self.items.push(ReclaimedItem {
+      kind,
+      path: path.to_string_lossy().into_owned(),
+      size_in_bytes,
+    });
+  }
+}
+
+/// Errors that can occur during garbage collection.
+#[derive(thiserror::Error, Debug)]
+pub enum CollectGarbageError {
+  /// Failed to list the contents of a directory.
+  #[error("failed to read directory: {0}")]
+  ReadDir(#[from] io::Error),
+
+  /// Failed to read or delete automatic backup records.
+  #[error("failed to access automatic backups: {0}")]
+  AutomaticBackups(#[from] BackupRepositoryError),
+
+  /// Failed to get the automatic backups directory.
+  #[error("failed to get automatic backups directory: {0}")]
+  AutomaticBackupsDir(#[from] GetAutomaticBackupsDirError),
+
+  /// Failed to get the path of an automatic backup.
+  #[error("failed to get automatic backup path: {0}")]
+  AutomaticBackupPath(#[from] GetAutomaticBackupArchivePathError),
+
+  /// Failed to prune the automatic backup store.
+  #[error("failed to prune backup store: {0}")]
+  BackupStore(#[from] PruneBackupStoreError),
+
+  /// Failed to read or delete manual backup records.
+  #[error("failed to access manual backups: {0}")]
+  ManualBackups(#[from] ManualBackupRepositoryError),
+
+  /// Failed to get the manual backups directory.
+  #[error("failed to get manual backups directory: {0}")]
+  ManualBackupsDir(#[from] GetManualBackupsDirError),
+
+  /// Failed to get the path of a manual backup.
+  #[error("failed to get manual backup path: {0}")]
+  ManualBackupPath(#[from] GetManualBackupArchivePathError),
+}
+
+/// Removes what failed installations and backups left behind: release
+/// archives in the download directories, partial extractions and replaced
+/// installations in the staging directories, `cat-launcher-*-install-dir`
+/// directories in `temp_dir`, backup records without an archive, backup
+/// archives without a record and backup store content no backup refers
+/// to.
+///
+/// Only files named after an asset of a cached or default release count
+/// as release archives, so partial downloads, which can be resumed, and
+/// corrupted download markers are kept.
+///
+/// Anything younger than `min_age` is kept, since it may belong to an
+/// installation or backup in progress. So are the files of the downloads
+/// named in `queued_downloads`, which may be paused. Items that can't be
+/// removed are skipped. Only the data of `variants` is looked at.
+#[allow(clippy::too_many_arguments)]
+pub async fn collect_garbage(
+  variants: &[GameVariant],
+  data_dir: &Path,
+  temp_dir: &Path,
+  resources_dir: &Path,
+  queued_downloads: &[String],
+  min_age: Duration,
+  releases_repository: &impl ReleasesRepository,
+  backup_repository: &impl BackupRepository,
+  manual_backup_repository: &impl ManualBackupRepository,
+) -> Result<GarbageCollectionReport, CollectGarbageError> {
+  let mut report = GarbageCollectionReport::default();
+
+  for variant in variants {
+    let asset_names = get_release_asset_names(
+      variant,
+      resources_dir,
+      releases_repository,
+    )
+    .await;
+    let download_dir = get_asset_download_dir(variant, data_dir);
+    for path in list_entries(&download_dir).await? {
+      let Some(name) = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+      else {
+        continue;
+      };
+      let is_queued = queued_downloads
+        .iter()
+        .any(|d| name.starts_with(d.as_str()));
+      if asset_names.contains(&name)
+        && !is_queued
+        && path.is_file()
+        && is_old(&path, min_age).await
+      {
+        remove(&path, GarbageKind::DownloadArchive, &mut report)
+          .await;
+      }
+    }
+
+    // Installations are moved out of the staging directory once they're
+    // extracted, along with the installations they replaced.
+    let staging_dir = get_staging_dir(variant, data_dir);
+    for path in list_entries(&staging_dir).await? {
+      if is_old(&path, min_age).await {
+        remove(&path, GarbageKind::StagingDir, &mut report).await;
+      }
+    }
+  }
+
+  for dir in list_entries(temp_dir).await? {
+    let is_install_dir = dir
+      .file_name()
+      .map(|name| name.to_string_lossy())
+      .is_some_and(|name| {
+        name.starts_with("cat-launcher-")
+          && name.ends_with("-install-dir")
+      });
+    if !is_install_dir || !dir.is_dir() {
+      continue;
+    }
+
+    for path in list_entries(&dir).await? {
+      if is_old(&path, min_age).await {
+        remove(&path, GarbageKind::TempInstallDir, &mut report).await;
+      }
+    }
+  }
+
+  collect_automatic_backups(
+    variants,
+    data_dir,
+    min_age,
+    backup_repository,
+    &mut report,
+  )
+  .await?;
+  collect_manual_backups(
+    variants,
+    data_dir,
+    min_age,
+    manual_backup_repository,
+    &mut report,
+  )
+  .await?;
+
+  Ok(report)
+}
+
+async fn collect_automatic_backups(
+  variants: &[GameVariant],
+  data_dir: &Path,
+  min_age: Duration,
+  backup_repository: &impl BackupRepository,
+  report: &mut GarbageCollectionReport,
+) -> Result<(), CollectGarbageError> {
+  let mut archives = HashSet::new();
+  for variant in variants {
+    for backup in backup_repository
+      .get_backups_sorted_by_timestamp(variant)
+      .await?
+    {
+      let manifest_path =
+        get_or_create_automatic_backup_manifest_filepath(
+          variant,
+          backup.id,
+          &backup.release_version,
+          backup.timestamp,
+          data_dir,
+        )
+        .await?;
+      let path = get_or_create_automatic_backup_archive_filepath(
+        variant,
+        backup.id,
+        &backup.release_version,
+        backup.timestamp,
+        data_dir,
+      )
+      .await?;
+
+      // The record is added before its manifest is written. Backups made
+      // before the backup store existed have an archive instead.
+      if !fs::try_exists(&manifest_path).await?
+        && !fs::try_exists(&path).await?
+        && is_old_timestamp(backup.timestamp, min_age)
+      {
+        backup_repository.delete_backup_entry(backup.id).await?;
+        report.add(GarbageKind::BackupRecord, &manifest_path, 0);
+      }
+      archives.insert(manifest_path);
+      archives.insert(path);
+    }
+  }
+
+  let backups_dir =
+    get_or_create_automatic_backups_dir(data_dir).await?;
+  for path in list_entries(&backups_dir).await? {
+    if !archives.contains(&path) && is_old(&path, min_age).await {
+      remove(&path, GarbageKind::BackupArchive, report).await;
+    }
+  }
+
+  for variant in variants {
+    for blob in prune_backup_store(
+      variant,
+      data_dir,
+      backup_repository,
+      min_age,
+    )
+    .await?
+    {
+      report.add(
+        GarbageKind::BackupBlob,
+        &blob.path,
+        blob.size_in_bytes,
+      );
+    }
+  }
+
+  Ok(())
+}
+
+async fn collect_manual_backups(
+  variants: &[GameVariant],
+  data_dir: &Path,
+  min_age: Duration,
+  manual_backup_repository: &impl ManualBackupRepository,
+  report: &mut GarbageCollectionReport,
+) -> Result<(), CollectGarbageError> {
+  let mut archives = HashSet::new();
+  for variant in variants {
+    for backup in manual_backup_repository
+      .get_manual_backups_sorted_by_timestamp(variant)
+      .await?
+    {
+      let path = get_or_create_manual_backup_archive_filepath(
+        backup.id,
+        &backup.name,
+        data_dir,
+      )
+      .await?;
+
+      // The record is added before its archive is created.
+      if !fs::try_exists(&path).await?
+        && is_old_timestamp(backup.timestamp, min_age)
+      {
+        manual_backup_repository
+          .delete_manual_backup_entry(backup.id)
+          .await?;
+        report.add(GarbageKind::BackupRecord, &path, 0);
+      }
+      archives.insert(path);
+    }
+  }
+
+  let backups_dir =
+    get_or_create_manual_backups_dir(data_dir).await?;
+  for path in list_entries(&backups_dir).await? {
+    if !archives.contains(&path) && is_old(&path, min_age).await {
+      remove(&path, GarbageKind::BackupArchive, report).await;
+    }
+  }
+
+  Ok(())
+}
+
+/// Returns the names of the assets of the cached and default releases of
+/// `variant`. If the cache can't be read, fewer archives are recognised,
+/// and so kept.
+async fn get_release_asset_names(
+  variant: &GameVariant,
+  resources_dir: &Path,
+  releases_repository: &impl ReleasesRepository,
+) -> HashSet<String> {
+  let cached_releases = releases_repository
+    .get_cached_releases(variant)
+    .await
+    .unwrap_or_default();
+  let default_releases =
+    get_default_releases(variant, resources_dir).await;
+
+  cached_releases
+    .iter()
+    .chain(&default_releases)
+    .flat_map(|release| &release.assets)
+    .map(|asset| asset.name.clone())
+    .collect()
+}
+
+/// Removes the file or directory at `path` and adds it to `report`.
+async fn remove(
+  path: &Path,
+  kind: GarbageKind,
+  report: &mut GarbageCollectionReport,
+) {
+  let size = get_dir_size(path).await.unwrap_or_default();
+  let removed = if path.is_dir() {
+    fs::remove_dir_all(path).await
+  } else {
+    fs::remove_file(path).await
+  };
+  if removed.is_ok() {
+    report.add(kind, path, size);
+  }
+}
+
+/// Returns whether `path` was last modified at least `min_age` ago.
+async fn is_old(path: &Path, min_age: Duration) -> bool {
+  fs::symlink_metadata(path)
+    .await
+    .and_then(|metadata| metadata.modified())
+    .ok()
+    // Something modified in the future was modified just now.
+    .map(|modified| modified.elapsed().unwrap_or_default())
+    .is_some_and(|age| age >= min_age)
+}
+
+/// Returns whether the Unix `timestamp`, in seconds, is at least
+/// `min_age` ago.
+fn is_old_timestamp(timestamp: u64, min_age: Duration) -> bool {
+  UNIX_EPOCH
+    .checked_add(Duration::from_secs(timestamp))
+    .map(|time| {
+      SystemTime::now().duration_since(time).unwrap_or_default()
+    })
+    .is_some_and(|age| age >= min_age)
+}
+
+/// Returns the paths of the entries in `dir`. A directory that doesn't
+/// exist is empty.
+async fn list_entries(dir: &Path) -> io::Result<Vec<PathBuf>> {
+  let mut entries = match fs::read_dir(dir).await {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+      return Ok(Vec::new());
+    }
+    Err(e) => return Err(e),
+  };
+
+  let mut paths = Vec::new();
+  while let Some(entry) = entries.next_entry().await? {
+    paths.push(entry.path());
+  }
+
+  Ok(paths)
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use chrono::Utc;
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
+  use crate::infra::github::asset::GitHubAsset;
+  use crate::infra::github::release::GitHubRelease;
+  use crate::infra::testing::test_database::TestDatabase;
+  use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+  use crate::manual_backups::repository::sqlite_manual_backup_repository::SqliteManualBackupRepository;
+  use crate::variants::variant_descriptor::VariantRegistry;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  /// Caches a release of `variant` with the given assets.
+  async fn cache_release(
+    releases_repo: &SqliteReleasesRepository,
+    variant: &GameVariant,
+    asset_names: &[&str],
+  ) -> TestResult {
+    let release = GitHubRelease {
+      id: 1,
+      tag_name: "v2".to_string(),
+      prerelease: false,
+      body: None,
+      assets: asset_names
+        .iter()
+        .zip(1..)
+        .map(|(name, id)| GitHubAsset {
+          id,
+          browser_download_url: format!("https://example.com/{name}"),
+          name: name.to_string(),
+          digest: None,
+        })
+        .collect(),
+      created_at: Utc::now(),
+    };
+    releases_repo
+      .update_cached_releases(variant, &[release])
+      .await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_collect_garbage_removes_orphans_only() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let backup_repo = SqliteBackupRepository::new(db.pool().clone());
+    let manual_backup_repo =
+      SqliteManualBackupRepository::new(db.pool().clone());
+    let releases_repo =
+      SqliteReleasesRepository::new(db.pool().clone());
+    let temp_data = TempDir::new()?;
+    let temp_cache = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+    let data_dir = temp_data.path();
+    let variant = GameVariant::DarkDaysAhead;
+    cache_release(
+      &releases_repo,
+      &variant,
+      &["v2.zip", "v3.zip", "v4.zip"],
+    )
+    .await?;
+
+    let download_dir = get_asset_download_dir(&variant, data_dir);
+    let installation = download_dir.join("v1");
+    fs::create_dir_all(&installation).await?;
+    let orphaned_archive = download_dir.join("v2.zip");
+    fs::write(&orphaned_archive, [0u8; 10]).await?;
+    let paused_download = download_dir.join("v3.zip");
+    fs::write(&paused_download, [0u8; 5]).await?;
+    let interrupted_download = download_dir.join("v4.zip.part");
+    fs::write(&interrupted_download, [0u8; 5]).await?;
+    let validator = download_dir.join("v4.zip.part.validator");
+    fs::write(&validator, b"etag").await?;
+    let corrupted_marker = download_dir.join("v5.corrupted");
+    fs::write(&corrupted_marker, b"v5.zip").await?;
+    let unknown_file = download_dir.join("notes.txt");
+    fs::write(&unknown_file, b"notes").await?;
+
+    let staging_dir = get_staging_dir(&variant, data_dir);
+    let partial_extraction = staging_dir.join("v6");
+    fs::create_dir_all(&partial_extraction).await?;
+    fs::write(partial_extraction.join("game"), [0u8; 6]).await?;
+    let replaced_installation = staging_dir.join("v1.replaced");
+    fs::create_dir_all(&replaced_installation).await?;
+    fs::write(replaced_installation.join("game"), [0u8; 8]).await?;
+
+    let mod_temp_dir = temp_cache
+      .path()
+      .join("cat-launcher-mod-install-dir")
+      .join("some-mod");
+    fs::create_dir_all(&mod_temp_dir).await?;
+    fs::write(mod_temp_dir.join("mod.zip"), [0u8; 7]).await?;
+    let unrelated_dir = temp_cache.path().join("other");
+    fs::create_dir_all(&unrelated_dir).await?;
+
+    let kept_id =
+      backup_repo.add_backup_entry(&variant, "v1", 1000).await?;
+    let kept_archive =
+      get_or_create_automatic_backup_archive_filepath(
+        &variant, kept_id, "v1", 1000, data_dir,
+      )
+      .await?;
+    fs::write(&kept_archive, [0u8; 3]).await?;
+    let stale_id =
+      backup_repo.add_backup_entry(&variant, "v1", 2000).await?;
+    let orphaned_backup =
+      get_or_create_automatic_backups_dir(data_dir)
+        .await?
+        .join("999_orphan.zip");
+    fs::write(&orphaned_backup, [0u8; 4]).await?;
+
+    let report = collect_garbage(
+      &VariantRegistry::default().variants(),
+      data_dir,
+      temp_cache.path(),
+      temp_res.path(),
+      &["v3.zip".to_string()],
+      Duration::ZERO,
+      &releases_repo,
+      &backup_repo,
+      &manual_backup_repo,
+    )
+    .await?;
+
+    assert!(installation.exists());
+    assert!(!orphaned_archive.exists());
+    assert!(paused_download.exists());
+    assert!(interrupted_download.exists());
+    assert!(validator.exists());
+    assert!(corrupted_marker.exists());
+    assert!(unknown_file.exists());
+    assert!(!partial_extraction.exists());
+    assert!(!replaced_installation.exists());
+    assert!(!mod_temp_dir.exists());
+    assert!(unrelated_dir.exists());
+    assert!(kept_archive.exists());
+    assert!(!orphaned_backup.exists());
+
+    let backups = backup_repo
+      .get_backups_sorted_by_timestamp(&variant)
+      .await?;
+    assert_eq!(backups.len(), 1);
+    assert_eq!(backups[0].id, kept_id);
+    assert_ne!(backups[0].id, stale_id);
+
+    let mut kinds: Vec<_> = report
+      .items
+      .iter()
+      .map(|item| (item.kind, item.size_in_bytes))
+      .collect();
+    kinds.sort_by_key(|(kind, size)| (*kind as u8, *size));
+    assert_eq!(
+      kinds,
+      vec![
+        (GarbageKind::DownloadArchive, 10),
+        (GarbageKind::StagingDir, 6),
+        (GarbageKind::StagingDir, 8),
+        (GarbageKind::TempInstallDir, 7),
+        (GarbageKind::BackupRecord, 0),
+        (GarbageKind::BackupArchive, 4),
+      ]
+    );
+    assert_eq!(report.reclaimed_bytes, 35);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_collect_garbage_keeps_recent_items() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let backup_repo = SqliteBackupRepository::new(db.pool().clone());
+    let manual_backup_repo =
+      SqliteManualBackupRepository::new(db.pool().clone());
+    let releases_repo =
+      SqliteReleasesRepository::new(db.pool().clone());
+    let temp_data = TempDir::new()?;
+    let temp_cache = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+    let data_dir = temp_data.path();
+    cache_release(
+      &releases_repo,
+      &GameVariant::DarkDaysAhead,
+      &["v2.zip"],
+    )
+    .await?;
+
+    let archive =
+      get_asset_download_dir(&GameVariant::DarkDaysAhead, data_dir)
+        .join("v2.zip");
+    fs::create_dir_all(archive.parent().unwrap()).await?;
+    fs::write(&archive, [0u8; 10]).await?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    backup_repo
+      .add_backup_entry(&GameVariant::DarkDaysAhead, "v1", now)
+      .await?;
+
+    let report = collect_garbage(
+      &VariantRegistry::default().variants(),
+      data_dir,
+      temp_cache.path(),
+      temp_res.path(),
+      &[],
+      Duration::from_secs(60 * 60),
+      &releases_repo,
+      &backup_repo,
+      &manual_backup_repo,
+    )
+    .await?;
+
+    assert!(report.items.is_empty());
+    assert!(archive.exists());
+    assert_eq!(
+      backup_repo
+        .get_backups_sorted_by_timestamp(&GameVariant::DarkDaysAhead)
+        .await?
+        .len(),
+      1
+    );
+
+    Ok(())
+  }
+}
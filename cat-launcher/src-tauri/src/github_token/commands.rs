@@ -0,0 +1,73 @@
+use tauri::{State, command};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::github_token::github_token::{
+  ClearGitHubTokenError, SetGitHubTokenError,
+  ValidateGitHubTokenError, delete_github_token, save_github_token,
+  validate_saved_github_token,
+};
+use crate::github_token::repository::sqlite_github_token_repository::SqliteGitHubTokenRepository;
+use crate::infra::github::rate_limit::GitHubRateLimit;
+use crate::infra::http_client::ReqwestHttpClient;
+
+/// Errors that can occur when setting the GitHub token via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum SetGitHubTokenCommandError {
+  /// Failed to set the GitHub token.
+  #[error("failed to set GitHub token: {0}")]
+  Set(#[from] SetGitHubTokenError),
+}
+
+/// Validates and stores a GitHub token, and uses it for subsequent GitHub API requests.
+#[command]
+pub async fn set_github_token(
+  token: String,
+  client: State<'_, ReqwestHttpClient>,
+  repository: State<'_, SqliteGitHubTokenRepository>,
+) -> Result<GitHubRateLimit, SetGitHubTokenCommandError> {
+  let (token, rate_limit) =
+    save_github_token(&token, &*client, &*repository).await?;
+
+  client.set_github_token(Some(token));
+
+  Ok(rate_limit)
+}
+
+/// Errors that can occur when validating the GitHub token via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum ValidateGitHubTokenCommandError {
+  /// Failed to validate the GitHub token.
+  #[error("failed to validate GitHub token: {0}")]
+  Validate(#[from] ValidateGitHubTokenError),
+}
+
+/// Validates the stored GitHub token and returns its rate limit.
+#[command]
+pub async fn validate_github_token(
+  client: State<'_, ReqwestHttpClient>,
+  repository: State<'_, SqliteGitHubTokenRepository>,
+) -> Result<GitHubRateLimit, ValidateGitHubTokenCommandError> {
+  Ok(validate_saved_github_token(&*client, &*repository).await?)
+}
+
+/// Errors that can occur when clearing the GitHub token via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum ClearGitHubTokenCommandError {
+  /// Failed to clear the GitHub token.
+  #[error("failed to clear GitHub token: {0}")]
+  Clear(#[from] ClearGitHubTokenError),
+}
+
+/// Removes the stored GitHub token. Subsequent GitHub API requests are unauthenticated.
+#[command]
+pub async fn clear_github_token(
+  client: State<'_, ReqwestHttpClient>,
+  repository: State<'_, SqliteGitHubTokenRepository>,
+) -> Result<(), ClearGitHubTokenCommandError> {
+  delete_github_token(&*repository).await?;
+
+  client.set_github_token(None);
+
+  Ok(())
+}
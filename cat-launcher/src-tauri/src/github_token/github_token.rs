@@ -0,0 +1,154 @@
+use crate::github_token::repository::{
+  GitHubTokenRepository, GitHubTokenRepositoryError,
+};
+use crate::infra::github::rate_limit::{
+  FetchRateLimitError, GitHubRateLimit, fetch_rate_limit,
+};
+use crate::infra::github::types::GitHubToken;
+use crate::infra::http_client::HttpClient;
+
+/// Errors that can occur when setting the GitHub token.
+#[derive(thiserror::Error, Debug)]
+pub enum SetGitHubTokenError {
+  /// The token is empty.
+  #[error("GitHub token is empty")]
+  Empty,
+
+  /// The token could not be validated against the GitHub API.
+  #[error("failed to validate GitHub token: {0}")]
+  Validate(#[from] FetchRateLimitError),
+
+  /// An error occurred in the GitHub token repository.
+  #[error("failed to store GitHub token: {0}")]
+  Repository(#[from] GitHubTokenRepositoryError),
+}
+
+/// Validates the given token and stores it.
+///
+/// The token is only stored if GitHub accepts it. Returns the rate limit
+/// that applies to the token.
+pub async fn save_github_token(
+  token: &str,
+  client: &impl HttpClient,
+  repository: &impl GitHubTokenRepository,
+) -> Result<(GitHubToken, GitHubRateLimit), SetGitHubTokenError> {
+  let token = token.trim();
+  if token.is_empty() {
+    return Err(SetGitHubTokenError::Empty);
+  }
+
+  let token = GitHubToken::new(token);
+  let rate_limit = fetch_rate_limit(client, Some(&token)).await?;
+  repository.set_token(&token).await?;
+
+  Ok((token, rate_limit))
+}
+
+/// Errors that can occur when validating the stored GitHub token.
+#[derive(thiserror::Error, Debug)]
+pub enum ValidateGitHubTokenError {
+  /// No token has been stored.
+  #[error("no GitHub token is set")]
+  NotSet,
+
+  /// The token could not be validated against the GitHub API.
+  #[error("failed to validate GitHub token: {0}")]
+  Validate(#[from] FetchRateLimitError),
+
+  /// An error occurred in the GitHub token repository.
+  #[error("failed to load GitHub token: {0}")]
+  Repository(#[from] GitHubTokenRepositoryError),
+}
+
+/// Validates the stored token and returns the rate limit that applies to it.
+pub async fn validate_saved_github_token(
+  client: &impl HttpClient,
+  repository: &impl GitHubTokenRepository,
+) -> Result<GitHubRateLimit, ValidateGitHubTokenError> {
+  let token = repository
+    .get_token()
+    .await?
+    .ok_or(ValidateGitHubTokenError::NotSet)?;
+
+  Ok(fetch_rate_limit(client, Some(&token)).await?)
+}
+
+/// Errors that can occur when clearing the GitHub token.
+#[derive(thiserror::Error, Debug)]
+pub enum ClearGitHubTokenError {
+  /// An error occurred in the GitHub token repository.
+  #[error("failed to clear GitHub token: {0}")]
+  Repository(#[from] GitHubTokenRepositoryError),
+}
+
+/// Removes the stored token.
+pub async fn delete_github_token(
+  repository: &impl GitHubTokenRepository,
+) -> Result<(), ClearGitHubTokenError> {
+  repository.clear_token().await?;
+  Ok(())
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use std::collections::HashMap;
+
+  use super::*;
+  use crate::github_token::repository::sqlite_github_token_repository::SqliteGitHubTokenRepository;
+  use crate::infra::testing::http_client::TestHttpClient;
+  use crate::infra::testing::test_database::TestDatabase;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_save_github_token_rejects_empty_token() -> TestResult
+  {
+    let db = TestDatabase::builder().build()?;
+    let repo = SqliteGitHubTokenRepository::new(db.pool().clone());
+    let client = TestHttpClient::new(HashMap::new())?;
+
+    let result = save_github_token("  ", &client, &repo).await;
+
+    assert!(matches!(result, Err(SetGitHubTokenError::Empty)));
+    assert_eq!(client.request_count(), 0);
+    assert_eq!(repo.get_token().await?, None);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_validate_saved_github_token_without_token()
+  -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo = SqliteGitHubTokenRepository::new(db.pool().clone());
+    let client = TestHttpClient::new(HashMap::new())?;
+
+    let result = validate_saved_github_token(&client, &repo).await;
+
+    assert!(matches!(result, Err(ValidateGitHubTokenError::NotSet)));
+    assert_eq!(client.request_count(), 0);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_delete_github_token() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo = SqliteGitHubTokenRepository::new(db.pool().clone());
+
+    repo.set_token(&GitHubToken::new("token")).await?;
+    delete_github_token(&repo).await?;
+
+    assert_eq!(repo.get_token().await?, None);
+
+    Ok(())
+  }
+}
@@ -0,0 +1,4 @@
+pub mod commands;
+#[allow(clippy::module_inception)]
+pub mod github_token;
+pub mod repository;
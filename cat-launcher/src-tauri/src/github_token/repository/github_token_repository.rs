@@ -0,0 +1,41 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use crate::infra::github::types::GitHubToken;
+
+/// Errors that can occur when interacting with the GitHub token repository.
+#[derive(thiserror::Error, Debug)]
+pub enum GitHubTokenRepositoryError {
+  /// An error occurred while retrieving the token.
+  #[error("failed to get GitHub token: {0}")]
+  Get(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while storing the token.
+  #[error("failed to set GitHub token: {0}")]
+  Set(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while removing the token.
+  #[error("failed to clear GitHub token: {0}")]
+  Clear(Box<dyn Error + Send + Sync>),
+}
+
+/// A repository for the GitHub token used to authenticate API requests.
+#[async_trait]
+pub trait GitHubTokenRepository: Send + Sync {
+  /// Retrieves the stored token, if any.
+  async fn get_token(
+    &self,
+  ) -> Result<Option<GitHubToken>, GitHubTokenRepositoryError>;
+
+  /// Stores the token, replacing any previous one.
+  async fn set_token(
+    &self,
+    token: &GitHubToken,
+  ) -> Result<(), GitHubTokenRepositoryError>;
+
+  /// Removes the stored token.
+  async fn clear_token(
+    &self,
+  ) -> Result<(), GitHubTokenRepositoryError>;
+}
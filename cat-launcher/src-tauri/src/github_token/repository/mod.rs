@@ -0,0 +1,6 @@
+pub mod github_token_repository;
+pub mod sqlite_github_token_repository;
+
+pub use github_token_repository::{
+  GitHubTokenRepository, GitHubTokenRepositoryError,
+};
@@ -0,0 +1,114 @@
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::OptionalExtension;
+
+use crate::github_token::repository::{
+  GitHubTokenRepository, GitHubTokenRepositoryError,
+};
+use crate::infra::github::types::GitHubToken;
+use crate::infra::repository::db_helper::run_db;
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+/// A SQLite-backed implementation of the `GitHubTokenRepository`.
+#[derive(Clone)]
+pub struct SqliteGitHubTokenRepository {
+  pool: Pool,
+}
+
+impl SqliteGitHubTokenRepository {
+  /// Creates a new `SqliteGitHubTokenRepository` with the given connection pool.
+  pub fn new(pool: Pool) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl GitHubTokenRepository for SqliteGitHubTokenRepository {
+  async fn get_token(
+    &self,
+  ) -> Result<Option<GitHubToken>, GitHubTokenRepositoryError> {
+    let pool = self.pool.clone();
+
+    let token: Option<String> = run_db(pool, move |conn| {
+      conn
+        .query_row(
+          "SELECT token FROM github_token WHERE _id = 1",
+          [],
+          |row| row.get(0),
+        )
+        .optional()
+    })
+    .await
+    .map_err(GitHubTokenRepositoryError::Get)?;
+
+    Ok(token.map(GitHubToken::new))
+  }
+
+  async fn set_token(
+    &self,
+    token: &GitHubToken,
+  ) -> Result<(), GitHubTokenRepositoryError> {
+    let pool = self.pool.clone();
+    let token = token.expose().to_string();
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO github_token (_id, token) VALUES (1, ?1)",
+        [&token],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(GitHubTokenRepositoryError::Set)
+  }
+
+  async fn clear_token(
+    &self,
+  ) -> Result<(), GitHubTokenRepositoryError> {
+    let pool = self.pool.clone();
+
+    run_db(pool, move |conn| {
+      conn.execute("DELETE FROM github_token", [])?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(GitHubTokenRepositoryError::Clear)
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::infra::testing::test_database::TestDatabase;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_set_get_and_clear_token() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo = SqliteGitHubTokenRepository::new(db.pool().clone());
+
+    assert_eq!(repo.get_token().await?, None);
+
+    repo.set_token(&GitHubToken::new("first")).await?;
+    repo.set_token(&GitHubToken::new("second")).await?;
+    assert_eq!(
+      repo.get_token().await?,
+      Some(GitHubToken::new("second"))
+    );
+
+    repo.clear_token().await?;
+    assert_eq!(repo.get_token().await?, None);
+
+    Ok(())
+  }
+}
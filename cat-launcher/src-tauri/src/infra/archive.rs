@@ -1,19 +1,33 @@
 use std::fs::{File, read_dir};
-use std::io;
-use std::path::{Path, PathBuf};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 
+use bzip2::read::BzDecoder;
 use dmg;
+use downloader::progress::{Phase, Reporter};
 use flate2::read::GzDecoder;
+use sevenz_rust::{Password, SevenZArchiveEntry, SevenZReader};
 use tar::Archive;
 use tokio::fs;
 use tokio::task::JoinError;
+use tokio_util::sync::CancellationToken;
 use unrar::error::UnrarError;
+use xz2::read::XzDecoder;
 use zip::CompressionMethod::Deflated;
 use zip::ZipWriter;
 use zip::result::ZipError;
 use zip::write::FileOptions;
 
-use crate::filesystem::utils::{CopyDirError, copy_dir_all};
+use crate::constants::{
+  MAX_UNTRUSTED_ARCHIVE_ENTRIES, MAX_UNTRUSTED_ARCHIVE_SIZE,
+};
+use crate::filesystem::disk_space::{
+  InsufficientSpace, ensure_available_space,
+};
+use crate::filesystem::utils::{
+  CopyDirError, copy_dir_all, get_dir_size,
+};
 use crate::infra::utils::OS;
 
 /// Represents errors that can occur during archive extraction.
@@ -31,77 +45,332 @@ pub enum ExtractionError {
   #[error("rar extraction failed: {0}")]
   Rar(#[from] UnrarError),
 
+  #[error("7z extraction failed: {0}")]
+  SevenZip(#[from] sevenz_rust::Error),
+
   #[error("failed to copy from DMG: {0}")]
   Copy(#[from] CopyDirError),
 
   #[error("unexpected join error: {0}")]
   Join(#[from] JoinError),
+
+  #[error("extraction was cancelled")]
+  Cancelled,
+
+  #[error(transparent)]
+  InsufficientSpace(#[from] InsufficientSpace),
+
+  #[error("archive entry is outside the target directory: {0}")]
+  PathEscape(String),
+
+  #[error("archive entry is a link, which is not allowed: {0}")]
+  LinkNotAllowed(String),
+
+  #[error("archive entry links outside the target directory: {0}")]
+  LinkEscape(String),
+
+  #[error("archive has more than {limit} entries")]
+  TooManyEntries { limit: u64 },
+
+  #[error("archive content is larger than {limit} bytes")]
+  TooLarge { limit: u64 },
+}
+
+/// What an archive may contain to be extracted.
+///
+/// Regardless of the policy, entries are never extracted outside the
+/// target directory, and links never point outside of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractionPolicy {
+  /// Whether symbolic and hard links are extracted.
+  pub allow_links: bool,
+  /// The maximum number of entries, including directories.
+  pub max_entries: Option<u64>,
+  /// The maximum total size of the extracted files, in bytes.
+  pub max_size: Option<u64>,
+}
+
+impl ExtractionPolicy {
+  /// For archives of the game itself and backups made by the launcher.
+  pub const TRUSTED: Self = Self {
+    allow_links: true,
+    max_entries: None,
+    max_size: None,
+  };
+
+  /// For third-party content, which may come from anywhere. Links are
+  /// refused, and the limits protect against zip bombs.
+  pub const UNTRUSTED: Self = Self {
+    allow_links: false,
+    max_entries: Some(MAX_UNTRUSTED_ARCHIVE_ENTRIES),
+    max_size: Some(MAX_UNTRUSTED_ARCHIVE_SIZE),
+  };
+
+  fn check_entries(
+    &self,
+    entries: u64,
+  ) -> Result<(), ExtractionError> {
+    match self.max_entries {
+      Some(limit) if entries > limit => {
+        Err(ExtractionError::TooManyEntries { limit })
+      }
+      _ => Ok(()),
+    }
+  }
+
+  fn check_size(&self, size: u64) -> Result<(), ExtractionError> {
+    match self.max_size {
+      Some(limit) if size > limit => {
+        Err(ExtractionError::TooLarge { limit })
+      }
+      _ => Ok(()),
+    }
+  }
+
+  fn check_link(&self, name: &Path) -> Result<(), ExtractionError> {
+    if self.allow_links {
+      Ok(())
+    } else {
+      Err(ExtractionError::LinkNotAllowed(name.display().to_string()))
+    }
+  }
+}
+
+/// The message that extraction progress is set up with.
+pub const EXTRACTION_MESSAGE: &str = "Extracting";
+
+/// Reports how far an extraction has got: the bytes extracted as progress
+/// and the entries extracted as message.
+struct ExtractionProgress {
+  reporter: Arc<dyn Reporter + Send + Sync>,
+  total_entries: Option<u64>,
+  entries: u64,
+}
+
+impl ExtractionProgress {
+  fn new(
+    reporter: Arc<dyn Reporter + Send + Sync>,
+    total_entries: Option<u64>,
+    total_bytes: Option<u64>,
+  ) -> Self {
+    reporter.setup_phase(
+      Phase::Extract,
+      total_bytes,
+      EXTRACTION_MESSAGE,
+    );
+    Self {
+      reporter,
+      total_entries,
+      entries: 0,
+    }
+  }
+
+  /// Reports that another entry was extracted, bringing the bytes
+  /// extracted so far to `bytes`.
+  fn entry_extracted(&mut self, bytes: u64) {
+    self.entries += 1;
+    let message = match self.total_entries {
+      Some(total) => format!("{} of {total} entries", self.entries),
+      None => format!("{} entries", self.entries),
+    };
+    self.reporter.set_message(&message);
+    self.reporter.progress(bytes);
+  }
+}
+
+/// The formats of archives that can be extracted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveFormat {
+  Zip,
+  Tar(Compression),
+  SevenZip,
+  Rar,
+  Dmg,
+}
+
+/// How the tarball inside a compressed tar archive is compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+  Gzip,
+  Xz,
+  Bzip2,
+  Zstd,
 }
 
 /// Extracts an archive at the given `archive_path` to the `target_dir` for the specified `os`.
-/// Supports zip, tar.gz, dmg, and rar formats.
+/// Supports zip, tar.gz, tar.xz, tar.bz2, tar.zst, 7z, dmg, and rar
+/// formats. The format is told from the first bytes of the archive, not
+/// its name, except for DMG images, which need a `.dmg` extension.
+///
+/// Entries are checked against `policy` as they are extracted, so an
+/// archive that violates it may be partially extracted. Since the content
+/// of a DMG image can't be checked that way, only trusted ones are
+/// extracted.
+///
+/// Nothing is extracted unless the extracted files, as far as their size
+/// can be told from the archive, fit into `target_dir`.
+///
+/// Progress is reported to `progress`, set up for the extraction phase
+/// with [`EXTRACTION_MESSAGE`].
+///
+/// Cancelling `cancel` stops the extraction before the next entry, leaving
+/// what was extracted so far for the caller to clean up.
 pub async fn extract_archive(
   archive_path: &Path,
   target_dir: &Path,
   os: &OS,
+  policy: ExtractionPolicy,
+  progress: Arc<dyn Reporter + Send + Sync>,
+  cancel: &CancellationToken,
 ) -> Result<(), ExtractionError> {
   let archive_path = archive_path.to_owned();
   let target_dir = target_dir.to_owned();
+  let cancel = cancel.clone();
 
   if fs::metadata(&target_dir).await.is_err() {
     fs::create_dir_all(&target_dir).await?;
   }
 
-  let extension = archive_path.extension().and_then(|s| s.to_str());
+  let format = {
+    let archive_path = archive_path.clone();
+    tokio::task::spawn_blocking(move || {
+      detect_archive_format(&archive_path)
+    })
+    .await??
+  };
 
-  let file_stem_extension = archive_path
-    .file_stem()
-    .and_then(|s| Path::new(s).extension())
-    .and_then(|s| s.to_str());
+  match format {
+    Some(ArchiveFormat::Zip) => {
+      tokio::task::spawn_blocking(move || {
+        extract_zip(
+          &archive_path,
+          &target_dir,
+          policy,
+          progress,
+          &cancel,
+        )
+      })
+      .await?
+    }
 
-  match extension {
-    Some("zip") => {
+    Some(ArchiveFormat::Tar(compression)) => {
       tokio::task::spawn_blocking(move || {
-        let file = File::open(&archive_path)?;
-        let mut archive = zip::ZipArchive::new(file)?;
-        archive.extract(&target_dir)?;
-        Ok::<(), ExtractionError>(())
+        let mut file = File::open(&archive_path)?;
+        // Only gzip records the uncompressed size. For the others, at
+        // least make sure that the archive itself would fit.
+        let required = match compression {
+          Compression::Gzip => get_gzip_uncompressed_size(&mut file)?,
+          _ => file.metadata()?.len(),
+        };
+        ensure_available_space(&target_dir, required)?;
+
+        let reader: Box<dyn Read> = match compression {
+          Compression::Gzip => Box::new(GzDecoder::new(file)),
+          Compression::Xz => Box::new(XzDecoder::new(file)),
+          Compression::Bzip2 => Box::new(BzDecoder::new(file)),
+          Compression::Zstd => Box::new(zstd::Decoder::new(file)?),
+        };
+        extract_tar(reader, &target_dir, policy, progress, &cancel)
       })
       .await?
     }
 
-    Some("gz") => match file_stem_extension {
-      Some("tar") => {
-        tokio::task::spawn_blocking(move || {
-          let file = File::open(&archive_path)?;
-          let tar = GzDecoder::new(file);
-          let mut archive = Archive::new(tar);
-          archive.unpack(&target_dir)?;
-          Ok::<(), ExtractionError>(())
-        })
-        .await?
-      }
-      _ => Err(ExtractionError::UnsupportedArchive),
-    },
+    Some(ArchiveFormat::SevenZip) => {
+      tokio::task::spawn_blocking(move || {
+        extract_7z(
+          &archive_path,
+          &target_dir,
+          policy,
+          progress,
+          &cancel,
+        )
+      })
+      .await?
+    }
 
-    Some("dmg") => {
+    Some(ArchiveFormat::Dmg)
+      if policy == ExtractionPolicy::TRUSTED =>
+    {
       let handle = tokio::task::spawn_blocking(move || {
         let info = dmg::Attach::new(&archive_path).with()?;
         Ok::<_, ExtractionError>(info)
       })
       .await??;
 
+      // Copying out of the mounted image can't be interrupted, so only
+      // check before starting it.
+      if cancel.is_cancelled() {
+        return Err(ExtractionError::Cancelled);
+      }
+
+      // If the size can't be told, let the copy itself fail.
+      let required =
+        get_dir_size(&handle.mount_point).await.unwrap_or_default();
+      ensure_available_space(&target_dir, required)?;
+
+      progress.setup_phase(
+        Phase::Extract,
+        Some(required),
+        EXTRACTION_MESSAGE,
+      );
       copy_dir_all(&handle.mount_point, &target_dir, os).await?;
+      progress.progress(required);
       Ok(())
     }
 
-    Some("rar") => {
+    Some(ArchiveFormat::Rar) => {
       tokio::task::spawn_blocking(move || {
+        // unrar writes the entries itself, so check all of them before
+        // extracting any.
+        let mut entries = 0u64;
+        let mut required = 0u64;
+        for header in
+          unrar::Archive::new(&archive_path).open_for_listing()?
+        {
+          let header = header?;
+          get_contained_path(&header.filename)?;
+          if is_rar_link(header.file_attr) {
+            policy.check_link(&header.filename)?;
+          }
+
+          entries += 1;
+          policy.check_entries(entries)?;
+          required = required.saturating_add(header.unpacked_size);
+          policy.check_size(required)?;
+        }
+        ensure_available_space(&target_dir, required)?;
+
+        let mut progress = ExtractionProgress::new(
+          progress,
+          Some(entries),
+          Some(required),
+        );
+        let canonical_target_dir =
+          std::fs::canonicalize(&target_dir)?;
         let mut archive =
           unrar::Archive::new(&archive_path).open_for_processing()?;
-
+        let mut extracted = 0u64;
         while let Some(header) = archive.read_header()? {
+          if cancel.is_cancelled() {
+            return Err(ExtractionError::Cancelled);
+          }
+
+          let entry = header.entry();
+          let relative_path = get_contained_path(&entry.filename)?;
+          let is_link = is_rar_link(entry.file_attr);
+          prepare_entry_path(
+            &target_dir,
+            &canonical_target_dir,
+            &relative_path,
+            entry.is_directory(),
+          )?;
+          extracted = extracted.saturating_add(entry.unpacked_size);
+
           archive = header.extract_to(&target_dir)?;
+          if is_link {
+            check_extracted_link(&target_dir, &relative_path)?;
+          }
+          progress.entry_extracted(extracted);
         }
         Ok(())
       })
@@ -112,6 +381,473 @@ pub async fn extract_archive(
   }
 }
 
+fn extract_zip(
+  archive_path: &Path,
+  target_dir: &Path,
+  policy: ExtractionPolicy,
+  progress: Arc<dyn Reporter + Send + Sync>,
+  cancel: &CancellationToken,
+) -> Result<(), ExtractionError> {
+  let file = File::open(archive_path)?;
+  let mut archive = zip::ZipArchive::new(file)?;
+
+  policy.check_entries(archive.len() as u64)?;
+  let mut required = 0u64;
+  for index in 0..archive.len() {
+    required =
+      required.saturating_add(archive.by_index_raw(index)?.size());
+  }
+  policy.check_size(required)?;
+  ensure_available_space(target_dir, required)?;
+
+  let mut progress = ExtractionProgress::new(
+    progress,
+    Some(archive.len() as u64),
+    Some(required),
+  );
+  let canonical_target_dir = std::fs::canonicalize(target_dir)?;
+  let mut written = 0u64;
+  for index in 0..archive.len() {
+    if cancel.is_cancelled() {
+      return Err(ExtractionError::Cancelled);
+    }
+
+    extract_zip_entry(
+      &mut archive.by_index(index)?,
+      target_dir,
+      &canonical_target_dir,
+      policy,
+      &mut written,
+    )?;
+    progress.entry_extracted(written);
+  }
+
+  Ok(())
+}
+
+fn extract_zip_entry(
+  entry: &mut zip::read::ZipFile<'_, File>,
+  target_dir: &Path,
+  canonical_target_dir: &Path,
+  policy: ExtractionPolicy,
+  written: &mut u64,
+) -> Result<(), ExtractionError> {
+  let relative_path = get_contained_path(Path::new(entry.name()))?;
+  let path = prepare_entry_path(
+    target_dir,
+    canonical_target_dir,
+    &relative_path,
+    entry.is_dir(),
+  )?;
+  if entry.is_dir() {
+    return Ok(());
+  }
+
+  if entry.is_symlink() {
+    policy.check_link(&relative_path)?;
+    let mut link_target = String::new();
+    entry.read_to_string(&mut link_target)?;
+    check_link_target(&relative_path, Path::new(&link_target))?;
+    create_symlink(&link_target, &path)?;
+    return Ok(());
+  }
+
+  write_entry_file(entry, &path, policy, written)?;
+  set_unix_mode(&path, entry.unix_mode())?;
+  Ok(())
+}
+
+/// Extracts a 7z archive. Links are extracted as regular files containing
+/// the link target, since 7z doesn't reliably tell them apart.
+fn extract_7z(
+  archive_path: &Path,
+  target_dir: &Path,
+  policy: ExtractionPolicy,
+  progress: Arc<dyn Reporter + Send + Sync>,
+  cancel: &CancellationToken,
+) -> Result<(), ExtractionError> {
+  let mut reader =
+    SevenZReader::open(archive_path, Password::empty())?;
+
+  let files = &reader.archive().files;
+  let total_entries = files.len() as u64;
+  policy.check_entries(total_entries)?;
+  let mut required = 0u64;
+  for file in files {
+    get_contained_path(Path::new(file.name()))?;
+    required = required.saturating_add(file.size());
+  }
+  policy.check_size(required)?;
+  ensure_available_space(target_dir, required)?;
+
+  let mut progress = ExtractionProgress::new(
+    progress,
+    Some(total_entries),
+    Some(required),
+  );
+  let canonical_target_dir = std::fs::canonicalize(target_dir)?;
+  let mut written = 0u64;
+  // The callback can only fail with a 7z error, so keep ours here.
+  let mut error = None;
+  reader.for_each_entries(|entry, content| {
+    let result = if cancel.is_cancelled() {
+      Err(ExtractionError::Cancelled)
+    } else {
+      extract_7z_entry(
+        entry,
+        content,
+        target_dir,
+        &canonical_target_dir,
+        policy,
+        &mut written,
+      )
+    };
+
+    match result {
+      Ok(()) => {
+        progress.entry_extracted(written);
+        Ok(true)
+      }
+      Err(e) => {
+        error = Some(e);
+        Ok(false)
+      }
+    }
+  })?;
+
+  error.map_or(Ok(()), Err)
+}
+
+fn extract_7z_entry(
+  entry: &SevenZArchiveEntry,
+  content: &mut dyn Read,
+  target_dir: &Path,
+  canonical_target_dir: &Path,
+  policy: ExtractionPolicy,
+  written: &mut u64,
+) -> Result<(), ExtractionError> {
+  let relative_path = get_contained_path(Path::new(entry.name()))?;
+  let path = prepare_entry_path(
+    target_dir,
+    canonical_target_dir,
+    &relative_path,
+    entry.is_directory(),
+  )?;
+  if entry.is_directory() {
+    return Ok(());
+  }
+
+  write_entry_file(content, &path, policy, written)
+}
+
+/// Creates the directories an entry at `relative_path` is extracted into,
+/// making sure that they're inside the target directory, and returns the
+/// path to extract the entry to.
+fn prepare_entry_path(
+  target_dir: &Path,
+  canonical_target_dir: &Path,
+  relative_path: &Path,
+  is_dir: bool,
+) -> Result<PathBuf, ExtractionError> {
+  let path = target_dir.join(relative_path);
+  let dir = if is_dir {
+    path.as_path()
+  } else {
+    path.parent().unwrap_or(target_dir)
+  };
+  ensure_inside(canonical_target_dir, dir, relative_path)?;
+  std::fs::create_dir_all(dir)?;
+
+  // Replace rather than write through a link extracted earlier.
+  if !is_dir
+    && std::fs::symlink_metadata(&path)
+      .is_ok_and(|metadata| metadata.file_type().is_symlink())
+  {
+    std::fs::remove_file(&path)?;
+  }
+
+  Ok(path)
+}
+
+/// Writes the `content` of an entry to a file at `path`, adding its size
+/// to `written`. The sizes recorded in an archive may be lies, so the size
+/// limit of `policy` is checked against what is actually written.
+fn write_entry_file(
+  content: &mut dyn Read,
+  path: &Path,
+  policy: ExtractionPolicy,
+  written: &mut u64,
+) -> Result<(), ExtractionError> {
+  let mut file = File::create(path)?;
+  *written += match policy.max_size {
+    Some(limit) => {
+      let remaining = limit.saturating_sub(*written);
+      io::copy(
+        &mut content.take(remaining.saturating_add(1)),
+        &mut file,
+      )?
+    }
+    None => io::copy(content, &mut file)?,
+  };
+  policy.check_size(*written)
+}
+
+/// Determines the format of the archive at `archive_path` from its
+/// signature.
+fn detect_archive_format(
+  archive_path: &Path,
+) -> io::Result<Option<ArchiveFormat>> {
+  const SIGNATURES: &[(&[u8], ArchiveFormat)] = &[
+    (b"PK\x03\x04", ArchiveFormat::Zip),
+    // An empty zip archive
+    (b"PK\x05\x06", ArchiveFormat::Zip),
+    (b"\x1f\x8b", ArchiveFormat::Tar(Compression::Gzip)),
+    (b"\xfd7zXZ\x00", ArchiveFormat::Tar(Compression::Xz)),
+    (b"BZh", ArchiveFormat::Tar(Compression::Bzip2)),
+    (b"\x28\xb5\x2f\xfd", ArchiveFormat::Tar(Compression::Zstd)),
+    (b"7z\xbc\xaf\x27\x1c", ArchiveFormat::SevenZip),
+    (b"Rar!\x1a\x07", ArchiveFormat::Rar),
+  ];
+
+  let mut header = Vec::new();
+  File::open(archive_path)?.take(8).read_to_end(&mut header)?;
+
+  let format = SIGNATURES
+    .iter()
+    .find(|(signature, _)| header.starts_with(signature))
+    .map(|(_, format)| *format);
+  if format.is_some() {
+    return Ok(format);
+  }
+
+  // DMG images have their signature at the end, and other data may come
+  // first, so go by the extension instead.
+  let is_dmg = archive_path
+    .extension()
+    .is_some_and(|extension| extension.eq_ignore_ascii_case("dmg"));
+  Ok(is_dmg.then_some(ArchiveFormat::Dmg))
+}
+
+fn extract_tar(
+  reader: impl Read,
+  target_dir: &Path,
+  policy: ExtractionPolicy,
+  progress: Arc<dyn Reporter + Send + Sync>,
+  cancel: &CancellationToken,
+) -> Result<(), ExtractionError> {
+  let mut archive = Archive::new(reader);
+  // Neither the number of entries nor their size is known before reading
+  // through the whole archive.
+  let mut progress = ExtractionProgress::new(progress, None, None);
+
+  // Like `Archive::unpack`, create directories last, so that read-only
+  // ones don't keep their contents from being written.
+  let mut directories = Vec::new();
+  let mut entries = 0u64;
+  // Unlike zip entries, tar entries are exactly as large as their header
+  // says.
+  let mut size = 0u64;
+  for entry in archive.entries()? {
+    if cancel.is_cancelled() {
+      return Err(ExtractionError::Cancelled);
+    }
+
+    let mut entry = entry?;
+    entries += 1;
+    policy.check_entries(entries)?;
+    size = size.saturating_add(entry.size());
+    policy.check_size(size)?;
+
+    let relative_path = get_contained_path(&entry.path()?)?;
+    let entry_type = entry.header().entry_type();
+    if entry_type.is_symlink() || entry_type.is_hard_link() {
+      policy.check_link(&relative_path)?;
+      if let Some(link_name) = entry.link_name()? {
+        if entry_type.is_symlink() {
+          check_link_target(&relative_path, &link_name)?;
+        } else {
+          // Hard links are relative to the root of the archive.
+          get_contained_path(&link_name).map_err(|_| {
+            ExtractionError::LinkEscape(
+              relative_path.display().to_string(),
+            )
+          })?;
+        }
+      }
+    }
+
+    // `unpack_in` also refuses to write through links that lead outside
+    // of `target_dir`.
+    if entry_type.is_dir() {
+      directories.push(entry);
+    } else {
+      entry.unpack_in(target_dir)?;
+    }
+    progress.entry_extracted(size);
+  }
+
+  for mut directory in directories {
+    directory.unpack_in(target_dir)?;
+  }
+
+  Ok(())
+}
+
+/// Returns `path` as a relative path without any `..`, so that joining it
+/// to a directory never leads outside of it.
+fn get_contained_path(
+  path: &Path,
+) -> Result<PathBuf, ExtractionError> {
+  let mut contained = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::Normal(part) => contained.push(part),
+      Component::CurDir => {}
+      Component::ParentDir
+      | Component::RootDir
+      | Component::Prefix(_) => {
+        return Err(ExtractionError::PathEscape(
+          path.display().to_string(),
+        ));
+      }
+    }
+  }
+  Ok(contained)
+}
+
+/// Checks that a symbolic link at `link_path`, relative to the target
+/// directory, pointing to `link_target` stays inside the target directory.
+fn check_link_target(
+  link_path: &Path,
+  link_target: &Path,
+) -> Result<(), ExtractionError> {
+  let escape =
+    || ExtractionError::LinkEscape(link_path.display().to_string());
+
+  // The number of directories between the target directory and where the
+  // link points so far.
+  let mut depth = link_path.components().count().saturating_sub(1);
+  for component in link_target.components() {
+    match component {
+      Component::Normal(_) => depth += 1,
+      Component::CurDir => {}
+      Component::ParentDir => {
+        depth = depth.checked_sub(1).ok_or_else(escape)?;
+      }
+      Component::RootDir | Component::Prefix(_) => {
+        return Err(escape());
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Checks that a link extracted to `relative_path` in `target_dir` points
+/// inside of it, and removes the link if it doesn't. For archives whose
+/// entries are written by a library, where this is only known afterwards.
+fn check_extracted_link(
+  target_dir: &Path,
+  relative_path: &Path,
+) -> Result<(), ExtractionError> {
+  let path = target_dir.join(relative_path);
+  let Ok(link_target) = std::fs::read_link(&path) else {
+    return Ok(());
+  };
+
+  if let Err(e) = check_link_target(relative_path, &link_target) {
+    std::fs::remove_file(&path)
+      .or_else(|_| std::fs::remove_dir(&path))?;
+    return Err(e);
+  }
+  Ok(())
+}
+
+/// Checks that `dir`, with links resolved, is inside the target directory,
+/// so that nothing is written through a link that leads elsewhere. `dir`
+/// doesn't have to exist yet.
+fn ensure_inside(
+  canonical_target_dir: &Path,
+  dir: &Path,
+  relative_path: &Path,
+) -> Result<(), ExtractionError> {
+  let Some(existing) = dir.ancestors().find(|p| p.exists()) else {
+    return Ok(());
+  };
+  if std::fs::canonicalize(existing)?
+    .starts_with(canonical_target_dir)
+  {
+    Ok(())
+  } else {
+    Err(ExtractionError::PathEscape(
+      relative_path.display().to_string(),
+    ))
+  }
+}
+
+/// Returns whether a rar entry with the given attributes is a link. Unix
+/// archivers record the file mode, Windows ones the file attributes.
+fn is_rar_link(file_attr: u32) -> bool {
+  const S_IFMT: u32 = 0o170000;
+  const S_IFLNK: u32 = 0o120000;
+  const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+
+  file_attr & S_IFMT == S_IFLNK
+    || file_attr & FILE_ATTRIBUTE_REPARSE_POINT != 0
+}
+
+/// Returns the size of the content of a gzip file, as recorded at its end.
+///
+/// The recorded size wraps around at 4 GiB, so it's never taken to be
+/// smaller than the file itself. `file` is rewound afterwards.
+fn get_gzip_uncompressed_size(file: &mut File) -> io::Result<u64> {
+  let compressed_size = file.metadata()?.len();
+  if compressed_size < 4 {
+    return Ok(compressed_size);
+  }
+
+  let mut recorded_size = [0u8; 4];
+  file.seek(SeekFrom::End(-4))?;
+  file.read_exact(&mut recorded_size)?;
+  file.seek(SeekFrom::Start(0))?;
+
+  Ok(
+    u64::from(u32::from_le_bytes(recorded_size)).max(compressed_size),
+  )
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &str, path: &Path) -> io::Result<()> {
+  std::os::unix::fs::symlink(target, path)
+}
+
+/// Symbolic links need special privileges on Windows, so store the link
+/// target in a regular file instead.
+#[cfg(not(unix))]
+fn create_symlink(target: &str, path: &Path) -> io::Result<()> {
+  std::fs::write(path, target)
+}
+
+/// Applies the permissions of an archive entry. Like tar extraction, this
+/// drops the setuid, setgid and sticky bits, so that an archive can't
+/// install a setuid executable.
+#[cfg(unix)]
+fn set_unix_mode(path: &Path, mode: Option<u32>) -> io::Result<()> {
+  use std::os::unix::fs::PermissionsExt;
+
+  let Some(mode) = mode else {
+    return Ok(());
+  };
+  std::fs::set_permissions(
+    path,
+    std::fs::Permissions::from_mode(mode & 0o777),
+  )
+}
+
+#[cfg(not(unix))]
+fn set_unix_mode(_path: &Path, _mode: Option<u32>) -> io::Result<()> {
+  Ok(())
+}
+
 /// Represents errors that can occur during archive creation.
 #[derive(thiserror::Error, Debug)]
 pub enum ArchiveCreationError {
@@ -132,10 +868,16 @@ pub enum ArchiveCreationError {
 
   #[error("unexpected join error: {0}")]
   Join(#[from] JoinError),
+
+  #[error(transparent)]
+  InsufficientSpace(#[from] InsufficientSpace),
 }
 
 /// Creates a zip archive at `archive_path` containing the specified `paths_to_include`
 /// relative to the `source_dir`.
+///
+/// The archive isn't created unless the included files fit next to it
+/// uncompressed, which is as large as it can get.
 pub async fn create_zip_archive(
   source_dir: &Path,
   paths_to_include: &[PathBuf],
@@ -168,6 +910,14 @@ pub async fn create_zip_archive(
   let paths_to_include: Vec<PathBuf> =
     paths_to_include.iter().map(|p| p.to_path_buf()).collect();
 
+  // If the size can't be told, let the writing itself fail.
+  let mut required = 0u64;
+  for path in &paths_to_include {
+    required = required
+      .saturating_add(get_dir_size(path).await.unwrap_or_default());
+  }
+  ensure_available_space(&archive_path, required)?;
+
   tokio::task::spawn_blocking(move || {
     let file = File::create(&archive_path)?;
     let mut zip = ZipWriter::new(file);
@@ -232,3 +982,328 @@ fn add_to_zip(
 
   Ok(())
 }
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use std::io::Write;
+  use std::sync::Mutex;
+
+  use tempfile::TempDir;
+  use zip::write::SimpleFileOptions;
+
+  use super::*;
+  use crate::infra::installation_progress_monitor::noop_reporter::NoopReporter;
+  use crate::infra::testing::test_zip::create_test_zip;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  async fn extract_test_zip(
+    dir: &TempDir,
+    zip_bytes: &[u8],
+    policy: ExtractionPolicy,
+  ) -> Result<(), ExtractionError> {
+    let archive_path = dir.path().join("archive.zip");
+    std::fs::write(&archive_path, zip_bytes)?;
+
+    extract_archive(
+      &archive_path,
+      &dir.path().join("target"),
+      &OS::Linux,
+      policy,
+      Arc::new(NoopReporter),
+      &CancellationToken::new(),
+    )
+    .await
+  }
+
+  #[derive(Default)]
+  struct RecordingReporter {
+    setups: Mutex<Vec<(Phase, Option<u64>, String)>>,
+    messages: Mutex<Vec<String>>,
+    progress: Mutex<Vec<u64>>,
+  }
+
+  impl Reporter for RecordingReporter {
+    fn setup(&self, max_progress: Option<u64>, message: &str) {
+      self.setup_phase(Phase::Download, max_progress, message);
+    }
+    fn setup_phase(
+      &self,
+      phase: Phase,
+      max_progress: Option<u64>,
+      message: &str,
+    ) {
+      self.setups.lock().unwrap().push((
+        phase,
+        max_progress,
+        message.into(),
+      ));
+    }
+    fn progress(&self, current: u64) {
+      self.progress.lock().unwrap().push(current);
+    }
+    fn set_message(&self, message: &str) {
+      self.messages.lock().unwrap().push(message.into());
+    }
+    fn done(&self) {}
+  }
+
+  #[tokio::test]
+  async fn test_extract_zip_reports_progress() -> TestResult {
+    let dir = TempDir::new()?;
+    let archive_path = dir.path().join("archive.zip");
+    let zip_bytes =
+      create_test_zip(&[("a.txt", b"12345"), ("b.txt", b"123")])?;
+    std::fs::write(&archive_path, zip_bytes)?;
+    let reporter = Arc::new(RecordingReporter::default());
+
+    extract_archive(
+      &archive_path,
+      &dir.path().join("target"),
+      &OS::Linux,
+      ExtractionPolicy::TRUSTED,
+      reporter.clone(),
+      &CancellationToken::new(),
+    )
+    .await?;
+
+    assert_eq!(
+      *reporter.setups.lock().unwrap(),
+      vec![(Phase::Extract, Some(8), EXTRACTION_MESSAGE.to_string())]
+    );
+    assert_eq!(
+      *reporter.messages.lock().unwrap(),
+      vec!["1 of 2 entries", "2 of 2 entries"]
+    );
+    assert_eq!(*reporter.progress.lock().unwrap(), vec![5, 8]);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_extract_zip_rejects_path_escape() -> TestResult {
+    let dir = TempDir::new()?;
+    let zip_bytes = create_test_zip(&[("../escaped.txt", b"data")])?;
+
+    let result =
+      extract_test_zip(&dir, &zip_bytes, ExtractionPolicy::TRUSTED)
+        .await;
+
+    assert!(matches!(result, Err(ExtractionError::PathEscape(_))));
+    assert!(!dir.path().join("escaped.txt").exists());
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_extract_zip_links() -> TestResult {
+    let create_zip = |target: &str| -> TestResult<Vec<u8>> {
+      let mut buf = Vec::new();
+      let mut zip =
+        zip::ZipWriter::new(std::io::Cursor::new(&mut buf));
+      zip
+        .start_file("data/file.txt", SimpleFileOptions::default())?;
+      zip.write_all(b"data")?;
+      zip.add_symlink(
+        "data/link",
+        target,
+        SimpleFileOptions::default(),
+      )?;
+      zip.finish()?;
+      Ok(buf)
+    };
+
+    let dir = TempDir::new()?;
+    let result = extract_test_zip(
+      &dir,
+      &create_zip("file.txt")?,
+      ExtractionPolicy::UNTRUSTED,
+    )
+    .await;
+    assert!(matches!(
+      result,
+      Err(ExtractionError::LinkNotAllowed(_))
+    ));
+
+    let dir = TempDir::new()?;
+    let result = extract_test_zip(
+      &dir,
+      &create_zip("../../outside")?,
+      ExtractionPolicy::TRUSTED,
+    )
+    .await;
+    assert!(matches!(result, Err(ExtractionError::LinkEscape(_))));
+
+    let dir = TempDir::new()?;
+    extract_test_zip(
+      &dir,
+      &create_zip("../data/file.txt")?,
+      ExtractionPolicy::TRUSTED,
+    )
+    .await?;
+    assert!(
+      std::fs::symlink_metadata(dir.path().join("target/data/link"))?
+        .file_type()
+        .is_symlink()
+    );
+
+    Ok(())
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_extract_zip_drops_setuid_bit() -> TestResult {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut buf = Vec::new();
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut buf));
+    zip.start_file(
+      "game",
+      SimpleFileOptions::default().unix_permissions(0o755),
+    )?;
+    zip.write_all(b"data")?;
+    zip.finish()?;
+
+    // The writer drops the setuid bit, so set it in the central directory
+    // entry. Its external attributes start at offset 38 and hold the mode
+    // in their upper half, in little-endian order.
+    let header = buf
+      .windows(4)
+      .position(|w| w == b"PK\x01\x02")
+      .ok_or("no central directory entry")?;
+    buf[header + 41] |= (0o4000 >> 8) as u8;
+
+    let dir = TempDir::new()?;
+    extract_test_zip(&dir, &buf, ExtractionPolicy::UNTRUSTED).await?;
+
+    let mode = std::fs::metadata(dir.path().join("target/game"))?
+      .permissions()
+      .mode();
+    assert_eq!(mode & 0o7777, 0o755);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_check_extracted_link() -> TestResult {
+    let dir = TempDir::new()?;
+    std::fs::create_dir(dir.path().join("data"))?;
+    std::fs::write(dir.path().join("data/file.txt"), b"data")?;
+
+    create_symlink("file.txt", &dir.path().join("data/inside"))?;
+    check_extracted_link(dir.path(), Path::new("data/inside"))?;
+    assert!(dir.path().join("data/inside").exists());
+
+    create_symlink(
+      "../../outside",
+      &dir.path().join("data/outside"),
+    )?;
+    let result =
+      check_extracted_link(dir.path(), Path::new("data/outside"));
+    assert!(matches!(result, Err(ExtractionError::LinkEscape(_))));
+    assert!(
+      std::fs::symlink_metadata(dir.path().join("data/outside"))
+        .is_err()
+    );
+
+    // Regular files aren't links, so there's nothing to check.
+    check_extracted_link(dir.path(), Path::new("data/file.txt"))?;
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_extract_compressed_tar_without_extension()
+  -> TestResult {
+    let mut tarball = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_size(4);
+    header.set_mode(0o644);
+    tarball.append_data(
+      &mut header,
+      "data/file.txt",
+      &b"data"[..],
+    )?;
+    let tarball = tarball.into_inner()?;
+
+    let mut xz = xz2::write::XzEncoder::new(Vec::new(), 6);
+    xz.write_all(&tarball)?;
+    let mut bzip2 = bzip2::write::BzEncoder::new(
+      Vec::new(),
+      bzip2::Compression::default(),
+    );
+    bzip2.write_all(&tarball)?;
+
+    for compressed in [
+      xz.finish()?,
+      bzip2.finish()?,
+      zstd::encode_all(tarball.as_slice(), 0)?,
+    ] {
+      let dir = TempDir::new()?;
+      let archive_path = dir.path().join("download");
+      std::fs::write(&archive_path, compressed)?;
+
+      extract_archive(
+        &archive_path,
+        &dir.path().join("target"),
+        &OS::Linux,
+        ExtractionPolicy::UNTRUSTED,
+        Arc::new(NoopReporter),
+        &CancellationToken::new(),
+      )
+      .await?;
+
+      assert_eq!(
+        std::fs::read(dir.path().join("target/data/file.txt"))?,
+        b"data"
+      );
+    }
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_extract_zip_limits() -> TestResult {
+    let zip_bytes =
+      create_test_zip(&[("a.txt", b"12345"), ("b.txt", b"12345")])?;
+
+    let dir = TempDir::new()?;
+    let policy = ExtractionPolicy {
+      max_entries: Some(1),
+      ..ExtractionPolicy::UNTRUSTED
+    };
+    let result = extract_test_zip(&dir, &zip_bytes, policy).await;
+    assert!(matches!(
+      result,
+      Err(ExtractionError::TooManyEntries { limit: 1 })
+    ));
+
+    let dir = TempDir::new()?;
+    let policy = ExtractionPolicy {
+      max_size: Some(9),
+      ..ExtractionPolicy::UNTRUSTED
+    };
+    let result = extract_test_zip(&dir, &zip_bytes, policy).await;
+    assert!(matches!(
+      result,
+      Err(ExtractionError::TooLarge { limit: 9 })
+    ));
+
+    let dir = TempDir::new()?;
+    let policy = ExtractionPolicy {
+      max_size: Some(10),
+      ..ExtractionPolicy::UNTRUSTED
+    };
+    extract_test_zip(&dir, &zip_bytes, policy).await?;
+
+    Ok(())
+  }
+}
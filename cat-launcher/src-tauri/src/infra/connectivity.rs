@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use ts_rs::TS;
+
+/// Whether the launcher can reach the network.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub enum ConnectivityState {
+  /// The last request reached its server.
+  Online,
+  /// The last request failed to connect or timed out. Requests are still
+  /// made, so that the launcher notices once the network is back.
+  Unreachable,
+  /// Offline mode is enabled, so no requests are made at all.
+  Offline,
+}
+
+/// The launcher-wide connectivity state, shared by every HTTP client.
+#[derive(Debug)]
+pub struct Connectivity {
+  state: watch::Sender<ConnectivityState>,
+}
+
+impl Default for Connectivity {
+  fn default() -> Self {
+    Self {
+      state: watch::Sender::new(ConnectivityState::Online),
+    }
+  }
+}
+
+impl Connectivity {
+  /// Returns the current connectivity state.
+  pub fn state(&self) -> ConnectivityState {
+    *self.state.borrow()
+  }
+
+  /// Returns whether offline mode is enabled.
+  pub fn is_offline(&self) -> bool {
+    self.state() == ConnectivityState::Offline
+  }
+
+  /// Returns a receiver that is notified whenever the state changes.
+  pub fn subscribe(&self) -> watch::Receiver<ConnectivityState> {
+    self.state.subscribe()
+  }
+
+  /// Enables or disables offline mode.
+  ///
+  /// Disabling it optimistically assumes the network is reachable until
+  /// the next request says otherwise.
+  pub fn set_offline_mode(&self, enabled: bool) {
+    let new_state = if enabled {
+      ConnectivityState::Offline
+    } else {
+      ConnectivityState::Online
+    };
+    self.state.send_if_modified(|state| {
+      let is_modified = *state != new_state;
+      *state = new_state;
+      is_modified
+    });
+  }
+
+  /// Records whether a request reached its server.
+  ///
+  /// This has no effect while offline mode is enabled, e.g. if it was
+  /// enabled while the request was in flight.
+  pub fn record_request(&self, is_reachable: bool) {
+    let new_state = if is_reachable {
+      ConnectivityState::Online
+    } else {
+      ConnectivityState::Unreachable
+    };
+    self.state.send_if_modified(|state| {
+      if *state == ConnectivityState::Offline || *state == new_state {
+        return false;
+      }
+      *state = new_state;
+      true
+    });
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_record_request() {
+    let connectivity = Connectivity::default();
+    let receiver = connectivity.subscribe();
+
+    connectivity.record_request(true);
+    assert_eq!(connectivity.state(), ConnectivityState::Online);
+    assert!(!receiver.has_changed().unwrap());
+
+    connectivity.record_request(false);
+    assert_eq!(connectivity.state(), ConnectivityState::Unreachable);
+    assert!(receiver.has_changed().unwrap());
+
+    connectivity.record_request(true);
+    assert_eq!(connectivity.state(), ConnectivityState::Online);
+  }
+
+  #[test]
+  fn test_offline_mode_ignores_requests() {
+    let connectivity = Connectivity::default();
+
+    connectivity.set_offline_mode(true);
+    assert!(connectivity.is_offline());
+
+    connectivity.record_request(true);
+    connectivity.record_request(false);
+    assert_eq!(connectivity.state(), ConnectivityState::Offline);
+
+    connectivity.set_offline_mode(false);
+    assert_eq!(connectivity.state(), ConnectivityState::Online);
+  }
+}
@@ -1,12 +1,23 @@
 use std::num::NonZeroU16;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
 
+use crate::filesystem::disk_space::{
+  InsufficientSpace, ensure_available_space,
+};
+use crate::infra::download_queue::{
+  DownloadPriority, DownloadQueue, Interruption,
+};
 use crate::infra::http_client::{
   HttpClient, HttpClientError, ReqwestHttpClient,
 };
 use downloader::progress::Reporter;
+use reqwest::header::{
+  ETAG, HeaderMap, HeaderName, HeaderValue, IF_RANGE, LAST_MODIFIED,
+  RANGE,
+};
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
 
 /// Represents errors that can occur during file download.
 #[derive(Error, Debug)]
@@ -16,12 +27,24 @@ pub enum DownloadFileError {
 
   #[error("no download result found")]
   NoDownloadResult,
+
+  #[error("failed to request file: {0}")]
+  Request(#[from] HttpClientError),
+
+  #[error("download was cancelled")]
+  Cancelled,
+
+  #[error(transparent)]
+  InsufficientSpace(#[from] InsufficientSpace),
 }
 
 /// A struct for managing file downloads using a client C that implements `HttpClient`.
+///
+/// All downloads go through its queue, which runs a limited number of
+/// them at a time and lets them be paused, resumed and cancelled.
 pub struct Downloader<C = ReqwestHttpClient> {
   client: C,
-  parallel_requests: NonZeroU16,
+  queue: DownloadQueue,
 }
 
 impl<C: HttpClient + Clone + 'static> Downloader<C> {
@@ -29,62 +52,329 @@ impl<C: HttpClient + Clone + 'static> Downloader<C> {
   pub fn new(client: C, parallel_requests: NonZeroU16) -> Self {
     Self {
       client,
-      parallel_requests,
+      queue: DownloadQueue::new(parallel_requests),
     }
   }
 
+  /// Returns the queue that downloads wait in.
+  pub fn queue(&self) -> &DownloadQueue {
+    &self.queue
+  }
+
   /// Downloads a file from the given `url` to the `download_dir` and reports progress.
   pub async fn download_file(
     &self,
     url: &str,
     download_dir: &Path,
+    priority: DownloadPriority,
     reporter: Arc<dyn Reporter + Send + Sync>,
   ) -> Result<PathBuf, DownloadFileError> {
+    self
+      .download_file_mirrored(
+        url,
+        &[],
+        download_dir,
+        priority,
+        &CancellationToken::new(),
+        reporter,
+      )
+      .await
+      .result
+  }
+
+  /// Downloads a file that is available from the given `url` as well as
+  /// from the given mirror URLs to the `download_dir`, and reports progress.
+  ///
+  /// The file is named after `url`. A URL that fails is not tried again
+  /// while others are left, and every attempt is returned along with the
+  /// result, so that callers can tell which mirrors work.
+  ///
+  /// The download can be cancelled through `cancel` as well as through the
+  /// queue. Either way, nothing of it is left in `download_dir`.
+  pub async fn download_file_mirrored(
+    &self,
+    url: &str,
+    mirror_urls: &[&str],
+    download_dir: &Path,
+    priority: DownloadPriority,
+    cancel: &CancellationToken,
+    reporter: Arc<dyn Reporter + Send + Sync>,
+  ) -> MirroredDownload {
+    if self.client.is_offline() {
+      return MirroredDownload {
+        result: Err(HttpClientError::Offline.into()),
+        attempts: Vec::new(),
+      };
+    }
+
+    // The downloader rejects URLs that are listed twice.
+    let mut urls = vec![url];
+    for mirror_url in mirror_urls {
+      if !urls.contains(mirror_url) {
+        urls.push(*mirror_url);
+      }
+    }
+
+    let file_name =
+      downloader::Download::new_mirrored(&urls).file_name;
+
+    let download = tokio::select! {
+      download = self.download_queued(
+        url,
+        &urls,
+        &file_name,
+        download_dir,
+        priority,
+        reporter,
+      ) => download,
+      () = cancel.cancelled() => MirroredDownload {
+        result: Err(DownloadFileError::Cancelled),
+        attempts: Vec::new(),
+      },
+    };
+
+    if matches!(download.result, Err(DownloadFileError::Cancelled)) {
+      remove_partial_download(&download_dir.join(&file_name)).await;
+    }
+
+    download
+  }
+
+  /// Downloads a file once the queue lets it. A download that is paused
+  /// and resumed continues from the partial file.
+  async fn download_queued(
+    &self,
+    url: &str,
+    urls: &[&str],
+    file_name: &Path,
+    download_dir: &Path,
+    priority: DownloadPriority,
+    reporter: Arc<dyn Reporter + Send + Sync>,
+  ) -> MirroredDownload {
+    let ticket =
+      self
+        .queue
+        .enqueue(url, &file_name.to_string_lossy(), priority);
+    let reporter = ticket.reporter(reporter);
+    let mut attempts = Vec::new();
+
+    loop {
+      if ticket.acquire().await.is_err() {
+        break;
+      }
+
+      let outcome = tokio::select! {
+        (result, run_attempts) =
+          self.download_once(urls, download_dir, reporter.clone()) => {
+          Ok((result, run_attempts))
+        }
+        interruption = ticket.interrupted() => Err(interruption),
+      };
+
+      match outcome {
+        Ok((result, run_attempts)) => {
+          attempts.extend(run_attempts);
+          return MirroredDownload { result, attempts };
+        }
+        // A paused download resumes from the partial file once it gets a
+        // slot again, unless the file on the server has changed since.
+        Err(Interruption::Paused) => continue,
+        Err(Interruption::Cancelled) => break,
+      }
+    }
+
+    MirroredDownload {
+      result: Err(DownloadFileError::Cancelled),
+      attempts,
+    }
+  }
+
+  async fn download_once(
+    &self,
+    urls: &[&str],
+    download_dir: &Path,
+    reporter: Arc<dyn Reporter + Send + Sync>,
+  ) -> (Result<PathBuf, DownloadFileError>, Vec<DownloadAttempt>) {
     let mut builder = downloader::downloader::Builder::default();
-    builder
-      .download_folder(download_dir)
-      .parallel_requests(self.parallel_requests.get());
+    builder.download_folder(download_dir);
 
-    let mut downloader = builder.build_with_client(
-      DownloaderClient::new(self.client.clone()),
-    )?;
+    let client =
+      DownloaderClient::new(self.client.clone(), download_dir);
+    let insufficient_space = client.insufficient_space.clone();
+    let mut downloader = match builder.build_with_client(client) {
+      Ok(downloader) => downloader,
+      Err(e) => return (Err(e.into()), Vec::new()),
+    };
 
-    let dl = downloader::Download::new(url).progress(reporter);
+    let dl =
+      downloader::Download::new_mirrored(urls).progress(reporter);
 
-    let results = downloader.async_download(&[dl]).await?;
+    match downloader.async_download(&[dl]).await {
+      Ok(results) => match results.into_iter().next() {
+        Some(Ok(summary)) => {
+          let attempts = get_attempts(&summary);
+          (Ok(summary.file_name), attempts)
+        }
+        Some(Err(e)) => {
+          // The URLs weren't at fault, so don't report any attempts.
+          if let Some(insufficient_space) = insufficient_space
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+          {
+            return (Err(insufficient_space.into()), Vec::new());
+          }
 
-    if let Some(res) = results.into_iter().next() {
-      match res {
-        Ok(summary) => Ok(summary.file_name),
-        Err(e) => Err(DownloadFileError::DownloaderCreation(e)),
+          let attempts = match &e {
+            downloader::Error::Download(summary)
+            | downloader::Error::File(summary) => {
+              get_attempts(summary)
+            }
+            _ => Vec::new(),
+          };
+          (Err(DownloadFileError::DownloaderCreation(e)), attempts)
+        }
+        None => {
+          (Err(DownloadFileError::NoDownloadResult), Vec::new())
+        }
+      },
+      Err(e) => {
+        (Err(DownloadFileError::DownloaderCreation(e)), Vec::new())
       }
-    } else {
-      Err(DownloadFileError::NoDownloadResult)
     }
   }
 }
 
+/// Removes what the downloader keeps of an unfinished download of `file`,
+/// so that a cancelled download doesn't leave anything behind.
+async fn remove_partial_download(file: &Path) {
+  for suffix in [".part", ".part.validator"] {
+    let mut path = file.as_os_str().to_os_string();
+    path.push(suffix);
+    let _ = tokio::fs::remove_file(path).await;
+  }
+}
+
+/// An attempt to download a file from one of its URLs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadAttempt {
+  /// The URL the file was requested from.
+  pub url: String,
+  /// Whether the file was downloaded from the URL.
+  pub succeeded: bool,
+}
+
+/// The outcome of downloading a file that is available from several URLs.
+#[derive(Debug)]
+pub struct MirroredDownload {
+  /// The path of the downloaded file, or why it couldn't be downloaded.
+  pub result: Result<PathBuf, DownloadFileError>,
+  /// The attempts that were made, in order.
+  pub attempts: Vec<DownloadAttempt>,
+}
+
+fn get_attempts(
+  summary: &downloader::DownloadSummary,
+) -> Vec<DownloadAttempt> {
+  summary
+    .status
+    .iter()
+    .map(|(url, status)| DownloadAttempt {
+      url: url.clone(),
+      succeeded: (200..300).contains(status),
+    })
+    .collect()
+}
+
 /// Adapts the launcher's `HttpClient` to the `downloader` crate's `HttpClient` trait.
+///
+/// Before any of a response is written, it checks that it fits into the
+/// download directory. The `downloader` crate only keeps the status of a
+/// failed request, so the reason is kept in `insufficient_space`.
 #[derive(Clone)]
-struct DownloaderClient<C>(C);
+struct DownloaderClient<C> {
+  client: C,
+  download_dir: PathBuf,
+  insufficient_space: Arc<Mutex<Option<InsufficientSpace>>>,
+}
 
 impl<C> DownloaderClient<C> {
-  fn new(client: C) -> Self {
-    Self(client)
+  fn new(client: C, download_dir: &Path) -> Self {
+    Self {
+      client,
+      download_dir: download_dir.to_path_buf(),
+      insufficient_space: Arc::default(),
+    }
+  }
+}
+
+impl<C: HttpClient> DownloaderClient<C> {
+  async fn send(
+    &self,
+    url: &str,
+    headers: HeaderMap,
+  ) -> Result<DownloaderResponse, DownloaderClientError> {
+    let response = self.client.get_with_headers(url, headers).await?;
+
+    // For partial content, this is only what's left to download.
+    if response.status().is_success()
+      && let Some(content_length) = response.content_length()
+      && let Err(e) =
+        ensure_available_space(&self.download_dir, content_length)
+    {
+      *self
+        .insufficient_space
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = Some(e);
+      return Err(e.into());
+    }
+
+    Ok(DownloaderResponse(response))
   }
 }
 
+/// Errors of a `DownloaderClient` request.
+#[derive(Error, Debug)]
+enum DownloaderClientError {
+  #[error(transparent)]
+  Request(#[from] HttpClientError),
+
+  #[error(transparent)]
+  InsufficientSpace(#[from] InsufficientSpace),
+}
+
 impl<C: HttpClient + Clone + 'static> downloader::HttpClient
   for DownloaderClient<C>
 {
-  type Error = HttpClientError;
+  type Error = DownloaderClientError;
   type Response = DownloaderResponse;
 
   async fn get(
     &self,
     url: &str,
   ) -> Result<Self::Response, Self::Error> {
-    Ok(DownloaderResponse(self.0.get(url).await?))
+    self.send(url, HeaderMap::new()).await
+  }
+
+  async fn get_range(
+    &self,
+    url: &str,
+    offset: u64,
+    if_range: &str,
+  ) -> Result<Self::Response, Self::Error> {
+    let (Ok(range), Ok(if_range)) = (
+      HeaderValue::from_str(&format!("bytes={offset}-")),
+      HeaderValue::from_str(if_range),
+    ) else {
+      // The validator can't be sent, so the partial file can't be
+      // trusted to be resumable.
+      return self.send(url, HeaderMap::new()).await;
+    };
+
+    let mut headers = HeaderMap::new();
+    headers.insert(RANGE, range);
+    headers.insert(IF_RANGE, if_range);
+    self.send(url, headers).await
   }
 }
 
@@ -92,7 +382,7 @@ impl<C: HttpClient + Clone + 'static> downloader::HttpClient
 struct DownloaderResponse(reqwest::Response);
 
 impl downloader::Response for DownloaderResponse {
-  type Error = HttpClientError;
+  type Error = DownloaderClientError;
   type Bytes = bytes::Bytes;
 
   fn status(&self) -> u16 {
@@ -103,9 +393,33 @@ impl downloader::Response for DownloaderResponse {
     self.0.content_length()
   }
 
+  fn etag(&self) -> Option<String> {
+    get_header(&self.0, ETAG)
+  }
+
+  fn last_modified(&self) -> Option<String> {
+    get_header(&self.0, LAST_MODIFIED)
+  }
+
   async fn chunk(
     &mut self,
   ) -> Result<Option<Self::Bytes>, Self::Error> {
-    self.0.chunk().await.map_err(HttpClientError::Http)
+    self
+      .0
+      .chunk()
+      .await
+      .map_err(|e| HttpClientError::Http(e).into())
   }
 }
+
+fn get_header(
+  response: &reqwest::Response,
+  name: HeaderName,
+) -> Option<String> {
+  response
+    .headers()
+    .get(name)?
+    .to_str()
+    .ok()
+    .map(str::to_owned)
+}
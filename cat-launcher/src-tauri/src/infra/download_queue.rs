@@ -0,0 +1,501 @@
+use std::num::NonZeroU16;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use downloader::progress::{Phase, Reporter};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::infra::watched::Watched;
+
+/// How urgently a download should start, relative to the others in the
+/// queue.
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Serialize,
+  Deserialize,
+  TS,
+)]
+#[ts(export)]
+pub enum DownloadPriority {
+  Low,
+  Normal,
+  High,
+}
+
+/// Where a download is in the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub enum DownloadStatus {
+  /// Waiting for a free slot.
+  Queued,
+  /// Being downloaded.
+  Downloading,
+  /// Waiting to be resumed. A paused download continues where it left off.
+  Paused,
+}
+
+/// A download in the queue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct QueuedDownload {
+  #[ts(type = "number")]
+  pub id: u64,
+  /// The URL the file is downloaded from, not counting mirrors.
+  pub url: String,
+  /// The name of the downloaded file.
+  pub file_name: String,
+  pub priority: DownloadPriority,
+  pub status: DownloadStatus,
+  /// The number of bytes already downloaded.
+  pub bytes_downloaded: u64,
+  /// The total number of bytes to be downloaded, or 0 if not yet known.
+  pub total_bytes: u64,
+}
+
+/// Errors that can occur when controlling a download in the queue.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum DownloadQueueError {
+  #[error("download {0} not found")]
+  NotFound(u64),
+
+  #[error("download {0} was cancelled")]
+  Cancelled(u64),
+}
+
+/// Why a running download has to stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interruption {
+  Paused,
+  Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryStatus {
+  Queued,
+  Downloading,
+  Paused,
+  Cancelled,
+}
+
+#[derive(Default)]
+struct EntryProgress {
+  bytes_downloaded: AtomicU64,
+  total_bytes: AtomicU64,
+}
+
+struct Entry {
+  id: u64,
+  url: String,
+  file_name: String,
+  priority: DownloadPriority,
+  status: EntryStatus,
+  progress: Arc<EntryProgress>,
+}
+
+impl Entry {
+  /// Whether this entry starts before `other` when both are queued.
+  fn goes_before(&self, other: &Entry) -> bool {
+    (other.priority, self.id) < (self.priority, other.id)
+  }
+
+  fn to_queued_download(&self) -> Option<QueuedDownload> {
+    let status = match self.status {
+      EntryStatus::Queued => DownloadStatus::Queued,
+      EntryStatus::Downloading => DownloadStatus::Downloading,
+      EntryStatus::Paused => DownloadStatus::Paused,
+      EntryStatus::Cancelled => return None,
+    };
+
+    Some(QueuedDownload {
+      id: self.id,
+      url: self.url.clone(),
+      file_name: self.file_name.clone(),
+      priority: self.priority,
+      status,
+      bytes_downloaded: self
+        .progress
+        .bytes_downloaded
+        .load(Ordering::Relaxed),
+      total_bytes: self.progress.total_bytes.load(Ordering::Relaxed),
+    })
+  }
+}
+
+#[derive(Default)]
+struct Entries {
+  next_id: u64,
+  entries: Vec<Entry>,
+}
+
+/// Schedules downloads so that at most a fixed number run at a time, the
+/// ones with the highest priority first, and lets them be paused, resumed
+/// and cancelled.
+pub struct DownloadQueue {
+  slots: usize,
+  entries: Watched<Entries>,
+}
+
+impl DownloadQueue {
+  /// Creates a queue that runs up to `slots` downloads at a time.
+  pub fn new(slots: NonZeroU16) -> Self {
+    Self {
+      slots: usize::from(slots.get()),
+      entries: Watched::default(),
+    }
+  }
+
+  fn update<T>(
+    &self,
+    id: u64,
+    f: impl FnOnce(&mut Entry) -> T,
+  ) -> Result<T, DownloadQueueError> {
+    self.entries.update(|entries| {
+      let entry = entries
+        .entries
+        .iter_mut()
+        .find(|e| e.id == id && e.status != EntryStatus::Cancelled)
+        .ok_or(DownloadQueueError::NotFound(id))?;
+      Ok(f(entry))
+    })
+  }
+
+  /// Adds a download to the queue. It's removed again when the returned
+  /// ticket is dropped.
+  pub fn enqueue(
+    &self,
+    url: &str,
+    file_name: &str,
+    priority: DownloadPriority,
+  ) -> QueueTicket<'_> {
+    let progress = Arc::new(EntryProgress::default());
+    let id = self.entries.update(|entries| {
+      let id = entries.next_id;
+      entries.next_id += 1;
+      entries.entries.push(Entry {
+        id,
+        url: url.to_string(),
+        file_name: file_name.to_string(),
+        priority,
+        status: EntryStatus::Queued,
+        progress: progress.clone(),
+      });
+      id
+    });
+
+    QueueTicket {
+      queue: self,
+      id,
+      progress,
+    }
+  }
+
+  /// Returns the downloads in the queue, in the order they start in.
+  pub fn list(&self) -> Vec<QueuedDownload> {
+    let entries = self.entries.lock();
+    let mut downloads = entries
+      .entries
+      .iter()
+      .filter_map(Entry::to_queued_download)
+      .collect::<Vec<_>>();
+    downloads.sort_by_key(|d| (std::cmp::Reverse(d.priority), d.id));
+    downloads
+  }
+
+  /// Pauses a download, freeing its slot for the next one in the queue.
+  pub fn pause(&self, id: u64) -> Result<(), DownloadQueueError> {
+    self.update(id, |entry| entry.status = EntryStatus::Paused)
+  }
+
+  /// Puts a paused download back into the queue.
+  pub fn resume(&self, id: u64) -> Result<(), DownloadQueueError> {
+    self.update(id, |entry| {
+      if entry.status == EntryStatus::Paused {
+        entry.status = EntryStatus::Queued;
+      }
+    })
+  }
+
+  /// Cancels a download, whether it's queued, running or paused.
+  pub fn cancel(&self, id: u64) -> Result<(), DownloadQueueError> {
+    self.update(id, |entry| entry.status = EntryStatus::Cancelled)
+  }
+
+  /// Changes the priority of a download. This only matters while it's
+  /// waiting for a slot.
+  pub fn set_priority(
+    &self,
+    id: u64,
+    priority: DownloadPriority,
+  ) -> Result<(), DownloadQueueError> {
+    self.update(id, |entry| entry.priority = priority)
+  }
+
+  fn remove(&self, id: u64) {
+    self
+      .entries
+      .update(|entries| entries.entries.retain(|e| e.id != id));
+  }
+}
+
+/// A download's place in the queue.
+pub struct QueueTicket<'a> {
+  queue: &'a DownloadQueue,
+  id: u64,
+  progress: Arc<EntryProgress>,
+}
+
+impl QueueTicket<'_> {
+  /// Returns the ID of the download in the queue.
+  pub fn id(&self) -> u64 {
+    self.id
+  }
+
+  /// Waits until the download may run, i.e. it isn't paused and there is a
+  /// free slot that no download with a higher priority is waiting for.
+  pub async fn acquire(&self) -> Result<(), DownloadQueueError> {
+    let slots = self.queue.slots;
+    self
+      .queue
+      .entries
+      .wait_for(|entries| {
+        let entry =
+          entries.entries.iter().find(|e| e.id == self.id)?;
+        match entry.status {
+          EntryStatus::Cancelled => {
+            return Some(Err(DownloadQueueError::Cancelled(
+              entry.id,
+            )));
+          }
+          EntryStatus::Downloading => return Some(Ok(())),
+          EntryStatus::Paused => return None,
+          EntryStatus::Queued => {}
+        }
+
+        let running = entries
+          .entries
+          .iter()
+          .filter(|e| e.status == EntryStatus::Downloading)
+          .count();
+        let ahead = entries
+          .entries
+          .iter()
+          .filter(|e| {
+            e.status == EntryStatus::Queued && e.goes_before(entry)
+          })
+          .count();
+        if running + ahead >= slots {
+          return None;
+        }
+
+        let entry =
+          entries.entries.iter_mut().find(|e| e.id == self.id)?;
+        entry.status = EntryStatus::Downloading;
+        Some(Ok(()))
+      })
+      .await
+  }
+
+  /// Waits until the download is paused or cancelled.
+  pub async fn interrupted(&self) -> Interruption {
+    self
+      .queue
+      .entries
+      .wait_for(|entries| {
+        let entry =
+          entries.entries.iter().find(|e| e.id == self.id)?;
+        match entry.status {
+          EntryStatus::Paused => Some(Interruption::Paused),
+          EntryStatus::Cancelled => Some(Interruption::Cancelled),
+          EntryStatus::Queued | EntryStatus::Downloading => None,
+        }
+      })
+      .await
+  }
+
+  /// Wraps `reporter` so that the progress it's given is also shown in the
+  /// queue.
+  pub fn reporter(
+    &self,
+    reporter: Arc<dyn Reporter + Send + Sync>,
+  ) -> Arc<dyn Reporter + Send + Sync> {
+    Arc::new(QueueReporter {
+      inner: reporter,
+      progress: self.progress.clone(),
+    })
+  }
+}
+
+impl Drop for QueueTicket<'_> {
+  fn drop(&mut self) {
+    self.queue.remove(self.id);
+  }
+}
+
+struct QueueReporter {
+  inner: Arc<dyn Reporter + Send + Sync>,
+  progress: Arc<EntryProgress>,
+}
+
+impl Reporter for QueueReporter {
+  fn setup(&self, max_progress: Option<u64>, message: &str) {
+    if let Some(total) = max_progress {
+      self.progress.total_bytes.store(total, Ordering::Relaxed);
+    }
+    self.inner.setup(max_progress, message);
+  }
+
+  fn setup_phase(
+    &self,
+    phase: Phase,
+    max_progress: Option<u64>,
+    message: &str,
+  ) {
+    if let Some(total) = max_progress {
+      self.progress.total_bytes.store(total, Ordering::Relaxed);
+    }
+    self.inner.setup_phase(phase, max_progress, message);
+  }
+
+  fn progress(&self, current: u64) {
+    self
+      .progress
+      .bytes_downloaded
+      .store(current, Ordering::Relaxed);
+    self.inner.progress(current);
+  }
+
+  fn set_message(&self, message: &str) {
+    self.inner.set_message(message);
+  }
+
+  fn done(&self) {
+    self.inner.done();
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use std::time::Duration;
+
+  use tokio::time::timeout;
+
+  use super::*;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  const WAIT: Duration = Duration::from_secs(5);
+
+  fn statuses(
+    queue: &DownloadQueue,
+  ) -> Vec<(String, DownloadStatus)> {
+    queue
+      .list()
+      .into_iter()
+      .map(|d| (d.file_name, d.status))
+      .collect()
+  }
+
+  #[tokio::test]
+  async fn test_higher_priority_downloads_start_first() -> TestResult
+  {
+    let queue = DownloadQueue::new(NonZeroU16::MIN);
+
+    let a =
+      queue.enqueue("https://e.test/a", "a", DownloadPriority::Low);
+    timeout(WAIT, a.acquire()).await??;
+
+    let b = queue.enqueue(
+      "https://e.test/b",
+      "b",
+      DownloadPriority::Normal,
+    );
+    let c =
+      queue.enqueue("https://e.test/c", "c", DownloadPriority::High);
+    assert_eq!(
+      statuses(&queue),
+      vec![
+        ("c".to_string(), DownloadStatus::Queued),
+        ("b".to_string(), DownloadStatus::Queued),
+        ("a".to_string(), DownloadStatus::Downloading),
+      ]
+    );
+
+    drop(a);
+    timeout(WAIT, c.acquire()).await??;
+    assert_eq!(
+      statuses(&queue),
+      vec![
+        ("c".to_string(), DownloadStatus::Downloading),
+        ("b".to_string(), DownloadStatus::Queued),
+      ]
+    );
+
+    queue.set_priority(b.id(), DownloadPriority::High)?;
+    drop(c);
+    timeout(WAIT, b.acquire()).await??;
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_pause_resume_and_cancel() -> TestResult {
+    let queue = DownloadQueue::new(NonZeroU16::MIN);
+
+    let a = queue.enqueue(
+      "https://e.test/a",
+      "a",
+      DownloadPriority::Normal,
+    );
+    let b = queue.enqueue(
+      "https://e.test/b",
+      "b",
+      DownloadPriority::Normal,
+    );
+    timeout(WAIT, a.acquire()).await??;
+
+    // Pausing a running download frees its slot.
+    queue.pause(a.id())?;
+    assert_eq!(
+      timeout(WAIT, a.interrupted()).await?,
+      Interruption::Paused
+    );
+    timeout(WAIT, b.acquire()).await??;
+
+    queue.resume(a.id())?;
+    assert_eq!(
+      statuses(&queue),
+      vec![
+        ("a".to_string(), DownloadStatus::Queued),
+        ("b".to_string(), DownloadStatus::Downloading),
+      ]
+    );
+
+    queue.cancel(a.id())?;
+    assert_eq!(
+      timeout(WAIT, a.acquire()).await?,
+      Err(DownloadQueueError::Cancelled(a.id()))
+    );
+    assert_eq!(
+      queue.pause(a.id()),
+      Err(DownloadQueueError::NotFound(a.id()))
+    );
+    assert_eq!(statuses(&queue).len(), 1);
+
+    Ok(())
+  }
+}
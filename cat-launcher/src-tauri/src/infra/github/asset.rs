@@ -1,10 +1,15 @@
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::sync::Arc;
 
 use downloader::progress::Reporter;
 use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
 
-use crate::infra::download::{DownloadFileError, Downloader};
+use crate::filesystem::utils::{FileHashError, get_file_sha256};
+use crate::infra::download::{
+  DownloadFileError, Downloader, MirroredDownload,
+};
+use crate::infra::download_queue::DownloadPriority;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct GitHubAsset {
@@ -20,20 +25,52 @@ pub enum AssetDownloadError {
   Download(#[from] DownloadFileError),
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum AssetVerificationError {
+  #[error("failed to hash asset: {0}")]
+  Hash(#[from] FileHashError),
+}
+
 impl GitHubAsset {
+  /// Downloads the asset from GitHub or any of the given mirror URLs.
   pub async fn download(
     &self,
     downloader: &Downloader,
     download_dir: &Path,
+    mirror_urls: &[&str],
+    priority: DownloadPriority,
+    cancel: &CancellationToken,
     progress: Arc<dyn Reporter + Send + Sync>,
-  ) -> Result<PathBuf, AssetDownloadError> {
+  ) -> MirroredDownload {
     downloader
-      .download_file(
+      .download_file_mirrored(
         &self.browser_download_url,
+        mirror_urls,
         download_dir,
+        priority,
+        cancel,
         progress,
       )
       .await
-      .map_err(AssetDownloadError::from)
+  }
+
+  /// Checks the file at `filepath` against the asset's SHA-256 digest.
+  ///
+  /// Returns `true` if the digest is unknown or uses an unsupported algorithm,
+  /// since there is nothing to verify against.
+  pub async fn verify_digest(
+    &self,
+    filepath: &Path,
+  ) -> Result<bool, AssetVerificationError> {
+    let Some(expected) = self
+      .digest
+      .as_deref()
+      .and_then(|digest| digest.strip_prefix("sha256:"))
+    else {
+      return Ok(true);
+    };
+
+    let actual = get_file_sha256(filepath).await?;
+    Ok(actual.eq_ignore_ascii_case(expected))
   }
 }
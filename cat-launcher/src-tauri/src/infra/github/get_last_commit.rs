@@ -1,4 +1,10 @@
+use chrono::{DateTime, Utc};
+
+use crate::infra::github::repository::GitHubApiCacheRepository;
 use crate::infra::github::types::GitHubCommit;
+use crate::infra::github::utils::{
+  GitHubApiRequestError, get_github_api_response,
+};
 use crate::infra::http_client::{HttpClient, HttpClientError};
 
 #[derive(thiserror::Error, Debug)]
@@ -14,28 +20,42 @@ pub enum GetLastCommitError {
 
   #[error("no commits found in repository")]
   NoCommitsFound,
+
+  #[error("GitHub API rate limit exceeded until {reset_at}")]
+  RateLimited { reset_at: DateTime<Utc> },
 }
 
 pub async fn get_last_commit(
   repo: &str,
   client: &impl HttpClient,
+  cache_repository: &impl GitHubApiCacheRepository,
 ) -> Result<GitHubCommit, GetLastCommitError> {
   let api_url = format!(
     "https://api.github.com/repos/{}/commits?per_page=1",
     repo
   );
 
-  let response = client.get(&api_url).await?;
-
-  if !response.status().is_success() {
-    return Err(GetLastCommitError::InvalidResponse(format!(
-      "GitHub API returned status: {}",
-      response.status()
-    )));
-  }
+  let response =
+    match get_github_api_response(client, &api_url, cache_repository)
+      .await
+    {
+      Ok(response) => response,
+      Err(GitHubApiRequestError::Fetch(e)) => {
+        return Err(GetLastCommitError::FetchGithub(e));
+      }
+      Err(GitHubApiRequestError::Status(status)) => {
+        return Err(GetLastCommitError::InvalidResponse(format!(
+          "GitHub API returned status: {}",
+          status
+        )));
+      }
+      Err(GitHubApiRequestError::RateLimited { reset_at }) => {
+        return Err(GetLastCommitError::RateLimited { reset_at });
+      }
+    };
 
   let commits: Vec<GitHubCommit> =
-    response.json().await.map_err(HttpClientError::from)?;
+    serde_json::from_str(&response.body)?;
 
   let commit = commits
     .into_iter()
@@ -1,5 +1,7 @@
 pub mod asset;
 pub mod get_last_commit;
+pub mod rate_limit;
 pub mod release;
+pub mod repository;
 pub mod types;
 pub mod utils;
@@ -0,0 +1,252 @@
+use chrono::{DateTime, Duration, Utc};
+use reqwest::StatusCode;
+use reqwest::header::{
+  AUTHORIZATION, HeaderMap, HeaderName, HeaderValue, RETRY_AFTER,
+};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::infra::github::types::GitHubToken;
+use crate::infra::http_client::{HttpClient, HttpClientError};
+
+/// The GitHub API rate limit of the current client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GitHubRateLimit {
+  /// The maximum number of requests allowed per hour.
+  pub limit: u32,
+  /// The number of requests remaining in the current window.
+  pub remaining: u32,
+  /// When the current window resets.
+  #[ts(type = "string")]
+  pub reset_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct RateLimitResponse {
+  rate: RateLimitResource,
+}
+
+#[derive(Deserialize)]
+struct RateLimitResource {
+  limit: u32,
+  remaining: u32,
+  reset: i64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum FetchRateLimitError {
+  #[error("failed to make API call: {0}")]
+  Fetch(#[from] HttpClientError),
+
+  #[error("GitHub rejected the token")]
+  Unauthorized,
+
+  #[error("token contains invalid characters")]
+  InvalidToken,
+
+  #[error("GitHub API returned status: {0}")]
+  Status(StatusCode),
+
+  #[error("failed to parse GitHub response: {0}")]
+  Parse(#[from] serde_json::Error),
+
+  #[error("invalid rate limit reset time: {0}")]
+  InvalidReset(i64),
+}
+
+/// How long to wait when GitHub rate limits a request without saying until
+/// when. GitHub recommends waiting at least a minute in that case.
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::minutes(1);
+
+const X_RATELIMIT_REMAINING: HeaderName =
+  HeaderName::from_static("x-ratelimit-remaining");
+const X_RATELIMIT_RESET: HeaderName =
+  HeaderName::from_static("x-ratelimit-reset");
+
+fn parse_header<T: std::str::FromStr>(
+  headers: &HeaderMap,
+  name: &HeaderName,
+) -> Option<T> {
+  headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// Determines whether a response was rejected because of a rate limit and,
+/// if so, when requests will be accepted again.
+///
+/// GitHub signals both its primary and secondary rate limits with a 403 or
+/// 429. The primary limit sets `X-RateLimit-Remaining` to 0 and
+/// `X-RateLimit-Reset` to the epoch second at which it resets; the secondary
+/// limit may set `Retry-After` instead, or only mention the rate limit in
+/// the response body.
+pub fn get_rate_limit_reset(
+  status: StatusCode,
+  headers: &HeaderMap,
+  body: &str,
+  now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+  if status != StatusCode::FORBIDDEN
+    && status != StatusCode::TOO_MANY_REQUESTS
+  {
+    return None;
+  }
+
+  let retry_after = parse_header::<i64>(headers, &RETRY_AFTER)
+    .map(|seconds| now + Duration::seconds(seconds));
+  let remaining =
+    parse_header::<u64>(headers, &X_RATELIMIT_REMAINING);
+  let reset = parse_header::<i64>(headers, &X_RATELIMIT_RESET)
+    .and_then(|reset| DateTime::from_timestamp(reset, 0));
+
+  let is_rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+    || retry_after.is_some()
+    || remaining == Some(0)
+    || body.to_lowercase().contains("rate limit");
+  if !is_rate_limited {
+    return None;
+  }
+
+  Some(
+    retry_after
+      .or(reset)
+      .unwrap_or(now + DEFAULT_RATE_LIMIT_WAIT),
+  )
+}
+
+/// Creates an `Authorization` header value for the given token.
+///
+/// The value is marked as sensitive so that it is never printed.
+pub fn bearer_auth_header(
+  token: &GitHubToken,
+) -> Option<HeaderValue> {
+  let mut value =
+    HeaderValue::from_str(&format!("Bearer {}", token.expose()))
+      .ok()?;
+  value.set_sensitive(true);
+  Some(value)
+}
+
+/// Fetches the current rate limit from `/rate_limit`.
+///
+/// If `token` is given, the request is authenticated with it, which makes
+/// this a cheap way to validate a token: the endpoint doesn't count against
+/// the rate limit.
+pub async fn fetch_rate_limit(
+  client: &impl HttpClient,
+  token: Option<&GitHubToken>,
+) -> Result<GitHubRateLimit, FetchRateLimitError> {
+  let mut headers = HeaderMap::new();
+  if let Some(token) = token {
+    let value = bearer_auth_header(token)
+      .ok_or(FetchRateLimitError::InvalidToken)?;
+    headers.insert(AUTHORIZATION, value);
+  }
+
+  let response = client
+    .get_with_headers("https://api.github.com/rate_limit", headers)
+    .await?;
+
+  let status = response.status();
+  if status == StatusCode::UNAUTHORIZED {
+    return Err(FetchRateLimitError::Unauthorized);
+  }
+  if !status.is_success() {
+    return Err(FetchRateLimitError::Status(status));
+  }
+
+  let body = response.text().await.map_err(HttpClientError::from)?;
+  let RateLimitResponse { rate } = serde_json::from_str(&body)?;
+
+  Ok(GitHubRateLimit {
+    limit: rate.limit,
+    remaining: rate.remaining,
+    reset_at: DateTime::from_timestamp(rate.reset, 0)
+      .ok_or(FetchRateLimitError::InvalidReset(rate.reset))?,
+  })
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+
+  fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+      headers.insert(*name, HeaderValue::from_static(value));
+    }
+    headers
+  }
+
+  #[test]
+  fn test_get_rate_limit_reset_primary_limit() {
+    let now = Utc::now();
+    let reset_at = get_rate_limit_reset(
+      StatusCode::FORBIDDEN,
+      &headers(&[
+        ("x-ratelimit-remaining", "0"),
+        ("x-ratelimit-reset", "1700000000"),
+      ]),
+      "",
+      now,
+    );
+
+    assert_eq!(reset_at, DateTime::from_timestamp(1_700_000_000, 0));
+  }
+
+  #[test]
+  fn test_get_rate_limit_reset_retry_after() {
+    let now = Utc::now();
+    let reset_at = get_rate_limit_reset(
+      StatusCode::TOO_MANY_REQUESTS,
+      &headers(&[("retry-after", "30")]),
+      "",
+      now,
+    );
+
+    assert_eq!(reset_at, Some(now + Duration::seconds(30)));
+  }
+
+  #[test]
+  fn test_get_rate_limit_reset_message_only() {
+    let now = Utc::now();
+    let reset_at = get_rate_limit_reset(
+      StatusCode::FORBIDDEN,
+      &HeaderMap::new(),
+      r#"{"message": "API rate limit exceeded"}"#,
+      now,
+    );
+
+    assert_eq!(reset_at, Some(now + DEFAULT_RATE_LIMIT_WAIT));
+  }
+
+  #[test]
+  fn test_get_rate_limit_reset_not_rate_limited() {
+    let now = Utc::now();
+
+    assert_eq!(
+      get_rate_limit_reset(
+        StatusCode::FORBIDDEN,
+        &headers(&[("x-ratelimit-remaining", "12")]),
+        r#"{"message": "Resource not accessible"}"#,
+        now,
+      ),
+      None
+    );
+    assert_eq!(
+      get_rate_limit_reset(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        &headers(&[("x-ratelimit-remaining", "0")]),
+        "",
+        now,
+      ),
+      None
+    );
+  }
+}
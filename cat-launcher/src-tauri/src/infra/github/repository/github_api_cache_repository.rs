@@ -0,0 +1,48 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+/// A previously received GitHub API response and its cache validators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedGitHubResponse {
+  /// The `ETag` header of the response, if any.
+  pub etag: Option<String>,
+  /// The `Last-Modified` header of the response, if any.
+  pub last_modified: Option<String>,
+  /// The `Link` header of the response, used for pagination.
+  pub link: Option<String>,
+  /// The response body.
+  pub body: String,
+}
+
+/// Errors that can occur when interacting with the GitHub API cache repository.
+#[derive(thiserror::Error, Debug)]
+pub enum GitHubApiCacheRepositoryError {
+  /// An error occurred while retrieving a cached response.
+  #[error("failed to get cached GitHub response: {0}")]
+  Get(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while storing a response.
+  #[error("failed to update cached GitHub response: {0}")]
+  Update(Box<dyn Error + Send + Sync>),
+}
+
+/// A repository for caching GitHub API responses by request URL.
+#[async_trait]
+pub trait GitHubApiCacheRepository: Send + Sync {
+  /// Retrieves the cached response for the given URL, if any.
+  async fn get_cached_response(
+    &self,
+    url: &str,
+  ) -> Result<
+    Option<CachedGitHubResponse>,
+    GitHubApiCacheRepositoryError,
+  >;
+
+  /// Stores the response for the given URL, replacing any previous one.
+  async fn update_cached_response(
+    &self,
+    url: &str,
+    response: &CachedGitHubResponse,
+  ) -> Result<(), GitHubApiCacheRepositoryError>;
+}
@@ -0,0 +1,7 @@
+pub mod github_api_cache_repository;
+pub mod sqlite_github_api_cache_repository;
+
+pub use github_api_cache_repository::{
+  CachedGitHubResponse, GitHubApiCacheRepository,
+  GitHubApiCacheRepositoryError,
+};
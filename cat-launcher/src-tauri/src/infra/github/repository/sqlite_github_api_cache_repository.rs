@@ -0,0 +1,145 @@
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::OptionalExtension;
+
+use crate::infra::github::repository::{
+  CachedGitHubResponse, GitHubApiCacheRepository,
+  GitHubApiCacheRepositoryError,
+};
+use crate::infra::repository::db_helper::run_db;
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+/// A SQLite-backed implementation of the `GitHubApiCacheRepository`.
+#[derive(Clone)]
+pub struct SqliteGitHubApiCacheRepository {
+  pool: Pool,
+}
+
+impl SqliteGitHubApiCacheRepository {
+  /// Creates a new `SqliteGitHubApiCacheRepository` with the given connection pool.
+  pub fn new(pool: Pool) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl GitHubApiCacheRepository for SqliteGitHubApiCacheRepository {
+  async fn get_cached_response(
+    &self,
+    url: &str,
+  ) -> Result<
+    Option<CachedGitHubResponse>,
+    GitHubApiCacheRepositoryError,
+  > {
+    let pool = self.pool.clone();
+    let url = url.to_string();
+
+    run_db(pool, move |conn| {
+      conn
+        .query_row(
+          "SELECT etag, last_modified, link, body FROM github_api_cache WHERE url = ?1",
+          [&url],
+          |row| {
+            Ok(CachedGitHubResponse {
+              etag: row.get(0)?,
+              last_modified: row.get(1)?,
+              link: row.get(2)?,
+              body: row.get(3)?,
+            })
+          },
+        )
+        .optional()
+    })
+    .await
+    .map_err(GitHubApiCacheRepositoryError::Get)
+  }
+
+  async fn update_cached_response(
+    &self,
+    url: &str,
+    response: &CachedGitHubResponse,
+  ) -> Result<(), GitHubApiCacheRepositoryError> {
+    let pool = self.pool.clone();
+    let url = url.to_string();
+    let response = response.clone();
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO github_api_cache (url, etag, last_modified, link, body) VALUES (?1, ?2, ?3, ?4, ?5)",
+        (
+          &url,
+          &response.etag,
+          &response.last_modified,
+          &response.link,
+          &response.body,
+        ),
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(GitHubApiCacheRepositoryError::Update)
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::infra::testing::test_database::TestDatabase;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_get_cached_response_missing() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo = SqliteGitHubApiCacheRepository::new(db.pool().clone());
+
+    let cached = repo
+      .get_cached_response(
+        "https://api.github.com/repos/a/b/releases",
+      )
+      .await?;
+    assert_eq!(cached, None);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_update_and_get_cached_response() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo = SqliteGitHubApiCacheRepository::new(db.pool().clone());
+    let url = "https://api.github.com/repos/a/b/releases";
+
+    let first = CachedGitHubResponse {
+      etag: Some("\"abc\"".to_string()),
+      last_modified: None,
+      link: Some(
+        "<https://api.github.com/next>; rel=\"next\"".into(),
+      ),
+      body: "[]".to_string(),
+    };
+    repo.update_cached_response(url, &first).await?;
+    assert_eq!(repo.get_cached_response(url).await?, Some(first));
+
+    let second = CachedGitHubResponse {
+      etag: None,
+      last_modified: Some(
+        "Wed, 21 Oct 2015 07:28:00 GMT".to_string(),
+      ),
+      link: None,
+      body: "[1]".to_string(),
+    };
+    repo.update_cached_response(url, &second).await?;
+    assert_eq!(repo.get_cached_response(url).await?, Some(second));
+
+    Ok(())
+  }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -18,3 +20,26 @@ pub struct GitHubAuthor {
   #[serde(with = "rfc3339")]
   pub date: DateTime<Utc>,
 }
+
+/// A GitHub personal access token.
+///
+/// The `Debug` output is redacted so that the token never ends up in logs.
+#[derive(Clone, PartialEq, Eq)]
+pub struct GitHubToken(String);
+
+impl GitHubToken {
+  pub fn new(token: impl Into<String>) -> Self {
+    Self(token.into())
+  }
+
+  /// Returns the raw token. Only use this to authenticate requests.
+  pub fn expose(&self) -> &str {
+    &self.0
+  }
+}
+
+impl fmt::Debug for GitHubToken {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("GitHubToken(<redacted>)")
+  }
+}
@@ -1,12 +1,127 @@
-use reqwest::header::LINK;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use reqwest::header::{
+  ETAG, HeaderMap, HeaderName, HeaderValue, IF_MODIFIED_SINCE,
+  IF_NONE_MATCH, LAST_MODIFIED, LINK,
+};
 
+use crate::infra::github::rate_limit::get_rate_limit_reset;
 use crate::infra::github::release::GitHubRelease;
+use crate::infra::github::repository::{
+  CachedGitHubResponse, GitHubApiCacheRepository,
+};
 use crate::infra::http_client::{HttpClient, HttpClientError};
 
+#[derive(thiserror::Error, Debug)]
+pub enum GitHubApiRequestError {
+  #[error("failed to make API call: {0}")]
+  Fetch(#[from] HttpClientError),
+
+  #[error("GitHub API returned status: {0}")]
+  Status(StatusCode),
+
+  #[error("GitHub API rate limit exceeded until {reset_at}")]
+  RateLimited { reset_at: DateTime<Utc> },
+}
+
+fn header_string(
+  headers: &HeaderMap,
+  name: HeaderName,
+) -> Option<String> {
+  headers
+    .get(name)
+    .and_then(|value| value.to_str().ok())
+    .map(str::to_string)
+}
+
+/// Sends a GET request to the GitHub API, revalidating any cached response.
+///
+/// If a response for `url` was cached earlier, its `ETag` and `Last-Modified`
+/// values are sent as `If-None-Match` and `If-Modified-Since`. A
+/// `304 Not Modified` is then answered from the cache; GitHub does not count
+/// such responses against the rate limit. If there's no cached response to
+/// answer it from, the request is repeated unconditionally.
+pub async fn get_github_api_response(
+  client: &impl HttpClient,
+  url: &str,
+  cache_repository: &impl GitHubApiCacheRepository,
+) -> Result<CachedGitHubResponse, GitHubApiRequestError> {
+  let cached = cache_repository
+    .get_cached_response(url)
+    .await
+    .unwrap_or_default(); // It's okay to make an unconditional request.
+
+  let mut headers = HeaderMap::new();
+  if let Some(cached) = &cached {
+    if let Some(etag) = cached
+      .etag
+      .as_deref()
+      .and_then(|etag| HeaderValue::from_str(etag).ok())
+    {
+      headers.insert(IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = cached
+      .last_modified
+      .as_deref()
+      .and_then(|date| HeaderValue::from_str(date).ok())
+    {
+      headers.insert(IF_MODIFIED_SINCE, last_modified);
+    }
+  }
+
+  let mut response = client.get_with_headers(url, headers).await?;
+  if response.status() == StatusCode::NOT_MODIFIED {
+    match cached {
+      Some(cached) => return Ok(cached),
+      // There's nothing to answer a 304 from, so ask for the whole
+      // response instead.
+      None => {
+        response =
+          client.get_with_headers(url, HeaderMap::new()).await?;
+      }
+    }
+  }
+  let status = response.status();
+
+  if !status.is_success() {
+    let headers = response.headers().clone();
+    let body = response.text().await.unwrap_or_default();
+    if let Some(reset_at) =
+      get_rate_limit_reset(status, &headers, &body, Utc::now())
+    {
+      return Err(GitHubApiRequestError::RateLimited { reset_at });
+    }
+
+    return Err(GitHubApiRequestError::Status(status));
+  }
+
+  let headers = response.headers();
+  let etag = header_string(headers, ETAG);
+  let last_modified = header_string(headers, LAST_MODIFIED);
+  let link = header_string(headers, LINK);
+
+  let body = response.text().await.map_err(HttpClientError::from)?;
+
+  let fresh = CachedGitHubResponse {
+    etag,
+    last_modified,
+    link,
+    body,
+  };
+
+  if fresh.etag.is_some() || fresh.last_modified.is_some() {
+    // Failing to cache only means the next request is unconditional.
+    let _ =
+      cache_repository.update_cached_response(url, &fresh).await;
+  }
+
+  Ok(fresh)
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum GitHubReleaseFetchError {
   #[error("failed to fetch from GitHub: {0}")]
-  Fetch(#[from] HttpClientError),
+  Fetch(#[from] GitHubApiRequestError),
 
   #[error("failed to parse GitHub response: {0}")]
   Parse(#[from] serde_json::Error),
@@ -24,10 +139,19 @@ fn next_page_url(link_header: &str) -> Option<String> {
   })
 }
 
-pub async fn fetch_github_releases(
+/// Fetches releases newest first, page by page, until a page contains a
+/// release for which `is_known` returns true.
+///
+/// Every release of the fetched pages is returned, including known ones, so
+/// that changes to them (e.g. assets uploaded after they were first fetched)
+/// are picked up. No further pages are requested, so syncing against a cache
+/// only requests the first page and the pages that contain new releases.
+pub async fn fetch_github_releases_until(
   client: &impl HttpClient,
   repo: &str,
   num_releases: Option<usize>,
+  cache_repository: &impl GitHubApiCacheRepository,
+  is_known: impl Fn(&GitHubRelease) -> bool,
 ) -> Result<Vec<GitHubRelease>, GitHubReleaseFetchError> {
   if let Some(0) = num_releases {
     return Ok(Vec::new());
@@ -50,28 +174,17 @@ pub async fn fetch_github_releases(
       break;
     }
 
-    let response = client.get(&url).await?;
-    response
-      .error_for_status_ref()
-      .map_err(HttpClientError::from)?;
-
-    let link_header = response
-      .headers()
-      .get(LINK)
-      .and_then(|value| value.to_str().ok());
+    let response =
+      get_github_api_response(client, &url, cache_repository).await?;
 
-    next_url = link_header.and_then(next_page_url);
+    next_url = response.link.as_deref().and_then(next_page_url);
 
-    let response_text =
-      response.text().await.map_err(HttpClientError::from)?;
-    match serde_json::from_str::<Vec<GitHubRelease>>(&response_text) {
-      Ok(releases) => {
-        all_releases.extend(releases);
-      }
-      Err(e) => {
-        return Err(GitHubReleaseFetchError::Parse(e));
-      }
+    let releases =
+      serde_json::from_str::<Vec<GitHubRelease>>(&response.body)?;
+    if releases.iter().any(&is_known) {
+      next_url = None;
     }
+    all_releases.extend(releases);
   }
 
   Ok(all_releases)
@@ -80,7 +193,7 @@ pub async fn fetch_github_releases(
 #[derive(thiserror::Error, Debug)]
 pub enum FetchGitHubReleaseByTagError {
   #[error("failed to fetch from GitHub: {0}")]
-  Fetch(#[from] HttpClientError),
+  Fetch(#[from] GitHubApiRequestError),
 
   #[error("failed to parse GitHub response: {0}")]
   Parse(#[from] serde_json::Error),
@@ -90,6 +203,7 @@ pub async fn fetch_github_release_by_tag(
   client: &impl HttpClient,
   repo: &str,
   tag: &str,
+  cache_repository: &impl GitHubApiCacheRepository,
 ) -> Result<GitHubRelease, FetchGitHubReleaseByTagError> {
   let url = format!(
     "https://api.github.com/repos/{}/releases/tags/{}",
@@ -97,12 +211,93 @@ pub async fn fetch_github_release_by_tag(
     urlencoding::encode(tag)
   );
 
-  let response = client.get(&url).await?;
-  response
-    .error_for_status_ref()
-    .map_err(HttpClientError::from)?;
+  let response =
+    get_github_api_response(client, &url, cache_repository).await?;
+  Ok(serde_json::from_str::<GitHubRelease>(&response.body)?)
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use std::sync::{Mutex, PoisonError};
+
+  use async_trait::async_trait;
+  use reqwest::Response;
+
+  use super::*;
+  use crate::infra::github::repository::sqlite_github_api_cache_repository::SqliteGitHubApiCacheRepository;
+  use crate::infra::testing::test_database::TestDatabase;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  /// Answers the first request with a `304 Not Modified`, whatever its
+  /// headers, and later ones with a fresh response.
+  #[derive(Default)]
+  struct NotModifiedOnceClient {
+    requests: Mutex<Vec<HeaderMap>>,
+  }
+
+  #[async_trait]
+  impl HttpClient for NotModifiedOnceClient {
+    async fn get(
+      &self,
+      url: &str,
+    ) -> Result<Response, HttpClientError> {
+      self.get_with_headers(url, HeaderMap::new()).await
+    }
+
+    async fn get_with_headers(
+      &self,
+      _url: &str,
+      headers: HeaderMap,
+    ) -> Result<Response, HttpClientError> {
+      let mut requests =
+        self.requests.lock().unwrap_or_else(PoisonError::into_inner);
+      requests.push(headers);
 
-  let response_text =
-    response.text().await.map_err(HttpClientError::from)?;
-  Ok(serde_json::from_str::<GitHubRelease>(&response_text)?)
+      let response = if requests.len() == 1 {
+        tauri::http::Response::builder()
+          .status(StatusCode::NOT_MODIFIED)
+          .body(Vec::new())
+      } else {
+        tauri::http::Response::builder()
+          .status(StatusCode::OK)
+          .header(ETAG, "\"fresh\"")
+          .body(b"[]".to_vec())
+      };
+
+      Ok(Response::from(response.unwrap()))
+    }
+  }
+
+  #[tokio::test]
+  async fn test_not_modified_without_cached_response_is_retried()
+  -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let cache =
+      SqliteGitHubApiCacheRepository::new(db.pool().clone());
+    let client = NotModifiedOnceClient::default();
+    let url = "https://api.github.com/repos/owner/repo/releases";
+
+    let response =
+      get_github_api_response(&client, url, &cache).await?;
+
+    assert_eq!(response.body, "[]");
+    assert_eq!(response.etag.as_deref(), Some("\"fresh\""));
+    assert_eq!(cache.get_cached_response(url).await?, Some(response));
+
+    let requests = client.requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[1].contains_key(IF_NONE_MATCH));
+    assert!(!requests[1].contains_key(IF_MODIFIED_SINCE));
+
+    Ok(())
+  }
 }
@@ -1,5 +1,13 @@
+use std::sync::{Arc, RwLock};
+
 use async_trait::async_trait;
-use reqwest::{Client, Response};
+use reqwest::header::{AUTHORIZATION, HeaderMap};
+use reqwest::{Client, RequestBuilder, Response};
+use url::Url;
+
+use crate::infra::connectivity::{Connectivity, ConnectivityState};
+use crate::infra::github::rate_limit::bearer_auth_header;
+use crate::infra::github::types::GitHubToken;
 
 #[derive(Debug, thiserror::Error)]
 pub enum HttpClientError {
@@ -14,12 +22,46 @@ pub enum HttpClientError {
 
   #[error("failed to set URL component")]
   UrlComponentError,
+
+  #[error("offline mode is enabled")]
+  Offline,
+}
+
+/// Returns whether `error`, or any error that caused it, is
+/// `HttpClientError::Offline`.
+pub fn is_offline_error(
+  error: &(dyn std::error::Error + 'static),
+) -> bool {
+  let mut current = Some(error);
+  while let Some(error) = current {
+    if matches!(
+      error.downcast_ref::<HttpClientError>(),
+      Some(HttpClientError::Offline)
+    ) {
+      return true;
+    }
+    current = error.source();
+  }
+  false
 }
 
 #[async_trait]
 pub trait HttpClient: Send + Sync {
   async fn get(&self, url: &str)
   -> Result<Response, HttpClientError>;
+
+  /// Sends a GET request with additional request headers.
+  async fn get_with_headers(
+    &self,
+    url: &str,
+    headers: HeaderMap,
+  ) -> Result<Response, HttpClientError>;
+
+  /// Returns whether offline mode is enabled, in which case every request
+  /// fails with `HttpClientError::Offline`.
+  fn is_offline(&self) -> bool {
+    false
+  }
 }
 
 #[async_trait]
@@ -30,6 +72,19 @@ impl HttpClient for Client {
   ) -> Result<Response, HttpClientError> {
     self.get(url).send().await.map_err(HttpClientError::from)
   }
+
+  async fn get_with_headers(
+    &self,
+    url: &str,
+    headers: HeaderMap,
+  ) -> Result<Response, HttpClientError> {
+    self
+      .get(url)
+      .headers(headers)
+      .send()
+      .await
+      .map_err(HttpClientError::from)
+  }
 }
 
 /// Creates and returns a `reqwest::Client` instance configured with a user-agent
@@ -41,12 +96,95 @@ pub fn create_http_client() -> Result<Client, HttpClientError> {
 #[derive(Clone, Debug)]
 pub struct ReqwestHttpClient {
   client: Client,
+  github_token: Arc<RwLock<Option<GitHubToken>>>,
+  connectivity: Arc<Connectivity>,
 }
 
 impl ReqwestHttpClient {
   pub fn new(client: Client) -> Self {
-    Self { client }
+    Self {
+      client,
+      github_token: Arc::default(),
+      connectivity: Arc::default(),
+    }
+  }
+
+  /// Returns the connectivity state of this client and its clones.
+  pub fn connectivity_state(&self) -> ConnectivityState {
+    self.connectivity.state()
+  }
+
+  /// Returns a receiver that is notified whenever the connectivity state
+  /// changes.
+  pub fn subscribe_to_connectivity(
+    &self,
+  ) -> tokio::sync::watch::Receiver<ConnectivityState> {
+    self.connectivity.subscribe()
   }
+
+  /// Enables or disables offline mode for this client and its clones.
+  pub fn set_offline_mode(&self, enabled: bool) {
+    self.connectivity.set_offline_mode(enabled);
+  }
+
+  /// Sets the token that is attached to GitHub API requests.
+  ///
+  /// Passing `None` makes subsequent requests unauthenticated again.
+  pub fn set_github_token(&self, token: Option<GitHubToken>) {
+    let mut guard = match self.github_token.write() {
+      Ok(guard) => guard,
+      Err(poisoned) => poisoned.into_inner(),
+    };
+    *guard = token;
+  }
+
+  fn request(
+    &self,
+    url: &str,
+    mut headers: HeaderMap,
+  ) -> RequestBuilder {
+    // The token is only ever sent to the GitHub API, never to asset
+    // downloads or third-party hosts. An explicit Authorization header
+    // takes precedence.
+    if !headers.contains_key(AUTHORIZATION)
+      && is_github_api_url(url)
+      && let Ok(guard) = self.github_token.read()
+      && let Some(value) = guard.as_ref().and_then(bearer_auth_header)
+    {
+      headers.insert(AUTHORIZATION, value);
+    }
+
+    self.client.get(url).headers(headers)
+  }
+
+  async fn send(
+    &self,
+    url: &str,
+    headers: HeaderMap,
+  ) -> Result<Response, HttpClientError> {
+    if self.connectivity.is_offline() {
+      return Err(HttpClientError::Offline);
+    }
+
+    let result = self.request(url, headers).send().await;
+    // Any response, even an error status, means the server was reached.
+    self.connectivity.record_request(
+      result
+        .as_ref()
+        .err()
+        .is_none_or(|e| !e.is_connect() && !e.is_timeout()),
+    );
+
+    result.map_err(HttpClientError::from)
+  }
+}
+
+/// Returns whether the URL points to the GitHub REST API.
+fn is_github_api_url(url: &str) -> bool {
+  Url::parse(url).is_ok_and(|url| {
+    url.scheme() == "https"
+      && url.host_str() == Some("api.github.com")
+  })
 }
 
 #[async_trait]
@@ -55,11 +193,122 @@ impl HttpClient for ReqwestHttpClient {
     &self,
     url: &str,
   ) -> Result<Response, HttpClientError> {
-    self
-      .client
-      .get(url)
-      .send()
-      .await
-      .map_err(HttpClientError::from)
+    self.send(url, HeaderMap::new()).await
+  }
+
+  async fn get_with_headers(
+    &self,
+    url: &str,
+    headers: HeaderMap,
+  ) -> Result<Response, HttpClientError> {
+    self.send(url, headers).await
+  }
+
+  fn is_offline(&self) -> bool {
+    self.connectivity.is_offline()
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_is_github_api_url() {
+    assert!(is_github_api_url(
+      "https://api.github.com/repos/a/b/releases"
+    ));
+    assert!(!is_github_api_url(
+      "http://api.github.com/repos/a/b/releases"
+    ));
+    assert!(!is_github_api_url(
+      "https://github.com/a/b/releases/download/v1/asset.zip"
+    ));
+    assert!(!is_github_api_url(
+      "https://api.github.com.example.com/repos/a/b"
+    ));
+    assert!(!is_github_api_url("not a url"));
+  }
+
+  #[test]
+  fn test_request_attaches_token_only_to_github_api() {
+    let client = ReqwestHttpClient::new(Client::new());
+    client.set_github_token(Some(GitHubToken::new("secret")));
+
+    let api_request = client
+      .request("https://api.github.com/rate_limit", HeaderMap::new())
+      .build()
+      .unwrap();
+    assert_eq!(
+      api_request
+        .headers()
+        .get(AUTHORIZATION)
+        .unwrap()
+        .to_str()
+        .unwrap(),
+      "Bearer secret"
+    );
+
+    let download_request = client
+      .request(
+        "https://github.com/a/b/releases/download/v1/asset.zip",
+        HeaderMap::new(),
+      )
+      .build()
+      .unwrap();
+    assert!(download_request.headers().get(AUTHORIZATION).is_none());
+
+    client.set_github_token(None);
+    let api_request = client
+      .request("https://api.github.com/rate_limit", HeaderMap::new())
+      .build()
+      .unwrap();
+    assert!(api_request.headers().get(AUTHORIZATION).is_none());
+  }
+
+  #[tokio::test]
+  async fn test_offline_mode_skips_requests() {
+    let client = ReqwestHttpClient::new(Client::new());
+    let clone = client.clone();
+    client.set_offline_mode(true);
+
+    // Clones, e.g. the one used by the downloader, share the state.
+    let result = clone.get("https://api.github.com").await;
+    assert!(matches!(result, Err(HttpClientError::Offline)));
+    assert!(clone.is_offline());
+
+    client.set_offline_mode(false);
+    assert!(!client.is_offline());
+    assert_eq!(
+      client.connectivity_state(),
+      ConnectivityState::Online
+    );
+  }
+
+  #[test]
+  fn test_is_offline_error() {
+    #[derive(thiserror::Error, Debug)]
+    #[error("wrapped: {0}")]
+    struct Wrapped(#[from] HttpClientError);
+
+    assert!(is_offline_error(&Wrapped(HttpClientError::Offline)));
+    assert!(!is_offline_error(&Wrapped(
+      HttpClientError::UrlComponentError
+    )));
+  }
+
+  #[test]
+  fn test_debug_does_not_leak_token() {
+    let client = ReqwestHttpClient::new(Client::new());
+    client.set_github_token(Some(GitHubToken::new("secret")));
+
+    assert!(!format!("{:?}", client).contains("secret"));
   }
 }
@@ -1,15 +1,21 @@
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
 
-use downloader::progress::Reporter;
+use downloader::progress::{Phase, Reporter};
 use tauri::ipc::{Channel, InvokeResponseBody};
 
-use crate::infra::installation_progress_monitor::download_progress::DownloadProgress;
+use crate::infra::installation_progress_monitor::download_progress::{
+  DownloadProgress, InstallationPhase,
+};
 
-/// Reports download progress back to the frontend via a Tauri channel.
+/// Reports download and extraction progress back to the frontend via a
+/// Tauri channel.
 pub struct ChannelReporter {
   channel: Channel,
   // Use AtomicU64 for interior mutability across threads without locking.
   total_bytes: AtomicU64,
+  phase: Mutex<InstallationPhase>,
+  message: Mutex<Option<String>>,
 }
 
 impl ChannelReporter {
@@ -18,24 +24,55 @@ impl ChannelReporter {
     Self {
       channel,
       total_bytes: AtomicU64::new(0),
+      phase: Mutex::new(InstallationPhase::Downloading),
+      message: Mutex::new(None),
     }
   }
 }
 
 impl Reporter for ChannelReporter {
   /// Sets up the total bytes to be downloaded.
-  fn setup(&self, max_progress: Option<u64>, _message: &str) {
-    if let Some(total) = max_progress {
-      self.total_bytes.store(total, Ordering::Relaxed);
-    }
+  fn setup(&self, max_progress: Option<u64>, message: &str) {
+    self.setup_phase(Phase::Download, max_progress, message);
   }
 
-  /// Sends the current download progress to the channel.
+  /// Sets up the total bytes to be downloaded or extracted, and which of
+  /// the two is reported from now on.
+  fn setup_phase(
+    &self,
+    phase: Phase,
+    max_progress: Option<u64>,
+    _message: &str,
+  ) {
+    self
+      .total_bytes
+      .store(max_progress.unwrap_or(0), Ordering::Relaxed);
+
+    let phase = match phase {
+      Phase::Download => InstallationPhase::Downloading,
+      Phase::Extract => InstallationPhase::Extracting,
+    };
+    *self.phase.lock().unwrap_or_else(PoisonError::into_inner) =
+      phase;
+    *self.message.lock().unwrap_or_else(PoisonError::into_inner) =
+      None;
+  }
+
+  /// Sends the current progress to the channel.
   fn progress(&self, current: u64) {
     let total_bytes = self.total_bytes.load(Ordering::Relaxed);
+    let phase =
+      *self.phase.lock().unwrap_or_else(PoisonError::into_inner);
+    let message = self
+      .message
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .clone();
     let progress = DownloadProgress {
+      phase,
       bytes_downloaded: current,
       total_bytes,
+      message,
     };
     if let Ok(value) = serde_json::to_value(progress) {
       let _ = self
@@ -44,7 +81,17 @@ impl Reporter for ChannelReporter {
     }
   }
 
-  fn set_message(&self, _message: &str) {}
+  /// Keeps the message to send with the next progress. Only extraction
+  /// messages are meant for users; the downloader's name the URL and
+  /// status code of a finished download.
+  fn set_message(&self, message: &str) {
+    let phase =
+      *self.phase.lock().unwrap_or_else(PoisonError::into_inner);
+    if phase == InstallationPhase::Extracting {
+      *self.message.lock().unwrap_or_else(PoisonError::into_inner) =
+        Some(message.to_string());
+    }
+  }
 
   fn done(&self) {}
 }
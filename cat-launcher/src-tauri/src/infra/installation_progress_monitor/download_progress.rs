@@ -1,12 +1,29 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+/// Represents what an installation is busy with.
+pub enum InstallationPhase {
+  /// The archive is being downloaded.
+  Downloading,
+  /// The downloaded archive is being extracted.
+  Extracting,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
-/// Represents the progress of a file download.
+/// Represents the progress of a file download or of the extraction that
+/// follows it.
 pub struct DownloadProgress {
-  /// The number of bytes already downloaded.
+  /// Whether the progress is of the download or of the extraction.
+  pub phase: InstallationPhase,
+  /// The number of bytes already downloaded or extracted.
   pub bytes_downloaded: u64,
-  /// The total number of bytes to be downloaded.
+  /// The total number of bytes to be downloaded or extracted. 0 if unknown.
   pub total_bytes: u64,
+  /// While extracting, how many entries were extracted, e.g. "3 of 10 entries".
+  pub message: Option<String>,
 }
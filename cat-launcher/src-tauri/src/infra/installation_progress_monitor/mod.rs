@@ -1,2 +1,3 @@
 pub mod channel_reporter;
 pub mod download_progress;
+pub mod noop_reporter;
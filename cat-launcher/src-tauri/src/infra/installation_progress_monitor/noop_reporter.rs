@@ -0,0 +1,12 @@
+use downloader::progress::Reporter;
+
+/// Discards progress, for installations that run without a frontend
+/// listening, e.g. scheduled updates.
+pub struct NoopReporter;
+
+impl Reporter for NoopReporter {
+  fn setup(&self, _max_progress: Option<u64>, _message: &str) {}
+  fn progress(&self, _current: u64) {}
+  fn set_message(&self, _message: &str) {}
+  fn done(&self) {}
+}
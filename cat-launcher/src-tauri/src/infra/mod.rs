@@ -1,11 +1,15 @@
 pub mod archive;
 pub mod autoupdate;
+pub mod connectivity;
 pub mod download;
+pub mod download_queue;
 pub mod github;
 pub mod http_client;
 pub mod installation_progress_monitor;
 pub mod repository;
 pub mod rfc3339;
+pub mod snapshot_store;
 #[cfg(test)]
 pub(crate) mod testing;
 pub mod utils;
+pub mod watched;
@@ -4,7 +4,7 @@ use rusqlite::Connection;
 use strum::IntoEnumIterator;
 
 use crate::theme::theme::Theme;
-use crate::variants::game_variant::GameVariant;
+use crate::variants::variant_descriptor::builtin_descriptors;
 
 #[derive(thiserror::Error, Debug)]
 /// Errors that can occur during database initialization.
@@ -33,10 +33,11 @@ pub fn apply_schema(
 pub fn seed_reference_data(
   conn: &Connection,
 ) -> Result<(), InitializeDatabaseError> {
-  for variant in GameVariant::iter() {
+  // Custom variants are added when their descriptors are stored.
+  for descriptor in builtin_descriptors() {
     conn.execute(
       "INSERT OR IGNORE INTO variants (name) VALUES (?1)",
-      [variant.to_string()],
+      [descriptor.id],
     )?;
   }
 
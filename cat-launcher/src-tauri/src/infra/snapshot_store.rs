@@ -0,0 +1,505 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinError;
+use walkdir::WalkDir;
+
+use crate::constants::SNAPSHOT_CHUNK_SIZE;
+use crate::filesystem::disk_space::{
+  InsufficientSpace, ensure_available_space,
+};
+use crate::filesystem::utils::get_sha256;
+
+/// Errors that can occur when creating, restoring or pruning snapshots.
+#[derive(thiserror::Error, Debug)]
+pub enum SnapshotError {
+  /// Failed to read or write a file.
+  #[error("failed to access snapshot files: {0}")]
+  Io(#[from] io::Error),
+
+  /// Failed to walk a directory that is being snapshotted.
+  #[error("failed to walk directory: {0}")]
+  Walk(#[from] walkdir::Error),
+
+  /// Failed to read or write a manifest.
+  #[error("invalid snapshot manifest: {0}")]
+  Manifest(#[from] serde_json::Error),
+
+  /// A path can't be recorded in, or restored from, a manifest.
+  #[error("invalid path in snapshot: {0}")]
+  InvalidPath(String),
+
+  /// A blob a manifest refers to is missing or doesn't match its hash.
+  #[error("snapshot blob {0} is missing or corrupted")]
+  CorruptBlob(String),
+
+  #[error(transparent)]
+  InsufficientSpace(#[from] InsufficientSpace),
+
+  #[error("unexpected join error: {0}")]
+  Join(#[from] JoinError),
+}
+
+/// The content of a snapshot, referring to blobs in the store.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+  /// The directories in the snapshot, so that empty ones are restored too.
+  directories: Vec<String>,
+  files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestFile {
+  /// The path relative to the base directory, separated by `/`.
+  path: String,
+  /// The hashes of the chunks the file consists of, in order.
+  chunks: Vec<String>,
+}
+
+/// A blob that was removed from the store because no snapshot refers to
+/// it anymore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrunedBlob {
+  /// Where the blob was stored.
+  pub path: PathBuf,
+  /// The size of the blob on disk.
+  pub size_in_bytes: u64,
+}
+
+/// Snapshots `paths_to_include`, which must be inside `source_dir`, into
+/// the store at `store_dir`, and writes the manifest of the snapshot to
+/// `manifest_path`. Paths that don't exist are skipped.
+///
+/// Files are split into chunks that are stored as compressed blobs named
+/// after their SHA-256 hash, so that content shared with earlier snapshots
+/// is only stored once. The manifest is written last, so a snapshot that
+/// failed leaves at most unreferenced blobs behind for pruning.
+pub async fn create_snapshot(
+  source_dir: &Path,
+  paths_to_include: &[PathBuf],
+  store_dir: &Path,
+  manifest_path: &Path,
+) -> Result<(), SnapshotError> {
+  let source_dir = source_dir.to_owned();
+  let paths_to_include = paths_to_include.to_vec();
+  let store_dir = store_dir.to_owned();
+  let manifest_path = manifest_path.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let mut manifest = Manifest::default();
+    for path in paths_to_include.iter().filter(|path| path.exists()) {
+      for entry in WalkDir::new(path).sort_by_file_name() {
+        let entry = entry?;
+        let relative_path =
+          get_manifest_path(&source_dir, entry.path())?;
+
+        // Links aren't followed, and saves don't have any.
+        if entry.file_type().is_dir() {
+          manifest.directories.push(relative_path);
+        } else if entry.file_type().is_file() {
+          manifest.files.push(ManifestFile {
+            path: relative_path,
+            chunks: store_file(entry.path(), &store_dir)?,
+          });
+        }
+      }
+    }
+
+    let part_path = manifest_path.with_extension("part");
+    fs::write(&part_path, serde_json::to_vec(&manifest)?)?;
+    fs::rename(&part_path, &manifest_path)?;
+    Ok(())
+  })
+  .await?
+}
+
+/// Restores the snapshot with the manifest at `manifest_path` from the
+/// store at `store_dir` into `target_dir`.
+///
+/// Like extracting an archive, files in the snapshot overwrite existing
+/// ones, and other files are left alone.
+pub async fn restore_snapshot(
+  manifest_path: &Path,
+  store_dir: &Path,
+  target_dir: &Path,
+) -> Result<(), SnapshotError> {
+  let manifest_path = manifest_path.to_owned();
+  let store_dir = store_dir.to_owned();
+  let target_dir = target_dir.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let manifest = read_manifest(&manifest_path)?;
+
+    for dir in &manifest.directories {
+      fs::create_dir_all(target_dir.join(get_relative_path(dir)?))?;
+    }
+
+    for file in &manifest.files {
+      let path = target_dir.join(get_relative_path(&file.path)?);
+      if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+      }
+
+      let mut output = File::create(&path)?;
+      for hash in &file.chunks {
+        output.write_all(&read_blob(&store_dir, hash)?)?;
+      }
+    }
+
+    Ok(())
+  })
+  .await?
+}
+
+/// Removes the blobs in the store at `store_dir` that none of the
+/// manifests at `manifest_paths` refer to. Manifests that don't exist are
+/// skipped.
+///
+/// Blobs modified less than `min_age` ago are kept, since they may belong
+/// to a snapshot whose manifest isn't written yet.
+pub async fn prune_snapshot_store(
+  store_dir: &Path,
+  manifest_paths: &[PathBuf],
+  min_age: Duration,
+) -> Result<Vec<PrunedBlob>, SnapshotError> {
+  let store_dir = store_dir.to_owned();
+  let manifest_paths = manifest_paths.to_vec();
+
+  tokio::task::spawn_blocking(move || {
+    let mut referenced = HashSet::new();
+    for manifest_path in &manifest_paths {
+      match read_manifest(manifest_path) {
+        Ok(manifest) => referenced
+          .extend(manifest.files.into_iter().flat_map(|f| f.chunks)),
+        Err(SnapshotError::Io(e))
+          if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+      }
+    }
+
+    let blobs_dir = store_dir.join("blobs");
+    if !blobs_dir.exists() {
+      return Ok(Vec::new());
+    }
+
+    let mut pruned = Vec::new();
+    for entry in WalkDir::new(&blobs_dir).min_depth(2).max_depth(2) {
+      let entry = entry?;
+      if referenced
+        .contains(entry.file_name().to_string_lossy().as_ref())
+      {
+        continue;
+      }
+
+      let metadata = entry.metadata()?;
+      // Something modified in the future was modified just now.
+      let age = metadata.modified()?.elapsed().unwrap_or_default();
+      if !metadata.is_file() || age < min_age {
+        continue;
+      }
+
+      fs::remove_file(entry.path())?;
+      pruned.push(PrunedBlob {
+        path: entry.into_path(),
+        size_in_bytes: metadata.len(),
+      });
+    }
+
+    Ok(pruned)
+  })
+  .await?
+}
+
+/// Stores the file at `path` in chunks, returning their hashes.
+fn store_file(
+  path: &Path,
+  store_dir: &Path,
+) -> Result<Vec<String>, SnapshotError> {
+  let mut file = File::open(path)?;
+  let mut chunks = Vec::new();
+  loop {
+    let mut chunk = Vec::new();
+    (&mut file)
+      .take(SNAPSHOT_CHUNK_SIZE)
+      .read_to_end(&mut chunk)?;
+    if chunk.is_empty() {
+      break;
+    }
+
+    let hash = get_sha256(&chunk);
+    store_blob(store_dir, &hash, &chunk)?;
+    chunks.push(hash);
+  }
+
+  Ok(chunks)
+}
+
+fn store_blob(
+  store_dir: &Path,
+  hash: &str,
+  chunk: &[u8],
+) -> Result<(), SnapshotError> {
+  let path = get_blob_path(store_dir, hash);
+  if path.exists() {
+    // Pruning spares recently modified blobs, so this keeps the blob
+    // around until the manifest that refers to it is written.
+    File::options()
+      .write(true)
+      .open(&path)?
+      .set_modified(SystemTime::now())?;
+    return Ok(());
+  }
+
+  ensure_available_space(store_dir, chunk.len() as u64)?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+
+  let part_path = path.with_extension("part");
+  let mut encoder = DeflateEncoder::new(
+    File::create(&part_path)?,
+    Compression::default(),
+  );
+  encoder.write_all(chunk)?;
+  encoder.finish()?;
+  fs::rename(&part_path, &path)?;
+
+  Ok(())
+}
+
+/// Reads a blob, checking that its content matches its hash.
+fn read_blob(
+  store_dir: &Path,
+  hash: &str,
+) -> Result<Vec<u8>, SnapshotError> {
+  let corrupt = || SnapshotError::CorruptBlob(hash.to_string());
+
+  let file = match File::open(get_blob_path(store_dir, hash)) {
+    Ok(file) => file,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+      return Err(corrupt());
+    }
+    Err(e) => return Err(e.into()),
+  };
+
+  let mut chunk = Vec::new();
+  match DeflateDecoder::new(file).read_to_end(&mut chunk) {
+    Ok(_) => {}
+    Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+      return Err(corrupt());
+    }
+    Err(e) => return Err(e.into()),
+  }
+
+  if get_sha256(&chunk) != hash {
+    return Err(corrupt());
+  }
+
+  Ok(chunk)
+}
+
+fn read_manifest(
+  manifest_path: &Path,
+) -> Result<Manifest, SnapshotError> {
+  Ok(serde_json::from_slice(&fs::read(manifest_path)?)?)
+}
+
+/// Blobs are spread over directories named after the first two characters
+/// of their hash, so that no directory gets too large.
+fn get_blob_path(store_dir: &Path, hash: &str) -> PathBuf {
+  store_dir
+    .join("blobs")
+    .join(hash.get(..2).unwrap_or_default())
+    .join(hash)
+}
+
+/// Returns `path` relative to `source_dir`, separated by `/` on all
+/// platforms, so that snapshots can be restored anywhere.
+fn get_manifest_path(
+  source_dir: &Path,
+  path: &Path,
+) -> Result<String, SnapshotError> {
+  let invalid =
+    || SnapshotError::InvalidPath(path.display().to_string());
+
+  let parts = path
+    .strip_prefix(source_dir)
+    .map_err(|_| invalid())?
+    .components()
+    .map(|component| match component {
+      Component::Normal(part) => part.to_str().ok_or_else(invalid),
+      _ => Err(invalid()),
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  Ok(parts.join("/"))
+}
+
+/// Returns a path from a manifest as a relative path, refusing any that
+/// would lead outside of the directory it's restored into.
+fn get_relative_path(path: &str) -> Result<PathBuf, SnapshotError> {
+  let mut relative_path = PathBuf::new();
+  for part in path.split('/') {
+    match Path::new(part).components().next() {
+      Some(Component::Normal(part)) => relative_path.push(part),
+      _ => return Err(SnapshotError::InvalidPath(path.to_string())),
+    }
+  }
+  Ok(relative_path)
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  fn count_blobs(store_dir: &Path) -> usize {
+    WalkDir::new(store_dir.join("blobs"))
+      .min_depth(2)
+      .into_iter()
+      .count()
+  }
+
+  #[tokio::test]
+  async fn test_snapshots_share_blobs_and_restore() -> TestResult {
+    let dir = TempDir::new()?;
+    let source_dir = dir.path().join("source");
+    let store_dir = dir.path().join("store");
+    let save_dir = source_dir.join("save");
+    fs::create_dir_all(save_dir.join("World").join("maps"))?;
+    fs::write(save_dir.join("World").join("world.json"), b"{}")?;
+    let large = vec![7u8; SNAPSHOT_CHUNK_SIZE as usize + 10];
+    fs::write(save_dir.join("World").join("large.sav"), &large)?;
+
+    let first = dir.path().join("first.json");
+    create_snapshot(
+      &source_dir,
+      std::slice::from_ref(&save_dir),
+      &store_dir,
+      &first,
+    )
+    .await?;
+    assert_eq!(count_blobs(&store_dir), 3);
+
+    fs::write(
+      save_dir.join("World").join("world.json"),
+      b"{\"a\":1}",
+    )?;
+    let second = dir.path().join("second.json");
+    create_snapshot(
+      &source_dir,
+      std::slice::from_ref(&save_dir),
+      &store_dir,
+      &second,
+    )
+    .await?;
+    // Only the changed file needed a new blob.
+    assert_eq!(count_blobs(&store_dir), 4);
+
+    let target_dir = dir.path().join("target");
+    restore_snapshot(&first, &store_dir, &target_dir).await?;
+    let restored = target_dir.join("save").join("World");
+    assert_eq!(fs::read(restored.join("world.json"))?, b"{}");
+    assert_eq!(fs::read(restored.join("large.sav"))?, large);
+    assert!(restored.join("maps").is_dir());
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_prune_snapshot_store() -> TestResult {
+    let dir = TempDir::new()?;
+    let source_dir = dir.path().join("source");
+    let store_dir = dir.path().join("store");
+    let save_dir = source_dir.join("save");
+    fs::create_dir_all(&save_dir)?;
+
+    fs::write(save_dir.join("world.json"), b"old")?;
+    let old = dir.path().join("old.json");
+    let paths = std::slice::from_ref(&save_dir);
+    create_snapshot(&source_dir, paths, &store_dir, &old).await?;
+    fs::write(save_dir.join("world.json"), b"new")?;
+    let new = dir.path().join("new.json");
+    create_snapshot(&source_dir, paths, &store_dir, &new).await?;
+
+    let pruned = prune_snapshot_store(
+      &store_dir,
+      &[old.clone(), new.clone()],
+      Duration::ZERO,
+    )
+    .await?;
+    assert!(pruned.is_empty());
+
+    fs::remove_file(&old)?;
+    let pruned = prune_snapshot_store(
+      &store_dir,
+      &[old.clone(), new.clone()],
+      Duration::ZERO,
+    )
+    .await?;
+    assert_eq!(pruned.len(), 1);
+    assert_eq!(count_blobs(&store_dir), 1);
+
+    let target_dir = dir.path().join("target");
+    restore_snapshot(&new, &store_dir, &target_dir).await?;
+    assert_eq!(fs::read(target_dir.join("save/world.json"))?, b"new");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_restore_snapshot_detects_corrupt_blob() -> TestResult
+  {
+    let dir = TempDir::new()?;
+    let source_dir = dir.path().join("source");
+    let store_dir = dir.path().join("store");
+    let save_dir = source_dir.join("save");
+    fs::create_dir_all(&save_dir)?;
+    fs::write(save_dir.join("world.json"), b"data")?;
+
+    let manifest = dir.path().join("manifest.json");
+    create_snapshot(
+      &source_dir,
+      std::slice::from_ref(&save_dir),
+      &store_dir,
+      &manifest,
+    )
+    .await?;
+
+    let hash = get_sha256(b"data");
+    let mut encoder =
+      DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(b"tampered")?;
+    fs::write(get_blob_path(&store_dir, &hash), encoder.finish()?)?;
+
+    let result = restore_snapshot(
+      &manifest,
+      &store_dir,
+      &dir.path().join("target"),
+    )
+    .await;
+    assert!(
+      matches!(result, Err(SnapshotError::CorruptBlob(h)) if h == hash)
+    );
+
+    Ok(())
+  }
+}
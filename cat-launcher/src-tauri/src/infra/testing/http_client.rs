@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use async_trait::async_trait;
+use reqwest::header::HeaderMap;
 use reqwest::{Client, Response};
 use url::Url;
 
@@ -83,4 +84,20 @@ impl HttpClient for TestHttpClient {
       .await
       .map_err(HttpClientError::from)
   }
+
+  async fn get_with_headers(
+    &self,
+    url: &str,
+    headers: HeaderMap,
+  ) -> Result<Response, HttpClientError> {
+    self.request_count.fetch_add(1, Ordering::Relaxed);
+    let rewritten_url = self.rewrite_url(url)?;
+    self
+      .client
+      .get(&rewritten_url)
+      .headers(headers)
+      .send()
+      .await
+      .map_err(HttpClientError::from)
+  }
 }
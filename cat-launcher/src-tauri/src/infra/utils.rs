@@ -2,9 +2,9 @@ use std::io;
 use std::path::Path;
 
 use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use tokio::fs;
-
-use crate::variants::GameVariant;
+use ts_rs::TS;
 
 /// A trait for assets in the launcher.
 pub trait Asset {
@@ -15,17 +15,6 @@ pub trait Asset {
   fn id(&self) -> &str;
 }
 
-/// Returns the GitHub repository string for a given game variant.
-pub fn get_github_repo_for_variant(
-  variant: &GameVariant,
-) -> &'static str {
-  match variant {
-    GameVariant::DarkDaysAhead => "CleverRaven/Cataclysm-DDA",
-    GameVariant::BrightNights => "cataclysmbnteam/Cataclysm-BN",
-    GameVariant::TheLastGeneration => "Cataclysm-TLG/Cataclysm-TLG",
-  }
-}
-
 /// Represents errors that can occur while reading and deserializing data from a file.
 #[derive(thiserror::Error, Debug)]
 pub enum ReadFromFileError {
@@ -45,7 +34,10 @@ pub async fn read_from_file<T: DeserializeOwned>(
   Ok(v)
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
 pub enum OS {
   Linux,
   Windows,
@@ -69,7 +61,10 @@ pub fn get_os_enum(
   }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
 pub enum Arch {
   ARM64,
   X64,
@@ -0,0 +1,97 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use tokio::sync::watch;
+
+/// State behind a mutex that can be waited on until it satisfies a
+/// condition.
+pub struct Watched<T> {
+  state: Mutex<T>,
+  // Woken whenever the state is updated.
+  changed: watch::Sender<()>,
+}
+
+impl<T: Default> Default for Watched<T> {
+  fn default() -> Self {
+    Self::new(T::default())
+  }
+}
+
+impl<T> Watched<T> {
+  pub fn new(state: T) -> Self {
+    Self {
+      state: Mutex::new(state),
+      changed: watch::Sender::new(()),
+    }
+  }
+
+  /// Locks the state. Changes made through the guard don't wake anyone
+  /// waiting; use [`Watched::update`] for those.
+  pub fn lock(&self) -> MutexGuard<'_, T> {
+    self.state.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  /// Changes the state and wakes everyone waiting on it.
+  pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+    let result = f(&mut self.lock());
+    self.changed.send_replace(());
+    result
+  }
+
+  /// Waits until `check` returns `Some`, checking again whenever the state
+  /// is updated. Changes made by `check` itself don't wake anyone.
+  pub async fn wait_for<R>(
+    &self,
+    mut check: impl FnMut(&mut T) -> Option<R>,
+  ) -> R {
+    loop {
+      // Subscribe before checking, so that no update is missed.
+      let mut changed = self.changed.subscribe();
+      if let Some(result) = check(&mut self.lock()) {
+        return result;
+      }
+      // The sender lives as long as `self`, so this can't fail.
+      let _ = changed.changed().await;
+    }
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use std::time::Duration;
+
+  use tokio::time::timeout;
+
+  use super::*;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_wait_for_wakes_on_update() -> TestResult {
+    let watched = Watched::new(0);
+
+    let update = async {
+      tokio::task::yield_now().await;
+      watched.update(|n| *n = 1);
+      tokio::task::yield_now().await;
+      watched.update(|n| *n = 2);
+    };
+    let (seen, ()) = timeout(Duration::from_secs(5), async {
+      tokio::join!(
+        watched.wait_for(|n| (*n == 2).then_some(*n)),
+        update
+      )
+    })
+    .await?;
+    assert_eq!(seen, 2);
+
+    Ok(())
+  }
+}
@@ -7,15 +7,27 @@ use tauri::{AppHandle, Manager, State, command};
 use cat_macros::CommandErrorSerialize;
 
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::download_mirrors::repository::sqlite_download_mirrors_repository::SqliteDownloadMirrorsRepository;
+use crate::emulated_builds::emulated_builds::EmulatedBuildsSetting;
 use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
 use crate::game_release::game_release::GameRelease;
 use crate::game_release::utils::{get_release_by_id, GetReleaseError};
 use crate::infra::download::Downloader;
+use crate::infra::http_client::is_offline_error;
 use crate::infra::installation_progress_monitor::channel_reporter::ChannelReporter;
 use crate::infra::utils::{HostSystem, HostSystemError};
 use crate::install_release::install_release::ReleaseInstallationError;
+use crate::install_release::running_installs::{
+  AlreadyInstallingError, RunningInstalls,
+};
+use crate::installed_releases::repository::sqlite_release_retention_policy_repository::SqliteReleaseRetentionPolicyRepository;
+use crate::launch_game::running_games::RunningGames;
+use crate::sideloaded_releases::repository::sqlite_sideloaded_releases_repository::SqliteSideloadedReleasesRepository;
 
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavorSelection;
+use crate::variants::game_variant::UnknownGameVariantError;
+use crate::variants::variant_descriptor::VariantRegistry;
 
 /// Errors that can occur when executing the `install_release` command.
 #[derive(thiserror::Error, Debug, CommandErrorSerialize)]
@@ -24,9 +36,13 @@ pub enum InstallReleaseCommandError {
   #[error("system directory not found: {0}")]
   SystemDir(#[from] tauri::Error),
 
+  /// Offline mode is enabled, so the release could not be downloaded.
+  #[error("offline mode is enabled")]
+  Offline,
+
   /// The installation process failed.
   #[error("installation failed: {0}")]
-  Install(#[from] ReleaseInstallationError),
+  Install(ReleaseInstallationError),
 
   /// Failed to retrieve the release information from the repository.
   #[error("failed to obtain release: {0}")]
@@ -35,6 +51,24 @@ pub enum InstallReleaseCommandError {
   /// The host system (OS/architecture) is not supported.
   #[error("failed to determine host system: {0}")]
   HostSystem(#[from] HostSystemError),
+
+  /// Another release of the variant is being installed.
+  #[error("already installing: {0}")]
+  AlreadyInstalling(#[from] AlreadyInstallingError),
+
+  /// The game variant is not registered.
+  #[error("unknown game variant: {0}")]
+  UnknownVariant(#[from] UnknownGameVariantError),
+}
+
+impl From<ReleaseInstallationError> for InstallReleaseCommandError {
+  fn from(e: ReleaseInstallationError) -> Self {
+    if is_offline_error(&e) {
+      Self::Offline
+    } else {
+      Self::Install(e)
+    }
+  }
 }
 
 /// A Tauri command that installs a specific game release.
@@ -42,43 +76,88 @@ pub enum InstallReleaseCommandError {
 /// This command handles downloading the release asset and extracting it to the
 /// appropriate directory, while reporting progress via a channel.
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn install_release(
   app_handle: AppHandle,
   variant: GameVariant,
   release_id: &str,
   releases_repository: State<'_, SqliteReleasesRepository>,
+  sideloaded_releases_repository: State<
+    '_,
+    SqliteSideloadedReleasesRepository,
+  >,
   active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+  retention_policy_repository: State<
+    '_,
+    SqliteReleaseRetentionPolicyRepository,
+  >,
+  download_mirrors_repository: State<
+    '_,
+    SqliteDownloadMirrorsRepository,
+  >,
   downloader: State<'_, Downloader>,
+  running_installs: State<'_, RunningInstalls>,
+  running_games: State<'_, RunningGames>,
+  emulated_builds: State<'_, EmulatedBuildsSetting>,
+  build_flavors: State<'_, BuildFlavorSelection>,
+  variant_registry: State<'_, VariantRegistry>,
   on_download_progress: Channel,
 ) -> Result<GameRelease, InstallReleaseCommandError> {
   let data_dir = app_handle.path().app_local_data_dir()?;
   let resource_dir = app_handle.path().resource_dir()?;
 
   let host_system = HostSystem::current(OS, ARCH)?;
+  let flavor = build_flavors.get(&variant);
 
+  let descriptor = variant_registry.get(&variant)?;
   let mut release = get_release_by_id(
     &variant,
+    &descriptor,
     release_id,
     &host_system.os,
+    flavor,
     &data_dir,
     &resource_dir,
     &*releases_repository,
+    &*sideloaded_releases_repository,
   )
   .await?;
 
   let progress = Arc::new(ChannelReporter::new(on_download_progress));
+  let install = running_installs.start(variant)?;
 
   release
     .install_release(
+      &descriptor,
       &downloader,
       &host_system,
+      emulated_builds.is_allowed(),
+      flavor,
       &data_dir,
       &resource_dir,
       &*releases_repository,
       &*active_release_repository,
+      &*retention_policy_repository,
+      &*download_mirrors_repository,
+      &running_games,
+      false,
+      install.cancel_token(),
       progress,
     )
     .await?;
 
   Ok(release)
 }
+
+/// A Tauri command that cancels the installation of the given variant that
+/// is in progress, whether started by the user or by an automatic update.
+///
+/// Returns whether an installation was running. A cancelled installation
+/// fails with a cancellation error once it has cleaned up after itself.
+#[command]
+pub fn cancel_install(
+  variant: GameVariant,
+  running_installs: State<'_, RunningInstalls>,
+) -> bool {
+  running_installs.cancel(&variant)
+}
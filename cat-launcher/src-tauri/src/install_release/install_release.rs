@@ -1,25 +1,46 @@
-use std::path::Path;
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use chrono::Utc;
 use downloader::progress::Reporter;
 use tokio::fs;
+use tokio_util::sync::CancellationToken;
 
 use crate::active_release::active_release::ActiveReleaseError;
 use crate::active_release::repository::ActiveReleaseRepository;
+use crate::download_mirrors::download_mirrors::{
+  MirrorUrl, get_mirror_urls, record_mirror_attempts,
+};
+use crate::download_mirrors::repository::DownloadMirrorsRepository;
 use crate::fetch_releases::repository::ReleasesRepository;
 use crate::filesystem::paths::{
   AssetDownloadDirError, AssetExtractionDirError,
+  GetExecutablePathError, find_game_executable,
+  get_asset_installation_dir, get_asset_staging_dir,
+  get_corrupted_download_marker_path,
   get_or_create_asset_download_dir,
-  get_or_create_asset_installation_dir,
 };
 use crate::game_release::game_release::{
   GameRelease, GameReleaseStatus,
 };
-use crate::infra::archive::{ExtractionError, extract_archive};
+use crate::infra::archive::{
+  ExtractionError, ExtractionPolicy, extract_archive,
+};
 use crate::infra::download::Downloader;
-use crate::infra::github::asset::AssetDownloadError;
+use crate::infra::download_queue::DownloadPriority;
+use crate::infra::github::asset::{
+  AssetDownloadError, AssetVerificationError, GitHubAsset,
+};
 use crate::infra::utils::HostSystem;
 use crate::install_release::installation_status::status::GetInstallationStatusError;
+use crate::installed_releases::repository::ReleaseRetentionPolicyRepository;
+use crate::installed_releases::retention_policy::ReleaseRetentionPolicy;
+use crate::launch_game::running_games::RunningGames;
+use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::variant_descriptor::VariantDescriptor;
 
 /// Errors that can occur during the release installation process.
 #[derive(thiserror::Error, Debug)]
@@ -44,10 +65,27 @@ pub enum ReleaseInstallationError {
   #[error("failed to download asset: {0}")]
   Download(#[from] AssetDownloadError),
 
+  /// Failed to verify the downloaded asset against its digest.
+  #[error("failed to verify asset: {0}")]
+  Verify(#[from] AssetVerificationError),
+
+  /// The downloaded asset does not match its published SHA-256 digest.
+  #[error("asset {0} is corrupted: digest mismatch")]
+  Corrupted(String),
+
   /// Failed to extract the downloaded archive.
   #[error("failed to extract asset: {0}")]
   Extract(#[from] ExtractionError),
 
+  /// The extracted archive doesn't contain the game executable.
+  #[error("extracted asset has no game executable: {0}")]
+  MissingExecutable(#[from] GetExecutablePathError),
+
+  /// Failed to move the extracted release into its installation
+  /// directory.
+  #[error("failed to move installation into place: {0}")]
+  MoveIntoPlace(io::Error),
+
   /// Failed to determine the release status during or after installation.
   #[error("failed to get release status: {0}")]
   ReleaseStatus(#[from] GetInstallationStatusError),
@@ -55,6 +93,15 @@ pub enum ReleaseInstallationError {
   /// Failed to update the active release in the repository.
   #[error("failed to set active release: {0}")]
   ActiveRelease(#[from] ActiveReleaseError),
+
+  /// The installation was cancelled.
+  #[error("installation was cancelled")]
+  Cancelled,
+
+  /// The variant's game was launched during the installation, and the
+  /// release being played was to be kept.
+  #[error("the game is being played")]
+  GameRunning,
 }
 
 impl GameRelease {
@@ -63,23 +110,111 @@ impl GameRelease {
   /// This process involves:
   /// 1. Checking the current status.
   /// 2. Downloading the appropriate asset if not already downloaded.
-  /// 3. Extracting the asset to the installation directory.
-  /// 4. Setting this release as the active one.
-  /// 5. Cleaning up the downloaded archive and other old installations.
+  /// 3. Verifying the asset against its published digest.
+  /// 4. Extracting the asset to a staging directory and checking that it
+  ///    contains the game executable.
+  /// 5. Renaming the staging directory to the installation directory.
+  /// 6. Setting this release as the active one.
+  /// 7. Cleaning up the downloaded archive and, depending on the variant's
+  ///    retention policy, other old installations. If the variant's game is
+  ///    being played, the old installations are only deleted once it stops.
+  ///
+  /// If any step fails, whatever was in the installation directory is
+  /// restored and the previously active release stays active, so a failed
+  /// installation never leaves a partial one behind.
+  ///
+  /// The installation can be cancelled through `cancel` until the release
+  /// is set as the active one. A cancelled installation doesn't leave a
+  /// partial download behind either.
+  ///
+  /// If `keep_running_release` is set, the installation fails rather than
+  /// switching the active release while the variant's game is being
+  /// played. The downloaded archive is kept for a later attempt.
   #[allow(clippy::too_many_arguments)]
   pub async fn install_release(
     &mut self,
+    descriptor: &VariantDescriptor,
+    downloader: &Downloader,
+    host_system: &HostSystem,
+    allow_emulated: bool,
+    flavor: BuildFlavor,
+    data_dir: &Path,
+    resources_dir: &Path,
+    releases_repository: &impl ReleasesRepository,
+    active_release_repository: &impl ActiveReleaseRepository,
+    retention_policy_repository: &impl ReleaseRetentionPolicyRepository,
+    download_mirrors_repository: &impl DownloadMirrorsRepository,
+    running_games: &RunningGames,
+    keep_running_release: bool,
+    cancel: &CancellationToken,
+    progress: Arc<dyn Reporter + Send + Sync>,
+  ) -> Result<(), ReleaseInstallationError> {
+    let result = self
+      .install(
+        descriptor,
+        downloader,
+        host_system,
+        allow_emulated,
+        flavor,
+        data_dir,
+        resources_dir,
+        releases_repository,
+        active_release_repository,
+        retention_policy_repository,
+        download_mirrors_repository,
+        running_games,
+        keep_running_release,
+        cancel,
+        progress,
+      )
+      .await;
+
+    // A successful installation has already been moved out of the staging
+    // directory, so anything left there is a partial extraction.
+    let staging_dir = get_asset_staging_dir(
+      &self.variant,
+      &self.version,
+      flavor,
+      data_dir,
+    );
+    let _ = fs::remove_dir_all(&staging_dir).await;
+
+    // Whatever step was interrupted, the partial download has already been
+    // removed and the release hasn't been activated.
+    if result.is_err() && cancel.is_cancelled() {
+      return Err(ReleaseInstallationError::Cancelled);
+    }
+
+    result
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  async fn install(
+    &mut self,
+    descriptor: &VariantDescriptor,
     downloader: &Downloader,
     host_system: &HostSystem,
+    allow_emulated: bool,
+    flavor: BuildFlavor,
     data_dir: &Path,
     resources_dir: &Path,
     releases_repository: &impl ReleasesRepository,
     active_release_repository: &impl ActiveReleaseRepository,
+    retention_policy_repository: &impl ReleaseRetentionPolicyRepository,
+    download_mirrors_repository: &impl DownloadMirrorsRepository,
+    running_games: &RunningGames,
+    keep_running_release: bool,
+    cancel: &CancellationToken,
     progress: Arc<dyn Reporter + Send + Sync>,
   ) -> Result<(), ReleaseInstallationError> {
     if self.status == GameReleaseStatus::Unknown {
       self.status = self
-        .get_installation_status(&host_system.os, data_dir)
+        .get_installation_status(
+          descriptor,
+          &host_system.os,
+          flavor,
+          data_dir,
+        )
         .await?;
     }
 
@@ -95,47 +230,281 @@ impl GameRelease {
       get_or_create_asset_download_dir(&self.variant, data_dir)
         .await?;
     let asset = self
-      .get_asset(host_system, resources_dir, releases_repository)
+      .get_asset(
+        descriptor,
+        host_system,
+        allow_emulated,
+        flavor,
+        resources_dir,
+        releases_repository,
+      )
       .await
       .ok_or(ReleaseInstallationError::NoCompatibleAsset)?;
 
+    // The mirror the asset was downloaded from, if it was downloaded now
+    // and not from GitHub.
+    let mut mirror = None;
     if self.status == GameReleaseStatus::NotDownloaded
       || self.status == GameReleaseStatus::Corrupted
       || self.status == GameReleaseStatus::Unknown
     {
-      asset.download(downloader, &download_dir, progress).await?;
+      mirror = self
+        .download_asset(
+          descriptor,
+          &asset,
+          downloader,
+          &download_dir,
+          download_mirrors_repository,
+          cancel,
+          progress.clone(),
+        )
+        .await?;
       self.status = GameReleaseStatus::NotInstalled;
     }
 
     let download_filepath = download_dir.join(&asset.name);
-    let installation_dir = get_or_create_asset_installation_dir(
+    let marker_path = get_corrupted_download_marker_path(
       &self.variant,
       &self.version,
+      flavor,
       data_dir,
-    )
-    .await?;
+    );
+    if !asset.verify_digest(&download_filepath).await? {
+      // A corrupted archive has to be downloaded again, so don't keep it.
+      let _ = fs::remove_file(&download_filepath).await;
+      // Remember the corruption so that the status reflects it, and stop
+      // using the mirror that served it until it's retried.
+      let _ = fs::write(&marker_path, &asset.name).await;
+      if let Some(mirror) = mirror {
+        let _ = download_mirrors_repository
+          .record_download_result(mirror.mirror_id, false, Utc::now())
+          .await;
+      }
+      self.status = GameReleaseStatus::Corrupted;
+      return Err(ReleaseInstallationError::Corrupted(asset.name));
+    }
+    let _ = fs::remove_file(&marker_path).await;
+
+    let staging_dir = get_asset_staging_dir(
+      &self.variant,
+      &self.version,
+      flavor,
+      data_dir,
+    );
+    // Start from scratch in case an earlier attempt left something behind.
+    let _ = fs::remove_dir_all(&staging_dir).await;
+    fs::create_dir_all(&staging_dir)
+      .await
+      .map_err(AssetExtractionDirError::from)?;
 
     extract_archive(
       &download_filepath,
-      &installation_dir,
+      &staging_dir,
       &host_system.os,
+      ExtractionPolicy::TRUSTED,
+      progress,
+      cancel,
     )
     .await?;
 
-    self.status = GameReleaseStatus::ReadyToPlay;
+    find_game_executable(&staging_dir, descriptor, &host_system.os)
+      .await?;
+
+    // This is the last chance to cancel. Once the release is active, the
+    // installation is kept.
+    if cancel.is_cancelled() {
+      return Err(ReleaseInstallationError::Cancelled);
+    }
+    // The game may have been launched while the release was being
+    // downloaded or extracted.
+    if keep_running_release && running_games.is_running(&self.variant)
+    {
+      return Err(ReleaseInstallationError::GameRunning);
+    }
 
-    self
+    let installation_dir = get_asset_installation_dir(
+      &self.variant,
+      &self.version,
+      flavor,
+      data_dir,
+    );
+    let replaced = move_into_place(&staging_dir, &installation_dir)
+      .await
+      .map_err(ReleaseInstallationError::MoveIntoPlace)?;
+
+    if let Err(e) = self
       .variant
       .set_active_release(&self.version, active_release_repository)
-      .await?;
+      .await
+    {
+      restore_replaced(&installation_dir, replaced).await;
+      return Err(e.into());
+    }
+
+    if let Some(replaced) = replaced {
+      let _ = fs::remove_dir_all(&replaced).await;
+    }
+
+    self.status = GameReleaseStatus::ReadyToPlay;
 
     // Failure to remove file does not mean failure to install
     let _ = fs::remove_file(&download_filepath).await;
 
-    delete_other_installations(&installation_dir).await;
+    // If the policy can't be read, keep other installations rather than
+    // deleting releases the user may have asked to keep.
+    if let Ok(ReleaseRetentionPolicy::KeepActiveOnly) = self
+      .variant
+      .get_release_retention_policy(retention_policy_repository)
+      .await
+      && !running_games.defer_pruning(&self.variant)
+    {
+      delete_other_installations(&installation_dir).await;
+    }
 
     Ok(())
   }
+
+  /// Downloads the asset of this release from GitHub or any of the
+  /// download mirrors that aren't dead, and records which mirrors worked.
+  ///
+  /// Returns the mirror the asset was downloaded from, if any.
+  #[allow(clippy::too_many_arguments)]
+  async fn download_asset(
+    &self,
+    descriptor: &VariantDescriptor,
+    asset: &GitHubAsset,
+    downloader: &Downloader,
+    download_dir: &Path,
+    download_mirrors_repository: &impl DownloadMirrorsRepository,
+    cancel: &CancellationToken,
+    progress: Arc<dyn Reporter + Send + Sync>,
+  ) -> Result<Option<MirrorUrl>, AssetDownloadError> {
+    let now = Utc::now();
+    let mirror_urls = get_mirror_urls(
+      &descriptor.repo,
+      &self.version,
+      &asset.name,
+      now,
+      download_mirrors_repository,
+    )
+    .await
+    .unwrap_or_default(); // It's okay to download from GitHub only.
+    let urls = mirror_urls
+      .iter()
+      .map(|m| m.url.as_str())
+      .collect::<Vec<_>>();
+
+    // The game itself comes before mods, tilesets and soundpacks, which
+    // are of no use without it.
+    let download = asset
+      .download(
+        downloader,
+        download_dir,
+        &urls,
+        DownloadPriority::High,
+        cancel,
+        progress,
+      )
+      .await;
+
+    // Failure to record mirror results does not mean failure to download.
+    let _ = record_mirror_attempts(
+      &mirror_urls,
+      &download.attempts,
+      now,
+      download_mirrors_repository,
+    )
+    .await;
+
+    download.result?;
+
+    let mirror = download
+      .attempts
+      .iter()
+      .rfind(|attempt| attempt.succeeded)
+      .and_then(|attempt| {
+        mirror_urls.into_iter().find(|m| m.url == attempt.url)
+      });
+
+    Ok(mirror)
+  }
+}
+
+/// Renames `staging_dir` to `installation_dir`. Whatever was already at
+/// `installation_dir` is moved next to `staging_dir` and returned, so that
+/// it can be restored if the installation fails later on.
+pub async fn move_into_place(
+  staging_dir: &Path,
+  installation_dir: &Path,
+) -> io::Result<Option<PathBuf>> {
+  if let Some(parent) = installation_dir.parent() {
+    fs::create_dir_all(parent).await?;
+  }
+
+  let replaced = if fs::try_exists(installation_dir).await? {
+    let mut replaced_name = OsString::from(staging_dir.as_os_str());
+    replaced_name.push(".replaced");
+    let replaced = PathBuf::from(replaced_name);
+
+    let _ = fs::remove_dir_all(&replaced).await;
+    fs::rename(installation_dir, &replaced).await?;
+    Some(replaced)
+  } else {
+    None
+  };
+
+  if let Err(e) = fs::rename(staging_dir, installation_dir).await {
+    restore_replaced(installation_dir, replaced).await;
+    return Err(e);
+  }
+
+  Ok(replaced)
+}
+
+/// Puts back what `move_into_place` moved out of `installation_dir`.
+pub async fn restore_replaced(
+  installation_dir: &Path,
+  replaced: Option<PathBuf>,
+) {
+  let _ = fs::remove_dir_all(installation_dir).await;
+  if let Some(replaced) = replaced {
+    let _ = fs::rename(&replaced, installation_dir).await;
+  }
+}
+
+/// Deletes the installations of `variant` other than that of its active
+/// release, if its retention policy says so.
+///
+/// This is what installing a release does once it's active, for when that
+/// had to be put off because the game was being played.
+pub async fn prune_installations(
+  variant: &GameVariant,
+  flavor: BuildFlavor,
+  data_dir: &Path,
+  active_release_repository: &impl ActiveReleaseRepository,
+  retention_policy_repository: &impl ReleaseRetentionPolicyRepository,
+) {
+  // If the policy can't be read, keep other installations rather than
+  // deleting releases the user may have asked to keep.
+  let Ok(ReleaseRetentionPolicy::KeepActiveOnly) = variant
+    .get_release_retention_policy(retention_policy_repository)
+    .await
+  else {
+    return;
+  };
+  let Ok(Some(active_version)) =
+    variant.get_active_release(active_release_repository).await
+  else {
+    return;
+  };
+
+  let installation_dir = get_asset_installation_dir(
+    variant,
+    &active_version,
+    flavor,
+    data_dir,
+  );
+  delete_other_installations(&installation_dir).await;
 }
 
 async fn delete_other_installations(installation_dir: &Path) {
@@ -183,21 +552,26 @@ async fn delete_other_installations(installation_dir: &Path) {
 mod tests {
   use super::*;
   use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+  use crate::download_mirrors::download_mirrors::add_download_mirror;
+  use crate::download_mirrors::repository::sqlite_download_mirrors_repository::SqliteDownloadMirrorsRepository;
   use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
   use crate::filesystem::paths::{
     get_game_executable_filepath,
     get_or_create_asset_download_dir, get_or_create_asset_installation_dir,
   };
+  use crate::filesystem::utils::get_sha256;
   use crate::game_release::game_release::{
     GameRelease, GameReleaseStatus, ReleaseType,
   };
-  use crate::game_release::utils::get_platform_asset_substrs;
   use crate::infra::github::asset::GitHubAsset;
   use crate::infra::github::release::GitHubRelease;
   use crate::infra::http_client::ReqwestHttpClient;
   use crate::infra::testing::test_database::TestDatabase;
+  use crate::infra::testing::test_zip::create_test_zip;
   use crate::infra::utils::{Arch, HostSystem, OS};
+  use crate::installed_releases::repository::sqlite_release_retention_policy_repository::SqliteReleaseRetentionPolicyRepository;
   use crate::variants::GameVariant;
+  use crate::variants::variant_descriptor::VariantRegistry;
   use chrono::Utc;
   use downloader::progress::Reporter;
   use github_mock_api::{Asset as MockAsset, MockServer};
@@ -230,6 +604,7 @@ mod tests {
       GameVariant::DarkDaysAhead => "dda",
       GameVariant::BrightNights => "bn",
       GameVariant::TheLastGeneration => "tlg",
+      _ => panic!("no test assets for {variant}"),
     };
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
       .join("src")
@@ -240,6 +615,21 @@ mod tests {
       .join(variant_dir)
   }
 
+  /// Returns the substrings identifying the native asset of the variant
+  /// for the given platform, in the build flavor selected for it.
+  fn get_platform_asset_substrs(
+    variant: &GameVariant,
+    host_system: &HostSystem,
+  ) -> Vec<String> {
+    VariantRegistry::default()
+      .get(variant)
+      .expect("built-in variants should be registered")
+      .asset_substrs(host_system, BuildFlavor::default(), false)
+      .into_iter()
+      .map(String::from)
+      .collect()
+  }
+
   fn get_test_archive_path(
     variant: GameVariant,
     host_system: &HostSystem,
@@ -356,6 +746,7 @@ mod tests {
       (GameVariant::TheLastGeneration, OS::Linux, _) => {
         "linux-tiles-sounds"
       }
+      _ => panic!("no expected asset for {variant}"),
     }
   }
 
@@ -363,20 +754,31 @@ mod tests {
     TestDatabase,
     SqliteReleasesRepository,
     SqliteActiveReleaseRepository,
+    SqliteReleaseRetentionPolicyRepository,
+    SqliteDownloadMirrorsRepository,
   )> {
     let db = TestDatabase::builder().build()?;
     let releases_repo =
       SqliteReleasesRepository::new(db.pool().clone());
     let active_repo =
       SqliteActiveReleaseRepository::new(db.pool().clone());
-    Ok((db, releases_repo, active_repo))
+    let retention_repo =
+      SqliteReleaseRetentionPolicyRepository::new(db.pool().clone());
+    let mirrors_repo =
+      SqliteDownloadMirrorsRepository::new(db.pool().clone());
+    Ok((db, releases_repo, active_repo, retention_repo, mirrors_repo))
   }
 
   #[tokio::test]
   async fn test_install_release_already_ready_to_play() -> TestResult
   {
-    let (_db, releases_repo, active_repo) =
-      setup_test_repos().await?;
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
     let downloader = create_downloader();
     let temp_data = TempDir::new()?;
     let temp_res = TempDir::new()?;
@@ -394,15 +796,23 @@ mod tests {
 
       release
         .install_release(
+          &*VariantRegistry::default().get(&variant)?,
           &downloader,
           &HostSystem {
             os: OS::Linux,
             arch: Arch::X64,
           },
+          false,
+          BuildFlavor::default(),
           temp_data.path(),
           temp_res.path(),
           &releases_repo,
           &active_repo,
+          &retention_repo,
+          &mirrors_repo,
+          &RunningGames::default(),
+          false,
+          &CancellationToken::new(),
           Arc::new(DummyReporter),
         )
         .await?;
@@ -417,8 +827,13 @@ mod tests {
   #[tokio::test]
   async fn test_install_release_unknown_status_already_installed()
   -> TestResult {
-    let (_db, releases_repo, active_repo) =
-      setup_test_repos().await?;
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
     let downloader = create_downloader();
     let temp_data = TempDir::new()?;
     let temp_res = TempDir::new()?;
@@ -433,6 +848,7 @@ mod tests {
       let install_dir = get_or_create_asset_installation_dir(
         &variant,
         version,
+        BuildFlavor::default(),
         temp_data.path(),
       )
       .await?;
@@ -453,15 +869,23 @@ mod tests {
 
       release
         .install_release(
+          &*VariantRegistry::default().get(&variant)?,
           &downloader,
           &HostSystem {
             os: OS::Linux,
             arch: Arch::X64,
           },
+          false,
+          BuildFlavor::default(),
           temp_data.path(),
           temp_res.path(),
           &releases_repo,
           &active_repo,
+          &retention_repo,
+          &mirrors_repo,
+          &RunningGames::default(),
+          false,
+          &CancellationToken::new(),
           Arc::new(DummyReporter),
         )
         .await?;
@@ -475,8 +899,13 @@ mod tests {
 
   #[tokio::test]
   async fn test_install_release_no_compatible_asset() -> TestResult {
-    let (_db, releases_repo, active_repo) =
-      setup_test_repos().await?;
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
     let downloader = create_downloader();
     let temp_data = TempDir::new()?;
     let temp_res = TempDir::new()?;
@@ -517,15 +946,23 @@ mod tests {
 
       let result = release
         .install_release(
+          &*VariantRegistry::default().get(&variant)?,
           &downloader,
           &HostSystem {
             os: OS::Linux,
             arch: Arch::X64,
           },
+          false,
+          BuildFlavor::default(),
           temp_data.path(),
           temp_res.path(),
           &releases_repo,
           &active_repo,
+          &retention_repo,
+          &mirrors_repo,
+          &RunningGames::default(),
+          false,
+          &CancellationToken::new(),
           Arc::new(DummyReporter),
         )
         .await;
@@ -541,8 +978,13 @@ mod tests {
   #[tokio::test]
   async fn test_install_release_successful_download_and_extract()
   -> TestResult {
-    let (_db, releases_repo, active_repo) =
-      setup_test_repos().await?;
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
     let server = MockServer::start().await?;
     let downloader = create_downloader();
     let temp_data = TempDir::new()?;
@@ -557,7 +999,7 @@ mod tests {
     .enumerate()
     {
       let repo_full =
-        crate::infra::utils::get_github_repo_for_variant(&variant);
+        VariantRegistry::default().get(&variant)?.repo.clone();
       let parts: Vec<&str> = repo_full.split('/').collect();
       let (owner, repo_name) = (parts[0], parts[1]);
       let version = "cdda-exp-1";
@@ -619,12 +1061,20 @@ mod tests {
 
       release
         .install_release(
+          &*VariantRegistry::default().get(&variant)?,
           &downloader,
           &host_system,
+          false,
+          BuildFlavor::default(),
           temp_data.path(),
           temp_res.path(),
           &releases_repo,
           &active_repo,
+          &retention_repo,
+          &mirrors_repo,
+          &RunningGames::default(),
+          false,
+          &CancellationToken::new(),
           Arc::new(DummyReporter),
         )
         .await?;
@@ -645,7 +1095,9 @@ mod tests {
 
       let executable = get_game_executable_filepath(
         &variant,
+        &*VariantRegistry::default().get(&variant)?,
         version,
+        BuildFlavor::default(),
         temp_data.path(),
         &host_system.os,
       )
@@ -663,8 +1115,13 @@ mod tests {
   #[tokio::test]
   async fn test_install_release_already_downloaded_not_installed()
   -> TestResult {
-    let (_db, releases_repo, active_repo) =
-      setup_test_repos().await?;
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
     let downloader = create_downloader();
     let temp_data = TempDir::new()?;
     let temp_res = TempDir::new()?;
@@ -720,12 +1177,20 @@ mod tests {
 
       release
         .install_release(
+          &*VariantRegistry::default().get(&variant)?,
           &downloader,
           &host_system,
+          false,
+          BuildFlavor::default(),
           temp_data.path(),
           temp_res.path(),
           &releases_repo,
           &active_repo,
+          &retention_repo,
+          &mirrors_repo,
+          &RunningGames::default(),
+          false,
+          &CancellationToken::new(),
           Arc::new(DummyReporter),
         )
         .await?;
@@ -735,7 +1200,9 @@ mod tests {
 
       let executable = get_game_executable_filepath(
         &variant,
+        &*VariantRegistry::default().get(&variant)?,
         version,
+        BuildFlavor::default(),
         temp_data.path(),
         &host_system.os,
       )
@@ -756,8 +1223,13 @@ mod tests {
   #[tokio::test]
   async fn test_install_release_deletes_other_installations()
   -> TestResult {
-    let (_db, releases_repo, active_repo) =
-      setup_test_repos().await?;
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
     let downloader = create_downloader();
     let temp_data = TempDir::new()?;
     let temp_res = TempDir::new()?;
@@ -779,6 +1251,7 @@ mod tests {
       let old_install_dir = get_or_create_asset_installation_dir(
         &variant,
         old_version,
+        BuildFlavor::default(),
         temp_data.path(),
       )
       .await?;
@@ -828,12 +1301,20 @@ mod tests {
 
       release
         .install_release(
+          &*VariantRegistry::default().get(&variant)?,
           &downloader,
           &host_system,
+          false,
+          BuildFlavor::default(),
           temp_data.path(),
           temp_res.path(),
           &releases_repo,
           &active_repo,
+          &retention_repo,
+          &mirrors_repo,
+          &RunningGames::default(),
+          false,
+          &CancellationToken::new(),
           Arc::new(DummyReporter),
         )
         .await?;
@@ -847,6 +1328,7 @@ mod tests {
       let new_install_dir = get_or_create_asset_installation_dir(
         &variant,
         new_version,
+        BuildFlavor::default(),
         temp_data.path(),
       )
       .await?;
@@ -857,7 +1339,9 @@ mod tests {
 
       let executable = get_game_executable_filepath(
         &variant,
+        &*VariantRegistry::default().get(&variant)?,
         new_version,
+        BuildFlavor::default(),
         temp_data.path(),
         &host_system.os,
       )
@@ -872,10 +1356,875 @@ mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  async fn test_install_release_keeps_other_installations_with_keep_all_policy()
+  -> TestResult {
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
+    let downloader = create_downloader();
+    let temp_data = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+
+    for (variant_index, variant) in [
+      GameVariant::DarkDaysAhead,
+      GameVariant::BrightNights,
+      GameVariant::TheLastGeneration,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+      let host_system = HostSystem {
+        os: OS::Linux,
+        arch: Arch::X64,
+      };
+
+      variant
+        .set_release_retention_policy(
+          ReleaseRetentionPolicy::KeepAll,
+          &retention_repo,
+        )
+        .await?;
+
+      let old_version = "v0.1.0";
+      let old_install_dir = get_or_create_asset_installation_dir(
+        &variant,
+        old_version,
+        BuildFlavor::default(),
+        temp_data.path(),
+      )
+      .await?;
+      let old_sub_dir = old_install_dir.join("cataclysm-dda");
+      tokio::fs::create_dir_all(&old_sub_dir).await?;
+      tokio::fs::write(
+        old_sub_dir.join("cataclysm-launcher"),
+        b"old",
+      )
+      .await?;
+
+      let new_version = "v0.2.0";
+      let archive_path = get_test_archive_path(variant, &host_system);
+      let asset_name = get_test_asset_name(&archive_path);
+
+      let download_dir =
+        get_or_create_asset_download_dir(&variant, temp_data.path())
+          .await?;
+      tokio::fs::copy(&archive_path, download_dir.join(&asset_name))
+        .await?;
+
+      releases_repo
+        .update_cached_releases(
+          &variant,
+          &[GitHubRelease {
+            id: 999 + variant_index as u64,
+            tag_name: new_version.to_string(),
+            prerelease: false,
+            body: Some("body".to_string()),
+            assets: vec![GitHubAsset {
+              id: 1111 + variant_index as u64,
+              browser_download_url: "http://invalid.local/file.zip"
+                .to_string(),
+              name: asset_name.clone(),
+              digest: None,
+            }],
+            created_at: Utc::now(),
+          }],
+        )
+        .await?;
+
+      let mut release = create_test_release(
+        variant,
+        new_version,
+        GameReleaseStatus::NotInstalled,
+      );
+
+      release
+        .install_release(
+          &*VariantRegistry::default().get(&variant)?,
+          &downloader,
+          &host_system,
+          false,
+          BuildFlavor::default(),
+          temp_data.path(),
+          temp_res.path(),
+          &releases_repo,
+          &active_repo,
+          &retention_repo,
+          &mirrors_repo,
+          &RunningGames::default(),
+          false,
+          &CancellationToken::new(),
+          Arc::new(DummyReporter),
+        )
+        .await?;
+
+      assert_eq!(release.status, GameReleaseStatus::ReadyToPlay);
+      assert!(
+        old_sub_dir.join("cataclysm-launcher").is_file(),
+        "Old installation should be kept with the KeepAll policy"
+      );
+
+      let active = active_repo.get_active_release(&variant).await?;
+      assert_eq!(active, Some(new_version.to_string()));
+    }
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_install_release_defers_pruning_while_game_is_running()
+  -> TestResult {
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
+    let temp_data = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+    let variant = GameVariant::DarkDaysAhead;
+    let host_system = HostSystem {
+      os: OS::Linux,
+      arch: Arch::X64,
+    };
+
+    let old_install_dir = get_or_create_asset_installation_dir(
+      &variant,
+      "v0.1.0",
+      BuildFlavor::default(),
+      temp_data.path(),
+    )
+    .await?;
+
+    let version = "v0.2.0";
+    let archive_path = get_test_archive_path(variant, &host_system);
+    let asset_name = get_test_asset_name(&archive_path);
+    let download_dir =
+      get_or_create_asset_download_dir(&variant, temp_data.path())
+        .await?;
+    tokio::fs::copy(&archive_path, download_dir.join(&asset_name))
+      .await?;
+    releases_repo
+      .update_cached_releases(
+        &variant,
+        &[GitHubRelease {
+          id: 1999,
+          tag_name: version.to_string(),
+          prerelease: false,
+          body: Some("body".to_string()),
+          assets: vec![GitHubAsset {
+            id: 2111,
+            browser_download_url: "http://invalid.local/file.zip"
+              .to_string(),
+            name: asset_name,
+            digest: None,
+          }],
+          created_at: Utc::now(),
+        }],
+      )
+      .await?;
+
+    let running_games = RunningGames::default();
+    let session = running_games.start(variant);
+
+    let mut release = create_test_release(
+      variant,
+      version,
+      GameReleaseStatus::NotInstalled,
+    );
+    release
+      .install_release(
+        &*VariantRegistry::default().get(&variant)?,
+        &create_downloader(),
+        &host_system,
+        false,
+        BuildFlavor::default(),
+        temp_data.path(),
+        temp_res.path(),
+        &releases_repo,
+        &active_repo,
+        &retention_repo,
+        &mirrors_repo,
+        &running_games,
+        false,
+        &CancellationToken::new(),
+        Arc::new(DummyReporter),
+      )
+      .await?;
+
+    assert!(
+      old_install_dir.exists(),
+      "Installation in use should be kept while the game is running"
+    );
+
+    drop(session);
+    assert!(running_games.wait_until_stopped(&variant).await);
+    prune_installations(
+      &variant,
+      BuildFlavor::default(),
+      temp_data.path(),
+      &active_repo,
+      &retention_repo,
+    )
+    .await;
+
+    assert!(!old_install_dir.exists());
+    assert!(
+      get_asset_installation_dir(
+        &variant,
+        version,
+        BuildFlavor::default(),
+        temp_data.path()
+      )
+      .exists()
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_install_release_keeps_running_release() -> TestResult
+  {
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
+    let temp_data = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+    let variant = GameVariant::DarkDaysAhead;
+    let host_system = HostSystem {
+      os: OS::Linux,
+      arch: Arch::X64,
+    };
+    variant.set_active_release("v0.1.0", &active_repo).await?;
+
+    let version = "v0.2.0";
+    let archive_path = get_test_archive_path(variant, &host_system);
+    let asset_name = get_test_asset_name(&archive_path);
+    let download_dir =
+      get_or_create_asset_download_dir(&variant, temp_data.path())
+        .await?;
+    let downloaded_archive = download_dir.join(&asset_name);
+    tokio::fs::copy(&archive_path, &downloaded_archive).await?;
+    releases_repo
+      .update_cached_releases(
+        &variant,
+        &[GitHubRelease {
+          id: 2999,
+          tag_name: version.to_string(),
+          prerelease: false,
+          body: Some("body".to_string()),
+          assets: vec![GitHubAsset {
+            id: 3111,
+            browser_download_url: "http://invalid.local/file.zip"
+              .to_string(),
+            name: asset_name,
+            digest: None,
+          }],
+          created_at: Utc::now(),
+        }],
+      )
+      .await?;
+
+    let running_games = RunningGames::default();
+    let _session = running_games.start(variant);
+
+    let mut release = create_test_release(
+      variant,
+      version,
+      GameReleaseStatus::NotInstalled,
+    );
+    let result = release
+      .install_release(
+        &*VariantRegistry::default().get(&variant)?,
+        &create_downloader(),
+        &host_system,
+        false,
+        BuildFlavor::default(),
+        temp_data.path(),
+        temp_res.path(),
+        &releases_repo,
+        &active_repo,
+        &retention_repo,
+        &mirrors_repo,
+        &running_games,
+        true,
+        &CancellationToken::new(),
+        Arc::new(DummyReporter),
+      )
+      .await;
+
+    assert!(matches!(
+      result,
+      Err(ReleaseInstallationError::GameRunning)
+    ));
+    assert!(
+      downloaded_archive.exists(),
+      "The archive should be kept for a later attempt"
+    );
+    assert!(
+      !get_asset_installation_dir(
+        &variant,
+        version,
+        BuildFlavor::default(),
+        temp_data.path()
+      )
+      .exists()
+    );
+    assert_eq!(
+      active_repo.get_active_release(&variant).await?,
+      Some("v0.1.0".to_string())
+    );
+
+    Ok(())
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  async fn install_downloaded_archive_with_digest(
+    releases_repo: &SqliteReleasesRepository,
+    active_repo: &SqliteActiveReleaseRepository,
+    retention_repo: &SqliteReleaseRetentionPolicyRepository,
+    mirrors_repo: &SqliteDownloadMirrorsRepository,
+    variant: GameVariant,
+    release_id: u64,
+    digest: impl FnOnce(&Path) -> Option<String>,
+    cancel: &CancellationToken,
+    temp_data: &TempDir,
+    temp_res: &TempDir,
+  ) -> TestResult<(
+    GameRelease,
+    PathBuf,
+    Result<(), ReleaseInstallationError>,
+  )> {
+    let version = "v1.0.0";
+    let host_system = HostSystem {
+      os: OS::Linux,
+      arch: Arch::X64,
+    };
+
+    let archive_path = get_test_archive_path(variant, &host_system);
+    let asset_name = get_test_asset_name(&archive_path);
+
+    let download_dir =
+      get_or_create_asset_download_dir(&variant, temp_data.path())
+        .await?;
+    let downloaded_archive = download_dir.join(&asset_name);
+    tokio::fs::copy(&archive_path, &downloaded_archive).await?;
+
+    releases_repo
+      .update_cached_releases(
+        &variant,
+        &[GitHubRelease {
+          id: release_id,
+          tag_name: version.to_string(),
+          prerelease: false,
+          body: Some("body".to_string()),
+          assets: vec![GitHubAsset {
+            id: release_id,
+            browser_download_url: "http://invalid.local/file.zip"
+              .to_string(),
+            name: asset_name,
+            digest: digest(&archive_path),
+          }],
+          created_at: Utc::now(),
+        }],
+      )
+      .await?;
+
+    let mut release = create_test_release(
+      variant,
+      version,
+      GameReleaseStatus::NotInstalled,
+    );
+
+    let result = release
+      .install_release(
+        &*VariantRegistry::default().get(&variant)?,
+        &create_downloader(),
+        &host_system,
+        false,
+        BuildFlavor::default(),
+        temp_data.path(),
+        temp_res.path(),
+        releases_repo,
+        active_repo,
+        retention_repo,
+        mirrors_repo,
+        &RunningGames::default(),
+        false,
+        cancel,
+        Arc::new(DummyReporter),
+      )
+      .await;
+
+    Ok((release, downloaded_archive, result))
+  }
+
+  #[tokio::test]
+  async fn test_install_release_digest_mismatch() -> TestResult {
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
+    let temp_data = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+
+    for (variant_index, variant) in [
+      GameVariant::DarkDaysAhead,
+      GameVariant::BrightNights,
+      GameVariant::TheLastGeneration,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+      let (release, downloaded_archive, result) =
+        install_downloaded_archive_with_digest(
+          &releases_repo,
+          &active_repo,
+          &retention_repo,
+          &mirrors_repo,
+          variant,
+          1212 + variant_index as u64,
+          |_| Some(format!("sha256:{}", "0".repeat(64))),
+          &CancellationToken::new(),
+          &temp_data,
+          &temp_res,
+        )
+        .await?;
+
+      assert!(matches!(
+        result,
+        Err(ReleaseInstallationError::Corrupted(_))
+      ));
+      assert_eq!(release.status, GameReleaseStatus::Corrupted);
+      assert!(
+        !downloaded_archive.exists(),
+        "Corrupted archive should be deleted"
+      );
+      assert_eq!(
+        release
+          .get_installation_status(
+            &*VariantRegistry::default().get(&variant)?,
+            &OS::Linux,
+            BuildFlavor::default(),
+            temp_data.path()
+          )
+          .await?,
+        GameReleaseStatus::Corrupted
+      );
+      assert!(
+        get_game_executable_filepath(
+          &variant,
+          &*VariantRegistry::default().get(&variant)?,
+          &release.version,
+          BuildFlavor::default(),
+          temp_data.path(),
+          &OS::Linux,
+        )
+        .await
+        .is_err(),
+        "Corrupted archive should not be extracted"
+      );
+      assert_eq!(
+        active_repo.get_active_release(&variant).await?,
+        None
+      );
+    }
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_install_release_digest_match() -> TestResult {
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
+    let temp_data = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+
+    for (variant_index, variant) in [
+      GameVariant::DarkDaysAhead,
+      GameVariant::BrightNights,
+      GameVariant::TheLastGeneration,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+      let (release, _, result) =
+        install_downloaded_archive_with_digest(
+          &releases_repo,
+          &active_repo,
+          &retention_repo,
+          &mirrors_repo,
+          variant,
+          1313 + variant_index as u64,
+          |archive_path| {
+            let bytes =
+              std::fs::read(archive_path).expect("read test archive");
+            Some(format!("sha256:{}", get_sha256(&bytes)))
+          },
+          &CancellationToken::new(),
+          &temp_data,
+          &temp_res,
+        )
+        .await?;
+
+      result?;
+      assert_eq!(release.status, GameReleaseStatus::ReadyToPlay);
+    }
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_install_release_corrupted_mirror_is_skipped()
+  -> TestResult {
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
+    let server = MockServer::start().await?;
+    let temp_data = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+
+    let variant = GameVariant::DarkDaysAhead;
+    let repo_full =
+      VariantRegistry::default().get(&variant)?.repo.clone();
+    let parts: Vec<&str> = repo_full.split('/').collect();
+    let (owner, repo_name) = (parts[0], parts[1]);
+    let version = "cdda-exp-1";
+    let host_system = HostSystem {
+      os: OS::Linux,
+      arch: Arch::X64,
+    };
+    let asset_name = format!(
+      "{}-test.zip",
+      get_platform_asset_substrs(&variant, &host_system)[0]
+    );
+
+    // GitHub doesn't have the asset, so it's downloaded from the mirror,
+    // which serves something else.
+    server
+      .add_asset(
+        owner,
+        repo_name,
+        version,
+        MockAsset::from_bytes(
+          &asset_name,
+          b"not the archive".to_vec(),
+          "application/zip",
+        ),
+      )
+      .await;
+    add_download_mirror(
+      &format!(
+        "{}/{{repo}}/releases/download/{{tag}}/{{asset}}",
+        server.uri()
+      ),
+      &mirrors_repo,
+    )
+    .await?;
+
+    releases_repo
+      .update_cached_releases(
+        &variant,
+        &[GitHubRelease {
+          id: 1414,
+          tag_name: version.to_string(),
+          prerelease: false,
+          body: Some("body".to_string()),
+          assets: vec![GitHubAsset {
+            id: 1414,
+            browser_download_url: format!(
+              "{}/nonexistent/{}",
+              server.uri(),
+              asset_name
+            ),
+            name: asset_name.clone(),
+            digest: Some(format!("sha256:{}", "0".repeat(64))),
+          }],
+          created_at: Utc::now(),
+        }],
+      )
+      .await?;
+
+    let mut release = create_test_release(
+      variant,
+      version,
+      GameReleaseStatus::NotDownloaded,
+    );
+    let result = release
+      .install_release(
+        &*VariantRegistry::default().get(&variant)?,
+        &create_downloader(),
+        &host_system,
+        false,
+        BuildFlavor::default(),
+        temp_data.path(),
+        temp_res.path(),
+        &releases_repo,
+        &active_repo,
+        &retention_repo,
+        &mirrors_repo,
+        &RunningGames::default(),
+        false,
+        &CancellationToken::new(),
+        Arc::new(DummyReporter),
+      )
+      .await;
+
+    assert!(matches!(
+      result,
+      Err(ReleaseInstallationError::Corrupted(_))
+    ));
+    assert_eq!(
+      release
+        .get_installation_status(
+          &*VariantRegistry::default().get(&variant)?,
+          &host_system.os,
+          BuildFlavor::default(),
+          temp_data.path()
+        )
+        .await?,
+      GameReleaseStatus::Corrupted
+    );
+    assert!(
+      get_mirror_urls(
+        &repo_full,
+        version,
+        &asset_name,
+        Utc::now(),
+        &mirrors_repo
+      )
+      .await?
+      .is_empty(),
+      "The mirror that served the corrupted archive should be skipped"
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_install_release_cancelled() -> TestResult {
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
+    let temp_data = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+
+    for (variant_index, variant) in [
+      GameVariant::DarkDaysAhead,
+      GameVariant::BrightNights,
+      GameVariant::TheLastGeneration,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+      variant.set_active_release("v0.9.0", &active_repo).await?;
+
+      let (release, downloaded_archive, result) =
+        install_downloaded_archive_with_digest(
+          &releases_repo,
+          &active_repo,
+          &retention_repo,
+          &mirrors_repo,
+          variant,
+          1414 + variant_index as u64,
+          |_| None,
+          &cancel,
+          &temp_data,
+          &temp_res,
+        )
+        .await?;
+
+      assert!(matches!(
+        result,
+        Err(ReleaseInstallationError::Cancelled)
+      ));
+      assert_eq!(release.status, GameReleaseStatus::NotInstalled);
+      assert!(
+        downloaded_archive.exists(),
+        "A completely downloaded archive should be kept"
+      );
+      assert!(
+        !get_asset_installation_dir(
+          &variant,
+          &release.version,
+          BuildFlavor::default(),
+          temp_data.path()
+        )
+        .exists(),
+        "The partial installation should be removed"
+      );
+
+      let active = active_repo.get_active_release(&variant).await?;
+      assert_eq!(active, Some("v0.9.0".to_string()));
+    }
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_install_release_missing_executable_restores_previous_state()
+  -> TestResult {
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
+    let temp_data = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+    let host_system = HostSystem {
+      os: OS::Windows,
+      arch: Arch::X64,
+    };
+
+    for (variant_index, variant) in [
+      GameVariant::DarkDaysAhead,
+      GameVariant::BrightNights,
+      GameVariant::TheLastGeneration,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+      let version = "v1.0.0";
+      variant.set_active_release("v0.9.0", &active_repo).await?;
+
+      let installation_dir = get_or_create_asset_installation_dir(
+        &variant,
+        version,
+        BuildFlavor::default(),
+        temp_data.path(),
+      )
+      .await?;
+      let previous_file = installation_dir.join("previous.txt");
+      tokio::fs::write(&previous_file, b"previous").await?;
+
+      let asset_substrs =
+        get_platform_asset_substrs(&variant, &host_system);
+      let asset_name = format!("{}-test.zip", asset_substrs[0]);
+      let download_dir =
+        get_or_create_asset_download_dir(&variant, temp_data.path())
+          .await?;
+      tokio::fs::write(
+        download_dir.join(&asset_name),
+        create_test_zip(&[("readme.txt", b"no game here")])?,
+      )
+      .await?;
+
+      releases_repo
+        .update_cached_releases(
+          &variant,
+          &[GitHubRelease {
+            id: 1515 + variant_index as u64,
+            tag_name: version.to_string(),
+            prerelease: false,
+            body: Some("body".to_string()),
+            assets: vec![GitHubAsset {
+              id: 1515 + variant_index as u64,
+              browser_download_url: "http://invalid.local/file.zip"
+                .to_string(),
+              name: asset_name,
+              digest: None,
+            }],
+            created_at: Utc::now(),
+          }],
+        )
+        .await?;
+
+      let mut release = create_test_release(
+        variant,
+        version,
+        GameReleaseStatus::NotInstalled,
+      );
+
+      let result = release
+        .install_release(
+          &*VariantRegistry::default().get(&variant)?,
+          &create_downloader(),
+          &host_system,
+          false,
+          BuildFlavor::default(),
+          temp_data.path(),
+          temp_res.path(),
+          &releases_repo,
+          &active_repo,
+          &retention_repo,
+          &mirrors_repo,
+          &RunningGames::default(),
+          false,
+          &CancellationToken::new(),
+          Arc::new(DummyReporter),
+        )
+        .await;
+
+      assert!(matches!(
+        result,
+        Err(ReleaseInstallationError::MissingExecutable(_))
+      ));
+      assert_eq!(release.status, GameReleaseStatus::NotInstalled);
+      assert!(
+        previous_file.is_file(),
+        "The previous installation directory should be untouched"
+      );
+      assert!(
+        !installation_dir.join("readme.txt").exists(),
+        "The invalid release should not be moved into place"
+      );
+      assert!(
+        !get_asset_staging_dir(
+          &variant,
+          version,
+          BuildFlavor::default(),
+          temp_data.path()
+        )
+        .exists(),
+        "The staging directory should be removed"
+      );
+
+      let active = active_repo.get_active_release(&variant).await?;
+      assert_eq!(active, Some("v0.9.0".to_string()));
+    }
+
+    Ok(())
+  }
+
   #[tokio::test]
   async fn test_install_release_download_error() -> TestResult {
-    let (_db, releases_repo, active_repo) =
-      setup_test_repos().await?;
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
     let downloader = create_downloader();
     let temp_data = TempDir::new()?;
     let temp_res = TempDir::new()?;
@@ -931,12 +2280,20 @@ mod tests {
 
         let result = release
           .install_release(
+            &*VariantRegistry::default().get(&variant)?,
             &downloader,
             &host_system,
+            false,
+            BuildFlavor::default(),
             temp_data.path(),
             temp_res.path(),
             &releases_repo,
             &active_repo,
+            &retention_repo,
+            &mirrors_repo,
+            &RunningGames::default(),
+            false,
+            &CancellationToken::new(),
             Arc::new(DummyReporter),
           )
           .await;
@@ -952,8 +2309,13 @@ mod tests {
 
   #[tokio::test]
   async fn test_install_release_extraction_error() -> TestResult {
-    let (_db, releases_repo, active_repo) =
-      setup_test_repos().await?;
+    let (
+      _db,
+      releases_repo,
+      active_repo,
+      retention_repo,
+      mirrors_repo,
+    ) = setup_test_repos().await?;
     let downloader = create_downloader();
     let temp_data = TempDir::new()?;
     let temp_res = TempDir::new()?;
@@ -1015,12 +2377,20 @@ mod tests {
 
         let result = release
           .install_release(
+            &*VariantRegistry::default().get(&variant)?,
             &downloader,
             &host_system,
+            false,
+            BuildFlavor::default(),
             temp_data.path(),
             temp_res.path(),
             &releases_repo,
             &active_repo,
+            &retention_repo,
+            &mirrors_repo,
+            &RunningGames::default(),
+            false,
+            &CancellationToken::new(),
             Arc::new(DummyReporter),
           )
           .await;
@@ -1,16 +1,22 @@
-use std::env::consts::OS;
+use std::env::consts::{ARCH, OS};
 
 use tauri::{AppHandle, Manager, State, command};
 
 use cat_macros::CommandErrorSerialize;
 
+use crate::emulated_builds::emulated_builds::EmulatedBuildsSetting;
 use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
 use crate::game_release::game_release::GameReleaseStatus;
 use crate::game_release::utils::{
   GetReleaseError, get_release_by_id,
 };
-use crate::infra::utils::{OSNotSupportedError, get_os_enum};
+use crate::infra::utils::{HostSystem, HostSystemError};
+use crate::install_release::installation_status::status::GetInstallationStatusError;
+use crate::sideloaded_releases::repository::sqlite_sideloaded_releases_repository::SqliteSideloadedReleasesRepository;
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavorSelection;
+use crate::variants::game_variant::UnknownGameVariantError;
+use crate::variants::variant_descriptor::VariantRegistry;
 
 /// Errors that can occur when getting the installation status via a Tauri command.
 #[derive(thiserror::Error, Debug, CommandErrorSerialize)]
@@ -23,33 +29,68 @@ pub enum GetInstallationStatusCommandError {
   #[error("failed to obtain release: {0}")]
   Release(#[from] GetReleaseError),
 
-  /// The current operating system is not supported.
-  #[error("failed to get OS enum: {0}")]
-  Os(#[from] OSNotSupportedError),
+  /// Failed to determine the status of the release.
+  #[error("failed to get release status: {0}")]
+  Status(#[from] GetInstallationStatusError),
+
+  /// The host system (OS/architecture) is not supported.
+  #[error("failed to determine host system: {0}")]
+  HostSystem(#[from] HostSystemError),
+
+  /// The game variant is not registered.
+  #[error("unknown game variant: {0}")]
+  UnknownVariant(#[from] UnknownGameVariantError),
 }
 
 /// A Tauri command that returns the installation status of a specific release.
+/// Releases that aren't installed and have no build for the host system are
+/// not available.
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn get_installation_status(
   app_handle: AppHandle,
   variant: GameVariant,
   release_id: &str,
   releases_repository: State<'_, SqliteReleasesRepository>,
+  sideloaded_releases_repository: State<
+    '_,
+    SqliteSideloadedReleasesRepository,
+  >,
+  emulated_builds: State<'_, EmulatedBuildsSetting>,
+  build_flavors: State<'_, BuildFlavorSelection>,
+  variant_registry: State<'_, VariantRegistry>,
 ) -> Result<GameReleaseStatus, GetInstallationStatusCommandError> {
   let data_dir = app_handle.path().app_local_data_dir()?;
   let resource_dir = app_handle.path().resource_dir()?;
 
-  let os = get_os_enum(OS)?;
+  let host_system = HostSystem::current(OS, ARCH)?;
+  let flavor = build_flavors.get(&variant);
 
+  let descriptor = variant_registry.get(&variant)?;
   let release = get_release_by_id(
     &variant,
+    &descriptor,
     release_id,
-    &os,
+    &host_system.os,
+    flavor,
     &data_dir,
     &resource_dir,
     &*releases_repository,
+    &*sideloaded_releases_repository,
   )
   .await?;
 
-  Ok(release.status)
+  let status = release
+    .get_host_status(
+      &descriptor,
+      &host_system,
+      emulated_builds.is_allowed(),
+      flavor,
+      &data_dir,
+      &resource_dir,
+      &*releases_repository,
+    )
+    .await?;
+
+  Ok(status)
 }
@@ -2,14 +2,18 @@ use std::path::Path;
 
 use tokio::fs;
 
+use crate::fetch_releases::repository::ReleasesRepository;
 use crate::filesystem::paths::{
   AssetDownloadDirError, AssetExtractionDirError,
-  GetExecutablePathError, get_game_executable_filepath,
+  GetExecutablePathError, get_corrupted_download_marker_path,
+  get_game_executable_filepath,
 };
 use crate::game_release::game_release::{
   GameRelease, GameReleaseStatus,
 };
-use crate::infra::utils::OS;
+use crate::infra::utils::{HostSystem, OS};
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::variant_descriptor::VariantDescriptor;
 
 /// Errors that can occur when checking the installation status of a release.
 #[derive(thiserror::Error, Debug)]
@@ -31,15 +35,20 @@ impl GameRelease {
   /// Returns the current installation status of the game release.
   ///
   /// It checks for the existence of the game executable in the appropriate
-  /// directory for the given operating system.
+  /// directory for the given operating system. A release that isn't
+  /// installed because its last download was corrupted is reported as such.
   pub async fn get_installation_status(
     &self,
+    descriptor: &VariantDescriptor,
     os: &OS,
+    flavor: BuildFlavor,
     data_dir: &Path,
   ) -> Result<GameReleaseStatus, GetInstallationStatusError> {
     let executable_path = match get_game_executable_filepath(
       &self.variant,
+      descriptor,
       &self.version,
+      flavor,
       data_dir,
       os,
     )
@@ -47,7 +56,7 @@ impl GameRelease {
     {
       Ok(path) => path,
       Err(GetExecutablePathError::DoesNotExist) => {
-        return Ok(GameReleaseStatus::NotDownloaded);
+        return Ok(self.get_missing_status(flavor, data_dir).await);
       }
       Err(e) => {
         return Err(GetInstallationStatusError::Executable(e));
@@ -56,9 +65,195 @@ impl GameRelease {
 
     match fs::metadata(&executable_path).await {
       Ok(metadata) if metadata.is_file() => {}
-      _ => return Ok(GameReleaseStatus::NotDownloaded),
+      _ => {
+        return Ok(self.get_missing_status(flavor, data_dir).await);
+      }
     }
 
     Ok(GameReleaseStatus::ReadyToPlay)
   }
+
+  /// Returns the status of a release that isn't installed.
+  async fn get_missing_status(
+    &self,
+    flavor: BuildFlavor,
+    data_dir: &Path,
+  ) -> GameReleaseStatus {
+    let marker_path = get_corrupted_download_marker_path(
+      &self.variant,
+      &self.version,
+      flavor,
+      data_dir,
+    );
+
+    match fs::try_exists(&marker_path).await {
+      Ok(true) => GameReleaseStatus::Corrupted,
+      _ => GameReleaseStatus::NotDownloaded,
+    }
+  }
+
+  /// Returns the status of the game release on the host system.
+  ///
+  /// This is its installation status, unless the release isn't installed
+  /// and publishes no asset the host system can run, in which case it is
+  /// not available.
+  #[allow(clippy::too_many_arguments)]
+  pub async fn get_host_status(
+    &self,
+    descriptor: &VariantDescriptor,
+    host_system: &HostSystem,
+    allow_emulated: bool,
+    flavor: BuildFlavor,
+    data_dir: &Path,
+    resources_dir: &Path,
+    releases_repository: &impl ReleasesRepository,
+  ) -> Result<GameReleaseStatus, GetInstallationStatusError> {
+    let status = self
+      .get_installation_status(
+        descriptor,
+        &host_system.os,
+        flavor,
+        data_dir,
+      )
+      .await?;
+
+    if status == GameReleaseStatus::NotDownloaded
+      && self
+        .get_asset(
+          descriptor,
+          host_system,
+          allow_emulated,
+          flavor,
+          resources_dir,
+          releases_repository,
+        )
+        .await
+        .is_none()
+    {
+      return Ok(GameReleaseStatus::NotAvailable);
+    }
+
+    Ok(status)
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use std::time::Duration;
+
+  use chrono::Utc;
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
+  use crate::filesystem::paths::get_asset_download_dir;
+  use crate::game_release::game_release::ReleaseType;
+  use crate::garbage_collection::garbage_collection::collect_garbage;
+  use crate::infra::github::asset::GitHubAsset;
+  use crate::infra::github::release::GitHubRelease;
+  use crate::infra::testing::test_database::TestDatabase;
+  use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+  use crate::manual_backups::repository::sqlite_manual_backup_repository::SqliteManualBackupRepository;
+  use crate::variants::GameVariant;
+  use crate::variants::variant_descriptor::VariantRegistry;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_corrupted_status_survives_garbage_collection()
+  -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let releases_repo =
+      SqliteReleasesRepository::new(db.pool().clone());
+    let backup_repo = SqliteBackupRepository::new(db.pool().clone());
+    let manual_backup_repo =
+      SqliteManualBackupRepository::new(db.pool().clone());
+    let temp_data = TempDir::new()?;
+    let temp_cache = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+    let data_dir = temp_data.path();
+    let variant = GameVariant::DarkDaysAhead;
+
+    let asset_name = "cdda-windows-tiles-x64-v1.zip";
+    releases_repo
+      .update_cached_releases(
+        &variant,
+        &[GitHubRelease {
+          id: 1,
+          tag_name: "v1".to_string(),
+          prerelease: false,
+          body: None,
+          assets: vec![GitHubAsset {
+            id: 1,
+            browser_download_url: format!(
+              "https://example.com/{asset_name}"
+            ),
+            name: asset_name.to_string(),
+            digest: None,
+          }],
+          created_at: Utc::now(),
+        }],
+      )
+      .await?;
+
+    let release = GameRelease {
+      variant,
+      version: "v1".to_string(),
+      body: None,
+      release_type: ReleaseType::Stable,
+      status: GameReleaseStatus::Unknown,
+      created_at: Utc::now(),
+    };
+    let download_dir = get_asset_download_dir(&variant, data_dir);
+    fs::create_dir_all(&download_dir).await?;
+    let archive = download_dir.join(asset_name);
+    fs::write(&archive, b"corrupted").await?;
+    fs::write(
+      get_corrupted_download_marker_path(
+        &variant,
+        "v1",
+        BuildFlavor::default(),
+        data_dir,
+      ),
+      asset_name,
+    )
+    .await?;
+
+    collect_garbage(
+      &[variant],
+      data_dir,
+      temp_cache.path(),
+      temp_res.path(),
+      &[],
+      Duration::ZERO,
+      &releases_repo,
+      &backup_repo,
+      &manual_backup_repo,
+    )
+    .await?;
+
+    assert!(!archive.exists());
+    let descriptor = VariantRegistry::default().get(&variant)?;
+    assert_eq!(
+      release
+        .get_installation_status(
+          &descriptor,
+          &OS::Windows,
+          BuildFlavor::default(),
+          data_dir,
+        )
+        .await?,
+      GameReleaseStatus::Corrupted
+    );
+
+    Ok(())
+  }
 }
@@ -1,3 +1,4 @@
 pub mod commands;
 pub mod install_release;
 pub mod installation_status;
+pub mod running_installs;
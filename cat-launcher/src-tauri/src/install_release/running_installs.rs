@@ -0,0 +1,185 @@
+use tokio_util::sync::CancellationToken;
+
+use crate::infra::watched::Watched;
+use crate::variants::GameVariant;
+
+struct RunningInstall {
+  id: u64,
+  variant: GameVariant,
+  cancel: CancellationToken,
+}
+
+#[derive(Default)]
+struct Installs {
+  next_id: u64,
+  installs: Vec<RunningInstall>,
+}
+
+/// The error returned when an installation of a variant is started while
+/// another one is running.
+#[derive(thiserror::Error, Debug)]
+#[error("{0} is already being installed")]
+pub struct AlreadyInstallingError(pub GameVariant);
+
+/// The game installations in progress, so that they can be cancelled.
+#[derive(Default)]
+pub struct RunningInstalls {
+  installs: Watched<Installs>,
+}
+
+impl RunningInstalls {
+  /// Registers an installation of the given variant. It's unregistered
+  /// when the returned guard is dropped.
+  ///
+  /// Installations of a variant share their download and staging
+  /// directories, so only one can run at a time.
+  pub fn start(
+    &self,
+    variant: GameVariant,
+  ) -> Result<InstallGuard<'_>, AlreadyInstallingError> {
+    let cancel = CancellationToken::new();
+    let id = self.installs.update(|installs| {
+      if installs.installs.iter().any(|i| i.variant == variant) {
+        return Err(AlreadyInstallingError(variant));
+      }
+
+      let id = installs.next_id;
+      installs.next_id += 1;
+      installs.installs.push(RunningInstall {
+        id,
+        variant,
+        cancel: cancel.clone(),
+      });
+      Ok(id)
+    })?;
+
+    Ok(InstallGuard {
+      installs: self,
+      id,
+      cancel,
+    })
+  }
+
+  /// Cancels the installation of the given variant. Returns whether one
+  /// was running.
+  pub fn cancel(&self, variant: &GameVariant) -> bool {
+    let installs = self.installs.lock();
+    let install =
+      installs.installs.iter().find(|i| &i.variant == variant);
+    if let Some(install) = install {
+      install.cancel.cancel();
+    }
+    install.is_some()
+  }
+
+  /// Cancels all installations and waits until they have cleaned up
+  /// after themselves.
+  pub async fn cancel_all(&self) {
+    self
+      .installs
+      .wait_for(|installs| {
+        for install in &installs.installs {
+          install.cancel.cancel();
+        }
+        installs.installs.is_empty().then_some(())
+      })
+      .await
+  }
+
+  fn remove(&self, id: u64) {
+    self
+      .installs
+      .update(|installs| installs.installs.retain(|i| i.id != id));
+  }
+}
+
+/// Keeps an installation registered while it's running.
+pub struct InstallGuard<'a> {
+  installs: &'a RunningInstalls,
+  id: u64,
+  cancel: CancellationToken,
+}
+
+impl InstallGuard<'_> {
+  /// Returns the token that is cancelled when the installation should
+  /// stop.
+  pub fn cancel_token(&self) -> &CancellationToken {
+    &self.cancel
+  }
+}
+
+impl Drop for InstallGuard<'_> {
+  fn drop(&mut self) {
+    self.installs.remove(self.id);
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use std::time::Duration;
+
+  use tokio::time::timeout;
+
+  use super::*;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_cancel_only_affects_the_given_variant() -> TestResult
+  {
+    let installs = RunningInstalls::default();
+    let dda = installs.start(GameVariant::DarkDaysAhead)?;
+    let bn = installs.start(GameVariant::BrightNights)?;
+
+    assert!(installs.cancel(&GameVariant::DarkDaysAhead));
+    assert!(dda.cancel_token().is_cancelled());
+    assert!(!bn.cancel_token().is_cancelled());
+
+    drop(dda);
+    assert!(!installs.cancel(&GameVariant::DarkDaysAhead));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_cancel_all_waits_for_installs_to_end() -> TestResult {
+    let installs = RunningInstalls::default();
+    let install = installs.start(GameVariant::DarkDaysAhead)?;
+    let cancel = install.cancel_token().clone();
+
+    let end_install = async {
+      cancel.cancelled().await;
+      drop(install);
+    };
+    timeout(Duration::from_secs(5), async {
+      tokio::join!(installs.cancel_all(), end_install)
+    })
+    .await?;
+
+    assert!(!installs.cancel(&GameVariant::DarkDaysAhead));
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_start_rejects_second_install_of_variant() -> TestResult {
+    let installs = RunningInstalls::default();
+    let install = installs.start(GameVariant::DarkDaysAhead)?;
+
+    assert!(installs.start(GameVariant::DarkDaysAhead).is_err());
+    assert!(installs.start(GameVariant::BrightNights).is_ok());
+
+    drop(install);
+    assert!(installs.start(GameVariant::DarkDaysAhead).is_ok());
+
+    Ok(())
+  }
+}
@@ -0,0 +1,144 @@
+use std::env::consts::OS;
+
+use tauri::{AppHandle, Manager, State, command};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
+use crate::infra::utils::{OSNotSupportedError, get_os_enum};
+use crate::installed_releases::installed_releases::{
+  InstalledRelease, ListInstalledReleasesError, UninstallReleaseError,
+};
+use crate::installed_releases::repository::sqlite_release_retention_policy_repository::SqliteReleaseRetentionPolicyRepository;
+use crate::installed_releases::retention_policy::{
+  ReleaseRetentionPolicy, ReleaseRetentionPolicyError,
+};
+use crate::sideloaded_releases::repository::sqlite_sideloaded_releases_repository::SqliteSideloadedReleasesRepository;
+use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavorSelection;
+use crate::variants::game_variant::UnknownGameVariantError;
+use crate::variants::variant_descriptor::VariantRegistry;
+
+/// Errors that can occur when listing installed releases via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum ListInstalledReleasesCommandError {
+  /// The system's local data or resource directory could not be found.
+  #[error("system directory not found: {0}")]
+  SystemDir(#[from] tauri::Error),
+
+  /// The host operating system is not supported.
+  #[error("failed to determine OS: {0}")]
+  Os(#[from] OSNotSupportedError),
+
+  /// Failed to list the installed releases.
+  #[error("failed to list installed releases: {0}")]
+  List(#[from] ListInstalledReleasesError),
+
+  /// The game variant is not registered.
+  #[error("unknown game variant: {0}")]
+  UnknownVariant(#[from] UnknownGameVariantError),
+}
+
+/// Lists all installed releases of a game variant along with their size on disk.
+#[command]
+pub async fn list_installed_releases(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  releases_repository: State<'_, SqliteReleasesRepository>,
+  sideloaded_releases_repository: State<
+    '_,
+    SqliteSideloadedReleasesRepository,
+  >,
+  active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+  build_flavors: State<'_, BuildFlavorSelection>,
+  variant_registry: State<'_, VariantRegistry>,
+) -> Result<Vec<InstalledRelease>, ListInstalledReleasesCommandError>
+{
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let resource_dir = app_handle.path().resource_dir()?;
+  let os = get_os_enum(OS)?;
+  let descriptor = variant_registry.get(&variant)?;
+
+  let installed_releases = variant
+    .list_installed_releases(
+      &descriptor,
+      &os,
+      build_flavors.get(&variant),
+      &data_dir,
+      &resource_dir,
+      &*releases_repository,
+      &*sideloaded_releases_repository,
+      &*active_release_repository,
+    )
+    .await?;
+
+  Ok(installed_releases)
+}
+
+/// Errors that can occur when uninstalling a release via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum UninstallReleaseCommandError {
+  /// The system's local data directory could not be found.
+  #[error("system directory not found: {0}")]
+  SystemDir(#[from] tauri::Error),
+
+  /// Failed to uninstall the release.
+  #[error("failed to uninstall release: {0}")]
+  Uninstall(#[from] UninstallReleaseError),
+}
+
+/// Uninstalls a release of a game variant, freeing its disk space.
+#[command]
+pub async fn uninstall_release(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  release_id: &str,
+  active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+  build_flavors: State<'_, BuildFlavorSelection>,
+) -> Result<(), UninstallReleaseCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+
+  variant
+    .uninstall_release(
+      release_id,
+      build_flavors.get(&variant),
+      &data_dir,
+      &*active_release_repository,
+    )
+    .await?;
+
+  Ok(())
+}
+
+/// Errors that can occur when accessing the release retention policy via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum ReleaseRetentionPolicyCommandError {
+  /// An error occurred while accessing the retention policy.
+  #[error("failed to access release retention policy: {0}")]
+  RetentionPolicy(#[from] ReleaseRetentionPolicyError),
+}
+
+/// Retrieves the release retention policy of a game variant.
+#[command]
+pub async fn get_release_retention_policy(
+  variant: GameVariant,
+  repository: State<'_, SqliteReleaseRetentionPolicyRepository>,
+) -> Result<ReleaseRetentionPolicy, ReleaseRetentionPolicyCommandError>
+{
+  Ok(variant.get_release_retention_policy(&*repository).await?)
+}
+
+/// Sets the release retention policy of a game variant.
+#[command]
+pub async fn set_release_retention_policy(
+  variant: GameVariant,
+  policy: ReleaseRetentionPolicy,
+  repository: State<'_, SqliteReleaseRetentionPolicyRepository>,
+) -> Result<(), ReleaseRetentionPolicyCommandError> {
+  variant
+    .set_release_retention_policy(policy, &*repository)
+    .await?;
+
+  Ok(())
+}
@@ -0,0 +1,552 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use ts_rs::TS;
+
+use crate::active_release::active_release::ActiveReleaseError;
+use crate::active_release::repository::ActiveReleaseRepository;
+use crate::fetch_releases::repository::ReleasesRepository;
+use crate::fetch_releases::utils::{
+  get_default_releases, merge_releases,
+};
+use crate::filesystem::paths::{
+  get_asset_download_dir, get_asset_installation_dir,
+  get_installation_dir_name, parse_installation_dir_name,
+};
+use crate::filesystem::utils::{DirSizeError, get_dir_size};
+use crate::game_release::game_release::{
+  GameRelease, GameReleaseStatus,
+};
+use crate::game_release::utils::gh_release_to_game_release;
+use crate::infra::utils::OS;
+use crate::install_release::installation_status::status::GetInstallationStatusError;
+use crate::sideloaded_releases::repository::SideloadedReleasesRepository;
+use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::variant_descriptor::VariantDescriptor;
+
+/// A release of a game variant that is currently installed on disk.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct InstalledRelease {
+  /// The version (tag name) of the installed release.
+  pub version: String,
+  /// The build flavor the release is installed in.
+  pub flavor: BuildFlavor,
+  /// The total size of the installation on disk, in bytes.
+  pub size_in_bytes: u64,
+  /// Whether this release is the active release of its variant.
+  pub is_active: bool,
+}
+
+/// Errors that can occur when listing installed releases.
+#[derive(thiserror::Error, Debug)]
+pub enum ListInstalledReleasesError {
+  /// Failed to determine the installation status of a release.
+  #[error("failed to get installation status: {0}")]
+  Status(#[from] GetInstallationStatusError),
+
+  /// Failed to compute the size of an installation.
+  #[error("failed to get installation size: {0}")]
+  Size(#[from] DirSizeError),
+
+  /// Failed to retrieve the active release.
+  #[error("failed to get active release: {0}")]
+  ActiveRelease(#[from] ActiveReleaseError),
+
+  /// Failed to list the installation directories.
+  #[error("failed to read installations: {0}")]
+  ReadDir(#[from] io::Error),
+}
+
+/// Errors that can occur when uninstalling a release.
+#[derive(thiserror::Error, Debug)]
+pub enum UninstallReleaseError {
+  /// The release is the active release and cannot be uninstalled.
+  #[error("release {0} is active and cannot be uninstalled")]
+  Active(String),
+
+  /// The release is not installed.
+  #[error("release {0} is not installed")]
+  NotInstalled(String),
+
+  /// Failed to retrieve the active release.
+  #[error("failed to get active release: {0}")]
+  ActiveRelease(#[from] ActiveReleaseError),
+
+  /// Failed to remove the installation directory.
+  #[error("failed to remove installation: {0}")]
+  Remove(#[from] io::Error),
+}
+
+impl GameVariant {
+  /// Lists all releases of this variant that are installed on disk in
+  /// the given build flavor, newest first.
+  ///
+  /// Every installation directory is listed, so that sideloaded releases
+  /// and releases that are no longer cached are included. Cached, default
+  /// and sideloaded releases provide the version and creation date of an
+  /// installation. Other installations are named after their directory
+  /// and dated by its modification time.
+  #[allow(clippy::too_many_arguments)]
+  pub async fn list_installed_releases(
+    &self,
+    descriptor: &VariantDescriptor,
+    os: &OS,
+    flavor: BuildFlavor,
+    data_dir: &Path,
+    resources_dir: &Path,
+    releases_repository: &impl ReleasesRepository,
+    sideloaded_releases_repository: &impl SideloadedReleasesRepository,
+    active_release_repository: &impl ActiveReleaseRepository,
+  ) -> Result<Vec<InstalledRelease>, ListInstalledReleasesError> {
+    let cached_releases = releases_repository
+      .get_cached_releases(self)
+      .await
+      .unwrap_or_default(); // It's okay if cached releases couldn't be read.
+    let default_releases =
+      get_default_releases(self, resources_dir).await;
+    let sideloaded_releases = sideloaded_releases_repository
+      .get_sideloaded_releases(self)
+      .await
+      .unwrap_or_default(); // It's okay if sideloaded releases couldn't be read.
+
+    let mut known_releases = HashMap::new();
+    let gh_releases =
+      merge_releases(&cached_releases, &default_releases);
+    let releases = gh_releases
+      .iter()
+      .map(|r| gh_release_to_game_release(r, self, descriptor))
+      .chain(
+        sideloaded_releases
+          .iter()
+          .map(|r| r.to_game_release(self, descriptor)),
+      );
+    for release in releases {
+      known_releases
+        .entry(get_installation_dir_name(&release.version, flavor))
+        .or_insert(release);
+    }
+
+    let active_dir_name = self
+      .get_active_release(active_release_repository)
+      .await?
+      .map(|version| get_installation_dir_name(&version, flavor));
+
+    let mut installed_releases = Vec::new();
+    for installation_dir in
+      list_dirs(&get_asset_download_dir(self, data_dir)).await?
+    {
+      let Some(dir_name) = installation_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+      else {
+        continue;
+      };
+
+      let release = match known_releases.remove(&dir_name) {
+        Some(release) => release,
+        None => {
+          let Some(version) =
+            parse_installation_dir_name(&dir_name, flavor)
+          else {
+            continue;
+          };
+          let created_at = fs::metadata(&installation_dir)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_default();
+          GameRelease {
+            variant: *self,
+            version: version.to_string(),
+            body: None,
+            release_type: descriptor.release_type(version, false),
+            status: GameReleaseStatus::Unknown,
+            created_at,
+          }
+        }
+      };
+
+      if release
+        .get_installation_status(descriptor, os, flavor, data_dir)
+        .await?
+        != GameReleaseStatus::ReadyToPlay
+      {
+        continue;
+      }
+
+      let installed_release = InstalledRelease {
+        size_in_bytes: get_dir_size(&installation_dir).await?,
+        is_active: active_dir_name.as_ref() == Some(&dir_name),
+        version: release.version,
+        flavor,
+      };
+      installed_releases
+        .push((release.created_at, installed_release));
+    }
+
+    installed_releases
+      .sort_by_key(|(created_at, _)| Reverse(*created_at));
+    Ok(
+      installed_releases
+        .into_iter()
+        .map(|(_, installed_release)| installed_release)
+        .collect(),
+    )
+  }
+
+  /// Removes the installation of a release of this variant in the given
+  /// build flavor.
+  ///
+  /// The active release cannot be uninstalled. User data such as saves is
+  /// stored separately and is not affected.
+  pub async fn uninstall_release(
+    &self,
+    version: &str,
+    flavor: BuildFlavor,
+    data_dir: &Path,
+    active_release_repository: &impl ActiveReleaseRepository,
+  ) -> Result<(), UninstallReleaseError> {
+    let active_release =
+      self.get_active_release(active_release_repository).await?;
+    if active_release.as_deref() == Some(version) {
+      return Err(UninstallReleaseError::Active(version.to_string()));
+    }
+
+    let installation_dir =
+      get_asset_installation_dir(self, version, flavor, data_dir);
+    if !fs::metadata(&installation_dir)
+      .await
+      .is_ok_and(|metadata| metadata.is_dir())
+    {
+      return Err(UninstallReleaseError::NotInstalled(
+        version.to_string(),
+      ));
+    }
+
+    fs::remove_dir_all(&installation_dir).await?;
+
+    Ok(())
+  }
+}
+
+/// Returns the paths of the directories in `dir`. A directory that
+/// doesn't exist is empty.
+async fn list_dirs(dir: &Path) -> io::Result<Vec<PathBuf>> {
+  let mut entries = match fs::read_dir(dir).await {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+      return Ok(Vec::new());
+    }
+    Err(e) => return Err(e),
+  };
+
+  let mut dirs = Vec::new();
+  while let Some(entry) = entries.next_entry().await? {
+    if entry.file_type().await?.is_dir() {
+      dirs.push(entry.path());
+    }
+  }
+
+  Ok(dirs)
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+  use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
+  use crate::infra::github::release::GitHubRelease;
+  use crate::infra::testing::test_database::TestDatabase;
+  use crate::sideloaded_releases::repository::sqlite_sideloaded_releases_repository::SqliteSideloadedReleasesRepository;
+  use crate::sideloaded_releases::sideloaded_releases::SideloadedRelease;
+  use crate::variants::variant_descriptor::VariantRegistry;
+  use chrono::Duration;
+  use tempfile::TempDir;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  async fn install_dummy_release(
+    variant: &GameVariant,
+    version: &str,
+    data_dir: &Path,
+  ) -> TestResult {
+    let game_dir = get_asset_installation_dir(
+      variant,
+      version,
+      BuildFlavor::default(),
+      data_dir,
+    )
+    .join("cataclysm-dda");
+    fs::create_dir_all(&game_dir).await?;
+    fs::write(game_dir.join("cataclysm-launcher"), b"dummy").await?;
+    Ok(())
+  }
+
+  fn create_gh_release(
+    id: u64,
+    tag_name: &str,
+    days_ago: i64,
+  ) -> GitHubRelease {
+    GitHubRelease {
+      id,
+      tag_name: tag_name.to_string(),
+      prerelease: false,
+      body: None,
+      assets: vec![],
+      created_at: Utc::now() - Duration::days(days_ago),
+    }
+  }
+
+  #[tokio::test]
+  async fn test_list_installed_releases() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let releases_repo =
+      SqliteReleasesRepository::new(db.pool().clone());
+    let sideloaded_repo =
+      SqliteSideloadedReleasesRepository::new(db.pool().clone());
+    let active_repo =
+      SqliteActiveReleaseRepository::new(db.pool().clone());
+    let temp_data = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+    let variant = GameVariant::DarkDaysAhead;
+
+    releases_repo
+      .update_cached_releases(
+        &variant,
+        &[
+          create_gh_release(1, "v1.0.0", 3),
+          create_gh_release(2, "v2.0.0", 2),
+          create_gh_release(3, "v3.0.0", 1),
+        ],
+      )
+      .await?;
+
+    install_dummy_release(&variant, "v1.0.0", temp_data.path())
+      .await?;
+    install_dummy_release(&variant, "v2.0.0", temp_data.path())
+      .await?;
+    variant.set_active_release("v2.0.0", &active_repo).await?;
+
+    let installed = variant
+      .list_installed_releases(
+        &*VariantRegistry::default().get(&variant)?,
+        &OS::Linux,
+        BuildFlavor::default(),
+        temp_data.path(),
+        temp_res.path(),
+        &releases_repo,
+        &sideloaded_repo,
+        &active_repo,
+      )
+      .await?;
+
+    assert_eq!(installed.len(), 2);
+    assert_eq!(installed[0].version, "v2.0.0");
+    assert!(installed[0].is_active);
+    assert_eq!(installed[0].size_in_bytes, 5);
+    assert_eq!(installed[1].version, "v1.0.0");
+    assert!(!installed[1].is_active);
+
+    assert!(
+      !get_asset_installation_dir(
+        &variant,
+        "v3.0.0",
+        BuildFlavor::default(),
+        temp_data.path()
+      )
+      .exists(),
+      "Listing should not create directories for missing releases"
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_list_installed_releases_includes_uncached_releases()
+  -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let releases_repo =
+      SqliteReleasesRepository::new(db.pool().clone());
+    let sideloaded_repo =
+      SqliteSideloadedReleasesRepository::new(db.pool().clone());
+    let active_repo =
+      SqliteActiveReleaseRepository::new(db.pool().clone());
+    let temp_data = TempDir::new()?;
+    let temp_res = TempDir::new()?;
+    let variant = GameVariant::DarkDaysAhead;
+
+    releases_repo
+      .update_cached_releases(
+        &variant,
+        &[create_gh_release(1, "v1.0.0", 3)],
+      )
+      .await?;
+    sideloaded_repo
+      .add_sideloaded_release(
+        &variant,
+        &SideloadedRelease {
+          version: "local-build".to_string(),
+          created_at: Utc::now() - Duration::days(1),
+        },
+      )
+      .await?;
+
+    install_dummy_release(&variant, "v1.0.0", temp_data.path())
+      .await?;
+    install_dummy_release(&variant, "local-build", temp_data.path())
+      .await?;
+    // A release that is no longer cached.
+    install_dummy_release(&variant, "v0.9.0", temp_data.path())
+      .await?;
+    variant
+      .set_active_release("local-build", &active_repo)
+      .await?;
+
+    let installed = variant
+      .list_installed_releases(
+        &*VariantRegistry::default().get(&variant)?,
+        &OS::Linux,
+        BuildFlavor::default(),
+        temp_data.path(),
+        temp_res.path(),
+        &releases_repo,
+        &sideloaded_repo,
+        &active_repo,
+      )
+      .await?;
+
+    let mut versions: Vec<_> =
+      installed.iter().map(|r| r.version.as_str()).collect();
+    versions.sort_unstable();
+    // The uncached release is named after its installation directory.
+    assert_eq!(versions, ["local-build", "v0_9_0", "v1.0.0"]);
+    assert!(
+      installed
+        .iter()
+        .find(|r| r.version == "local-build")
+        .is_some_and(|r| r.is_active)
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_uninstall_release() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let active_repo =
+      SqliteActiveReleaseRepository::new(db.pool().clone());
+    let temp_data = TempDir::new()?;
+    let variant = GameVariant::BrightNights;
+
+    install_dummy_release(&variant, "v1.0.0", temp_data.path())
+      .await?;
+    install_dummy_release(&variant, "v2.0.0", temp_data.path())
+      .await?;
+    variant.set_active_release("v2.0.0", &active_repo).await?;
+
+    variant
+      .uninstall_release(
+        "v1.0.0",
+        BuildFlavor::default(),
+        temp_data.path(),
+        &active_repo,
+      )
+      .await?;
+
+    assert!(
+      !get_asset_installation_dir(
+        &variant,
+        "v1.0.0",
+        BuildFlavor::default(),
+        temp_data.path()
+      )
+      .exists()
+    );
+    assert!(
+      get_asset_installation_dir(
+        &variant,
+        "v2.0.0",
+        BuildFlavor::default(),
+        temp_data.path()
+      )
+      .exists()
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_uninstall_release_refuses_active_release()
+  -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let active_repo =
+      SqliteActiveReleaseRepository::new(db.pool().clone());
+    let temp_data = TempDir::new()?;
+    let variant = GameVariant::TheLastGeneration;
+
+    install_dummy_release(&variant, "v1.0.0", temp_data.path())
+      .await?;
+    variant.set_active_release("v1.0.0", &active_repo).await?;
+
+    let result = variant
+      .uninstall_release(
+        "v1.0.0",
+        BuildFlavor::default(),
+        temp_data.path(),
+        &active_repo,
+      )
+      .await;
+
+    assert!(matches!(result, Err(UninstallReleaseError::Active(_))));
+    assert!(
+      get_asset_installation_dir(
+        &variant,
+        "v1.0.0",
+        BuildFlavor::default(),
+        temp_data.path()
+      )
+      .exists()
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_uninstall_release_not_installed() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let active_repo =
+      SqliteActiveReleaseRepository::new(db.pool().clone());
+    let temp_data = TempDir::new()?;
+
+    let result = GameVariant::DarkDaysAhead
+      .uninstall_release(
+        "v1.0.0",
+        BuildFlavor::default(),
+        temp_data.path(),
+        &active_repo,
+      )
+      .await;
+
+    assert!(matches!(
+      result,
+      Err(UninstallReleaseError::NotInstalled(_))
+    ));
+
+    Ok(())
+  }
+}
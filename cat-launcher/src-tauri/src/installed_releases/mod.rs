@@ -0,0 +1,5 @@
+pub mod commands;
+#[allow(clippy::module_inception)]
+pub mod installed_releases;
+pub mod repository;
+pub mod retention_policy;
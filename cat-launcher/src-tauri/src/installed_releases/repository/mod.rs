@@ -0,0 +1,7 @@
+pub mod release_retention_policy_repository;
+pub mod sqlite_release_retention_policy_repository;
+
+pub use release_retention_policy_repository::{
+  ReleaseRetentionPolicyRepository,
+  ReleaseRetentionPolicyRepositoryError,
+};
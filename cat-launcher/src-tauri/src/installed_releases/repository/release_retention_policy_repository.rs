@@ -0,0 +1,42 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use crate::installed_releases::retention_policy::ReleaseRetentionPolicy;
+use crate::variants::game_variant::GameVariant;
+
+/// Errors that can occur when interacting with the release retention policy repository.
+#[derive(thiserror::Error, Debug)]
+pub enum ReleaseRetentionPolicyRepositoryError {
+  /// An error occurred while retrieving the retention policy.
+  #[error("failed to get release retention policy: {0}")]
+  Get(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while setting the retention policy.
+  #[error("failed to set release retention policy: {0}")]
+  Set(Box<dyn Error + Send + Sync>),
+
+  /// The stored retention policy value is invalid.
+  #[error("invalid release retention policy: {0}")]
+  InvalidPolicy(String),
+}
+
+/// A repository for managing the release retention policy of each game variant.
+#[async_trait]
+pub trait ReleaseRetentionPolicyRepository: Send + Sync {
+  /// Retrieves the retention policy for the given game variant, if one was set.
+  async fn get_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<
+    Option<ReleaseRetentionPolicy>,
+    ReleaseRetentionPolicyRepositoryError,
+  >;
+
+  /// Sets the retention policy for the given game variant.
+  async fn set_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+    policy: &ReleaseRetentionPolicy,
+  ) -> Result<(), ReleaseRetentionPolicyRepositoryError>;
+}
@@ -0,0 +1,100 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::OptionalExtension;
+use tokio::task;
+
+use crate::installed_releases::repository::{
+  ReleaseRetentionPolicyRepository,
+  ReleaseRetentionPolicyRepositoryError,
+};
+use crate::installed_releases::retention_policy::ReleaseRetentionPolicy;
+use crate::variants::game_variant::GameVariant;
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+/// A repository for managing release retention policies using a SQLite database.
+pub struct SqliteReleaseRetentionPolicyRepository {
+  pool: Pool,
+}
+
+impl SqliteReleaseRetentionPolicyRepository {
+  /// Creates a new instance of `SqliteReleaseRetentionPolicyRepository` with the given connection pool.
+  pub fn new(pool: Pool) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl ReleaseRetentionPolicyRepository
+  for SqliteReleaseRetentionPolicyRepository
+{
+  async fn get_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<
+    Option<ReleaseRetentionPolicy>,
+    ReleaseRetentionPolicyRepositoryError,
+  > {
+    let pool = self.pool.clone();
+    let game_variant = *game_variant;
+
+    task::spawn_blocking(move || {
+      let conn = pool.get().map_err(|e| {
+        ReleaseRetentionPolicyRepositoryError::Get(Box::new(e))
+      })?;
+
+      let stored_policy: Option<String> = conn
+        .query_row(
+          "SELECT policy FROM release_retention_policy WHERE game_variant = ?1",
+          [game_variant.to_string()],
+          |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| {
+          ReleaseRetentionPolicyRepositoryError::Get(Box::new(e))
+        })?;
+
+      stored_policy
+        .map(|policy| {
+          ReleaseRetentionPolicy::from_str(&policy).map_err(|_| {
+            ReleaseRetentionPolicyRepositoryError::InvalidPolicy(
+              policy.clone(),
+            )
+          })
+        })
+        .transpose()
+    })
+    .await
+    .map_err(|e| ReleaseRetentionPolicyRepositoryError::Get(Box::new(e)))?
+  }
+
+  async fn set_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+    policy: &ReleaseRetentionPolicy,
+  ) -> Result<(), ReleaseRetentionPolicyRepositoryError> {
+    let pool = self.pool.clone();
+    let game_variant = *game_variant;
+    let policy = policy.to_string();
+
+    task::spawn_blocking(move || {
+      let conn = pool.get().map_err(|e| {
+        ReleaseRetentionPolicyRepositoryError::Set(Box::new(e))
+      })?;
+      conn
+        .execute(
+          "INSERT OR REPLACE INTO release_retention_policy (game_variant, policy) VALUES (?1, ?2)",
+          (game_variant.to_string(), policy),
+        )
+        .map_err(|e| {
+          ReleaseRetentionPolicyRepositoryError::Set(Box::new(e))
+        })?;
+
+      Ok(())
+    })
+    .await
+    .map_err(|e| ReleaseRetentionPolicyRepositoryError::Set(Box::new(e)))?
+  }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoStaticStr};
+use ts_rs::TS;
+
+use crate::installed_releases::repository::{
+  ReleaseRetentionPolicyRepository,
+  ReleaseRetentionPolicyRepositoryError,
+};
+use crate::variants::GameVariant;
+
+/// Controls what happens to older installations when a new release is installed.
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  TS,
+  Display,
+  EnumString,
+  IntoStaticStr,
+  EnumIter,
+)]
+#[strum(ascii_case_insensitive)]
+#[ts(export)]
+pub enum ReleaseRetentionPolicy {
+  /// Only the newly installed release is kept; all others are deleted.
+  #[default]
+  KeepActiveOnly,
+  /// Every installed release is kept until it is explicitly uninstalled.
+  KeepAll,
+}
+
+/// Errors that can occur when interacting with the release retention policy.
+#[derive(thiserror::Error, Debug)]
+pub enum ReleaseRetentionPolicyError {
+  /// An error occurred in the release retention policy repository.
+  #[error("failed to access release retention policy: {0}")]
+  Repository(#[from] ReleaseRetentionPolicyRepositoryError),
+}
+
+impl GameVariant {
+  /// Retrieves the release retention policy for this variant.
+  ///
+  /// Falls back to the default policy if none has been set.
+  pub async fn get_release_retention_policy(
+    &self,
+    repository: &impl ReleaseRetentionPolicyRepository,
+  ) -> Result<ReleaseRetentionPolicy, ReleaseRetentionPolicyError> {
+    Ok(
+      repository
+        .get_retention_policy(self)
+        .await?
+        .unwrap_or_default(),
+    )
+  }
+
+  /// Sets the release retention policy for this variant.
+  pub async fn set_release_retention_policy(
+    &self,
+    policy: ReleaseRetentionPolicy,
+    repository: &impl ReleaseRetentionPolicyRepository,
+  ) -> Result<(), ReleaseRetentionPolicyError> {
+    repository.set_retention_policy(self, &policy).await?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::infra::testing::test_database::TestDatabase;
+  use crate::installed_releases::repository::sqlite_release_retention_policy_repository::SqliteReleaseRetentionPolicyRepository;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_get_release_retention_policy_defaults_to_keep_active_only()
+  -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteReleaseRetentionPolicyRepository::new(db.pool().clone());
+
+    for variant in [
+      GameVariant::DarkDaysAhead,
+      GameVariant::BrightNights,
+      GameVariant::TheLastGeneration,
+    ] {
+      let policy =
+        variant.get_release_retention_policy(&repo).await?;
+      assert_eq!(policy, ReleaseRetentionPolicy::KeepActiveOnly);
+    }
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_set_and_get_release_retention_policy() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteReleaseRetentionPolicyRepository::new(db.pool().clone());
+
+    GameVariant::BrightNights
+      .set_release_retention_policy(
+        ReleaseRetentionPolicy::KeepAll,
+        &repo,
+      )
+      .await?;
+
+    assert_eq!(
+      GameVariant::BrightNights
+        .get_release_retention_policy(&repo)
+        .await?,
+      ReleaseRetentionPolicy::KeepAll
+    );
+    assert_eq!(
+      GameVariant::DarkDaysAhead
+        .get_release_retention_policy(&repo)
+        .await?,
+      ReleaseRetentionPolicy::KeepActiveOnly
+    );
+
+    GameVariant::BrightNights
+      .set_release_retention_policy(
+        ReleaseRetentionPolicy::KeepActiveOnly,
+        &repo,
+      )
+      .await?;
+
+    assert_eq!(
+      GameVariant::BrightNights
+        .get_release_retention_policy(&repo)
+        .await?,
+      ReleaseRetentionPolicy::KeepActiveOnly
+    );
+
+    Ok(())
+  }
+}
@@ -8,11 +8,18 @@ use cat_macros::CommandErrorSerialize;
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
 use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
 use crate::infra::utils::{get_os_enum, OSNotSupportedError};
+use crate::install_release::install_release::prune_installations;
+use crate::installed_releases::repository::sqlite_release_retention_policy_repository::SqliteReleaseRetentionPolicyRepository;
 use crate::launch_game::launch_game::{
   launch_and_monitor_game, GameEvent, LaunchGameError,
 };
 use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+use crate::launch_game::running_games::RunningGames;
+use crate::sideloaded_releases::repository::sqlite_sideloaded_releases_repository::SqliteSideloadedReleasesRepository;
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavorSelection;
+use crate::variants::game_variant::UnknownGameVariantError;
+use crate::variants::variant_descriptor::VariantRegistry;
 
 /// Errors that can occur when executing the launch game command.
 #[derive(thiserror::Error, Debug, CommandErrorSerialize)]
@@ -32,6 +39,10 @@ pub enum LaunchGameCommandError {
   /// The current operating system is not supported.
   #[error("failed to get OS enum: {0}")]
   Os(#[from] OSNotSupportedError),
+
+  /// The game variant is not registered.
+  #[error("unknown game variant: {0}")]
+  UnknownVariant(#[from] UnknownGameVariantError),
 }
 
 /// Tauri command to launch and monitor a game instance.
@@ -39,6 +50,9 @@ pub enum LaunchGameCommandError {
 /// This command resolves necessary paths, gets the current system time,
 /// and starts the game monitoring process. It also sets up an event emitter
 /// to forward game events to the frontend.
+///
+/// Old installations whose deletion was put off while the game was running
+/// are deleted once it exits.
 #[command]
 #[allow(clippy::too_many_arguments)]
 pub async fn launch_game(
@@ -47,8 +61,15 @@ pub async fn launch_game(
   release_id: &str,
   world: Option<&str>,
   releases_repository: State<'_, SqliteReleasesRepository>,
+  sideloaded_releases_repository: State<
+    '_,
+    SqliteSideloadedReleasesRepository,
+  >,
   backup_repository: State<'_, SqliteBackupRepository>,
   active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+  running_games: State<'_, RunningGames>,
+  build_flavors: State<'_, BuildFlavorSelection>,
+  variant_registry: State<'_, VariantRegistry>,
 ) -> Result<(), LaunchGameCommandError> {
   let data_dir = app_handle.path().app_local_data_dir()?;
   let resource_dir = app_handle.path().resource_dir()?;
@@ -56,6 +77,8 @@ pub async fn launch_game(
   let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
   let os = get_os_enum(OS)?;
+  let flavor = build_flavors.get(&variant);
+  let descriptor = variant_registry.get(&variant)?;
 
   let emitter = app_handle.clone();
   let on_game_event = move |event: GameEvent| {
@@ -68,18 +91,46 @@ pub async fn launch_game(
 
   launch_and_monitor_game(
     &variant,
+    &descriptor,
     release_id,
     world,
     &os,
+    flavor,
     time,
     &data_dir,
     &resource_dir,
     &*releases_repository,
+    &*sideloaded_releases_repository,
     backup_repository.inner().clone(),
     &*active_release_repository,
+    running_games.start(variant),
     on_game_event,
   )
   .await?;
 
+  // Installing a release while the game is running leaves its old
+  // installations in place, so they're deleted once it exits.
+  let running_games = running_games.inner().clone();
+  tauri::async_runtime::spawn(async move {
+    if !running_games.wait_until_stopped(&variant).await {
+      return;
+    }
+
+    let active_release_repository: State<
+      SqliteActiveReleaseRepository,
+    > = app_handle.state();
+    let retention_policy_repository: State<
+      SqliteReleaseRetentionPolicyRepository,
+    > = app_handle.state();
+    prune_installations(
+      &variant,
+      flavor,
+      &data_dir,
+      active_release_repository.inner(),
+      retention_policy_repository.inner(),
+    )
+    .await;
+  });
+
   Ok(())
 }
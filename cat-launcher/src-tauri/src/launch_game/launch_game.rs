@@ -1,23 +1,28 @@
+use std::ffi::OsString;
 use std::future::Future;
 use std::io;
 use std::path::Path;
 use std::process::Stdio;
 
 use serde::Serialize;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command;
 use tokio::task::JoinError;
-use tokio::task::JoinSet;
+use tokio::task::{JoinHandle, JoinSet};
 use ts_rs::TS;
 
 use crate::active_release::repository::ActiveReleaseRepository;
-use crate::constants::MAX_BACKUPS;
+use crate::backups::backups::{
+  prune_backup_store, remove_backup_files,
+};
+use crate::constants::{MAX_BACKUPS, ORPHAN_MIN_AGE};
 use crate::fetch_releases::repository::ReleasesRepository;
 use crate::filesystem::paths::{
   AssetDownloadDirError, AssetExtractionDirError,
   GetAutomaticBackupArchivePathError, GetExecutablePathError,
   GetUserGameDataDirError, get_game_executable_filepath,
   get_or_create_automatic_backup_archive_filepath,
+  get_or_create_automatic_backup_manifest_filepath,
   get_or_create_user_game_data_dir,
 };
 use crate::game_release::game_release::GameRelease;
@@ -28,8 +33,15 @@ use crate::infra::utils::OS;
 use crate::launch_game::repository::{
   BackupRepository, BackupRepositoryError,
 };
+use crate::launch_game::running_games::GameSession;
+use crate::launch_game::terminal::{
+  NoTerminalError, terminal_command,
+};
 use crate::launch_game::utils::{BackupError, backup_save_files};
+use crate::sideloaded_releases::repository::SideloadedReleasesRepository;
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::variant_descriptor::VariantDescriptor;
 
 /// Errors that can occur during the game launch process.
 #[derive(thiserror::Error, Debug)]
@@ -62,13 +74,10 @@ pub enum LaunchGameError {
   #[error("failed to get user data directory: {0}")]
   UserGameDataDir(#[from] GetUserGameDataDirError),
 
-  /// Failed to capture stdout from the game process.
-  #[error("failed to get stdout from child process")]
-  Stdout,
-
-  /// Failed to capture stderr from the game process.
-  #[error("failed to get stderr from child process")]
-  Stderr,
+  /// The release is a terminal build, but no terminal to run it in was
+  /// found.
+  #[error("failed to find terminal: {0}")]
+  Terminal(#[from] NoTerminalError),
 
   /// Failed to retrieve information about the game release.
   #[error("failed to obtain release: {0}")]
@@ -121,9 +130,14 @@ impl GameRelease {
   ///
   /// This includes setting up the executable path, creating a backup of save files,
   /// and configuring the command arguments (e.g., `--userdir`, `--world`).
+  /// The release is launched in the given build flavor. Terminal builds are run inside a
+  /// terminal, and their output isn't captured.
+  #[allow(clippy::too_many_arguments)]
   pub async fn prepare_launch(
     &self,
+    descriptor: &VariantDescriptor,
     os: &OS,
+    flavor: BuildFlavor,
     world: Option<&str>,
     timestamp: u64,
     data_dir: &Path,
@@ -131,7 +145,9 @@ impl GameRelease {
   ) -> Result<(Command, Option<BackupError>), LaunchGameError> {
     let executable_path = get_game_executable_filepath(
       &self.variant,
+      descriptor,
       &self.version,
+      flavor,
       data_dir,
       os,
     )
@@ -173,27 +189,34 @@ impl GameRelease {
     let user_data_dir =
       get_or_create_user_game_data_dir(&self.variant, data_dir)
         .await?;
-    let mut command = Command::new(executable_path);
-
-    command
-      .current_dir(executable_dir)
-      .arg("--userdir")
-      .arg(user_data_dir)
-      .stdout(Stdio::piped())
-      .stderr(Stdio::piped());
-
+    let mut args: Vec<OsString> =
+      vec!["--userdir".into(), user_data_dir.into_os_string()];
     if let Some(world) = world {
-      command.arg("--world").arg(world);
+      args.push("--world".into());
+      args.push(world.into());
     }
 
+    let command = if flavor.needs_terminal() {
+      terminal_command(&executable_path, &args, &executable_dir, os)?
+    } else {
+      let mut command = Command::new(executable_path);
+      command
+        .current_dir(executable_dir)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+      command
+    };
+
     Ok((command, backup_failure))
   }
 }
 
 /// Runs the game process and monitors its output.
 ///
-/// Spawns the command, captures stdout and stderr, and forwards logs
-/// and the exit event via the provided `on_game_event` callback.
+/// Spawns the command, captures stdout and stderr if they're piped, and
+/// forwards logs and the exit event via the provided `on_game_event`
+/// callback.
 pub async fn run_game_and_monitor<F, Fut>(
   mut command: Command,
   on_game_event: F,
@@ -204,36 +227,21 @@ where
 {
   let mut child = command.spawn()?;
 
-  let stdout = child.stdout.take().ok_or(LaunchGameError::Stdout)?;
-  let stderr = child.stderr.take().ok_or(LaunchGameError::Stderr)?;
-
-  let mut stdout_reader = BufReader::new(stdout).lines();
-  let mut stderr_reader = BufReader::new(stderr).lines();
-
-  let on_game_event_clone = on_game_event.clone();
-  let stdout_task = tokio::spawn(async move {
-    while let Some(line) = stdout_reader.next_line().await.transpose()
-    {
-      if let Ok(line) = line {
-        on_game_event_clone(GameEvent::Log(line)).await;
-      }
-    }
-  });
-
-  let on_game_event_clone = on_game_event.clone();
-  let stderr_task = tokio::spawn(async move {
-    while let Some(line) = stderr_reader.next_line().await.transpose()
-    {
-      if let Ok(line) = line {
-        on_game_event_clone(GameEvent::Log(line)).await;
-      }
-    }
-  });
+  // Terminal builds write to their terminal, so there's nothing to
+  // forward for them.
+  let stdout_task = child
+    .stdout
+    .take()
+    .map(|stdout| forward_logs(stdout, on_game_event.clone()));
+  let stderr_task = child
+    .stderr
+    .take()
+    .map(|stderr| forward_logs(stderr, on_game_event.clone()));
 
   let status = child.wait().await?;
 
-  let stdout_task_result = stdout_task.await;
-  let stderr_task_result = stderr_task.await;
+  let stdout_task_result = join_optional(stdout_task).await;
+  let stderr_task_result = join_optional(stderr_task).await;
 
   // Exit is emitted before waiting for other tasks to complete so that
   // an error does not prevent the exit event from being ever emitted.
@@ -248,6 +256,34 @@ where
   Ok(())
 }
 
+fn forward_logs<R, F, Fut>(
+  reader: R,
+  on_game_event: F,
+) -> JoinHandle<()>
+where
+  R: AsyncRead + Unpin + Send + 'static,
+  F: Fn(GameEvent) -> Fut + Send + Sync + 'static,
+  Fut: Future<Output = ()> + Send,
+{
+  tokio::spawn(async move {
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await.transpose() {
+      if let Ok(line) = line {
+        on_game_event(GameEvent::Log(line)).await;
+      }
+    }
+  })
+}
+
+async fn join_optional(
+  task: Option<JoinHandle<()>>,
+) -> Result<(), JoinError> {
+  match task {
+    Some(task) => task.await,
+    None => Ok(()),
+  }
+}
+
 async fn cleanup_old_backups(
   backup_repository: impl BackupRepository + Clone + 'static,
   variant: &GameVariant,
@@ -271,48 +307,78 @@ async fn cleanup_old_backups(
     let variant_clone = *variant;
     let backup_repo_clone = backup_repository.clone();
     set.spawn(async move {
-      let path_res = get_or_create_automatic_backup_archive_filepath(
-        &variant_clone,
-        backup.id,
-        &backup.release_version,
-        backup.timestamp,
-        &data_dir_clone,
-      )
-      .await;
+      let archive_path_res =
+        get_or_create_automatic_backup_archive_filepath(
+          &variant_clone,
+          backup.id,
+          &backup.release_version,
+          backup.timestamp,
+          &data_dir_clone,
+        )
+        .await;
+      let manifest_path_res =
+        get_or_create_automatic_backup_manifest_filepath(
+          &variant_clone,
+          backup.id,
+          &backup.release_version,
+          backup.timestamp,
+          &data_dir_clone,
+        )
+        .await;
 
       if backup_repo_clone
         .delete_backup_entry(backup.id)
         .await
         .is_ok()
-        && let Ok(path) = path_res
+        && let Ok(archive_path) = archive_path_res
+        && let Ok(manifest_path) = manifest_path_res
       {
         // file deletion fails is ignored.
-        let _ = tokio::fs::remove_file(&path).await;
+        let _ =
+          remove_backup_files(&[&manifest_path, &archive_path]).await;
       }
     });
   }
 
   while set.join_next().await.is_some() {}
 
+  // Garbage collection prunes the store too, so a failure here only
+  // delays it.
+  if let Err(e) = prune_backup_store(
+    variant,
+    data_dir,
+    &backup_repository,
+    ORPHAN_MIN_AGE,
+  )
+  .await
+  {
+    eprintln!("Failed to prune backup store: {}", e);
+  }
+
   Ok(())
 }
 
 /// High-level function to launch and monitor a game release.
 ///
 /// This function coordinates retrieving the release information, preparing the launch
-/// environment (including backups), and starting the monitoring task.
+/// environment (including backups), and starting the monitoring task. The game
+/// session is held until the game exits.
 #[allow(clippy::too_many_arguments)]
 pub async fn launch_and_monitor_game<F, Fut>(
   variant: &GameVariant,
+  descriptor: &VariantDescriptor,
   release_id: &str,
   world: Option<&str>,
   os: &OS,
+  flavor: BuildFlavor,
   timestamp: u64,
   data_dir: &Path,
   resource_dir: &Path,
   releases_repository: &impl ReleasesRepository,
+  sideloaded_releases_repository: &impl SideloadedReleasesRepository,
   backup_repository: impl BackupRepository + Clone + 'static,
   active_release_repository: &impl ActiveReleaseRepository,
+  session: GameSession,
   on_game_event: F,
 ) -> Result<(), LaunchGameError>
 where
@@ -321,11 +387,14 @@ where
 {
   let release = get_release_by_id(
     variant,
+    descriptor,
     release_id,
     os,
+    flavor,
     data_dir,
     resource_dir,
     releases_repository,
+    sideloaded_releases_repository,
   )
   .await?;
 
@@ -336,7 +405,9 @@ where
 
   let (command, backup_failure) = release
     .prepare_launch(
+      descriptor,
       os,
+      flavor,
       world,
       timestamp,
       data_dir,
@@ -378,6 +449,7 @@ where
   // run_game_and_monitor streams to the frontend.
   tokio::spawn(async move {
     let result = run_game_and_monitor(command, on_game_event).await;
+    drop(session);
 
     if let Err(e) = result {
       eprintln!("Error running game: {}", e);
@@ -415,7 +487,10 @@ mod tests {
   use crate::infra::github::release::GitHubRelease;
   use crate::infra::testing::test_database::TestDatabase;
   use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+  use crate::launch_game::running_games::RunningGames;
+  use crate::sideloaded_releases::repository::sqlite_sideloaded_releases_repository::SqliteSideloadedReleasesRepository;
   use crate::variants::GameVariant;
+  use crate::variants::variant_descriptor::VariantRegistry;
   use chrono::Utc;
   use std::sync::{Arc, Mutex};
   use tempfile::TempDir;
@@ -445,18 +520,29 @@ mod tests {
     os: &OS,
   ) -> TestResult<std::path::PathBuf> {
     let install_dir = get_or_create_asset_installation_dir(
-      variant, version, data_dir,
+      variant,
+      version,
+      BuildFlavor::default(),
+      data_dir,
     )
     .await?;
     if os == &OS::Linux {
       get_or_create_directory(&install_dir, "cataclysm-dda").await?;
     }
 
-    let exec_dir =
-      get_game_executable_dir(variant, version, data_dir, os).await?;
+    let exec_dir = get_game_executable_dir(
+      variant,
+      version,
+      BuildFlavor::default(),
+      data_dir,
+      os,
+    )
+    .await?;
     tokio::fs::create_dir_all(&exec_dir).await?;
 
-    let exec_filename = get_game_executable_filenames(variant, os)[0];
+    let descriptor = VariantRegistry::default().get(variant)?;
+    let exec_filename =
+      get_game_executable_filenames(&descriptor, os)[0];
     let exec_path = exec_dir.join(exec_filename);
 
     let script: &[u8] = match os {
@@ -513,7 +599,9 @@ mod tests {
 
       let (command, backup_failure) = release
         .prepare_launch(
+          &*VariantRegistry::default().get(&variant)?,
           &os,
+          BuildFlavor::default(),
           Some("TestWorld"),
           1000,
           temp_data.path(),
@@ -557,7 +645,9 @@ mod tests {
 
       let result = release
         .prepare_launch(
+          &*VariantRegistry::default().get(&variant)?,
           &OS::Linux,
+          BuildFlavor::default(),
           None,
           1000,
           temp_data.path(),
@@ -605,7 +695,9 @@ mod tests {
 
       let (command, backup_failure) = release
         .prepare_launch(
+          &*VariantRegistry::default().get(&variant)?,
           &os,
+          BuildFlavor::default(),
           None,
           2000,
           temp_data.path(),
@@ -802,6 +894,7 @@ mod tests {
   #[cfg(unix)]
   async fn assert_launch_and_monitor_full_flow(
     releases_repo: &SqliteReleasesRepository,
+    sideloaded_repo: &SqliteSideloadedReleasesRepository,
     active_repo: &SqliteActiveReleaseRepository,
     backup_repo: &SqliteBackupRepository,
     variant: GameVariant,
@@ -836,15 +929,19 @@ mod tests {
 
     launch_and_monitor_game(
       &variant,
+      &*VariantRegistry::default().get(&variant)?,
       version,
       None,
       &os,
+      BuildFlavor::default(),
       5000,
       data_dir,
       resource_dir,
       releases_repo,
+      sideloaded_repo,
       backup_repo.clone(),
       active_repo,
+      RunningGames::default().start(variant),
       move |evt| {
         let events = events_clone.clone();
         async move {
@@ -886,6 +983,8 @@ mod tests {
     let db = TestDatabase::builder().build()?;
     let releases_repo =
       SqliteReleasesRepository::new(db.pool().clone());
+    let sideloaded_repo =
+      SqliteSideloadedReleasesRepository::new(db.pool().clone());
     let active_repo =
       SqliteActiveReleaseRepository::new(db.pool().clone());
     let backup_repo = SqliteBackupRepository::new(db.pool().clone());
@@ -899,6 +998,7 @@ mod tests {
     ] {
       assert_launch_and_monitor_full_flow(
         &releases_repo,
+        &sideloaded_repo,
         &active_repo,
         &backup_repo,
         variant,
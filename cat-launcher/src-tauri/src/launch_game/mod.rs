@@ -6,5 +6,9 @@ pub mod commands;
 pub mod launch_game;
 /// Repository for persistence related to game launches.
 pub mod repository;
+/// The games that are being played.
+pub mod running_games;
+/// Running terminal builds of the game inside a terminal.
+pub mod terminal;
 /// Utilities for game launch-related operations.
 pub mod utils;
@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+use std::sync::{Arc, MutexGuard};
+
+use crate::infra::watched::Watched;
+use crate::variants::GameVariant;
+
+struct RunningGame {
+  id: u64,
+  variant: GameVariant,
+}
+
+#[derive(Default)]
+struct Games {
+  next_id: u64,
+  games: Vec<RunningGame>,
+  // Variants whose old installations are to be deleted once they stop.
+  deferred_pruning: HashSet<GameVariant>,
+}
+
+/// The games that are being played, so that their installations aren't
+/// updated or deleted while they're in use.
+///
+/// Clones share the same state.
+#[derive(Clone, Default)]
+pub struct RunningGames {
+  games: Arc<Watched<Games>>,
+}
+
+impl RunningGames {
+  fn lock(&self) -> MutexGuard<'_, Games> {
+    self.games.lock()
+  }
+
+  /// Registers a game of the given variant. It's unregistered when the
+  /// returned session is dropped.
+  pub fn start(&self, variant: GameVariant) -> GameSession {
+    let id = self.games.update(|games| {
+      let id = games.next_id;
+      games.next_id += 1;
+      games.games.push(RunningGame { id, variant });
+      id
+    });
+
+    GameSession {
+      games: self.clone(),
+      id,
+    }
+  }
+
+  /// Returns whether a game of the given variant is being played.
+  pub fn is_running(&self, variant: &GameVariant) -> bool {
+    self.lock().games.iter().any(|g| &g.variant == variant)
+  }
+
+  /// If a game of the given variant is being played, records that its old
+  /// installations are to be deleted once it stops, and returns `true`.
+  pub fn defer_pruning(&self, variant: &GameVariant) -> bool {
+    let mut games = self.lock();
+    if !games.games.iter().any(|g| &g.variant == variant) {
+      return false;
+    }
+
+    games.deferred_pruning.insert(*variant);
+    true
+  }
+
+  /// Waits until no game of the given variant is being played. Returns
+  /// whether pruning its old installations was deferred until then, in
+  /// which case it's up to the caller to do so.
+  pub async fn wait_until_stopped(
+    &self,
+    variant: &GameVariant,
+  ) -> bool {
+    self
+      .games
+      .wait_for(|games| {
+        if games.games.iter().any(|g| &g.variant == variant) {
+          return None;
+        }
+        Some(games.deferred_pruning.remove(variant))
+      })
+      .await
+  }
+
+  fn remove(&self, id: u64) {
+    self
+      .games
+      .update(|games| games.games.retain(|g| g.id != id));
+  }
+}
+
+/// Keeps a game registered while it's being played.
+pub struct GameSession {
+  games: RunningGames,
+  id: u64,
+}
+
+impl Drop for GameSession {
+  fn drop(&mut self) {
+    self.games.remove(self.id);
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use std::time::Duration;
+
+  use tokio::time::timeout;
+
+  use super::*;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_pruning_is_only_deferred_while_running() -> TestResult
+  {
+    let games = RunningGames::default();
+    assert!(!games.defer_pruning(&GameVariant::DarkDaysAhead));
+
+    let session = games.start(GameVariant::DarkDaysAhead);
+    assert!(games.is_running(&GameVariant::DarkDaysAhead));
+    assert!(!games.is_running(&GameVariant::BrightNights));
+    assert!(games.defer_pruning(&GameVariant::DarkDaysAhead));
+    assert!(!games.defer_pruning(&GameVariant::BrightNights));
+
+    let stop = async {
+      tokio::task::yield_now().await;
+      drop(session);
+    };
+    let (deferred, ()) = timeout(Duration::from_secs(5), async {
+      tokio::join!(
+        games.wait_until_stopped(&GameVariant::DarkDaysAhead),
+        stop
+      )
+    })
+    .await?;
+    assert!(deferred);
+
+    // Pruning is only handed out once.
+    assert!(
+      !games.wait_until_stopped(&GameVariant::DarkDaysAhead).await
+    );
+
+    Ok(())
+  }
+}
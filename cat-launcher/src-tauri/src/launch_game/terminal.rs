@@ -0,0 +1,157 @@
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+use tokio::process::Command;
+
+use crate::infra::utils::OS;
+
+/// Terminal emulators tried on Linux when `$TERMINAL` isn't set, in order
+/// of preference, with the arguments that precede the command to run.
+///
+/// Some terminals hand the command over to a server process and exit
+/// right away unless told otherwise, which would end the game session
+/// immediately.
+const LINUX_TERMINALS: &[(&str, &[&str])] = &[
+  ("x-terminal-emulator", &["-e"]),
+  ("gnome-terminal", &["--wait", "--"]),
+  ("konsole", &["-e"]),
+  ("xfce4-terminal", &["--disable-server", "-x"]),
+  ("xterm", &["-e"]),
+];
+
+/// The error returned when no terminal emulator could be found.
+#[derive(thiserror::Error, Debug)]
+#[error("no terminal emulator found to run the game in")]
+pub struct NoTerminalError;
+
+/// Returns a command that runs the executable with the given arguments in
+/// a terminal of its own, which terminal builds need to draw in.
+///
+/// On macOS, the command only opens a Terminal window running the game,
+/// so it exits before the game does.
+pub fn terminal_command(
+  executable: &Path,
+  args: &[OsString],
+  working_dir: &Path,
+  os: &OS,
+) -> Result<Command, NoTerminalError> {
+  let mut command = match os {
+    OS::Windows => {
+      let mut command = Command::new(executable);
+      command.args(args);
+
+      // Console programs started by a GUI program get no window unless
+      // they're given a new console.
+      #[cfg(windows)]
+      {
+        const CREATE_NEW_CONSOLE: u32 = 0x0000_0010;
+        command.creation_flags(CREATE_NEW_CONSOLE);
+      }
+
+      command
+    }
+    OS::Mac => {
+      let script = format!(
+        "cd {} && exec {}",
+        shell_quote(working_dir.as_os_str()),
+        std::iter::once(executable.as_os_str())
+          .chain(args.iter().map(OsString::as_os_str))
+          .map(shell_quote)
+          .collect::<Vec<_>>()
+          .join(" ")
+      );
+
+      let mut command = Command::new("osascript");
+      command.arg("-e").arg(format!(
+        "tell application \"Terminal\" to do script \"{}\"",
+        script.replace('\\', "\\\\").replace('"', "\\\"")
+      ));
+      command
+    }
+    OS::Linux => {
+      let (terminal, terminal_args) =
+        find_linux_terminal().ok_or(NoTerminalError)?;
+
+      let mut command = Command::new(terminal);
+      command.args(terminal_args).arg(executable).args(args);
+      command
+    }
+  };
+
+  command.current_dir(working_dir);
+
+  Ok(command)
+}
+
+fn find_linux_terminal() -> Option<(PathBuf, Vec<&'static str>)> {
+  if let Some(terminal) = env::var_os("TERMINAL")
+    && let Some(path) = find_in_path(&terminal)
+  {
+    return Some((path, vec!["-e"]));
+  }
+
+  LINUX_TERMINALS.iter().find_map(|(terminal, args)| {
+    find_in_path(OsStr::new(terminal))
+      .map(|path| (path, args.to_vec()))
+  })
+}
+
+fn find_in_path(program: &OsStr) -> Option<PathBuf> {
+  let program = Path::new(program);
+  if program.is_absolute() {
+    return program.is_file().then(|| program.to_path_buf());
+  }
+
+  env::split_paths(&env::var_os("PATH")?)
+    .map(|dir| dir.join(program))
+    .find(|path| path.is_file())
+}
+
+/// Quotes a value for a POSIX shell.
+fn shell_quote(value: &OsStr) -> String {
+  format!("'{}'", value.to_string_lossy().replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_shell_quote() {
+    assert_eq!(shell_quote(OsStr::new("/a b/game")), "'/a b/game'");
+    assert_eq!(shell_quote(OsStr::new("it's")), "'it'\\''s'");
+  }
+
+  #[test]
+  fn test_mac_terminal_command_runs_game_in_terminal() {
+    let command = terminal_command(
+      Path::new("/Games/Cataclysm.sh"),
+      &[
+        OsString::from("--userdir"),
+        OsString::from("/User \"Data\""),
+      ],
+      Path::new("/Games"),
+      &OS::Mac,
+    )
+    .unwrap();
+
+    let command = command.as_std();
+    assert_eq!(command.get_program(), "osascript");
+    let args = command.get_args().collect::<Vec<_>>();
+    assert_eq!(
+      args,
+      vec![
+        "-e",
+        "tell application \"Terminal\" to do script \"cd '/Games' && exec '/Games/Cataclysm.sh' '--userdir' '/User \\\"Data\\\"'\""
+      ]
+    );
+  }
+}
@@ -1,25 +1,28 @@
 use std::path::Path;
 
 use crate::filesystem::paths::{
-  GetAutomaticBackupArchivePathError, GetUserGameDataDirError,
-  get_or_create_automatic_backup_archive_filepath,
-  get_or_create_user_game_data_dir,
-};
-use crate::infra::archive::{
-  ArchiveCreationError, create_zip_archive,
+  GetAutomaticBackupArchivePathError, GetBackupStoreDirError,
+  GetUserGameDataDirError,
+  get_or_create_automatic_backup_manifest_filepath,
+  get_or_create_backup_store_dir, get_or_create_user_game_data_dir,
 };
+use crate::infra::snapshot_store::{SnapshotError, create_snapshot};
 use crate::variants::GameVariant;
 
 /// Errors that can occur during the backup of game save files.
 #[derive(thiserror::Error, Debug)]
 pub enum BackupError {
-  /// Failed to determine the path for the backup archive.
-  #[error("failed to get backup archive path: {0}")]
+  /// Failed to determine the path for the backup manifest.
+  #[error("failed to get backup manifest path: {0}")]
   BackupArchivePath(#[from] GetAutomaticBackupArchivePathError),
 
-  /// Failed to create the zip archive for the backup.
-  #[error("failed to create archive: {0}")]
-  ArchiveCreation(#[from] ArchiveCreationError),
+  /// Failed to locate or create the backup store.
+  #[error("failed to get backup store directory: {0}")]
+  StoreDir(#[from] GetBackupStoreDirError),
+
+  /// Failed to snapshot the save files.
+  #[error("failed to create snapshot: {0}")]
+  Snapshot(#[from] SnapshotError),
 
   /// Failed to locate or create the user's game data directory.
   #[error("failed to get user game data directory: {0}")]
@@ -28,8 +31,9 @@ pub enum BackupError {
 
 /// Backs up game save files for a specific game variant.
 ///
-/// This function creates a zip archive containing the 'save' directory
-/// from the user's game data folder.
+/// This function snapshots the 'save' directory from the user's game data
+/// folder into the variant's backup store. Only content that no earlier
+/// backup has is stored again.
 pub async fn backup_save_files(
   variant: &GameVariant,
   id: i64,
@@ -41,21 +45,23 @@ pub async fn backup_save_files(
     get_or_create_user_game_data_dir(variant, data_dir).await?;
 
   let dirs_to_backup = vec![user_data_dir.join("save")];
-  let archive_path = get_or_create_automatic_backup_archive_filepath(
-    variant, id, version, timestamp, data_dir,
+  let store_dir =
+    get_or_create_backup_store_dir(variant, data_dir).await?;
+  let manifest_path =
+    get_or_create_automatic_backup_manifest_filepath(
+      variant, id, version, timestamp, data_dir,
+    )
+    .await?;
+
+  // A failed snapshot leaves no manifest behind, and the blobs it stored
+  // are pruned once no backup refers to them.
+  create_snapshot(
+    &user_data_dir,
+    &dirs_to_backup,
+    &store_dir,
+    &manifest_path,
   )
   .await?;
 
-  if let Err(e) =
-    create_zip_archive(&user_data_dir, &dirs_to_backup, &archive_path)
-      .await
-  {
-    // create_zip_archive may leave a partially written archive behind
-    // after creating the destination file; remove it so no orphaned
-    // file remains.
-    let _ = tokio::fs::remove_file(&archive_path).await;
-    return Err(e.into());
-  }
-
   Ok(())
 }
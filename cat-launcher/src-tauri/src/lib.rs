@@ -3,18 +3,29 @@ pub mod filesystem;
 
 pub mod active_release;
 mod backups;
+mod download_mirrors;
+mod download_queue;
+mod emulated_builds;
 mod fetch_releases;
 mod game_release;
 mod game_tips;
+mod garbage_collection;
+mod github_token;
 mod infra;
 mod install_release;
+mod installed_releases;
 mod last_played_world;
 mod launch_game;
 mod manual_backups;
 mod master_reset;
 mod mods;
+mod offline_mode;
 mod play_time;
+mod release_changelog;
+mod release_updates;
+mod sideloaded_releases;
 mod soundpacks;
+mod storage_usage;
 mod theme;
 mod tilesets;
 mod users;
@@ -31,12 +42,34 @@ use crate::active_release::commands::get_active_release;
 use crate::backups::commands::{
   delete_backup_by_id, list_backups_for_variant, restore_backup_by_id,
 };
+use crate::constants::INSTALL_CANCELLATION_TIMEOUT;
+use crate::download_mirrors::commands::{
+  add_download_mirror, list_download_mirrors, remove_download_mirror,
+};
+use crate::download_queue::commands::{
+  cancel_download, list_downloads, pause_download, resume_download,
+  set_download_priority,
+};
+use crate::emulated_builds::commands::{
+  get_emulated_builds_allowed, set_emulated_builds_allowed,
+};
 use crate::fetch_releases::commands::{
   fetch_release_notes, fetch_releases_for_variant,
 };
 use crate::game_tips::commands::get_tips;
-use crate::install_release::commands::install_release;
+use crate::garbage_collection::commands::run_garbage_collection;
+use crate::github_token::commands::{
+  clear_github_token, set_github_token, validate_github_token,
+};
+use crate::install_release::commands::{
+  cancel_install, install_release,
+};
 use crate::install_release::installation_status::commands::get_installation_status;
+use crate::install_release::running_installs::RunningInstalls;
+use crate::installed_releases::commands::{
+  get_release_retention_policy, list_installed_releases,
+  set_release_retention_policy, uninstall_release,
+};
 use crate::last_played_world::commands::get_last_played_world;
 use crate::launch_game::commands::launch_game;
 use crate::manual_backups::commands::{
@@ -50,14 +83,26 @@ use crate::mods::commands::{
   install_third_party_mod_command, list_all_mods_command,
   uninstall_third_party_mod_command,
 };
+use crate::offline_mode::commands::{
+  get_connectivity_state, set_offline_mode,
+};
 use crate::play_time::commands::{
   get_play_time_for_variant, get_play_time_for_version, log_play_time,
 };
+use crate::release_changelog::commands::get_release_changelog;
+use crate::release_updates::commands::{
+  check_release_update, get_release_update_policy,
+  set_release_update_policy,
+};
+use crate::sideloaded_releases::commands::sideload_release;
 use crate::soundpacks::commands::{
   get_third_party_soundpack_installation_status_command,
   install_third_party_soundpack_command, list_all_soundpacks_command,
   uninstall_third_party_soundpack_command,
 };
+use crate::storage_usage::commands::{
+  get_storage_usage_report, stream_storage_usage_report,
+};
 use crate::theme::commands::{
   get_preferred_theme, set_preferred_theme,
 };
@@ -68,17 +113,37 @@ use crate::tilesets::commands::{
 };
 use crate::users::commands::get_user_id;
 use crate::utils::{
-  autoupdate, manage_downloader, manage_http_client,
+  autoupdate, collect_garbage_at_startup, load_github_token,
+  manage_build_flavor_selection, manage_downloader,
+  manage_emulated_builds_setting, manage_http_client,
   manage_online_mod_repository_registry, manage_posthog,
-  manage_repositories, migrate_to_local_data_dir, on_quit,
+  manage_repositories, manage_running_installs,
+  manage_variant_registry, migrate_to_local_data_dir, on_quit,
+  schedule_release_updates, watch_connectivity,
 };
 use crate::variants::commands::get_game_variants_info;
 use crate::variants::commands::update_game_variant_order;
-use tauri::{AppHandle, command};
+use crate::variants::commands::{
+  get_build_flavors, list_variant_descriptors, register_game_variant,
+  set_build_flavor,
+};
+use tauri::{AppHandle, Manager, State, command};
 
+/// Quits the launcher, first cancelling any game installation in progress
+/// so that it doesn't leave a partial installation behind.
 #[command]
 fn confirm_quit(app_handle: AppHandle) {
-  app_handle.exit(0)
+  tauri::async_runtime::spawn(async move {
+    let running_installs: State<RunningInstalls> = app_handle.state();
+    // Quit anyway if cleaning up takes too long.
+    let _ = tokio::time::timeout(
+      INSTALL_CANCELLATION_TIMEOUT,
+      running_installs.cancel_all(),
+    )
+    .await;
+
+    app_handle.exit(0)
+  });
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -89,26 +154,67 @@ pub fn run() {
     .setup(|app| {
       manage_http_client(app)?;
       manage_repositories(app)?;
+      manage_variant_registry(app);
+      // Flavors are loaded after custom variants, which they may refer to.
+      manage_build_flavor_selection(app);
+      manage_emulated_builds_setting(app);
+      load_github_token(app);
+      watch_connectivity(app);
       manage_online_mod_repository_registry(app);
       manage_downloader(app);
+      manage_running_installs(app);
       manage_posthog(app);
 
       migrate_to_local_data_dir(app);
+      collect_garbage_at_startup(app);
 
       autoupdate(app);
+      schedule_release_updates(app);
       on_quit(app);
 
       Ok(())
     })
     .invoke_handler(tauri::generate_handler![
       get_game_variants_info,
+      list_variant_descriptors,
+      register_game_variant,
+      get_build_flavors,
+      set_build_flavor,
+      get_emulated_builds_allowed,
+      set_emulated_builds_allowed,
+      list_download_mirrors,
+      add_download_mirror,
+      remove_download_mirror,
+      list_downloads,
+      pause_download,
+      resume_download,
+      cancel_download,
+      set_download_priority,
       fetch_releases_for_variant,
       fetch_release_notes,
+      get_release_changelog,
       install_release,
+      cancel_install,
+      sideload_release,
       launch_game,
       get_active_release,
       get_installation_status,
+      list_installed_releases,
+      uninstall_release,
+      get_storage_usage_report,
+      stream_storage_usage_report,
+      run_garbage_collection,
+      get_release_retention_policy,
+      set_release_retention_policy,
+      get_release_update_policy,
+      set_release_update_policy,
+      check_release_update,
       get_tips,
+      set_github_token,
+      validate_github_token,
+      clear_github_token,
+      get_connectivity_state,
+      set_offline_mode,
       get_play_time_for_variant,
       get_play_time_for_version,
       log_play_time,
@@ -1,4 +1,7 @@
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
 
 use crate::filesystem::paths::{
   GetManualBackupArchivePathError, GetUserGameDataDirError,
@@ -6,9 +9,10 @@ use crate::filesystem::paths::{
   get_or_create_user_game_data_dir,
 };
 use crate::infra::archive::{
-  ArchiveCreationError, ExtractionError, create_zip_archive,
-  extract_archive,
+  ArchiveCreationError, ExtractionError, ExtractionPolicy,
+  create_zip_archive, extract_archive,
 };
+use crate::infra::installation_progress_monitor::noop_reporter::NoopReporter;
 use crate::infra::utils::OS;
 use crate::manual_backups::repository::manual_backup_repository::{
   ManualBackupEntry, ManualBackupRepository,
@@ -179,7 +183,15 @@ pub async fn restore_manual_backup(
     get_or_create_user_game_data_dir(&backup.game_variant, data_dir)
       .await?;
 
-  extract_archive(&archive_path, &user_data_dir, os).await?;
+  extract_archive(
+    &archive_path,
+    &user_data_dir,
+    os,
+    ExtractionPolicy::TRUSTED,
+    Arc::new(NoopReporter),
+    &CancellationToken::new(),
+  )
+  .await?;
 
   Ok(())
 }
@@ -8,7 +8,8 @@ use cat_macros::CommandErrorSerialize;
 
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
 use crate::infra::download::Downloader;
-use crate::infra::http_client::ReqwestHttpClient;
+use crate::infra::github::repository::sqlite_github_api_cache_repository::SqliteGitHubApiCacheRepository;
+use crate::infra::http_client::{ReqwestHttpClient, is_offline_error};
 use crate::infra::installation_progress_monitor::channel_reporter::ChannelReporter;
 use crate::infra::utils::{get_os_enum, OSNotSupportedError};
 use crate::mods::get_last_activity_for_third_party_mod::{
@@ -33,6 +34,7 @@ use crate::mods::uninstall_third_party_mod::{
   uninstall_third_party_mod, UninstallThirdPartyModError,
 };
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavorSelection;
 
 #[derive(thiserror::Error, Debug, CommandErrorSerialize)]
 pub enum ListAllModsCommandError {
@@ -58,6 +60,7 @@ pub async fn list_all_mods_command(
     OnlineModRepositoryRegistry,
   >,
   client: State<'_, ReqwestHttpClient>,
+  build_flavors: State<'_, BuildFlavorSelection>,
 ) -> Result<(), ListAllModsCommandError> {
   let data_dir = app.path().app_local_data_dir()?;
   let resource_dir = app.path().resource_dir()?;
@@ -71,6 +74,7 @@ pub async fn list_all_mods_command(
 
   list_all_mods(
     &variant,
+    build_flavors.get(&variant),
     &data_dir,
     &resource_dir,
     &os,
@@ -93,8 +97,23 @@ pub enum InstallThirdPartyModCommandError {
   #[error("failed to get OS information")]
   OSInfo(#[from] OSNotSupportedError),
 
+  #[error("offline mode is enabled")]
+  Offline,
+
   #[error("failed to install mod: {0}")]
-  Install(#[from] InstallThirdPartyModError),
+  Install(InstallThirdPartyModError),
+}
+
+impl From<InstallThirdPartyModError>
+  for InstallThirdPartyModCommandError
+{
+  fn from(e: InstallThirdPartyModError) -> Self {
+    if is_offline_error(&e) {
+      Self::Offline
+    } else {
+      Self::Install(e)
+    }
+  }
 }
 
 #[tauri::command]
@@ -190,8 +209,23 @@ pub enum GetLastActivityCommandError {
   #[error("failed to get OS information")]
   OSInfo(#[from] OSNotSupportedError),
 
+  #[error("offline mode is enabled")]
+  Offline,
+
   #[error("failed to get last activity: {0}")]
-  GetActivity(#[from] GetLastActivityForThirdPartyModError),
+  GetActivity(GetLastActivityForThirdPartyModError),
+}
+
+impl From<GetLastActivityForThirdPartyModError>
+  for GetLastActivityCommandError
+{
+  fn from(e: GetLastActivityForThirdPartyModError) -> Self {
+    if is_offline_error(&e) {
+      Self::Offline
+    } else {
+      Self::GetActivity(e)
+    }
+  }
 }
 
 #[tauri::command]
@@ -200,12 +234,17 @@ pub async fn get_last_activity_on_third_party_mod_command(
   variant: GameVariant,
   client: State<'_, ReqwestHttpClient>,
   mods_repository: State<'_, SqliteModsRepository>,
+  github_api_cache_repository: State<
+    '_,
+    SqliteGitHubApiCacheRepository,
+  >,
 ) -> Result<LastModActivity, GetLastActivityCommandError> {
   let last_activity = get_last_activity_for_third_party_mod(
     &id,
     &variant,
     client.inner(),
     mods_repository.inner(),
+    github_api_cache_repository.inner(),
   )
   .await?;
 
@@ -5,6 +5,7 @@ use url::Url;
 use crate::infra::github::get_last_commit::{
   GetLastCommitError, get_last_commit,
 };
+use crate::infra::github::repository::GitHubApiCacheRepository;
 use crate::infra::http_client::HttpClient;
 use crate::mods::get_third_party_mod_by_id::{
   GetThirdPartyModByIdError, get_third_party_mod_by_id,
@@ -60,6 +61,7 @@ pub async fn get_last_activity_for_third_party_mod(
   variant: &GameVariant,
   client: &impl HttpClient,
   mods_repository: &impl ModsRepository,
+  github_api_cache_repository: &impl GitHubApiCacheRepository,
 ) -> Result<LastModActivity, GetLastActivityForThirdPartyModError> {
   let mod_data =
     get_third_party_mod_by_id(mod_id, variant, mods_repository)
@@ -71,7 +73,9 @@ pub async fn get_last_activity_for_third_party_mod(
         GetLastActivityForThirdPartyModError::NoGithubActivity,
       )?;
 
-      let last_commit = get_last_commit(&repo, client).await?;
+      let last_commit =
+        get_last_commit(&repo, client, github_api_cache_repository)
+          .await?;
       let last_commit_date = last_commit.commit.author.date;
       let timestamp = last_commit_date.timestamp_millis();
 
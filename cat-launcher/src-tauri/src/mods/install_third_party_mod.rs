@@ -4,14 +4,18 @@ use std::sync::Arc;
 
 use downloader::progress::Reporter;
 use tokio::fs::create_dir_all;
+use tokio_util::sync::CancellationToken;
 
 use crate::filesystem::paths::{
   GetOrCreateDirectoryError, GetUserGameDataDirError,
   get_or_create_directory, get_or_create_user_game_data_dir,
 };
 use crate::filesystem::utils::{CopyDirError, copy_dir_all};
-use crate::infra::archive::{ExtractionError, extract_archive};
+use crate::infra::archive::{
+  ExtractionError, ExtractionPolicy, extract_archive,
+};
 use crate::infra::download::{DownloadFileError, Downloader};
+use crate::infra::download_queue::DownloadPriority;
 use crate::infra::utils::OS;
 use crate::mods::repository::installed_mods_repository::{
   InstalledModsRepository, InstalledModsRepositoryError,
@@ -75,13 +79,22 @@ pub async fn install_third_party_mod(
     .download_file(
       &mod_details.installation.download_url,
       &mod_temp_dir,
-      reporter,
+      DownloadPriority::Normal,
+      reporter.clone(),
     )
     .await?;
 
   let extraction_dir = mod_temp_dir.join("extracted");
   create_dir_all(&extraction_dir).await?;
-  extract_archive(&downloaded_file, &extraction_dir, os).await?;
+  extract_archive(
+    &downloaded_file,
+    &extraction_dir,
+    os,
+    ExtractionPolicy::UNTRUSTED,
+    reporter,
+    &CancellationToken::new(),
+  )
+  .await?;
 
   let mod_parent_dir = get_mod_parent_dir(
     &extraction_dir,
@@ -6,6 +6,7 @@ use crate::filesystem::paths::{
 use crate::infra::utils::OS;
 use crate::mods::types::OnlineModRepository;
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
 
 #[derive(Default)]
 pub struct OnlineModRepositoryRegistry {
@@ -34,12 +35,18 @@ pub enum GetStockModsDirError {
 pub async fn get_stock_mods_dir(
   variant: &GameVariant,
   release_version: &str,
+  flavor: BuildFlavor,
   data_dir: &Path,
   os: &OS,
 ) -> Result<PathBuf, GetStockModsDirError> {
-  let game_resources_dir =
-    get_game_resources_dir(variant, release_version, data_dir, os)
-      .await?;
+  let game_resources_dir = get_game_resources_dir(
+    variant,
+    release_version,
+    flavor,
+    data_dir,
+    os,
+  )
+  .await?;
 
   Ok(game_resources_dir.join("data").join("mods"))
 }
@@ -6,7 +6,7 @@ use std::path::Path;
 use tokio::fs::{read_dir, read_to_string};
 
 use crate::active_release::repository::ActiveReleaseRepository;
-use crate::infra::http_client::HttpClient;
+use crate::infra::http_client::{HttpClient, HttpClientError};
 use crate::infra::utils::{OS, sort_assets};
 use crate::mods::lib::{
   GetStockModsDirError, get_mods_resource_path, get_stock_mods_dir,
@@ -21,6 +21,7 @@ use crate::mods::types::{
   StockMod, ThirdPartyMod,
 };
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
 
 #[derive(thiserror::Error, Debug)]
 pub enum GetAllStockModsError {
@@ -36,6 +37,7 @@ pub enum GetAllStockModsError {
 
 pub async fn get_all_stock_mods(
   game_variant: &GameVariant,
+  flavor: BuildFlavor,
   data_dir: &Path,
   os: &OS,
   active_release_repository: &impl ActiveReleaseRepository,
@@ -51,6 +53,7 @@ pub async fn get_all_stock_mods(
     let stock_mods_dir = get_stock_mods_dir(
       game_variant,
       &release_version,
+      flavor,
       data_dir,
       os,
     )
@@ -93,6 +96,7 @@ pub enum ListAllModsError<E: std::error::Error> {
 #[allow(clippy::too_many_arguments)]
 pub async fn list_all_mods<F, E>(
   game_variant: &GameVariant,
+  flavor: BuildFlavor,
   data_dir: &Path,
   resource_dir: &Path,
   os: &OS,
@@ -130,9 +134,21 @@ where
   .map_err(ListAllModsError::Send)?;
 
   // 2. Fetch online mods from all registries and emit them.
+  let mut final_status = ModsUpdateStatus::Success;
   for repo in online_mod_repositories {
     let online_mods =
-      repo.get_mods_for_variant(game_variant, client).await?;
+      match repo.get_mods_for_variant(game_variant, client).await {
+        Ok(online_mods) => online_mods,
+        // Being offline isn't fatal: cached mods were already emitted,
+        // and bundled and stock mods are still listed below.
+        Err(FetchOnlineModsError::RequestFailed(
+          HttpClientError::Offline,
+        )) => {
+          final_status = ModsUpdateStatus::Offline;
+          break;
+        }
+        Err(e) => return Err(e.into()),
+      };
 
     let mut new_mods_added = false;
     for tp_mod in online_mods {
@@ -168,6 +184,7 @@ where
 
   let stock_mods = get_all_stock_mods(
     game_variant,
+    flavor,
     data_dir,
     os,
     active_release_repository,
@@ -202,7 +219,7 @@ where
   on_update(ModsUpdatePayload {
     variant: *game_variant,
     mods: all_mods,
-    status: ModsUpdateStatus::Success,
+    status: final_status,
   })
   .map_err(ListAllModsError::Send)?;
 
@@ -25,7 +25,7 @@ impl OnlineModRepository for BrightNightsModRepository {
     variant: &GameVariant,
     client: &dyn HttpClient,
   ) -> Result<Vec<ThirdPartyMod>, FetchOnlineModsError> {
-    if !matches!(variant, GameVariant::BrightNights) {
+    if *variant != GameVariant::BrightNights {
       return Ok(Vec::new());
     }
 
@@ -112,6 +112,9 @@ pub enum ModsUpdateStatus {
   Fetching,
   /// The fetch operation succeeded.
   Success,
+  /// Offline mode is enabled, so only cached, bundled and stock mods were
+  /// listed.
+  Offline,
   /// Reserved for future use when errors are streamed via the update channel
   /// instead of being returned as a command Result.
   #[allow(dead_code)]
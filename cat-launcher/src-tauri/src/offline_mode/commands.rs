@@ -0,0 +1,41 @@
+use tauri::{State, command};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::infra::connectivity::ConnectivityState;
+use crate::infra::http_client::ReqwestHttpClient;
+use crate::offline_mode::offline_mode::{
+  OfflineModeError, save_offline_mode,
+};
+use crate::offline_mode::repository::sqlite_offline_mode_repository::SqliteOfflineModeRepository;
+
+/// Returns whether the launcher is online, unreachable or in offline mode.
+#[command]
+pub fn get_connectivity_state(
+  client: State<'_, ReqwestHttpClient>,
+) -> ConnectivityState {
+  client.connectivity_state()
+}
+
+/// Errors that can occur when setting offline mode via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum SetOfflineModeCommandError {
+  /// Failed to store the offline mode setting.
+  #[error("failed to set offline mode: {0}")]
+  OfflineMode(#[from] OfflineModeError),
+}
+
+/// Enables or disables offline mode. While it is enabled, no network
+/// requests are made and cached or locally installed content is used.
+#[command]
+pub async fn set_offline_mode(
+  enabled: bool,
+  client: State<'_, ReqwestHttpClient>,
+  repository: State<'_, SqliteOfflineModeRepository>,
+) -> Result<ConnectivityState, SetOfflineModeCommandError> {
+  save_offline_mode(enabled, &*repository).await?;
+
+  client.set_offline_mode(enabled);
+
+  Ok(client.connectivity_state())
+}
@@ -0,0 +1,28 @@
+use crate::offline_mode::repository::{
+  OfflineModeRepository, OfflineModeRepositoryError,
+};
+
+/// Errors that can occur when accessing the offline mode setting.
+#[derive(thiserror::Error, Debug)]
+pub enum OfflineModeError {
+  /// An error occurred in the offline mode repository.
+  #[error("failed to access offline mode: {0}")]
+  Repository(#[from] OfflineModeRepositoryError),
+}
+
+/// Returns whether offline mode is enabled.
+pub async fn get_offline_mode(
+  repository: &impl OfflineModeRepository,
+) -> Result<bool, OfflineModeError> {
+  Ok(repository.get_offline_mode().await?)
+}
+
+/// Stores whether offline mode is enabled, so that it persists across
+/// restarts.
+pub async fn save_offline_mode(
+  enabled: bool,
+  repository: &impl OfflineModeRepository,
+) -> Result<(), OfflineModeError> {
+  repository.set_offline_mode(enabled).await?;
+  Ok(())
+}
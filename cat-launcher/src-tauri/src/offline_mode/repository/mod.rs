@@ -0,0 +1,6 @@
+pub mod offline_mode_repository;
+pub mod sqlite_offline_mode_repository;
+
+pub use offline_mode_repository::{
+  OfflineModeRepository, OfflineModeRepositoryError,
+};
@@ -0,0 +1,30 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+/// Errors that can occur when interacting with the offline mode repository.
+#[derive(thiserror::Error, Debug)]
+pub enum OfflineModeRepositoryError {
+  /// An error occurred while retrieving the offline mode setting.
+  #[error("failed to get offline mode: {0}")]
+  Get(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while storing the offline mode setting.
+  #[error("failed to set offline mode: {0}")]
+  Set(Box<dyn Error + Send + Sync>),
+}
+
+/// A repository for whether offline mode is enabled.
+#[async_trait]
+pub trait OfflineModeRepository: Send + Sync {
+  /// Retrieves whether offline mode is enabled. Defaults to `false`.
+  async fn get_offline_mode(
+    &self,
+  ) -> Result<bool, OfflineModeRepositoryError>;
+
+  /// Stores whether offline mode is enabled.
+  async fn set_offline_mode(
+    &self,
+    enabled: bool,
+  ) -> Result<(), OfflineModeRepositoryError>;
+}
@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::OptionalExtension;
+
+use crate::infra::repository::db_helper::run_db;
+use crate::offline_mode::repository::{
+  OfflineModeRepository, OfflineModeRepositoryError,
+};
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+/// A SQLite-backed implementation of the `OfflineModeRepository`.
+#[derive(Clone)]
+pub struct SqliteOfflineModeRepository {
+  pool: Pool,
+}
+
+impl SqliteOfflineModeRepository {
+  /// Creates a new `SqliteOfflineModeRepository` with the given connection pool.
+  pub fn new(pool: Pool) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl OfflineModeRepository for SqliteOfflineModeRepository {
+  async fn get_offline_mode(
+    &self,
+  ) -> Result<bool, OfflineModeRepositoryError> {
+    let pool = self.pool.clone();
+
+    let enabled: Option<bool> = run_db(pool, move |conn| {
+      conn
+        .query_row(
+          "SELECT enabled FROM offline_mode WHERE _id = 1",
+          [],
+          |row| row.get(0),
+        )
+        .optional()
+    })
+    .await
+    .map_err(OfflineModeRepositoryError::Get)?;
+
+    Ok(enabled.unwrap_or_default())
+  }
+
+  async fn set_offline_mode(
+    &self,
+    enabled: bool,
+  ) -> Result<(), OfflineModeRepositoryError> {
+    let pool = self.pool.clone();
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO offline_mode (_id, enabled) VALUES (1, ?1)",
+        [enabled],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(OfflineModeRepositoryError::Set)
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::infra::testing::test_database::TestDatabase;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_set_and_get_offline_mode() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo = SqliteOfflineModeRepository::new(db.pool().clone());
+
+    assert!(!repo.get_offline_mode().await?);
+
+    repo.set_offline_mode(true).await?;
+    assert!(repo.get_offline_mode().await?);
+
+    repo.set_offline_mode(false).await?;
+    assert!(!repo.get_offline_mode().await?);
+
+    Ok(())
+  }
+}
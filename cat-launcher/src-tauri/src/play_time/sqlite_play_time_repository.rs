@@ -37,7 +37,7 @@ impl PlayTimeRepository for SqlitePlayTimeRepository {
     }
 
     let pool = self.pool.clone();
-    let game_variant_id = game_variant.id();
+    let game_variant_id = game_variant.to_string();
     let version = version.to_owned();
     task::block_in_place(move || {
       let conn = pool.get().map_err(|e| {
@@ -61,7 +61,7 @@ impl PlayTimeRepository for SqlitePlayTimeRepository {
     version: &str,
   ) -> Result<i64, PlayTimeRepositoryError> {
     let pool = self.pool.clone();
-    let game_variant_id = game_variant.id();
+    let game_variant_id = game_variant.to_string();
     let version = version.to_owned();
     task::spawn_blocking(move || {
             let conn = pool
@@ -86,7 +86,7 @@ impl PlayTimeRepository for SqlitePlayTimeRepository {
     game_variant: &GameVariant,
   ) -> Result<i64, PlayTimeRepositoryError> {
     let pool = self.pool.clone();
-    let game_variant_id = game_variant.id();
+    let game_variant_id = game_variant.to_string();
     task::spawn_blocking(move || {
             let conn = pool
                 .get()
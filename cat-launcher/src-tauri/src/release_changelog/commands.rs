@@ -0,0 +1,78 @@
+use tauri::{AppHandle, Manager, State, command};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
+use crate::infra::github::repository::sqlite_github_api_cache_repository::SqliteGitHubApiCacheRepository;
+use crate::infra::http_client::{ReqwestHttpClient, is_offline_error};
+use crate::release_changelog::release_changelog::{
+  ReleaseChangelog, ReleaseChangelogError,
+};
+use crate::variants::GameVariant;
+use crate::variants::game_variant::UnknownGameVariantError;
+use crate::variants::variant_descriptor::VariantRegistry;
+
+/// Errors that can occur when getting a changelog via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum GetReleaseChangelogCommandError {
+  /// The system's resource directory could not be found.
+  #[error("system directory not found: {0}")]
+  SystemDir(#[from] tauri::Error),
+
+  /// Offline mode is enabled, so the request was not made.
+  #[error("offline mode is enabled")]
+  Offline,
+
+  /// Failed to build the changelog.
+  #[error("failed to get changelog: {0}")]
+  Changelog(ReleaseChangelogError),
+
+  /// The game variant is not registered.
+  #[error("unknown game variant: {0}")]
+  UnknownVariant(#[from] UnknownGameVariantError),
+}
+
+impl From<ReleaseChangelogError> for GetReleaseChangelogCommandError {
+  fn from(e: ReleaseChangelogError) -> Self {
+    if is_offline_error(&e) {
+      Self::Offline
+    } else {
+      Self::Changelog(e)
+    }
+  }
+}
+
+/// Gets the release notes of every release between two versions of a game
+/// variant.
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_release_changelog(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  from_version: String,
+  to_version: String,
+  client: State<'_, ReqwestHttpClient>,
+  releases_repository: State<'_, SqliteReleasesRepository>,
+  github_api_cache_repository: State<
+    '_,
+    SqliteGitHubApiCacheRepository,
+  >,
+  variant_registry: State<'_, VariantRegistry>,
+) -> Result<ReleaseChangelog, GetReleaseChangelogCommandError> {
+  let resources_dir = app_handle.path().resource_dir()?;
+  let descriptor = variant_registry.get(&variant)?;
+
+  let changelog = variant
+    .get_release_changelog(
+      &descriptor,
+      &from_version,
+      &to_version,
+      client.inner(),
+      &resources_dir,
+      &*releases_repository,
+      &*github_api_cache_repository,
+    )
+    .await?;
+
+  Ok(changelog)
+}
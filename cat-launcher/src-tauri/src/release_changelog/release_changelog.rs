@@ -0,0 +1,433 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::fetch_releases::repository::{
+  ReleasesRepository, ReleasesRepositoryError,
+};
+use crate::fetch_releases::utils::{
+  get_default_releases, merge_releases,
+};
+use crate::infra::github::release::GitHubRelease;
+use crate::infra::github::repository::GitHubApiCacheRepository;
+use crate::infra::github::utils::{
+  FetchGitHubReleaseByTagError, fetch_github_release_by_tag,
+  fetch_github_releases_until,
+};
+use crate::infra::http_client::HttpClient;
+use crate::variants::GameVariant;
+use crate::variants::variant_descriptor::VariantDescriptor;
+
+/// A release in a changelog, along with its notes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ChangelogRelease {
+  /// The version (tag name) of the release.
+  pub version: String,
+  /// When the release was created.
+  #[ts(type = "string")]
+  pub created_at: DateTime<Utc>,
+  /// The release notes, without list items that were already part of an
+  /// older release in the changelog.
+  pub notes: Option<String>,
+}
+
+/// Everything that changed between two versions of a game variant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ReleaseChangelog {
+  /// The game variant the releases belong to.
+  pub variant: GameVariant,
+  /// The older of the two versions, whose changes are not included.
+  pub from_version: String,
+  /// The newer of the two versions, whose changes are included.
+  pub to_version: String,
+  /// The releases after `from_version` up to and including `to_version`,
+  /// newest first.
+  pub releases: Vec<ChangelogRelease>,
+  /// Whether GitHub could be searched for releases missing from the cache.
+  /// If not, only cached releases are included.
+  pub is_complete: bool,
+}
+
+/// Errors that can occur when building a changelog.
+#[derive(thiserror::Error, Debug)]
+pub enum ReleaseChangelogError {
+  /// Failed to get one of the two versions from GitHub.
+  #[error("failed to get release from github: {0}")]
+  Fetch(#[from] FetchGitHubReleaseByTagError),
+
+  /// Failed to access the releases cache.
+  #[error("failed to access releases cache: {0}")]
+  Repository(#[from] ReleasesRepositoryError),
+}
+
+impl GameVariant {
+  /// Builds the changelog between two versions of this variant, in either
+  /// order.
+  ///
+  /// Cached releases are used where possible. Releases missing from the
+  /// cache are fetched from GitHub and cached; if that fails, e.g. because
+  /// the launcher is offline, the changelog only contains cached releases.
+  #[allow(clippy::too_many_arguments)]
+  pub async fn get_release_changelog(
+    &self,
+    descriptor: &VariantDescriptor,
+    from_version: &str,
+    to_version: &str,
+    client: &impl HttpClient,
+    resources_dir: &Path,
+    releases_repository: &impl ReleasesRepository,
+    github_api_cache_repository: &impl GitHubApiCacheRepository,
+  ) -> Result<ReleaseChangelog, ReleaseChangelogError> {
+    let default_releases =
+      get_default_releases(self, resources_dir).await;
+
+    let from_release = self
+      .get_changelog_release(
+        descriptor,
+        from_version,
+        &default_releases,
+        client,
+        releases_repository,
+        github_api_cache_repository,
+      )
+      .await?;
+    let to_release = self
+      .get_changelog_release(
+        descriptor,
+        to_version,
+        &default_releases,
+        client,
+        releases_repository,
+        github_api_cache_repository,
+      )
+      .await?;
+    let (oldest, newest) =
+      if from_release.created_at <= to_release.created_at {
+        (from_release, to_release)
+      } else {
+        (to_release, from_release)
+      };
+
+    // Releases are listed newest first, so everything newer than the
+    // oldest version has been seen once it's reached.
+    let repo = descriptor.repo.as_str();
+    let is_complete = match fetch_github_releases_until(
+      client,
+      repo,
+      None,
+      github_api_cache_repository,
+      |release| release.created_at <= oldest.created_at,
+    )
+    .await
+    {
+      Ok(fetched_releases) => {
+        releases_repository
+          .update_cached_releases(self, &fetched_releases)
+          .await?;
+        true
+      }
+      Err(_) => false,
+    };
+
+    let cached_releases =
+      releases_repository.get_cached_releases(self).await?;
+    let mut releases: Vec<GitHubRelease> =
+      merge_releases(&cached_releases, &default_releases)
+        .into_iter()
+        .filter(|release| {
+          release.created_at > oldest.created_at
+            && release.created_at <= newest.created_at
+        })
+        .collect();
+    releases.sort_by_key(|r| r.created_at);
+
+    let mut releases = get_changelog_releases(&releases);
+    releases.reverse();
+
+    Ok(ReleaseChangelog {
+      variant: *self,
+      from_version: oldest.tag_name,
+      to_version: newest.tag_name,
+      releases,
+      is_complete,
+    })
+  }
+
+  async fn get_changelog_release(
+    &self,
+    descriptor: &VariantDescriptor,
+    version: &str,
+    default_releases: &[GitHubRelease],
+    client: &impl HttpClient,
+    releases_repository: &impl ReleasesRepository,
+    github_api_cache_repository: &impl GitHubApiCacheRepository,
+  ) -> Result<GitHubRelease, ReleaseChangelogError> {
+    if let Some(release) = releases_repository
+      .get_cached_release_by_tag(self, version)
+      .await?
+    {
+      return Ok(release);
+    }
+
+    if let Some(release) = default_releases
+      .iter()
+      .find(|release| release.tag_name == version)
+    {
+      return Ok(release.clone());
+    }
+
+    let repo = descriptor.repo.as_str();
+    let release = fetch_github_release_by_tag(
+      client,
+      repo,
+      version,
+      github_api_cache_repository,
+    )
+    .await?;
+    releases_repository
+      .update_cached_releases(self, std::slice::from_ref(&release))
+      .await?;
+
+    Ok(release)
+  }
+}
+
+/// Converts releases sorted oldest first into changelog releases.
+///
+/// Release notes often repeat the changes of previous releases, so list
+/// items are only kept in the oldest release they appear in.
+fn get_changelog_releases(
+  releases: &[GitHubRelease],
+) -> Vec<ChangelogRelease> {
+  let mut seen_items = HashSet::new();
+
+  releases
+    .iter()
+    .map(|release| {
+      let notes = release
+        .body
+        .as_deref()
+        .map(|body| {
+          body
+            .lines()
+            .filter(|line| match get_list_item(line) {
+              Some(item) => seen_items.insert(item.to_string()),
+              None => true,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+        })
+        .filter(|notes| !notes.trim().is_empty());
+
+      ChangelogRelease {
+        version: release.tag_name.clone(),
+        created_at: release.created_at,
+        notes,
+      }
+    })
+    .collect()
+}
+
+/// Returns the text of a markdown list item, or `None` if `line` isn't one.
+fn get_list_item(line: &str) -> Option<&str> {
+  let line = line.trim();
+  ["- ", "* ", "+ "]
+    .iter()
+    .find_map(|marker| line.strip_prefix(marker))
+    .map(str::trim)
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
+  use crate::infra::github::repository::sqlite_github_api_cache_repository::SqliteGitHubApiCacheRepository;
+  use crate::infra::testing::http_client::TestHttpClient;
+  use crate::infra::testing::test_database::TestDatabase;
+  use crate::variants::variant_descriptor::VariantRegistry;
+  use github_mock_api::{MockServer, Release as MockRelease};
+  use std::collections::HashMap;
+  use tempfile::TempDir;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  async fn setup()
+  -> TestResult<(TestDatabase, MockServer, TestHttpClient)> {
+    let db = TestDatabase::builder().build()?;
+    let server = MockServer::start().await?;
+
+    let mut host_mappings = HashMap::new();
+    let uri = server.uri();
+    let host_port = uri
+      .strip_prefix("http://")
+      .ok_or("uri should start with http://")?;
+    host_mappings
+      .insert("api.github.com".to_string(), host_port.to_string());
+
+    let client = TestHttpClient::new(host_mappings)?;
+
+    Ok((db, server, client))
+  }
+
+  async fn add_bn_releases(
+    server: &MockServer,
+    releases: &[(u64, &str, &str, &str)],
+  ) {
+    for (id, tag, created_at, body) in releases {
+      let mut release =
+        MockRelease::new("cataclysmbnteam", "Cataclysm-BN", tag)
+          .created_at(*created_at)
+          .body(*body);
+      release.id = *id;
+      server
+        .add_release("cataclysmbnteam", "Cataclysm-BN", release)
+        .await;
+    }
+  }
+
+  #[tokio::test]
+  async fn test_get_release_changelog() -> TestResult {
+    let (db, server, client) = setup().await?;
+    let releases_repo =
+      SqliteReleasesRepository::new(db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(db.pool().clone());
+    let temp_res = TempDir::new()?;
+    let variant = GameVariant::BrightNights;
+
+    add_bn_releases(
+      &server,
+      &[
+        (1, "v1", "2024-01-01T00:00:00Z", "- Add A"),
+        (2, "v2", "2024-02-01T00:00:00Z", "## Changes\n- Add B"),
+        (
+          3,
+          "v3",
+          "2024-03-01T00:00:00Z",
+          "## Changes\n- Add B\n- Add C",
+        ),
+        (4, "v4", "2024-04-01T00:00:00Z", "- Add B"),
+        (5, "v5", "2024-05-01T00:00:00Z", "- Add D"),
+      ],
+    )
+    .await;
+
+    // The versions may be passed in either order.
+    let changelog = variant
+      .get_release_changelog(
+        &*VariantRegistry::default().get(&variant)?,
+        "v4",
+        "v1",
+        &client,
+        temp_res.path(),
+        &releases_repo,
+        &api_cache,
+      )
+      .await?;
+
+    assert_eq!(changelog.from_version, "v1");
+    assert_eq!(changelog.to_version, "v4");
+    assert!(changelog.is_complete);
+
+    let versions: Vec<&str> = changelog
+      .releases
+      .iter()
+      .map(|release| release.version.as_str())
+      .collect();
+    assert_eq!(versions, ["v4", "v3", "v2"]);
+    assert_eq!(changelog.releases[0].notes, None);
+    assert_eq!(
+      changelog.releases[1].notes.as_deref(),
+      Some("## Changes\n- Add C")
+    );
+    assert_eq!(
+      changelog.releases[2].notes.as_deref(),
+      Some("## Changes\n- Add B")
+    );
+
+    // The fetched releases were cached.
+    assert!(
+      releases_repo
+        .get_cached_release_by_tag(&variant, "v3")
+        .await?
+        .is_some()
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_get_release_changelog_falls_back_to_cache()
+  -> TestResult {
+    let (db, server, client) = setup().await?;
+    let releases_repo =
+      SqliteReleasesRepository::new(db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(db.pool().clone());
+    let temp_res = TempDir::new()?;
+    let variant = GameVariant::BrightNights;
+
+    let cached_releases: Vec<GitHubRelease> = [
+      (1, "v1", "2024-01-01T00:00:00Z"),
+      (2, "v2", "2024-02-01T00:00:00Z"),
+      (3, "v3", "2024-03-01T00:00:00Z"),
+    ]
+    .into_iter()
+    .map(|(id, tag, created_at)| GitHubRelease {
+      id,
+      tag_name: tag.to_string(),
+      prerelease: false,
+      body: Some(format!("- Release {tag}")),
+      assets: vec![],
+      created_at: created_at.parse().unwrap(),
+    })
+    .collect();
+    releases_repo
+      .update_cached_releases(&variant, &cached_releases)
+      .await?;
+
+    server
+      .add_mock_behavior(
+        github_mock_api::MockBehavior::builder()
+          .error(github_mock_api::MockError::InternalServerError)
+          .build(),
+      )
+      .await?;
+
+    let changelog = variant
+      .get_release_changelog(
+        &*VariantRegistry::default().get(&variant)?,
+        "v1",
+        "v3",
+        &client,
+        temp_res.path(),
+        &releases_repo,
+        &api_cache,
+      )
+      .await?;
+
+    assert!(!changelog.is_complete);
+    assert_eq!(changelog.releases.len(), 2);
+    assert_eq!(changelog.releases[0].version, "v3");
+    assert_eq!(
+      changelog.releases[0].notes.as_deref(),
+      Some("- Release v3")
+    );
+    assert_eq!(changelog.releases[1].version, "v2");
+
+    Ok(())
+  }
+}
@@ -0,0 +1,121 @@
+use std::env::consts::{ARCH, OS};
+
+use tauri::{AppHandle, Manager, State, command};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::emulated_builds::emulated_builds::EmulatedBuildsSetting;
+use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
+use crate::infra::github::repository::sqlite_github_api_cache_repository::SqliteGitHubApiCacheRepository;
+use crate::infra::http_client::ReqwestHttpClient;
+use crate::infra::utils::{HostSystem, HostSystemError};
+use crate::release_updates::release_update_policy::{
+  ReleaseUpdatePolicy, ReleaseUpdatePolicyError,
+};
+use crate::release_updates::release_updates::{
+  ReleaseUpdate, ReleaseUpdateError,
+};
+use crate::release_updates::repository::sqlite_release_update_policy_repository::SqliteReleaseUpdatePolicyRepository;
+use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavorSelection;
+use crate::variants::game_variant::UnknownGameVariantError;
+use crate::variants::variant_descriptor::VariantRegistry;
+
+/// Errors that can occur when accessing the release update policy via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum ReleaseUpdatePolicyCommandError {
+  /// An error occurred while accessing the update policy.
+  #[error("failed to access release update policy: {0}")]
+  UpdatePolicy(#[from] ReleaseUpdatePolicyError),
+}
+
+/// Retrieves the release update policy of a game variant.
+#[command]
+pub async fn get_release_update_policy(
+  variant: GameVariant,
+  repository: State<'_, SqliteReleaseUpdatePolicyRepository>,
+) -> Result<ReleaseUpdatePolicy, ReleaseUpdatePolicyCommandError> {
+  Ok(variant.get_release_update_policy(&*repository).await?)
+}
+
+/// Sets the release update policy of a game variant.
+#[command]
+pub async fn set_release_update_policy(
+  variant: GameVariant,
+  policy: ReleaseUpdatePolicy,
+  repository: State<'_, SqliteReleaseUpdatePolicyRepository>,
+) -> Result<(), ReleaseUpdatePolicyCommandError> {
+  variant
+    .set_release_update_policy(policy, &*repository)
+    .await?;
+
+  Ok(())
+}
+
+/// Errors that can occur when checking for a release update via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum CheckReleaseUpdateCommandError {
+  /// The system's resource directory could not be found.
+  #[error("system directory not found: {0}")]
+  SystemDir(#[from] tauri::Error),
+
+  /// The host system (OS/architecture) is not supported.
+  #[error("failed to determine host system: {0}")]
+  HostSystem(#[from] HostSystemError),
+
+  /// Failed to check for a newer release.
+  #[error("failed to check for release update: {0}")]
+  Update(#[from] ReleaseUpdateError),
+
+  /// The game variant is not registered.
+  #[error("unknown game variant: {0}")]
+  UnknownVariant(#[from] UnknownGameVariantError),
+}
+
+/// Checks for a newer release on the variant's update channel without
+/// installing it.
+///
+/// Meant to be called before launching the game, so that the user can choose
+/// whether to install the newer release first.
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn check_release_update(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  client: State<'_, ReqwestHttpClient>,
+  releases_repository: State<'_, SqliteReleasesRepository>,
+  github_api_cache_repository: State<
+    '_,
+    SqliteGitHubApiCacheRepository,
+  >,
+  active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+  update_policy_repository: State<
+    '_,
+    SqliteReleaseUpdatePolicyRepository,
+  >,
+  emulated_builds: State<'_, EmulatedBuildsSetting>,
+  build_flavors: State<'_, BuildFlavorSelection>,
+  variant_registry: State<'_, VariantRegistry>,
+) -> Result<Option<ReleaseUpdate>, CheckReleaseUpdateCommandError> {
+  let resource_dir = app_handle.path().resource_dir()?;
+  let host_system = HostSystem::current(OS, ARCH)?;
+  let descriptor = variant_registry.get(&variant)?;
+
+  let update = variant
+    .check_release_update(
+      &descriptor,
+      client.inner(),
+      &host_system,
+      emulated_builds.is_allowed(),
+      build_flavors.get(&variant),
+      &resource_dir,
+      &*releases_repository,
+      &*github_api_cache_repository,
+      &*active_release_repository,
+      &*update_policy_repository,
+    )
+    .await?;
+
+  Ok(update)
+}
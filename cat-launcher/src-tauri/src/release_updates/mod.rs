@@ -0,0 +1,4 @@
+pub mod commands;
+pub mod release_update_policy;
+pub mod release_updates;
+pub mod repository;
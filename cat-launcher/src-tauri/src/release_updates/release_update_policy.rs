@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoStaticStr};
+use ts_rs::TS;
+
+use crate::game_release::game_release::ReleaseType;
+use crate::release_updates::repository::{
+  ReleaseUpdatePolicyRepository, ReleaseUpdatePolicyRepositoryError,
+};
+use crate::variants::GameVariant;
+
+/// Controls what happens when a newer release of a variant's update channel
+/// is found.
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  TS,
+  Display,
+  EnumString,
+  IntoStaticStr,
+  EnumIter,
+)]
+#[strum(ascii_case_insensitive)]
+#[ts(export)]
+pub enum ReleaseUpdateMode {
+  /// Releases are never checked for updates.
+  #[default]
+  Off,
+  /// The user is notified about a newer release, but nothing is installed.
+  Notify,
+  /// The newer release is installed and made the active release.
+  AutoInstall,
+}
+
+/// The update policy of a game variant.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub struct ReleaseUpdatePolicy {
+  /// What to do when a newer release is found.
+  pub mode: ReleaseUpdateMode,
+  /// The type of releases to update to.
+  pub channel: ReleaseType,
+}
+
+impl Default for ReleaseUpdatePolicy {
+  fn default() -> Self {
+    Self {
+      mode: ReleaseUpdateMode::default(),
+      channel: ReleaseType::Stable,
+    }
+  }
+}
+
+/// Errors that can occur when interacting with the release update policy.
+#[derive(thiserror::Error, Debug)]
+pub enum ReleaseUpdatePolicyError {
+  /// An error occurred in the release update policy repository.
+  #[error("failed to access release update policy: {0}")]
+  Repository(#[from] ReleaseUpdatePolicyRepositoryError),
+}
+
+impl GameVariant {
+  /// Retrieves the release update policy for this variant.
+  ///
+  /// Falls back to the default policy if none has been set.
+  pub async fn get_release_update_policy(
+    &self,
+    repository: &impl ReleaseUpdatePolicyRepository,
+  ) -> Result<ReleaseUpdatePolicy, ReleaseUpdatePolicyError> {
+    Ok(
+      repository
+        .get_update_policy(self)
+        .await?
+        .unwrap_or_default(),
+    )
+  }
+
+  /// Sets the release update policy for this variant.
+  pub async fn set_release_update_policy(
+    &self,
+    policy: ReleaseUpdatePolicy,
+    repository: &impl ReleaseUpdatePolicyRepository,
+  ) -> Result<(), ReleaseUpdatePolicyError> {
+    repository.set_update_policy(self, &policy).await?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::infra::testing::test_database::TestDatabase;
+  use crate::release_updates::repository::sqlite_release_update_policy_repository::SqliteReleaseUpdatePolicyRepository;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_get_release_update_policy_defaults_to_off()
+  -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteReleaseUpdatePolicyRepository::new(db.pool().clone());
+
+    let policy = GameVariant::DarkDaysAhead
+      .get_release_update_policy(&repo)
+      .await?;
+    assert_eq!(policy, ReleaseUpdatePolicy::default());
+    assert_eq!(policy.mode, ReleaseUpdateMode::Off);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_set_and_get_release_update_policy() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteReleaseUpdatePolicyRepository::new(db.pool().clone());
+
+    let policy = ReleaseUpdatePolicy {
+      mode: ReleaseUpdateMode::AutoInstall,
+      channel: ReleaseType::Experimental,
+    };
+    GameVariant::BrightNights
+      .set_release_update_policy(policy, &repo)
+      .await?;
+
+    assert_eq!(
+      GameVariant::BrightNights
+        .get_release_update_policy(&repo)
+        .await?,
+      policy
+    );
+    assert_eq!(
+      GameVariant::DarkDaysAhead
+        .get_release_update_policy(&repo)
+        .await?,
+      ReleaseUpdatePolicy::default()
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,625 @@
+use std::convert::Infallible;
+use std::path::Path;
+use std::sync::Arc;
+
+use downloader::progress::Reporter;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use ts_rs::TS;
+
+use crate::active_release::active_release::ActiveReleaseError;
+use crate::active_release::repository::ActiveReleaseRepository;
+use crate::download_mirrors::repository::DownloadMirrorsRepository;
+use crate::fetch_releases::fetch_releases::FetchReleasesError;
+use crate::fetch_releases::repository::ReleasesRepository;
+use crate::fetch_releases::utils::{
+  get_default_releases, is_installable, merge_releases,
+};
+use crate::game_release::game_release::ReleaseType;
+use crate::game_release::utils::gh_release_to_game_release;
+use crate::infra::download::Downloader;
+use crate::infra::github::release::GitHubRelease;
+use crate::infra::github::repository::GitHubApiCacheRepository;
+use crate::infra::http_client::HttpClient;
+use crate::infra::utils::HostSystem;
+use crate::install_release::install_release::ReleaseInstallationError;
+use crate::installed_releases::repository::ReleaseRetentionPolicyRepository;
+use crate::launch_game::running_games::RunningGames;
+use crate::release_updates::release_update_policy::{
+  ReleaseUpdateMode, ReleaseUpdatePolicyError,
+};
+use crate::release_updates::repository::ReleaseUpdatePolicyRepository;
+use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::variant_descriptor::VariantDescriptor;
+
+/// What was done about a newer release.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub enum ReleaseUpdateAction {
+  /// The newer release is available but was not installed.
+  Available,
+  /// The newer release was installed and made the active release.
+  Installed,
+}
+
+/// A newer release found for a game variant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ReleaseUpdate {
+  /// The game variant the release belongs to.
+  pub variant: GameVariant,
+  /// The version that was active when the update was found.
+  pub previous_version: String,
+  /// The version of the newer release.
+  pub version: String,
+  /// What was done about the newer release.
+  pub action: ReleaseUpdateAction,
+}
+
+/// Errors that can occur when updating the active release of a variant.
+#[derive(thiserror::Error, Debug)]
+pub enum ReleaseUpdateError {
+  /// Failed to read the update policy.
+  #[error("failed to get release update policy: {0}")]
+  Policy(#[from] ReleaseUpdatePolicyError),
+
+  /// Failed to read the active release.
+  #[error("failed to get active release: {0}")]
+  ActiveRelease(#[from] ActiveReleaseError),
+
+  /// Failed to fetch the latest releases.
+  #[error("failed to fetch releases: {0}")]
+  Fetch(#[from] FetchReleasesError<Infallible>),
+
+  /// Failed to install the newer release.
+  #[error("failed to install release: {0}")]
+  Install(#[from] ReleaseInstallationError),
+}
+
+impl GameVariant {
+  /// Looks for a release on this variant's update channel that is newer than
+  /// the active release, without installing it.
+  ///
+  /// Returns `None` if updates are off, offline mode is enabled, no release
+  /// is active yet, or the active release is already up to date.
+  #[allow(clippy::too_many_arguments)]
+  pub async fn check_release_update(
+    &self,
+    descriptor: &VariantDescriptor,
+    client: &impl HttpClient,
+    host_system: &HostSystem,
+    allow_emulated: bool,
+    flavor: BuildFlavor,
+    resources_dir: &Path,
+    releases_repository: &impl ReleasesRepository,
+    github_api_cache_repository: &impl GitHubApiCacheRepository,
+    active_release_repository: &impl ActiveReleaseRepository,
+    update_policy_repository: &impl ReleaseUpdatePolicyRepository,
+  ) -> Result<Option<ReleaseUpdate>, ReleaseUpdateError> {
+    let found = self
+      .find_newer_release(
+        descriptor,
+        client,
+        host_system,
+        allow_emulated,
+        flavor,
+        resources_dir,
+        releases_repository,
+        github_api_cache_repository,
+        active_release_repository,
+        update_policy_repository,
+      )
+      .await?;
+
+    Ok(found.map(|(_, update, _)| update))
+  }
+
+  /// Looks for a release on this variant's update channel that is newer than
+  /// the active release, and notifies about or installs it depending on the
+  /// variant's update policy.
+  ///
+  /// Returns `None` if updates are off, offline mode is enabled, no release
+  /// is active yet, the active release is already up to date, or the
+  /// variant's game is being played.
+  ///
+  /// If the game is launched while the newer release is being installed,
+  /// the active release is kept and the newer one is reported as
+  /// available.
+  #[allow(clippy::too_many_arguments)]
+  pub async fn update_active_release(
+    &self,
+    descriptor: &VariantDescriptor,
+    client: &impl HttpClient,
+    downloader: &Downloader,
+    host_system: &HostSystem,
+    allow_emulated: bool,
+    flavor: BuildFlavor,
+    data_dir: &Path,
+    resources_dir: &Path,
+    releases_repository: &impl ReleasesRepository,
+    github_api_cache_repository: &impl GitHubApiCacheRepository,
+    active_release_repository: &impl ActiveReleaseRepository,
+    retention_policy_repository: &impl ReleaseRetentionPolicyRepository,
+    update_policy_repository: &impl ReleaseUpdatePolicyRepository,
+    download_mirrors_repository: &impl DownloadMirrorsRepository,
+    running_games: &RunningGames,
+    cancel: &CancellationToken,
+    progress: Arc<dyn Reporter + Send + Sync>,
+  ) -> Result<Option<ReleaseUpdate>, ReleaseUpdateError> {
+    // The release being played is left alone until the game exits.
+    if running_games.is_running(self) {
+      return Ok(None);
+    }
+
+    let Some((mode, mut update, newer_release)) = self
+      .find_newer_release(
+        descriptor,
+        client,
+        host_system,
+        allow_emulated,
+        flavor,
+        resources_dir,
+        releases_repository,
+        github_api_cache_repository,
+        active_release_repository,
+        update_policy_repository,
+      )
+      .await?
+    else {
+      return Ok(None);
+    };
+
+    if mode == ReleaseUpdateMode::AutoInstall {
+      let mut release =
+        gh_release_to_game_release(&newer_release, self, descriptor);
+      match release
+        .install_release(
+          descriptor,
+          downloader,
+          host_system,
+          allow_emulated,
+          flavor,
+          data_dir,
+          resources_dir,
+          releases_repository,
+          active_release_repository,
+          retention_policy_repository,
+          download_mirrors_repository,
+          running_games,
+          true,
+          cancel,
+          progress,
+        )
+        .await
+      {
+        Ok(()) => update.action = ReleaseUpdateAction::Installed,
+        // The game was launched in the meantime, so the newer release is
+        // left for the next check.
+        Err(ReleaseInstallationError::GameRunning) => {}
+        Err(e) => return Err(e.into()),
+      }
+    }
+
+    Ok(Some(update))
+  }
+
+  /// Fetches the latest releases and returns the update mode together with
+  /// the newer release, if there is one and updates aren't off.
+  #[allow(clippy::too_many_arguments)]
+  async fn find_newer_release(
+    &self,
+    descriptor: &VariantDescriptor,
+    client: &impl HttpClient,
+    host_system: &HostSystem,
+    allow_emulated: bool,
+    flavor: BuildFlavor,
+    resources_dir: &Path,
+    releases_repository: &impl ReleasesRepository,
+    github_api_cache_repository: &impl GitHubApiCacheRepository,
+    active_release_repository: &impl ActiveReleaseRepository,
+    update_policy_repository: &impl ReleaseUpdatePolicyRepository,
+  ) -> Result<
+    Option<(ReleaseUpdateMode, ReleaseUpdate, GitHubRelease)>,
+    ReleaseUpdateError,
+  > {
+    let policy = self
+      .get_release_update_policy(update_policy_repository)
+      .await?;
+    if policy.mode == ReleaseUpdateMode::Off || client.is_offline() {
+      return Ok(None);
+    }
+
+    let Some(active_version) =
+      self.get_active_release(active_release_repository).await?
+    else {
+      return Ok(None);
+    };
+
+    self
+      .fetch_releases(
+        descriptor,
+        client,
+        resources_dir,
+        releases_repository,
+        github_api_cache_repository,
+        std::slice::from_ref(&active_version),
+        |_| Ok::<(), Infallible>(()),
+        host_system,
+        allow_emulated,
+        flavor,
+      )
+      .await?;
+
+    let cached_releases = releases_repository
+      .get_cached_releases(self)
+      .await
+      .unwrap_or_default(); // It's okay if cached releases couldn't be read.
+    let default_releases =
+      get_default_releases(self, resources_dir).await;
+    let releases =
+      merge_releases(&cached_releases, &default_releases);
+
+    let Some(newer_release) = get_newer_release(
+      descriptor,
+      &releases,
+      &active_version,
+      policy.channel,
+      host_system,
+      allow_emulated,
+      flavor,
+    ) else {
+      return Ok(None);
+    };
+
+    let update = ReleaseUpdate {
+      variant: *self,
+      previous_version: active_version,
+      version: newer_release.tag_name.clone(),
+      action: ReleaseUpdateAction::Available,
+    };
+    Ok(Some((policy.mode, update, newer_release.clone())))
+  }
+}
+
+/// Returns the newest installable release of the given channel, if it is
+/// newer than the active release.
+///
+/// Releases are compared by creation date. If the active release is unknown,
+/// there's nothing to compare against, so no release is returned.
+fn get_newer_release<'a>(
+  descriptor: &VariantDescriptor,
+  releases: &'a [GitHubRelease],
+  active_version: &str,
+  channel: ReleaseType,
+  host_system: &HostSystem,
+  allow_emulated: bool,
+  flavor: BuildFlavor,
+) -> Option<&'a GitHubRelease> {
+  let active_release =
+    releases.iter().find(|r| r.tag_name == active_version)?;
+
+  releases
+    .iter()
+    .filter(|r| {
+      descriptor.release_type(&r.tag_name, r.prerelease) == channel
+    })
+    .filter(|r| {
+      is_installable(
+        descriptor,
+        r,
+        host_system,
+        allow_emulated,
+        flavor,
+      )
+    })
+    .max_by_key(|r| r.created_at)
+    .filter(|r| r.created_at > active_release.created_at)
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use std::collections::HashMap;
+  use std::num::NonZeroU16;
+
+  use chrono::{Duration, Utc};
+
+  use super::*;
+  use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+  use crate::download_mirrors::repository::sqlite_download_mirrors_repository::SqliteDownloadMirrorsRepository;
+  use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
+  use crate::infra::github::asset::GitHubAsset;
+  use crate::infra::github::repository::sqlite_github_api_cache_repository::SqliteGitHubApiCacheRepository;
+  use crate::infra::http_client::ReqwestHttpClient;
+  use crate::infra::testing::http_client::TestHttpClient;
+  use crate::infra::testing::test_database::TestDatabase;
+  use crate::infra::utils::{Arch, OS};
+  use crate::installed_releases::repository::sqlite_release_retention_policy_repository::SqliteReleaseRetentionPolicyRepository;
+  use crate::release_updates::release_update_policy::ReleaseUpdatePolicy;
+  use crate::release_updates::repository::sqlite_release_update_policy_repository::SqliteReleaseUpdatePolicyRepository;
+  use crate::variants::variant_descriptor::VariantRegistry;
+  use github_mock_api::MockServer;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  struct DummyReporter;
+  impl Reporter for DummyReporter {
+    fn setup(&self, _max_progress: Option<u64>, _message: &str) {}
+    fn progress(&self, _current: u64) {}
+    fn set_message(&self, _message: &str) {}
+    fn done(&self) {}
+  }
+
+  const HOST_SYSTEM: HostSystem = HostSystem {
+    os: OS::Linux,
+    arch: Arch::X64,
+  };
+
+  fn create_release(
+    id: u64,
+    tag_name: &str,
+    prerelease: bool,
+    days_ago: i64,
+  ) -> GitHubRelease {
+    GitHubRelease {
+      id,
+      tag_name: tag_name.to_string(),
+      prerelease,
+      body: None,
+      assets: vec![GitHubAsset {
+        id,
+        browser_download_url: "url".to_string(),
+        name: format!("cbn-linux-tiles-x64-{tag_name}.tar.gz"),
+        digest: None,
+      }],
+      created_at: Utc::now() - Duration::days(days_ago),
+    }
+  }
+
+  #[test]
+  fn test_get_newer_release() {
+    let descriptor = VariantRegistry::default()
+      .get(&GameVariant::BrightNights)
+      .expect("built-in variants should be registered");
+    let releases = vec![
+      create_release(1, "v1", false, 10),
+      create_release(2, "v2", true, 5),
+      create_release(3, "v3", false, 3),
+      create_release(4, "v4", true, 1),
+    ];
+
+    let newer = get_newer_release(
+      &descriptor,
+      &releases,
+      "v1",
+      ReleaseType::Stable,
+      &HOST_SYSTEM,
+      false,
+      BuildFlavor::default(),
+    );
+    assert_eq!(newer.map(|r| r.tag_name.as_str()), Some("v3"));
+
+    let newer = get_newer_release(
+      &descriptor,
+      &releases,
+      "v2",
+      ReleaseType::Experimental,
+      &HOST_SYSTEM,
+      false,
+      BuildFlavor::default(),
+    );
+    assert_eq!(newer.map(|r| r.tag_name.as_str()), Some("v4"));
+
+    let newer = get_newer_release(
+      &descriptor,
+      &releases,
+      "v4",
+      ReleaseType::Stable,
+      &HOST_SYSTEM,
+      false,
+      BuildFlavor::default(),
+    );
+    assert!(newer.is_none());
+
+    let newer = get_newer_release(
+      &descriptor,
+      &releases,
+      "unknown",
+      ReleaseType::Stable,
+      &HOST_SYSTEM,
+      false,
+      BuildFlavor::default(),
+    );
+    assert!(newer.is_none());
+  }
+
+  #[test]
+  fn test_get_newer_release_skips_uninstallable_releases() {
+    let descriptor = VariantRegistry::default()
+      .get(&GameVariant::BrightNights)
+      .expect("built-in variants should be registered");
+    let mut newest = create_release(2, "v2", false, 1);
+    newest.assets.clear();
+    let releases = vec![create_release(1, "v1", false, 10), newest];
+
+    let newer = get_newer_release(
+      &descriptor,
+      &releases,
+      "v1",
+      ReleaseType::Stable,
+      &HOST_SYSTEM,
+      false,
+      BuildFlavor::default(),
+    );
+    assert!(newer.is_none());
+  }
+
+  #[tokio::test]
+  async fn test_update_active_release_notifies() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let releases_repo =
+      SqliteReleasesRepository::new(db.pool().clone());
+    let api_cache =
+      SqliteGitHubApiCacheRepository::new(db.pool().clone());
+    let active_repo =
+      SqliteActiveReleaseRepository::new(db.pool().clone());
+    let retention_repo =
+      SqliteReleaseRetentionPolicyRepository::new(db.pool().clone());
+    let update_policy_repo =
+      SqliteReleaseUpdatePolicyRepository::new(db.pool().clone());
+    let mirrors_repo =
+      SqliteDownloadMirrorsRepository::new(db.pool().clone());
+    let temp_data = tempfile::TempDir::new()?;
+    let temp_res = tempfile::TempDir::new()?;
+    let variant = GameVariant::BrightNights;
+
+    let server = MockServer::start().await?;
+    let host_port = server
+      .uri()
+      .strip_prefix("http://")
+      .ok_or("uri should start with http://")?
+      .to_string();
+    let client = TestHttpClient::new(HashMap::from([(
+      "api.github.com".to_string(),
+      host_port,
+    )]))?;
+    let downloader = Downloader::new(
+      ReqwestHttpClient::new(reqwest::Client::new()),
+      NonZeroU16::new(1).expect("non zero parallel requests"),
+    );
+
+    releases_repo
+      .update_cached_releases(
+        &variant,
+        &[
+          create_release(1, "v1", false, 10),
+          create_release(2, "v2", false, 1),
+        ],
+      )
+      .await?;
+    variant.set_active_release("v1", &active_repo).await?;
+
+    // Updates are off by default.
+    let update = variant
+      .update_active_release(
+        &*VariantRegistry::default().get(&variant)?,
+        &client,
+        &downloader,
+        &HOST_SYSTEM,
+        false,
+        BuildFlavor::default(),
+        temp_data.path(),
+        temp_res.path(),
+        &releases_repo,
+        &api_cache,
+        &active_repo,
+        &retention_repo,
+        &update_policy_repo,
+        &mirrors_repo,
+        &RunningGames::default(),
+        &CancellationToken::new(),
+        Arc::new(DummyReporter),
+      )
+      .await?;
+    assert_eq!(update, None);
+
+    variant
+      .set_release_update_policy(
+        ReleaseUpdatePolicy {
+          mode: ReleaseUpdateMode::Notify,
+          channel: ReleaseType::Stable,
+        },
+        &update_policy_repo,
+      )
+      .await?;
+    let update = variant
+      .update_active_release(
+        &*VariantRegistry::default().get(&variant)?,
+        &client,
+        &downloader,
+        &HOST_SYSTEM,
+        false,
+        BuildFlavor::default(),
+        temp_data.path(),
+        temp_res.path(),
+        &releases_repo,
+        &api_cache,
+        &active_repo,
+        &retention_repo,
+        &update_policy_repo,
+        &mirrors_repo,
+        &RunningGames::default(),
+        &CancellationToken::new(),
+        Arc::new(DummyReporter),
+      )
+      .await?;
+
+    assert_eq!(
+      update,
+      Some(ReleaseUpdate {
+        variant,
+        previous_version: "v1".to_string(),
+        version: "v2".to_string(),
+        action: ReleaseUpdateAction::Available,
+      })
+    );
+    assert_eq!(
+      variant.get_active_release(&active_repo).await?,
+      Some("v1".to_string())
+    );
+
+    let checked = variant
+      .check_release_update(
+        &*VariantRegistry::default().get(&variant)?,
+        &client,
+        &HOST_SYSTEM,
+        false,
+        BuildFlavor::default(),
+        temp_res.path(),
+        &releases_repo,
+        &api_cache,
+        &active_repo,
+        &update_policy_repo,
+      )
+      .await?;
+    assert_eq!(checked, update);
+
+    // A variant that's being played is skipped.
+    let running_games = RunningGames::default();
+    let _session = running_games.start(variant);
+    let update = variant
+      .update_active_release(
+        &*VariantRegistry::default().get(&variant)?,
+        &client,
+        &downloader,
+        &HOST_SYSTEM,
+        false,
+        BuildFlavor::default(),
+        temp_data.path(),
+        temp_res.path(),
+        &releases_repo,
+        &api_cache,
+        &active_repo,
+        &retention_repo,
+        &update_policy_repo,
+        &mirrors_repo,
+        &running_games,
+        &CancellationToken::new(),
+        Arc::new(DummyReporter),
+      )
+      .await?;
+    assert_eq!(update, None);
+
+    Ok(())
+  }
+}
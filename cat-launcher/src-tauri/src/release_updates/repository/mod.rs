@@ -0,0 +1,6 @@
+pub mod release_update_policy_repository;
+pub mod sqlite_release_update_policy_repository;
+
+pub use release_update_policy_repository::{
+  ReleaseUpdatePolicyRepository, ReleaseUpdatePolicyRepositoryError,
+};
@@ -0,0 +1,42 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use crate::release_updates::release_update_policy::ReleaseUpdatePolicy;
+use crate::variants::game_variant::GameVariant;
+
+/// Errors that can occur when interacting with the release update policy repository.
+#[derive(thiserror::Error, Debug)]
+pub enum ReleaseUpdatePolicyRepositoryError {
+  /// An error occurred while retrieving the update policy.
+  #[error("failed to get release update policy: {0}")]
+  Get(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while setting the update policy.
+  #[error("failed to set release update policy: {0}")]
+  Set(Box<dyn Error + Send + Sync>),
+
+  /// The stored update policy value is invalid.
+  #[error("invalid release update policy: {0}")]
+  InvalidPolicy(String),
+}
+
+/// A repository for managing the release update policy of each game variant.
+#[async_trait]
+pub trait ReleaseUpdatePolicyRepository: Send + Sync {
+  /// Retrieves the update policy for the given game variant, if one was set.
+  async fn get_update_policy(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<
+    Option<ReleaseUpdatePolicy>,
+    ReleaseUpdatePolicyRepositoryError,
+  >;
+
+  /// Sets the update policy for the given game variant.
+  async fn set_update_policy(
+    &self,
+    game_variant: &GameVariant,
+    policy: &ReleaseUpdatePolicy,
+  ) -> Result<(), ReleaseUpdatePolicyRepositoryError>;
+}
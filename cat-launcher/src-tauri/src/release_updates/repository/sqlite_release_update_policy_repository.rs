@@ -0,0 +1,109 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::OptionalExtension;
+use tokio::task;
+
+use crate::game_release::game_release::ReleaseType;
+use crate::release_updates::release_update_policy::{
+  ReleaseUpdateMode, ReleaseUpdatePolicy,
+};
+use crate::release_updates::repository::{
+  ReleaseUpdatePolicyRepository, ReleaseUpdatePolicyRepositoryError,
+};
+use crate::variants::game_variant::GameVariant;
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+/// A repository for managing release update policies using a SQLite database.
+pub struct SqliteReleaseUpdatePolicyRepository {
+  pool: Pool,
+}
+
+impl SqliteReleaseUpdatePolicyRepository {
+  /// Creates a new instance of `SqliteReleaseUpdatePolicyRepository` with the given connection pool.
+  pub fn new(pool: Pool) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl ReleaseUpdatePolicyRepository
+  for SqliteReleaseUpdatePolicyRepository
+{
+  async fn get_update_policy(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<
+    Option<ReleaseUpdatePolicy>,
+    ReleaseUpdatePolicyRepositoryError,
+  > {
+    let pool = self.pool.clone();
+    let game_variant = *game_variant;
+
+    task::spawn_blocking(move || {
+      let conn = pool.get().map_err(|e| {
+        ReleaseUpdatePolicyRepositoryError::Get(Box::new(e))
+      })?;
+
+      let stored_policy: Option<(String, String)> = conn
+        .query_row(
+          "SELECT mode, channel FROM release_update_policy WHERE game_variant = ?1",
+          [game_variant.to_string()],
+          |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| {
+          ReleaseUpdatePolicyRepositoryError::Get(Box::new(e))
+        })?;
+
+      stored_policy
+        .map(|(mode, channel)| {
+          let mode = ReleaseUpdateMode::from_str(&mode).map_err(|_| {
+            ReleaseUpdatePolicyRepositoryError::InvalidPolicy(
+              mode.clone(),
+            )
+          })?;
+          let channel = ReleaseType::from_str(&channel).map_err(|_| {
+            ReleaseUpdatePolicyRepositoryError::InvalidPolicy(
+              channel.clone(),
+            )
+          })?;
+          Ok(ReleaseUpdatePolicy { mode, channel })
+        })
+        .transpose()
+    })
+    .await
+    .map_err(|e| ReleaseUpdatePolicyRepositoryError::Get(Box::new(e)))?
+  }
+
+  async fn set_update_policy(
+    &self,
+    game_variant: &GameVariant,
+    policy: &ReleaseUpdatePolicy,
+  ) -> Result<(), ReleaseUpdatePolicyRepositoryError> {
+    let pool = self.pool.clone();
+    let game_variant = *game_variant;
+    let mode = policy.mode.to_string();
+    let channel = policy.channel.to_string();
+
+    task::spawn_blocking(move || {
+      let conn = pool.get().map_err(|e| {
+        ReleaseUpdatePolicyRepositoryError::Set(Box::new(e))
+      })?;
+      conn
+        .execute(
+          "INSERT OR REPLACE INTO release_update_policy (game_variant, mode, channel) VALUES (?1, ?2, ?3)",
+          (game_variant.to_string(), mode, channel),
+        )
+        .map_err(|e| {
+          ReleaseUpdatePolicyRepositoryError::Set(Box::new(e))
+        })?;
+
+      Ok(())
+    })
+    .await
+    .map_err(|e| ReleaseUpdatePolicyRepositoryError::Set(Box::new(e)))?
+  }
+}
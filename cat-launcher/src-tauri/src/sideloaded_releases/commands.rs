@@ -0,0 +1,74 @@
+use std::env::consts::OS;
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager, State, command};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
+use crate::game_release::game_release::GameRelease;
+use crate::infra::utils::{OSNotSupportedError, get_os_enum};
+use crate::sideloaded_releases::repository::sqlite_sideloaded_releases_repository::SqliteSideloadedReleasesRepository;
+use crate::sideloaded_releases::sideloaded_releases::SideloadReleaseError;
+use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavorSelection;
+use crate::variants::game_variant::UnknownGameVariantError;
+use crate::variants::variant_descriptor::VariantRegistry;
+
+/// Errors that can occur when sideloading a release via a Tauri command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum SideloadReleaseCommandError {
+  /// The system's local data or resource directory could not be found.
+  #[error("system directory not found: {0}")]
+  SystemDir(#[from] tauri::Error),
+
+  /// The current operating system is not supported.
+  #[error("failed to get OS enum: {0}")]
+  Os(#[from] OSNotSupportedError),
+
+  /// Failed to sideload the release.
+  #[error("failed to sideload release: {0}")]
+  Sideload(#[from] SideloadReleaseError),
+
+  /// The game variant is not registered.
+  #[error("unknown game variant: {0}")]
+  UnknownVariant(#[from] UnknownGameVariantError),
+}
+
+/// A Tauri command that installs a release from a local archive.
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn sideload_release(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  archive_path: PathBuf,
+  version: Option<String>,
+  releases_repository: State<'_, SqliteReleasesRepository>,
+  sideloaded_releases_repository: State<
+    '_,
+    SqliteSideloadedReleasesRepository,
+  >,
+  build_flavors: State<'_, BuildFlavorSelection>,
+  variant_registry: State<'_, VariantRegistry>,
+) -> Result<GameRelease, SideloadReleaseCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let resources_dir = app_handle.path().resource_dir()?;
+  let os = get_os_enum(OS)?;
+  let descriptor = variant_registry.get(&variant)?;
+
+  let release = variant
+    .sideload_release(
+      &descriptor,
+      &archive_path,
+      version.as_deref(),
+      &os,
+      build_flavors.get(&variant),
+      &data_dir,
+      &resources_dir,
+      &*releases_repository,
+      &*sideloaded_releases_repository,
+    )
+    .await?;
+
+  Ok(release)
+}
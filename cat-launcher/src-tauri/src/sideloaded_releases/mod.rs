@@ -0,0 +1,4 @@
+pub mod commands;
+pub mod repository;
+#[allow(clippy::module_inception)]
+pub mod sideloaded_releases;
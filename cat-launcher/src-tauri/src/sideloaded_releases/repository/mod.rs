@@ -0,0 +1,6 @@
+pub mod sideloaded_releases_repository;
+pub mod sqlite_sideloaded_releases_repository;
+
+pub use sideloaded_releases_repository::{
+  SideloadedReleasesRepository, SideloadedReleasesRepositoryError,
+};
@@ -0,0 +1,37 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use crate::sideloaded_releases::sideloaded_releases::SideloadedRelease;
+use crate::variants::GameVariant;
+
+/// Errors that can occur when interacting with the sideloaded releases
+/// repository.
+#[derive(thiserror::Error, Debug)]
+pub enum SideloadedReleasesRepositoryError {
+  /// An error occurred while retrieving sideloaded releases.
+  #[error("failed to get sideloaded releases: {0}")]
+  Get(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while storing a sideloaded release.
+  #[error("failed to add sideloaded release: {0}")]
+  Add(Box<dyn Error + Send + Sync>),
+}
+
+/// A repository for releases that were installed from local archives
+/// instead of being downloaded.
+#[async_trait]
+pub trait SideloadedReleasesRepository: Send + Sync {
+  /// Retrieves the sideloaded releases of a variant, newest first.
+  async fn get_sideloaded_releases(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Vec<SideloadedRelease>, SideloadedReleasesRepositoryError>;
+
+  /// Stores a sideloaded release, replacing one with the same version.
+  async fn add_sideloaded_release(
+    &self,
+    game_variant: &GameVariant,
+    release: &SideloadedRelease,
+  ) -> Result<(), SideloadedReleasesRepositoryError>;
+}
@@ -0,0 +1,142 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use r2d2_sqlite::SqliteConnectionManager;
+
+use crate::infra::repository::db_helper::run_db;
+use crate::sideloaded_releases::repository::{
+  SideloadedReleasesRepository, SideloadedReleasesRepositoryError,
+};
+use crate::sideloaded_releases::sideloaded_releases::SideloadedRelease;
+use crate::variants::GameVariant;
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+/// A SQLite-backed implementation of the `SideloadedReleasesRepository`.
+#[derive(Clone)]
+pub struct SqliteSideloadedReleasesRepository {
+  pool: Pool,
+}
+
+impl SqliteSideloadedReleasesRepository {
+  /// Creates a new `SqliteSideloadedReleasesRepository` with the given connection pool.
+  pub fn new(pool: Pool) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl SideloadedReleasesRepository
+  for SqliteSideloadedReleasesRepository
+{
+  async fn get_sideloaded_releases(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Vec<SideloadedRelease>, SideloadedReleasesRepositoryError>
+  {
+    let pool = self.pool.clone();
+    let game_variant = game_variant.to_string();
+
+    let rows: Vec<(String, String)> = run_db(pool, move |conn| {
+      let mut stmt = conn.prepare(
+        "SELECT version, created_at FROM sideloaded_releases
+         WHERE game_variant = ?1 ORDER BY created_at DESC",
+      )?;
+      let rows = stmt
+        .query_map([game_variant], |row| {
+          Ok((row.get(0)?, row.get(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+      Ok::<_, rusqlite::Error>(rows)
+    })
+    .await
+    .map_err(SideloadedReleasesRepositoryError::Get)?;
+
+    rows
+      .into_iter()
+      .map(|(version, created_at)| {
+        let created_at =
+          created_at.parse::<DateTime<Utc>>().map_err(|e| {
+            SideloadedReleasesRepositoryError::Get(Box::new(e))
+          })?;
+        Ok(SideloadedRelease {
+          version,
+          created_at,
+        })
+      })
+      .collect()
+  }
+
+  async fn add_sideloaded_release(
+    &self,
+    game_variant: &GameVariant,
+    release: &SideloadedRelease,
+  ) -> Result<(), SideloadedReleasesRepositoryError> {
+    let pool = self.pool.clone();
+    let game_variant = game_variant.to_string();
+    let version = release.version.clone();
+    let created_at = release.created_at.to_rfc3339();
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO sideloaded_releases (game_variant, version, created_at)
+         VALUES (?1, ?2, ?3)",
+        [game_variant, version, created_at],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(SideloadedReleasesRepositoryError::Add)
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use chrono::TimeZone;
+
+  use super::*;
+  use crate::infra::testing::test_database::TestDatabase;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_add_and_get_sideloaded_releases() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteSideloadedReleasesRepository::new(db.pool().clone());
+    let variant = GameVariant::DarkDaysAhead;
+
+    let older = SideloadedRelease {
+      version: "local-1".to_string(),
+      created_at: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+    };
+    let newer = SideloadedRelease {
+      version: "local-2".to_string(),
+      created_at: Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap(),
+    };
+
+    repo.add_sideloaded_release(&variant, &older).await?;
+    repo.add_sideloaded_release(&variant, &newer).await?;
+    repo.add_sideloaded_release(&variant, &older).await?;
+
+    assert_eq!(
+      repo.get_sideloaded_releases(&variant).await?,
+      vec![newer, older]
+    );
+    assert!(
+      repo
+        .get_sideloaded_releases(&GameVariant::BrightNights)
+        .await?
+        .is_empty()
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,551 @@
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tokio::fs;
+use tokio_util::sync::CancellationToken;
+
+use crate::fetch_releases::repository::ReleasesRepository;
+use crate::fetch_releases::utils::{
+  get_default_releases, merge_releases,
+};
+use crate::filesystem::paths::{
+  AssetExtractionDirError, GetExecutablePathError,
+  find_game_executable, get_asset_installation_dir,
+  get_asset_staging_dir,
+};
+use crate::game_release::game_release::{
+  GameRelease, GameReleaseStatus,
+};
+use crate::infra::archive::{
+  ExtractionError, ExtractionPolicy, extract_archive,
+};
+use crate::infra::installation_progress_monitor::noop_reporter::NoopReporter;
+use crate::infra::utils::OS;
+use crate::install_release::install_release::{
+  move_into_place, restore_replaced,
+};
+use crate::sideloaded_releases::repository::{
+  SideloadedReleasesRepository, SideloadedReleasesRepositoryError,
+};
+use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::variant_descriptor::VariantDescriptor;
+
+/// The archive extensions stripped from an archive's name to infer a
+/// version from it.
+const ARCHIVE_EXTENSIONS: &[&str] = &[
+  ".tar.gz", ".tar.xz", ".tar.bz2", ".tar.zst", ".zip", ".7z",
+  ".rar", ".dmg",
+];
+
+/// A release that was installed from a local archive instead of being
+/// downloaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SideloadedRelease {
+  /// The version label of the release.
+  pub version: String,
+  /// When the release was sideloaded.
+  pub created_at: DateTime<Utc>,
+}
+
+impl SideloadedRelease {
+  /// Converts the sideloaded release into a `GameRelease` of the given
+  /// variant. Sideloaded builds weren't published as regular releases, so
+  /// they're typed like prereleases.
+  pub fn to_game_release(
+    &self,
+    variant: &GameVariant,
+    descriptor: &VariantDescriptor,
+  ) -> GameRelease {
+    GameRelease {
+      variant: *variant,
+      version: self.version.clone(),
+      body: None,
+      release_type: descriptor.release_type(&self.version, true),
+      status: GameReleaseStatus::Unknown,
+      created_at: self.created_at,
+    }
+  }
+}
+
+/// Errors that can occur when sideloading a release.
+#[derive(thiserror::Error, Debug)]
+pub enum SideloadReleaseError {
+  /// No version was given and none could be inferred from the archive.
+  #[error("could not infer a version from the archive name")]
+  NoVersion,
+
+  /// A release with the same version is already installed.
+  #[error("release {0} is already installed")]
+  AlreadyInstalled(String),
+
+  /// Failed to create the staging directory.
+  #[error("failed to create staging directory: {0}")]
+  StagingDir(#[from] AssetExtractionDirError),
+
+  /// Failed to extract the archive.
+  #[error("failed to extract archive: {0}")]
+  Extract(#[from] ExtractionError),
+
+  /// Failed to move the extracted release into the installation
+  /// directory.
+  #[error("failed to move installation into place: {0}")]
+  MoveIntoPlace(io::Error),
+
+  /// The archive doesn't contain a game executable for this variant.
+  #[error("archive does not contain a game executable")]
+  NoExecutable,
+
+  /// Failed to look for the game executable.
+  #[error("failed to find game executable: {0}")]
+  Executable(#[from] GetExecutablePathError),
+
+  /// Failed to store the sideloaded release.
+  #[error("failed to store sideloaded release: {0}")]
+  Repository(#[from] SideloadedReleasesRepositoryError),
+}
+
+impl GameVariant {
+  /// Installs a release of this variant from a local archive, e.g. one
+  /// shared on a LAN drive or a build that was never published.
+  ///
+  /// If no version is given, it is inferred from the archive's name: an
+  /// archive named like an asset of a known release is that release, and
+  /// any other archive is labelled with its name minus the extension.
+  ///
+  /// The archive is extracted through a staging directory into the normal
+  /// installation directory and must contain a game executable. The
+  /// release is then listed alongside downloaded releases and can be made
+  /// active like any of them.
+  #[allow(clippy::too_many_arguments)]
+  pub async fn sideload_release(
+    &self,
+    descriptor: &VariantDescriptor,
+    archive_path: &Path,
+    version: Option<&str>,
+    os: &OS,
+    flavor: BuildFlavor,
+    data_dir: &Path,
+    resources_dir: &Path,
+    releases_repository: &impl ReleasesRepository,
+    sideloaded_releases_repository: &impl SideloadedReleasesRepository,
+  ) -> Result<GameRelease, SideloadReleaseError> {
+    let version =
+      match version.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(version) => version.to_string(),
+        None => self
+          .infer_sideloaded_version(
+            archive_path,
+            resources_dir,
+            releases_repository,
+          )
+          .await
+          .ok_or(SideloadReleaseError::NoVersion)?,
+      };
+
+    let sideloaded_release = SideloadedRelease {
+      version,
+      created_at: Utc::now(),
+    };
+    let mut release =
+      sideloaded_release.to_game_release(self, descriptor);
+
+    if is_installed(&release, descriptor, os, flavor, data_dir).await
+    {
+      return Err(SideloadReleaseError::AlreadyInstalled(
+        release.version,
+      ));
+    }
+
+    let staging_dir =
+      get_asset_staging_dir(self, &release.version, flavor, data_dir);
+    // Start from scratch in case an earlier attempt left something behind.
+    let _ = fs::remove_dir_all(&staging_dir).await;
+    fs::create_dir_all(&staging_dir)
+      .await
+      .map_err(AssetExtractionDirError::from)?;
+
+    let result = self
+      .install_sideloaded_release(
+        descriptor,
+        archive_path,
+        &sideloaded_release,
+        &staging_dir,
+        os,
+        flavor,
+        data_dir,
+        sideloaded_releases_repository,
+      )
+      .await;
+
+    // A successful installation has already been moved out of the staging
+    // directory, so anything left there is a partial extraction.
+    let _ = fs::remove_dir_all(&staging_dir).await;
+    result?;
+
+    release.status = GameReleaseStatus::ReadyToPlay;
+    Ok(release)
+  }
+
+  /// Extracts the archive to `staging_dir`, checks that it contains a game
+  /// executable, and moves it into the installation directory of the
+  /// release. Whatever was in the installation directory is restored if the
+  /// release can't be stored.
+  ///
+  /// The archive may come from anywhere, so it's extracted as untrusted.
+  #[allow(clippy::too_many_arguments)]
+  async fn install_sideloaded_release(
+    &self,
+    descriptor: &VariantDescriptor,
+    archive_path: &Path,
+    sideloaded_release: &SideloadedRelease,
+    staging_dir: &Path,
+    os: &OS,
+    flavor: BuildFlavor,
+    data_dir: &Path,
+    sideloaded_releases_repository: &impl SideloadedReleasesRepository,
+  ) -> Result<(), SideloadReleaseError> {
+    extract_archive(
+      archive_path,
+      staging_dir,
+      os,
+      ExtractionPolicy::UNTRUSTED,
+      Arc::new(NoopReporter),
+      &CancellationToken::new(),
+    )
+    .await?;
+
+    find_game_executable(staging_dir, descriptor, os)
+      .await
+      .map_err(|e| match e {
+        GetExecutablePathError::DoesNotExist => {
+          SideloadReleaseError::NoExecutable
+        }
+        e => e.into(),
+      })?;
+
+    let installation_dir = get_asset_installation_dir(
+      self,
+      &sideloaded_release.version,
+      flavor,
+      data_dir,
+    );
+    let replaced = move_into_place(staging_dir, &installation_dir)
+      .await
+      .map_err(SideloadReleaseError::MoveIntoPlace)?;
+
+    if let Err(e) = sideloaded_releases_repository
+      .add_sideloaded_release(self, sideloaded_release)
+      .await
+    {
+      restore_replaced(&installation_dir, replaced).await;
+      return Err(e.into());
+    }
+
+    if let Some(replaced) = replaced {
+      let _ = fs::remove_dir_all(&replaced).await;
+    }
+
+    Ok(())
+  }
+
+  /// Returns the sideloaded releases of this variant that are still
+  /// installed, newest first.
+  pub async fn get_sideloaded_releases(
+    &self,
+    descriptor: &VariantDescriptor,
+    os: &OS,
+    flavor: BuildFlavor,
+    data_dir: &Path,
+    sideloaded_releases_repository: &impl SideloadedReleasesRepository,
+  ) -> Result<Vec<GameRelease>, SideloadedReleasesRepositoryError> {
+    let sideloaded_releases = sideloaded_releases_repository
+      .get_sideloaded_releases(self)
+      .await?;
+
+    let mut releases = Vec::new();
+    for sideloaded_release in &sideloaded_releases {
+      let mut release =
+        sideloaded_release.to_game_release(self, descriptor);
+      if is_installed(&release, descriptor, os, flavor, data_dir)
+        .await
+      {
+        release.status = GameReleaseStatus::ReadyToPlay;
+        releases.push(release);
+      }
+    }
+
+    Ok(releases)
+  }
+
+  async fn infer_sideloaded_version(
+    &self,
+    archive_path: &Path,
+    resources_dir: &Path,
+    releases_repository: &impl ReleasesRepository,
+  ) -> Option<String> {
+    let file_name = archive_path.file_name()?.to_str()?;
+
+    let cached_releases = releases_repository
+      .get_cached_releases(self)
+      .await
+      .unwrap_or_default(); // It's okay if cached releases couldn't be read.
+    let default_releases =
+      get_default_releases(self, resources_dir).await;
+    let known_release =
+      merge_releases(&cached_releases, &default_releases)
+        .into_iter()
+        .find(|r| r.assets.iter().any(|a| a.name == file_name));
+    if let Some(release) = known_release {
+      return Some(release.tag_name);
+    }
+
+    let stem = ARCHIVE_EXTENSIONS
+      .iter()
+      .find_map(|ext| file_name.strip_suffix(ext))
+      .unwrap_or(file_name);
+    Some(stem.to_string()).filter(|stem| !stem.is_empty())
+  }
+}
+
+/// Returns whether the release is installed and ready to play.
+async fn is_installed(
+  release: &GameRelease,
+  descriptor: &VariantDescriptor,
+  os: &OS,
+  flavor: BuildFlavor,
+  data_dir: &Path,
+) -> bool {
+  let installation_dir = get_asset_installation_dir(
+    &release.variant,
+    &release.version,
+    flavor,
+    data_dir,
+  );
+
+  // Checking the status creates the installation directory, so skip
+  // releases that were never installed.
+  if !fs::metadata(&installation_dir)
+    .await
+    .is_ok_and(|metadata| metadata.is_dir())
+  {
+    return false;
+  }
+
+  matches!(
+    release
+      .get_installation_status(descriptor, os, flavor, data_dir)
+      .await,
+    Ok(GameReleaseStatus::ReadyToPlay)
+  )
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use std::io::{Cursor, Write};
+
+  use tempfile::TempDir;
+  use zip::write::SimpleFileOptions;
+
+  use super::*;
+  use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
+  use crate::infra::testing::test_database::TestDatabase;
+  use crate::infra::testing::test_zip::create_test_zip;
+  use crate::sideloaded_releases::repository::sqlite_sideloaded_releases_repository::SqliteSideloadedReleasesRepository;
+  use crate::variants::variant_descriptor::VariantRegistry;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  struct TestContext {
+    _db: TestDatabase,
+    temp_dir: TempDir,
+    releases_repo: SqliteReleasesRepository,
+    sideloaded_repo: SqliteSideloadedReleasesRepository,
+  }
+
+  impl TestContext {
+    fn new() -> TestResult<Self> {
+      let db = TestDatabase::builder().build()?;
+      Ok(Self {
+        releases_repo: SqliteReleasesRepository::new(
+          db.pool().clone(),
+        ),
+        sideloaded_repo: SqliteSideloadedReleasesRepository::new(
+          db.pool().clone(),
+        ),
+        _db: db,
+        temp_dir: TempDir::new()?,
+      })
+    }
+
+    fn data_dir(&self) -> std::path::PathBuf {
+      self.temp_dir.path().join("data")
+    }
+
+    fn resources_dir(&self) -> std::path::PathBuf {
+      self.temp_dir.path().join("resources")
+    }
+
+    async fn write_archive(
+      &self,
+      name: &str,
+      files: &[(&str, &[u8])],
+    ) -> TestResult<std::path::PathBuf> {
+      let path = self.temp_dir.path().join(name);
+      tokio::fs::write(&path, create_test_zip(files)?).await?;
+      Ok(path)
+    }
+
+    async fn sideload(
+      &self,
+      archive_path: &Path,
+      version: Option<&str>,
+    ) -> Result<GameRelease, SideloadReleaseError> {
+      let variant = GameVariant::DarkDaysAhead;
+      let descriptor = VariantRegistry::default()
+        .get(&variant)
+        .expect("built-in variants should be registered");
+      variant
+        .sideload_release(
+          &descriptor,
+          archive_path,
+          version,
+          &OS::Windows,
+          BuildFlavor::default(),
+          &self.data_dir(),
+          &self.resources_dir(),
+          &self.releases_repo,
+          &self.sideloaded_repo,
+        )
+        .await
+    }
+  }
+
+  #[tokio::test]
+  async fn test_sideload_release() -> TestResult {
+    let ctx = TestContext::new()?;
+    let archive_path = ctx
+      .write_archive(
+        "tester-build.zip",
+        &[("cataclysm-tiles.exe", b"dummy exec")],
+      )
+      .await?;
+
+    let release = ctx.sideload(&archive_path, None).await?;
+
+    assert_eq!(release.version, "tester-build");
+    assert_eq!(release.status, GameReleaseStatus::ReadyToPlay);
+
+    let releases = GameVariant::DarkDaysAhead
+      .get_sideloaded_releases(
+        &*VariantRegistry::default()
+          .get(&GameVariant::DarkDaysAhead)?,
+        &OS::Windows,
+        BuildFlavor::default(),
+        &ctx.data_dir(),
+        &ctx.sideloaded_repo,
+      )
+      .await?;
+    assert_eq!(releases, vec![release]);
+
+    assert!(matches!(
+      ctx.sideload(&archive_path, Some("tester-build")).await,
+      Err(SideloadReleaseError::AlreadyInstalled(_))
+    ));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_sideload_release_without_executable() -> TestResult {
+    let ctx = TestContext::new()?;
+    let archive_path = ctx
+      .write_archive("not-a-game.zip", &[("readme.txt", b"hello")])
+      .await?;
+
+    let result = ctx.sideload(&archive_path, Some("local")).await;
+
+    assert!(matches!(
+      result,
+      Err(SideloadReleaseError::NoExecutable)
+    ));
+    assert!(
+      !get_asset_installation_dir(
+        &GameVariant::DarkDaysAhead,
+        "local",
+        BuildFlavor::default(),
+        &ctx.data_dir()
+      )
+      .exists()
+    );
+    assert!(
+      ctx
+        .sideloaded_repo
+        .get_sideloaded_releases(&GameVariant::DarkDaysAhead)
+        .await?
+        .is_empty()
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_sideload_release_rejects_links() -> TestResult {
+    let ctx = TestContext::new()?;
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file(
+      "cataclysm-tiles.exe",
+      SimpleFileOptions::default(),
+    )?;
+    zip.write_all(b"dummy exec")?;
+    zip.add_symlink(
+      "data",
+      "../../outside",
+      SimpleFileOptions::default(),
+    )?;
+    let archive_path = ctx.temp_dir.path().join("linked.zip");
+    tokio::fs::write(&archive_path, zip.finish()?.into_inner())
+      .await?;
+
+    let result = ctx.sideload(&archive_path, Some("linked")).await;
+
+    // Sideloaded archives are untrusted, so links aren't extracted at all.
+    assert!(matches!(
+      result,
+      Err(SideloadReleaseError::Extract(
+        ExtractionError::LinkNotAllowed(_)
+      ))
+    ));
+    let variant = GameVariant::DarkDaysAhead;
+    assert!(
+      !get_asset_installation_dir(
+        &variant,
+        "linked",
+        BuildFlavor::default(),
+        &ctx.data_dir()
+      )
+      .exists()
+    );
+    assert!(
+      !get_asset_staging_dir(
+        &variant,
+        "linked",
+        BuildFlavor::default(),
+        &ctx.data_dir()
+      )
+      .exists()
+    );
+
+    Ok(())
+  }
+}
@@ -8,6 +8,7 @@ use cat_macros::CommandErrorSerialize;
 
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
 use crate::infra::download::Downloader;
+use crate::infra::http_client::is_offline_error;
 use crate::infra::installation_progress_monitor::channel_reporter::ChannelReporter;
 use crate::infra::utils::{get_os_enum, OSNotSupportedError};
 use crate::soundpacks::get_third_party_soundpack_installation_status::{
@@ -23,6 +24,7 @@ use crate::soundpacks::uninstall_third_party_soundpack::{
     uninstall_third_party_soundpack, UninstallThirdPartySoundpackError,
 };
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavorSelection;
 
 /// Errors that can occur when listing all soundpacks via a command.
 #[derive(thiserror::Error, Debug, CommandErrorSerialize)]
@@ -46,6 +48,7 @@ pub async fn list_all_soundpacks_command(
   variant: GameVariant,
   app: tauri::AppHandle,
   active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+  build_flavors: State<'_, BuildFlavorSelection>,
 ) -> Result<Vec<Soundpack>, ListAllSoundpacksCommandError> {
   let data_dir = app.path().app_local_data_dir()?;
   let resource_dir = app.path().resource_dir()?;
@@ -54,6 +57,7 @@ pub async fn list_all_soundpacks_command(
 
   let soundpacks = list_all_soundpacks(
     &variant,
+    build_flavors.get(&variant),
     &data_dir,
     &resource_dir,
     &os,
@@ -75,9 +79,25 @@ pub enum InstallThirdPartySoundpackCommandError {
   #[error("failed to get OS information")]
   OSInfo(#[from] OSNotSupportedError),
 
+  /// Offline mode is enabled, so the request was not made.
+  #[error("offline mode is enabled")]
+  Offline,
+
   /// An error occurred while installing the soundpack.
   #[error("failed to install soundpack: {0}")]
-  Install(#[from] InstallThirdPartySoundpackError),
+  Install(InstallThirdPartySoundpackError),
+}
+
+impl From<InstallThirdPartySoundpackError>
+  for InstallThirdPartySoundpackCommandError
+{
+  fn from(e: InstallThirdPartySoundpackError) -> Self {
+    if is_offline_error(&e) {
+      Self::Offline
+    } else {
+      Self::Install(e)
+    }
+  }
 }
 
 /// Installs a third-party soundpack for a game variant.
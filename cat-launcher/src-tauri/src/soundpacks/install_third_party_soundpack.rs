@@ -6,14 +6,18 @@ use std::sync::Arc;
 use tokio::fs::{create_dir_all, read_to_string};
 
 use downloader::progress::Reporter;
+use tokio_util::sync::CancellationToken;
 
 use crate::filesystem::paths::{
   GetOrCreateDirectoryError, GetUserGameDataDirError,
   get_or_create_directory, get_or_create_user_game_data_dir,
 };
 use crate::filesystem::utils::{CopyDirError, copy_dir_all};
-use crate::infra::archive::{ExtractionError, extract_archive};
+use crate::infra::archive::{
+  ExtractionError, ExtractionPolicy, extract_archive,
+};
 use crate::infra::download::{DownloadFileError, Downloader};
+use crate::infra::download_queue::DownloadPriority;
 
 use crate::infra::utils::OS;
 use crate::soundpacks::paths::get_soundpacks_resource_path;
@@ -81,14 +85,23 @@ pub async fn install_third_party_soundpack(
     .download_file(
       &soundpack_details.installation.download_url,
       &soundpack_temp_dir,
-      reporter,
+      DownloadPriority::Normal,
+      reporter.clone(),
     )
     .await?;
 
   // Extract the soundpack to the temp directory
   let extraction_dir = soundpack_temp_dir.join("extracted");
   create_dir_all(&extraction_dir).await?;
-  extract_archive(&downloaded_file, &extraction_dir, os).await?;
+  extract_archive(
+    &downloaded_file,
+    &extraction_dir,
+    os,
+    ExtractionPolicy::UNTRUSTED,
+    reporter,
+    &CancellationToken::new(),
+  )
+  .await?;
 
   // Get the soundpack parent directory from the soundpack path
   let soundpack_parent_dir = get_soundpack_parent_dir(
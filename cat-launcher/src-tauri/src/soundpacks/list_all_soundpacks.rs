@@ -18,6 +18,7 @@ use crate::soundpacks::types::{
   Soundpack, StockSoundpack, ThirdPartySoundpack,
 };
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
 
 /// Errors that can occur when listing all soundpacks.
 #[derive(thiserror::Error, Debug)]
@@ -42,6 +43,7 @@ pub enum ListAllSoundpacksError {
 /// Lists all available soundpacks (both stock and third-party) for a game variant.
 pub async fn list_all_soundpacks(
   game_variant: &GameVariant,
+  flavor: BuildFlavor,
   data_dir: &Path,
   resource_dir: &Path,
   os: &OS,
@@ -64,6 +66,7 @@ pub async fn list_all_soundpacks(
     let stock_soundpacks_dir = get_stock_soundpacks_dir(
       game_variant,
       &release_version,
+      flavor,
       data_dir,
       os,
     )
@@ -5,6 +5,7 @@ use crate::filesystem::paths::{
 };
 use crate::infra::utils::OS;
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
 
 /// Errors that can occur when determining the stock soundpacks directory.
 #[derive(thiserror::Error, Debug)]
@@ -18,12 +19,18 @@ pub enum GetStockSoundpacksDirError {
 pub async fn get_stock_soundpacks_dir(
   variant: &GameVariant,
   release_version: &str,
+  flavor: BuildFlavor,
   data_dir: &Path,
   os: &OS,
 ) -> Result<PathBuf, GetStockSoundpacksDirError> {
-  let game_resources_dir =
-    get_game_resources_dir(variant, release_version, data_dir, os)
-      .await?;
+  let game_resources_dir = get_game_resources_dir(
+    variant,
+    release_version,
+    flavor,
+    data_dir,
+    os,
+  )
+  .await?;
 
   Ok(game_resources_dir.join("data").join("sound"))
 }
@@ -0,0 +1,73 @@
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, State, command};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+use crate::manual_backups::repository::sqlite_manual_backup_repository::SqliteManualBackupRepository;
+use crate::storage_usage::storage_usage::{
+  GetStorageUsageError, StorageUsageProgress, VariantStorageUsage,
+  get_storage_usage,
+};
+use crate::variants::variant_descriptor::VariantRegistry;
+
+/// Errors that can occur when computing the storage usage via a command.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum StorageUsageCommandError {
+  /// The system's local data directory could not be found.
+  #[error("system directory not found: {0}")]
+  SystemDir(#[from] tauri::Error),
+
+  /// Failed to compute the storage usage.
+  #[error("failed to get storage usage: {0}")]
+  Get(#[from] GetStorageUsageError),
+}
+
+/// Reports the disk space used by each game variant, by category.
+#[command]
+pub async fn get_storage_usage_report(
+  app_handle: AppHandle,
+  backup_repository: State<'_, SqliteBackupRepository>,
+  manual_backup_repository: State<'_, SqliteManualBackupRepository>,
+  variant_registry: State<'_, VariantRegistry>,
+) -> Result<Vec<VariantStorageUsage>, StorageUsageCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+
+  let usage = get_storage_usage(
+    &variant_registry.variants(),
+    &data_dir,
+    &*backup_repository,
+    &*manual_backup_repository,
+    |_| {},
+  )
+  .await?;
+
+  Ok(usage)
+}
+
+/// Like `get_storage_usage_report`, but also sends every measured item
+/// through `on_progress` as soon as it's measured, since measuring large
+/// directories takes a while.
+#[command]
+pub async fn stream_storage_usage_report(
+  app_handle: AppHandle,
+  backup_repository: State<'_, SqliteBackupRepository>,
+  manual_backup_repository: State<'_, SqliteManualBackupRepository>,
+  variant_registry: State<'_, VariantRegistry>,
+  on_progress: Channel<StorageUsageProgress>,
+) -> Result<Vec<VariantStorageUsage>, StorageUsageCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+
+  let usage = get_storage_usage(
+    &variant_registry.variants(),
+    &data_dir,
+    &*backup_repository,
+    &*manual_backup_repository,
+    |progress| {
+      let _ = on_progress.send(progress);
+    },
+  )
+  .await?;
+
+  Ok(usage)
+}
@@ -0,0 +1,3 @@
+pub mod commands;
+#[allow(clippy::module_inception)]
+pub mod storage_usage;
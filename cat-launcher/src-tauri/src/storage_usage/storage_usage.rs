@@ -0,0 +1,483 @@
+use std::cmp::Reverse;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use ts_rs::TS;
+
+use crate::filesystem::paths::{
+  get_asset_download_dir, get_automatic_backup_archive_filepath,
+  get_automatic_backup_manifest_filepath, get_backup_store_dir,
+  get_manual_backup_archive_filepath, get_staging_dir,
+  get_user_game_data_dir,
+};
+use crate::filesystem::utils::{DirSizeError, get_dir_size};
+use crate::launch_game::repository::{
+  BackupRepository, BackupRepositoryError,
+};
+use crate::manual_backups::repository::manual_backup_repository::{
+  ManualBackupRepository, ManualBackupRepositoryError,
+};
+use crate::variants::GameVariant;
+
+/// What disk space used by a game variant is used for.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub enum StorageCategory {
+  /// Installed releases of the game.
+  InstalledReleases,
+  /// Downloaded release archives, including unfinished downloads and
+  /// extractions.
+  Downloads,
+  /// Saved worlds.
+  Saves,
+  /// Third-party mods.
+  Mods,
+  /// Third-party tilesets.
+  Tilesets,
+  /// Third-party soundpacks.
+  Soundpacks,
+  /// Backups made automatically before the game is launched.
+  AutomaticBackups,
+  /// Backups made by the user.
+  ManualBackups,
+}
+
+/// Something that takes up disk space, e.g. a release or a world.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StorageItem {
+  /// The name of the item, e.g. the version of a release or the name of
+  /// a world.
+  pub name: String,
+  /// The size of the item on disk, in bytes.
+  pub size_in_bytes: u64,
+}
+
+/// The disk space used by a game variant for one purpose.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CategoryStorageUsage {
+  /// What the space is used for.
+  pub category: StorageCategory,
+  /// The total size of the items, in bytes.
+  pub size_in_bytes: u64,
+  /// The items, largest first.
+  pub items: Vec<StorageItem>,
+}
+
+/// The disk space used by a game variant.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VariantStorageUsage {
+  /// The game variant.
+  pub variant: GameVariant,
+  /// The total size of everything the variant uses, in bytes.
+  pub size_in_bytes: u64,
+  /// The space used for each category, in the order of
+  /// `StorageCategory`.
+  pub categories: Vec<CategoryStorageUsage>,
+}
+
+/// Reports that one more item has been measured while computing the
+/// storage usage.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StorageUsageProgress {
+  /// The variant the item belongs to.
+  pub variant: GameVariant,
+  /// What the item is used for.
+  pub category: StorageCategory,
+  /// The item that was measured.
+  pub item: StorageItem,
+  /// The number of items measured so far.
+  pub measured_items: u64,
+  /// The number of items to measure in total.
+  pub total_items: u64,
+}
+
+/// Errors that can occur when computing the storage usage.
+#[derive(thiserror::Error, Debug)]
+pub enum GetStorageUsageError {
+  /// Failed to list the contents of a directory.
+  #[error("failed to read directory: {0}")]
+  ReadDir(#[from] io::Error),
+
+  /// Failed to compute the size of an item.
+  #[error("failed to get size: {0}")]
+  Size(#[from] DirSizeError),
+
+  /// Failed to list the automatic backups.
+  #[error("failed to get automatic backups: {0}")]
+  AutomaticBackups(#[from] BackupRepositoryError),
+
+  /// Failed to list the manual backups.
+  #[error("failed to get manual backups: {0}")]
+  ManualBackups(#[from] ManualBackupRepositoryError),
+}
+
+const CATEGORIES: [StorageCategory; 8] = [
+  StorageCategory::InstalledReleases,
+  StorageCategory::Downloads,
+  StorageCategory::Saves,
+  StorageCategory::Mods,
+  StorageCategory::Tilesets,
+  StorageCategory::Soundpacks,
+  StorageCategory::AutomaticBackups,
+  StorageCategory::ManualBackups,
+];
+
+/// An item that is yet to be measured.
+struct StorageTarget {
+  variant: GameVariant,
+  category: StorageCategory,
+  name: String,
+  path: PathBuf,
+}
+
+/// Computes the disk space used by each of `variants` in `data_dir`.
+///
+/// All items are found first, so that `on_progress` can be called with
+/// the total number of items after each one is measured.
+pub async fn get_storage_usage(
+  variants: &[GameVariant],
+  data_dir: &Path,
+  backup_repository: &impl BackupRepository,
+  manual_backup_repository: &impl ManualBackupRepository,
+  on_progress: impl Fn(StorageUsageProgress),
+) -> Result<Vec<VariantStorageUsage>, GetStorageUsageError> {
+  let mut targets = Vec::new();
+  for variant in variants {
+    targets.extend(
+      find_storage_targets(
+        variant,
+        data_dir,
+        backup_repository,
+        manual_backup_repository,
+      )
+      .await?,
+    );
+  }
+
+  let mut usages: Vec<VariantStorageUsage> = variants
+    .iter()
+    .map(|variant| VariantStorageUsage {
+      variant: *variant,
+      size_in_bytes: 0,
+      categories: CATEGORIES
+        .iter()
+        .map(|category| CategoryStorageUsage {
+          category: *category,
+          size_in_bytes: 0,
+          items: Vec::new(),
+        })
+        .collect(),
+    })
+    .collect();
+
+  let total_items = targets.len() as u64;
+  for (index, target) in targets.into_iter().enumerate() {
+    let item = StorageItem {
+      name: target.name,
+      size_in_bytes: get_dir_size(&target.path).await?,
+    };
+
+    if let Some(usage) =
+      usages.iter_mut().find(|u| u.variant == target.variant)
+    {
+      usage.size_in_bytes += item.size_in_bytes;
+      if let Some(category) = usage
+        .categories
+        .iter_mut()
+        .find(|c| c.category == target.category)
+      {
+        category.size_in_bytes += item.size_in_bytes;
+        category.items.push(item.clone());
+      }
+    }
+
+    on_progress(StorageUsageProgress {
+      variant: target.variant,
+      category: target.category,
+      item,
+      measured_items: index as u64 + 1,
+      total_items,
+    });
+  }
+
+  for category in usages.iter_mut().flat_map(|u| &mut u.categories) {
+    category.items.sort_by_key(|i| Reverse(i.size_in_bytes));
+  }
+
+  Ok(usages)
+}
+
+async fn find_storage_targets(
+  variant: &GameVariant,
+  data_dir: &Path,
+  backup_repository: &impl BackupRepository,
+  manual_backup_repository: &impl ManualBackupRepository,
+) -> Result<Vec<StorageTarget>, GetStorageUsageError> {
+  let mut targets = Vec::new();
+  let mut add = |category, entries: Vec<(String, PathBuf)>| {
+    targets.extend(entries.into_iter().map(|(name, path)| {
+      StorageTarget {
+        variant: *variant,
+        category,
+        name,
+        path,
+      }
+    }));
+  };
+
+  // Releases are installed into, and downloaded to, the same directory.
+  let assets_dir = get_asset_download_dir(variant, data_dir);
+  add(
+    StorageCategory::InstalledReleases,
+    list_entries(&assets_dir, true).await?,
+  );
+  add(
+    StorageCategory::Downloads,
+    list_entries(&assets_dir, false).await?,
+  );
+  // Releases that are still being extracted, or whose extraction was
+  // interrupted, are part of their download until they're installed.
+  add(
+    StorageCategory::Downloads,
+    list_entries(&get_staging_dir(variant, data_dir), true).await?,
+  );
+
+  let user_data_dir = get_user_game_data_dir(variant, data_dir);
+  for (category, dir) in [
+    (StorageCategory::Saves, "save"),
+    (StorageCategory::Mods, "mods"),
+    (StorageCategory::Tilesets, "gfx"),
+    (StorageCategory::Soundpacks, "sound"),
+  ] {
+    add(
+      category,
+      list_entries(&user_data_dir.join(dir), true).await?,
+    );
+  }
+
+  // Backup archives are only attributed to their variant in the database.
+  let mut automatic_backups = Vec::new();
+  for backup in backup_repository
+    .get_backups_sorted_by_timestamp(variant)
+    .await?
+  {
+    let manifest_path = get_automatic_backup_manifest_filepath(
+      variant,
+      backup.id,
+      &backup.release_version,
+      backup.timestamp,
+      data_dir,
+    );
+    let path = get_automatic_backup_archive_filepath(
+      variant,
+      backup.id,
+      &backup.release_version,
+      backup.timestamp,
+      data_dir,
+    );
+    if fs::try_exists(&manifest_path).await? {
+      automatic_backups.push((backup.release_version, manifest_path));
+    } else if fs::try_exists(&path).await? {
+      automatic_backups.push((backup.release_version, path));
+    }
+  }
+  // Snapshot content is shared between backups, so the backup store is
+  // measured as a whole.
+  let store_dir = get_backup_store_dir(variant, data_dir);
+  if fs::try_exists(&store_dir).await? {
+    automatic_backups
+      .push(("Shared backup data".to_string(), store_dir));
+  }
+  add(StorageCategory::AutomaticBackups, automatic_backups);
+
+  let mut manual_backups = Vec::new();
+  for backup in manual_backup_repository
+    .get_manual_backups_sorted_by_timestamp(variant)
+    .await?
+  {
+    let path = get_manual_backup_archive_filepath(
+      backup.id,
+      &backup.name,
+      data_dir,
+    );
+    if fs::try_exists(&path).await? {
+      manual_backups.push((backup.name, path));
+    }
+  }
+  add(StorageCategory::ManualBackups, manual_backups);
+
+  Ok(targets)
+}
+
+/// Returns the names and paths of the directories, or of the files, in
+/// `dir`. A directory that doesn't exist is empty.
+async fn list_entries(
+  dir: &Path,
+  directories: bool,
+) -> io::Result<Vec<(String, PathBuf)>> {
+  let mut entries = match fs::read_dir(dir).await {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+      return Ok(Vec::new());
+    }
+    Err(e) => return Err(e),
+  };
+
+  let mut found = Vec::new();
+  while let Some(entry) = entries.next_entry().await? {
+    if entry.file_type().await?.is_dir() == directories {
+      found.push((
+        entry.file_name().to_string_lossy().into_owned(),
+        entry.path(),
+      ));
+    }
+  }
+  found.sort();
+
+  Ok(found)
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use std::sync::Mutex;
+
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::filesystem::paths::{
+    get_manual_backups_dir,
+    get_or_create_automatic_backup_archive_filepath,
+  };
+  use crate::infra::testing::test_database::TestDatabase;
+  use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+  use crate::manual_backups::repository::sqlite_manual_backup_repository::SqliteManualBackupRepository;
+  use crate::variants::variant_descriptor::VariantRegistry;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  fn get_category(
+    usages: &[VariantStorageUsage],
+    variant: GameVariant,
+    category: StorageCategory,
+  ) -> &CategoryStorageUsage {
+    usages
+      .iter()
+      .find(|u| u.variant == variant)
+      .and_then(|u| {
+        u.categories.iter().find(|c| c.category == category)
+      })
+      .expect("category is reported")
+  }
+
+  #[tokio::test]
+  async fn test_get_storage_usage_by_variant_and_category()
+  -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let backup_repo = SqliteBackupRepository::new(db.pool().clone());
+    let manual_backup_repo =
+      SqliteManualBackupRepository::new(db.pool().clone());
+    let temp_data = TempDir::new()?;
+    let data_dir = temp_data.path();
+    let variant = GameVariant::DarkDaysAhead;
+
+    let assets_dir = get_asset_download_dir(&variant, data_dir);
+    fs::create_dir_all(assets_dir.join("v1")).await?;
+    fs::write(assets_dir.join("v1").join("game"), [0u8; 100]).await?;
+    fs::write(assets_dir.join("v2.zip"), [0u8; 10]).await?;
+    let staging_dir = get_staging_dir(&variant, data_dir).join("v2");
+    fs::create_dir_all(&staging_dir).await?;
+    fs::write(staging_dir.join("game"), [0u8; 7]).await?;
+
+    let save_dir =
+      get_user_game_data_dir(&variant, data_dir).join("save");
+    for (world, size) in [("Small", 5), ("Large", 50)] {
+      fs::create_dir_all(save_dir.join(world)).await?;
+      fs::write(
+        save_dir.join(world).join("world.json"),
+        vec![0u8; size],
+      )
+      .await?;
+    }
+
+    let id =
+      backup_repo.add_backup_entry(&variant, "v1", 1000).await?;
+    let backup_path =
+      get_or_create_automatic_backup_archive_filepath(
+        &variant, id, "v1", 1000, data_dir,
+      )
+      .await?;
+    fs::write(&backup_path, [0u8; 20]).await?;
+    // A backup without an archive takes up no space.
+    backup_repo.add_backup_entry(&variant, "v0", 500).await?;
+    manual_backup_repo
+      .add_manual_backup_entry("Missing", &variant, 500, None)
+      .await?;
+
+    let progress = Mutex::new(Vec::new());
+    let usages = get_storage_usage(
+      &VariantRegistry::default().variants(),
+      data_dir,
+      &backup_repo,
+      &manual_backup_repo,
+      |p| progress.lock().unwrap().push(p),
+    )
+    .await?;
+
+    let installed = get_category(
+      &usages,
+      variant,
+      StorageCategory::InstalledReleases,
+    );
+    assert_eq!(installed.size_in_bytes, 100);
+    assert_eq!(installed.items[0].name, "v1");
+
+    let downloads =
+      get_category(&usages, variant, StorageCategory::Downloads);
+    assert_eq!(downloads.size_in_bytes, 17);
+
+    let saves =
+      get_category(&usages, variant, StorageCategory::Saves);
+    assert_eq!(saves.size_in_bytes, 55);
+    assert_eq!(saves.items[0].name, "Large");
+    assert_eq!(saves.items[1].name, "Small");
+
+    let backups = get_category(
+      &usages,
+      variant,
+      StorageCategory::AutomaticBackups,
+    );
+    assert_eq!(backups.size_in_bytes, 20);
+    assert_eq!(backups.items.len(), 1);
+
+    let dda = usages.iter().find(|u| u.variant == variant).unwrap();
+    assert_eq!(dda.size_in_bytes, 192);
+
+    let progress = progress.into_inner().unwrap();
+    assert_eq!(progress.len(), 6);
+    assert!(progress.iter().all(|p| p.total_items == 6));
+    assert_eq!(progress[5].measured_items, 6);
+
+    assert!(
+      !get_manual_backups_dir(data_dir).exists(),
+      "Measuring should not create directories"
+    );
+
+    Ok(())
+  }
+}
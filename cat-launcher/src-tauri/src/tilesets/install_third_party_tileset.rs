@@ -5,14 +5,18 @@ use std::sync::Arc;
 
 use downloader::progress::Reporter;
 use tokio::fs::{create_dir_all, read_to_string};
+use tokio_util::sync::CancellationToken;
 
 use crate::filesystem::paths::{
   GetOrCreateDirectoryError, GetUserGameDataDirError,
   get_or_create_directory, get_or_create_user_game_data_dir,
 };
 use crate::filesystem::utils::{CopyDirError, copy_dir_all};
-use crate::infra::archive::{ExtractionError, extract_archive};
+use crate::infra::archive::{
+  ExtractionError, ExtractionPolicy, extract_archive,
+};
 use crate::infra::download::{DownloadFileError, Downloader};
+use crate::infra::download_queue::DownloadPriority;
 
 use crate::infra::utils::OS;
 use crate::tilesets::paths::get_tilesets_resource_path;
@@ -88,13 +92,22 @@ pub async fn install_third_party_tileset(
     .download_file(
       &tileset_details.installation.download_url,
       &tileset_temp_dir,
-      reporter,
+      DownloadPriority::Normal,
+      reporter.clone(),
     )
     .await?;
 
   let extraction_dir = tileset_temp_dir.join("extracted");
   create_dir_all(&extraction_dir).await?;
-  extract_archive(&downloaded_file, &extraction_dir, os).await?;
+  extract_archive(
+    &downloaded_file,
+    &extraction_dir,
+    os,
+    ExtractionPolicy::UNTRUSTED,
+    reporter,
+    &CancellationToken::new(),
+  )
+  .await?;
 
   let tileset_parent_dir = get_tileset_parent_dir(
     &extraction_dir,
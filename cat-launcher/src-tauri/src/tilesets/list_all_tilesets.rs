@@ -18,6 +18,7 @@ use crate::tilesets::types::{
   StockTileset, ThirdPartyTileset, Tileset,
 };
 use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
 
 /// Errors that can occur when listing all tilesets.
 #[derive(thiserror::Error, Debug)]
@@ -42,6 +43,7 @@ pub enum ListAllTilesetsError {
 /// Lists all available tilesets (both stock and third-party) for a game variant.
 pub async fn list_all_tilesets(
   game_variant: &GameVariant,
+  flavor: BuildFlavor,
   data_dir: &Path,
   resource_dir: &Path,
   os: &OS,
@@ -63,6 +65,7 @@ pub async fn list_all_tilesets(
     let stock_tilesets_dir = get_stock_tilesets_dir(
       game_variant,
       &release_version,
+      flavor,
       data_dir,
       os,
     )
@@ -1,36 +1,67 @@
+use std::env::consts::{ARCH, OS};
 use std::fs;
 use std::io;
+use std::sync::Arc;
 
-use tauri::{App, Emitter, Listener, Manager, WindowEvent};
+use tauri::{
+  App, AppHandle, Emitter, Listener, Manager, WindowEvent,
+};
 
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
-use crate::constants::PARALLEL_REQUESTS;
+use crate::constants::{
+  ORPHAN_MIN_AGE, PARALLEL_REQUESTS, RELEASE_UPDATE_CHECK_INTERVAL,
+};
+use crate::download_mirrors::repository::sqlite_download_mirrors_repository::SqliteDownloadMirrorsRepository;
+use crate::emulated_builds::emulated_builds::{
+  EmulatedBuildsSetting, load_emulated_builds_setting,
+};
+use crate::emulated_builds::repository::sqlite_emulated_builds_repository::SqliteEmulatedBuildsRepository;
 use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
 use crate::filesystem::paths::{get_db_path, get_schema_file_path};
 use crate::filesystem::paths::GetSchemaFilePathError;
 use crate::filesystem::utils::{copy_dir_all, CopyDirError};
+use crate::garbage_collection::garbage_collection::collect_garbage;
+use crate::github_token::repository::GitHubTokenRepository;
+use crate::github_token::repository::sqlite_github_token_repository::SqliteGitHubTokenRepository;
 use crate::infra::autoupdate::update::run_updater;
 use crate::infra::download::Downloader;
+use crate::infra::github::repository::sqlite_github_api_cache_repository::SqliteGitHubApiCacheRepository;
 use crate::infra::http_client::{
   create_http_client, HttpClientError, ReqwestHttpClient,
 };
 use crate::infra::repository::sqlite_pool::{
   CreateSqlitePoolError, create_sqlite_pool,
 };
-use crate::infra::utils::{get_os_enum, OSNotSupportedError};
+use crate::infra::installation_progress_monitor::noop_reporter::NoopReporter;
+use crate::infra::utils::{get_os_enum, HostSystem, OSNotSupportedError};
+use crate::install_release::running_installs::RunningInstalls;
+use crate::installed_releases::repository::sqlite_release_retention_policy_repository::SqliteReleaseRetentionPolicyRepository;
 use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+use crate::launch_game::running_games::RunningGames;
 use crate::manual_backups::repository::sqlite_manual_backup_repository::SqliteManualBackupRepository;
 use crate::mods::lib::OnlineModRepositoryRegistry;
 use crate::mods::online::bright_nights::BrightNightsModRepository;
 use crate::mods::repository::sqlite_installed_mods_repository::SqliteInstalledModsRepository;
 use crate::mods::repository::sqlite_mods_repository::SqliteModsRepository;
+use crate::offline_mode::offline_mode::get_offline_mode;
+use crate::offline_mode::repository::sqlite_offline_mode_repository::SqliteOfflineModeRepository;
 use crate::play_time::sqlite_play_time_repository::SqlitePlayTimeRepository;
+use crate::release_updates::repository::sqlite_release_update_policy_repository::SqliteReleaseUpdatePolicyRepository;
+use crate::sideloaded_releases::repository::sqlite_sideloaded_releases_repository::SqliteSideloadedReleasesRepository;
 use crate::soundpacks::repository::sqlite_installed_soundpacks_repository::SqliteInstalledSoundpacksRepository;
 use crate::theme::sqlite_theme_preference_repository::SqliteThemePreferenceRepository;
 use crate::tilesets::repository::sqlite_installed_tilesets_repository::SqliteInstalledTilesetsRepository;
 use crate::users::repository::sqlite_users_repository::SqliteUsersRepository;
 use crate::users::service::get_or_create_user_id;
+use crate::variants::build_flavor::{
+  BuildFlavorSelection, load_build_flavors,
+};
+use crate::variants::register_game_variant::load_custom_game_variants;
+use crate::variants::repository::sqlite_build_flavor_repository::SqliteBuildFlavorRepository;
 use crate::variants::repository::sqlite_game_variant_order_repository::SqliteGameVariantOrderRepository;
+use crate::variants::repository::sqlite_variant_descriptor_repository::SqliteVariantDescriptorRepository;
+use crate::variants::GameVariant;
+use crate::variants::variant_descriptor::VariantRegistry;
 
 pub fn autoupdate(app: &App) {
   let handle = app.handle();
@@ -43,6 +74,106 @@ pub fn autoupdate(app: &App) {
   });
 }
 
+/// Periodically checks every variant for a newer release and acts on it
+/// according to the variant's update policy.
+pub fn schedule_release_updates(app: &App) {
+  let handle = app.handle();
+  let handle_for_closure = handle.clone();
+  handle.once("frontend-ready", move |_event| {
+    let handle = handle_for_closure.clone();
+    tauri::async_runtime::spawn(async move {
+      loop {
+        let variant_registry = handle.state::<VariantRegistry>();
+        for variant in variant_registry.variants() {
+          run_release_update(&handle, &variant).await;
+        }
+        tokio::time::sleep(RELEASE_UPDATE_CHECK_INTERVAL).await;
+      }
+    });
+  });
+}
+
+async fn run_release_update(
+  handle: &AppHandle,
+  variant: &GameVariant,
+) {
+  let (Ok(data_dir), Ok(resource_dir), Ok(host_system)) = (
+    handle.path().app_local_data_dir(),
+    handle.path().resource_dir(),
+    HostSystem::current(OS, ARCH),
+  ) else {
+    return;
+  };
+
+  let client: tauri::State<ReqwestHttpClient> = handle.state();
+  let downloader: tauri::State<Downloader> = handle.state();
+  let releases_repository: tauri::State<SqliteReleasesRepository> =
+    handle.state();
+  let github_api_cache_repository: tauri::State<
+    SqliteGitHubApiCacheRepository,
+  > = handle.state();
+  let active_release_repository: tauri::State<
+    SqliteActiveReleaseRepository,
+  > = handle.state();
+  let retention_policy_repository: tauri::State<
+    SqliteReleaseRetentionPolicyRepository,
+  > = handle.state();
+  let update_policy_repository: tauri::State<
+    SqliteReleaseUpdatePolicyRepository,
+  > = handle.state();
+  let download_mirrors_repository: tauri::State<
+    SqliteDownloadMirrorsRepository,
+  > = handle.state();
+  let running_games: tauri::State<RunningGames> = handle.state();
+  let emulated_builds: tauri::State<EmulatedBuildsSetting> =
+    handle.state();
+  let build_flavors: tauri::State<BuildFlavorSelection> =
+    handle.state();
+  let variant_registry: tauri::State<VariantRegistry> =
+    handle.state();
+  let Ok(descriptor) = variant_registry.get(variant) else {
+    return;
+  };
+  let running_installs: tauri::State<RunningInstalls> =
+    handle.state();
+  // A release of the variant is already being installed, maybe by the
+  // user, so the update waits until the next check.
+  let Ok(install) = running_installs.start(*variant) else {
+    return;
+  };
+
+  match variant
+    .update_active_release(
+      &descriptor,
+      client.inner(),
+      &downloader,
+      &host_system,
+      emulated_builds.is_allowed(),
+      build_flavors.get(variant),
+      &data_dir,
+      &resource_dir,
+      releases_repository.inner(),
+      github_api_cache_repository.inner(),
+      active_release_repository.inner(),
+      retention_policy_repository.inner(),
+      update_policy_repository.inner(),
+      download_mirrors_repository.inner(),
+      running_games.inner(),
+      install.cancel_token(),
+      Arc::new(NoopReporter),
+    )
+    .await
+  {
+    Ok(Some(update)) => {
+      let _ = handle.emit("release-update", update);
+    }
+    Ok(None) => {}
+    Err(e) => {
+      eprintln!("Failed to update release of {}: {}", variant, e);
+    }
+  }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum RepositoryError {
   #[error("failed to get system directory: {0}")]
@@ -65,11 +196,23 @@ pub fn manage_repositories(app: &App) -> Result<(), RepositoryError> {
   let pool = create_sqlite_pool(&db_path, &[schema_path])?;
 
   app.manage(SqliteReleasesRepository::new(pool.clone()));
+  app.manage(SqliteGitHubApiCacheRepository::new(pool.clone()));
+  app.manage(SqliteGitHubTokenRepository::new(pool.clone()));
+  app.manage(SqliteOfflineModeRepository::new(pool.clone()));
   app.manage(SqliteBackupRepository::new(pool.clone()));
   app.manage(SqliteManualBackupRepository::new(pool.clone()));
   app.manage(SqliteActiveReleaseRepository::new(pool.clone()));
+  app.manage(SqliteSideloadedReleasesRepository::new(pool.clone()));
+  app.manage(SqliteReleaseRetentionPolicyRepository::new(
+    pool.clone(),
+  ));
+  app.manage(SqliteReleaseUpdatePolicyRepository::new(pool.clone()));
   app.manage(SqlitePlayTimeRepository::new(pool.clone()));
   app.manage(SqliteGameVariantOrderRepository::new(pool.clone()));
+  app.manage(SqliteVariantDescriptorRepository::new(pool.clone()));
+  app.manage(SqliteBuildFlavorRepository::new(pool.clone()));
+  app.manage(SqliteEmulatedBuildsRepository::new(pool.clone()));
+  app.manage(SqliteDownloadMirrorsRepository::new(pool.clone()));
   app.manage(SqliteThemePreferenceRepository::new(pool.clone()));
   app.manage(SqliteInstalledModsRepository::new(pool.clone()));
   app.manage(SqliteModsRepository::new(pool.clone()));
@@ -142,15 +285,60 @@ async fn migrate_to_local_data_dir_impl(
   Ok(())
 }
 
+/// Removes what failed installations and backups of earlier sessions left
+/// behind.
+pub fn collect_garbage_at_startup(app: &App) {
+  let handle = app.handle().clone();
+  tauri::async_runtime::spawn(async move {
+    let (Ok(data_dir), Ok(temp_dir), Ok(resource_dir)) = (
+      handle.path().app_local_data_dir(),
+      handle.path().app_cache_dir(),
+      handle.path().resource_dir(),
+    ) else {
+      return;
+    };
+    let releases_repository =
+      handle.state::<SqliteReleasesRepository>();
+    let backup_repository = handle.state::<SqliteBackupRepository>();
+    let manual_backup_repository =
+      handle.state::<SqliteManualBackupRepository>();
+    let variant_registry = handle.state::<VariantRegistry>();
+
+    // Nothing has been queued for download yet.
+    if let Err(e) = collect_garbage(
+      &variant_registry.variants(),
+      &data_dir,
+      &temp_dir,
+      &resource_dir,
+      &[],
+      ORPHAN_MIN_AGE,
+      &*releases_repository,
+      &*backup_repository,
+      &*manual_backup_repository,
+    )
+    .await
+    {
+      eprintln!("Garbage collection failed: {}", e);
+    }
+  });
+}
+
 pub fn manage_downloader(app: &App) {
-  let client: tauri::State<reqwest::Client> = app.state();
-  let downloader = Downloader::new(
-    ReqwestHttpClient::new(client.inner().clone()),
-    PARALLEL_REQUESTS,
-  );
+  // Downloads share the managed client's connectivity state, so that they
+  // also respect offline mode.
+  let client: tauri::State<ReqwestHttpClient> = app.state();
+  let downloader =
+    Downloader::new(client.inner().clone(), PARALLEL_REQUESTS);
   app.manage(downloader);
 }
 
+/// Manages the registry of game installations in progress, through which
+/// they can be cancelled.
+pub fn manage_running_installs(app: &App) {
+  app.manage(RunningInstalls::default());
+  app.manage(RunningGames::default());
+}
+
 pub fn manage_http_client(app: &App) -> Result<(), HttpClientError> {
   let client = create_http_client()?;
   app.manage(client.clone());
@@ -158,6 +346,95 @@ pub fn manage_http_client(app: &App) -> Result<(), HttpClientError> {
   Ok(())
 }
 
+/// Applies the stored GitHub token, if any, to the managed HTTP client.
+pub fn load_github_token(app: &App) {
+  let handle = app.handle().clone();
+  tauri::async_runtime::spawn(async move {
+    let repository: tauri::State<SqliteGitHubTokenRepository> =
+      handle.state();
+    match repository.get_token().await {
+      Ok(Some(token)) => {
+        let client: tauri::State<ReqwestHttpClient> = handle.state();
+        client.set_github_token(Some(token));
+      }
+      Ok(None) => {}
+      Err(e) => eprintln!("Failed to load GitHub token: {}", e),
+    }
+  });
+}
+
+/// Manages the variant registry, registering the stored custom game
+/// variants first. This blocks, so that they are known before anything
+/// refers to them.
+pub fn manage_variant_registry(app: &App) {
+  let registry = VariantRegistry::default();
+  let repository: tauri::State<SqliteVariantDescriptorRepository> =
+    app.state();
+  if let Err(e) = tauri::async_runtime::block_on(
+    load_custom_game_variants(&registry, repository.inner()),
+  ) {
+    eprintln!("Failed to load custom game variants: {}", e);
+  }
+
+  app.manage(registry);
+}
+
+/// Manages the build flavor selection, applying the stored one first. This
+/// blocks, so that releases are never installed or launched in the wrong
+/// flavor.
+pub fn manage_build_flavor_selection(app: &App) {
+  let selection = BuildFlavorSelection::default();
+  let repository: tauri::State<SqliteBuildFlavorRepository> =
+    app.state();
+  if let Err(e) = tauri::async_runtime::block_on(load_build_flavors(
+    &selection,
+    repository.inner(),
+  )) {
+    eprintln!("Failed to load build flavors: {}", e);
+  }
+
+  app.manage(selection);
+}
+
+/// Manages the emulated builds setting, applying the stored one first. This
+/// blocks, so that release assets are never selected before it is known.
+pub fn manage_emulated_builds_setting(app: &App) {
+  let setting = EmulatedBuildsSetting::default();
+  let repository: tauri::State<SqliteEmulatedBuildsRepository> =
+    app.state();
+  if let Err(e) = tauri::async_runtime::block_on(
+    load_emulated_builds_setting(&setting, repository.inner()),
+  ) {
+    eprintln!("Failed to load emulated builds setting: {}", e);
+  }
+
+  app.manage(setting);
+}
+
+/// Applies the stored offline mode setting to the managed HTTP client, then
+/// emits "connectivity-changed" whenever the connectivity state changes.
+pub fn watch_connectivity(app: &App) {
+  let handle = app.handle().clone();
+  tauri::async_runtime::spawn(async move {
+    let client: tauri::State<ReqwestHttpClient> = handle.state();
+    let mut receiver = client.subscribe_to_connectivity();
+
+    let repository: tauri::State<SqliteOfflineModeRepository> =
+      handle.state();
+    match get_offline_mode(repository.inner()).await {
+      Ok(enabled) => client.set_offline_mode(enabled),
+      Err(e) => eprintln!("Failed to load offline mode: {}", e),
+    }
+
+    while receiver.changed().await.is_ok() {
+      let state = *receiver.borrow_and_update();
+      if let Err(e) = handle.emit("connectivity-changed", state) {
+        eprintln!("Failed to emit connectivity state: {}", e);
+      }
+    }
+  });
+}
+
 pub fn manage_posthog(app: &App) {
   let api_key =
     option_env!("VITE_PUBLIC_POSTHOG_KEY").unwrap_or_default();
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoStaticStr};
+use ts_rs::TS;
+
+use crate::infra::utils::HostSystem;
+use crate::variants::GameVariant;
+use crate::variants::repository::build_flavor_repository::{
+  BuildFlavorRepository, BuildFlavorRepositoryError,
+};
+use crate::variants::variant_descriptor::VariantDescriptor;
+
+/// A build flavor of the game. Releases publish a separate asset for each
+/// flavor they are built in.
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Hash,
+  Serialize,
+  Deserialize,
+  TS,
+  Display,
+  EnumString,
+  IntoStaticStr,
+  EnumIter,
+)]
+#[strum(ascii_case_insensitive)]
+#[ts(export)]
+pub enum BuildFlavor {
+  /// The graphical build with sound.
+  #[default]
+  TilesWithSounds,
+  /// The graphical build without sound.
+  Tiles,
+  /// The terminal build, which has to run inside a terminal.
+  Curses,
+}
+
+impl BuildFlavor {
+  /// Returns the suffix of the installation directory of a release in
+  /// this flavor, so that flavors of the same release don't collide.
+  ///
+  /// The default flavor has no suffix, which keeps installations made
+  /// before flavors could be chosen in place.
+  pub fn installation_dir_suffix(&self) -> Option<&'static str> {
+    match self {
+      BuildFlavor::TilesWithSounds => None,
+      BuildFlavor::Tiles => Some("tiles"),
+      BuildFlavor::Curses => Some("curses"),
+    }
+  }
+
+  /// Returns whether the game has to be launched inside a terminal.
+  pub fn needs_terminal(&self) -> bool {
+    matches!(self, BuildFlavor::Curses)
+  }
+}
+
+/// The build flavor selected for each variant. Variants without a selection
+/// use the default flavor.
+#[derive(Default)]
+pub struct BuildFlavorSelection {
+  flavors: RwLock<HashMap<GameVariant, BuildFlavor>>,
+}
+
+impl BuildFlavorSelection {
+  /// Returns the build flavor selected for the variant.
+  pub fn get(&self, variant: &GameVariant) -> BuildFlavor {
+    self
+      .flavors
+      .read()
+      .unwrap_or_else(PoisonError::into_inner)
+      .get(variant)
+      .copied()
+      .unwrap_or_default()
+  }
+
+  /// Selects the build flavor of the variant.
+  pub fn set(&self, variant: &GameVariant, flavor: BuildFlavor) {
+    self
+      .flavors
+      .write()
+      .unwrap_or_else(PoisonError::into_inner)
+      .insert(*variant, flavor);
+  }
+}
+
+/// The build flavors of a variant on the current platform.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct BuildFlavors {
+  /// The flavor releases are installed and launched in.
+  pub selected: BuildFlavor,
+  /// The flavors the variant publishes builds in for this platform.
+  pub available: Vec<BuildFlavor>,
+}
+
+/// Errors that can occur when selecting a build flavor.
+#[derive(thiserror::Error, Debug)]
+pub enum BuildFlavorError {
+  /// The variant publishes no builds in the flavor for this platform.
+  #[error("no {0} builds are available for this platform")]
+  Unavailable(BuildFlavor),
+
+  /// An error occurred in the build flavor repository.
+  #[error("failed to access build flavors: {0}")]
+  Repository(#[from] BuildFlavorRepositoryError),
+}
+
+impl GameVariant {
+  /// Returns the selected and available build flavors of this variant.
+  /// Flavors only published as emulated builds are available if
+  /// `allow_emulated` is set.
+  pub fn get_build_flavors(
+    &self,
+    descriptor: &VariantDescriptor,
+    host_system: &HostSystem,
+    allow_emulated: bool,
+    selection: &BuildFlavorSelection,
+  ) -> BuildFlavors {
+    BuildFlavors {
+      selected: selection.get(self),
+      available: descriptor.flavors(host_system, allow_emulated),
+    }
+  }
+
+  /// Selects the build flavor of this variant and stores it, so that it
+  /// persists across restarts.
+  ///
+  /// Releases that are installed in another flavor are kept, but have to
+  /// be installed again in the new flavor before they can be played.
+  pub async fn set_build_flavor(
+    &self,
+    descriptor: &VariantDescriptor,
+    flavor: BuildFlavor,
+    host_system: &HostSystem,
+    allow_emulated: bool,
+    selection: &BuildFlavorSelection,
+    repository: &impl BuildFlavorRepository,
+  ) -> Result<(), BuildFlavorError> {
+    let available = descriptor.flavors(host_system, allow_emulated);
+    if !available.contains(&flavor) {
+      return Err(BuildFlavorError::Unavailable(flavor));
+    }
+
+    repository.set_build_flavor(self, flavor).await?;
+    selection.set(self, flavor);
+
+    Ok(())
+  }
+}
+
+/// Selects the build flavors stored in the repository.
+pub async fn load_build_flavors(
+  selection: &BuildFlavorSelection,
+  repository: &impl BuildFlavorRepository,
+) -> Result<(), BuildFlavorError> {
+  for (variant, flavor) in repository.get_build_flavors().await? {
+    selection.set(&variant, flavor);
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::infra::testing::test_database::TestDatabase;
+  use crate::infra::utils::{Arch, OS};
+  use crate::variants::repository::sqlite_build_flavor_repository::SqliteBuildFlavorRepository;
+  use crate::variants::variant_descriptor::VariantRegistry;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  const LINUX: HostSystem = HostSystem {
+    os: OS::Linux,
+    arch: Arch::X64,
+  };
+
+  #[tokio::test]
+  async fn test_set_and_load_build_flavor() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo = SqliteBuildFlavorRepository::new(db.pool().clone());
+    let selection = BuildFlavorSelection::default();
+
+    let variant = GameVariant::DarkDaysAhead;
+    let dda = VariantRegistry::default().get(&variant)?;
+    assert_eq!(
+      variant.get_build_flavors(&dda, &LINUX, false, &selection),
+      BuildFlavors {
+        selected: BuildFlavor::TilesWithSounds,
+        available: vec![
+          BuildFlavor::TilesWithSounds,
+          BuildFlavor::Tiles,
+          BuildFlavor::Curses,
+        ],
+      }
+    );
+
+    variant
+      .set_build_flavor(
+        &dda,
+        BuildFlavor::Curses,
+        &LINUX,
+        false,
+        &selection,
+        &repo,
+      )
+      .await?;
+    assert_eq!(selection.get(&variant), BuildFlavor::Curses);
+    assert_eq!(
+      selection.get(&GameVariant::BrightNights),
+      BuildFlavor::TilesWithSounds
+    );
+
+    let loaded_selection = BuildFlavorSelection::default();
+    load_build_flavors(&loaded_selection, &repo).await?;
+    assert_eq!(loaded_selection.get(&variant), BuildFlavor::Curses);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_set_unavailable_build_flavor() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo = SqliteBuildFlavorRepository::new(db.pool().clone());
+    let selection = BuildFlavorSelection::default();
+
+    let variant = GameVariant::BrightNights;
+    let bn = VariantRegistry::default().get(&variant)?;
+    let result = variant
+      .set_build_flavor(
+        &bn,
+        BuildFlavor::Tiles,
+        &LINUX,
+        false,
+        &selection,
+        &repo,
+      )
+      .await;
+
+    assert!(matches!(
+      result,
+      Err(BuildFlavorError::Unavailable(BuildFlavor::Tiles))
+    ));
+    assert!(repo.get_build_flavors().await?.is_empty());
+
+    Ok(())
+  }
+}
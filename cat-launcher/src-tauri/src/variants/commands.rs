@@ -1,11 +1,23 @@
+use std::env::consts::{ARCH, OS};
+
 use tauri::{State, command};
 
 use cat_macros::CommandErrorSerialize;
 
+use crate::emulated_builds::emulated_builds::EmulatedBuildsSetting;
+use crate::infra::utils::{HostSystem, HostSystemError};
+use crate::variants::build_flavor::{
+  BuildFlavor, BuildFlavorError, BuildFlavorSelection, BuildFlavors,
+};
 use crate::variants::get_game_variants_info::{self, GameVariantInfo, GetGameVariantsInfoError};
+use crate::variants::register_game_variant::{self, RegisterGameVariantError};
+use crate::variants::repository::sqlite_build_flavor_repository::SqliteBuildFlavorRepository;
 use crate::variants::repository::sqlite_game_variant_order_repository::SqliteGameVariantOrderRepository;
+use crate::variants::repository::sqlite_variant_descriptor_repository::SqliteVariantDescriptorRepository;
 use crate::variants::update_game_variant_order::{self, UpdateGameVariantOrderError};
 use crate::variants::GameVariant;
+use crate::variants::game_variant::UnknownGameVariantError;
+use crate::variants::variant_descriptor::{VariantDescriptor, VariantRegistry};
 
 /// Errors that can occur when updating the game variant display order.
 #[derive(thiserror::Error, Debug, CommandErrorSerialize)]
@@ -48,11 +60,120 @@ pub async fn get_game_variants_info(
     '_,
     SqliteGameVariantOrderRepository,
   >,
+  variant_registry: State<'_, VariantRegistry>,
 ) -> Result<Vec<GameVariantInfo>, GetGameVariantsInfoCommandError> {
   let res = get_game_variants_info::get_game_variants_info(
+    &variant_registry,
     &*game_variant_order_repository,
   )
   .await?;
 
   Ok(res)
 }
+
+/// Errors that can occur when registering a custom game variant.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum RegisterGameVariantCommandError {
+  /// The descriptor is invalid or could not be stored.
+  #[error("failed to register game variant: {0}")]
+  Register(#[from] RegisterGameVariantError),
+}
+
+/// A Tauri command that registers a custom game variant, e.g. a fork of a
+/// built-in variant, from its descriptor.
+#[command]
+pub async fn register_game_variant(
+  descriptor: VariantDescriptor,
+  variant_descriptor_repository: State<
+    '_,
+    SqliteVariantDescriptorRepository,
+  >,
+  variant_registry: State<'_, VariantRegistry>,
+) -> Result<GameVariantInfo, RegisterGameVariantCommandError> {
+  let name = descriptor.name.clone();
+  let variant = register_game_variant::register_game_variant(
+    descriptor,
+    &variant_registry,
+    &*variant_descriptor_repository,
+  )
+  .await?;
+
+  Ok(GameVariantInfo { id: variant, name })
+}
+
+/// A Tauri command that lists the descriptors of all game variants,
+/// built-in ones first.
+#[command]
+pub fn list_variant_descriptors(
+  variant_registry: State<'_, VariantRegistry>,
+) -> Vec<VariantDescriptor> {
+  variant_registry
+    .descriptors()
+    .into_iter()
+    .map(|d| VariantDescriptor::clone(&d))
+    .collect()
+}
+
+/// Errors that can occur when getting or setting build flavors.
+#[derive(thiserror::Error, Debug, CommandErrorSerialize)]
+pub enum BuildFlavorCommandError {
+  /// The current operating system or architecture is not supported.
+  #[error("failed to determine host system: {0}")]
+  HostSystem(#[from] HostSystemError),
+
+  /// The flavor is unavailable or could not be stored.
+  #[error("failed to set build flavor: {0}")]
+  Set(#[from] BuildFlavorError),
+
+  /// The game variant is not registered.
+  #[error("unknown game variant: {0}")]
+  UnknownVariant(#[from] UnknownGameVariantError),
+}
+
+/// A Tauri command that returns the selected and available build flavors
+/// of a game variant.
+#[command]
+pub fn get_build_flavors(
+  variant: GameVariant,
+  emulated_builds: State<'_, EmulatedBuildsSetting>,
+  build_flavors: State<'_, BuildFlavorSelection>,
+  variant_registry: State<'_, VariantRegistry>,
+) -> Result<BuildFlavors, BuildFlavorCommandError> {
+  let host_system = HostSystem::current(OS, ARCH)?;
+  let descriptor = variant_registry.get(&variant)?;
+
+  Ok(variant.get_build_flavors(
+    &descriptor,
+    &host_system,
+    emulated_builds.is_allowed(),
+    &build_flavors,
+  ))
+}
+
+/// A Tauri command that selects the build flavor releases of a game
+/// variant are installed and launched in.
+#[command]
+pub async fn set_build_flavor(
+  variant: GameVariant,
+  flavor: BuildFlavor,
+  emulated_builds: State<'_, EmulatedBuildsSetting>,
+  build_flavors: State<'_, BuildFlavorSelection>,
+  build_flavor_repository: State<'_, SqliteBuildFlavorRepository>,
+  variant_registry: State<'_, VariantRegistry>,
+) -> Result<(), BuildFlavorCommandError> {
+  let host_system = HostSystem::current(OS, ARCH)?;
+  let descriptor = variant_registry.get(&variant)?;
+
+  variant
+    .set_build_flavor(
+      &descriptor,
+      flavor,
+      &host_system,
+      emulated_builds.is_allowed(),
+      &build_flavors,
+      &*build_flavor_repository,
+    )
+    .await?;
+
+  Ok(())
+}
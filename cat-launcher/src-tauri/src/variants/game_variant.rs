@@ -1,93 +1,155 @@
-use serde::{Deserialize, Serialize};
-use strum::{Display, EnumIter, EnumString, IntoStaticStr};
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use ts_rs::TS;
 
-use crate::game_release::game_release::ReleaseType;
-
-/// Represents the different variants of the game supported by the launcher.
-#[derive(
-  Debug,
-  Display,
-  Clone,
-  Copy,
-  PartialEq,
-  Eq,
-  Hash,
-  EnumIter,
-  Deserialize,
-  Serialize,
-  IntoStaticStr,
-  TS,
-  EnumString,
-)]
-#[non_exhaustive]
-pub enum GameVariant {
+/// The maximum length of the ID of a variant.
+pub const MAX_VARIANT_ID_LEN: usize = 32;
+
+/// Represents a variant of the game supported by the launcher.
+///
+/// A variant is identified by the ID of its
+/// [`VariantDescriptor`](crate::variants::variant_descriptor::VariantDescriptor),
+/// which is looked up in the
+/// [`VariantRegistry`](crate::variants::variant_descriptor::VariantRegistry).
+/// The built-in variants are available as associated constants.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, TS)]
+pub struct GameVariant(#[ts(type = "string")] VariantId);
+
+/// The ID of a variant, stored inline so that variants can be copied.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct VariantId {
+  len: u8,
+  bytes: [u8; MAX_VARIANT_ID_LEN],
+}
+
+#[allow(non_upper_case_globals)]
+impl GameVariant {
   /// Cataclysm: Dark Days Ahead
-  DarkDaysAhead,
+  pub const DarkDaysAhead: GameVariant =
+    GameVariant::builtin("DarkDaysAhead");
   /// Cataclysm: Bright Nights
-  BrightNights,
+  pub const BrightNights: GameVariant =
+    GameVariant::builtin("BrightNights");
   /// The Last Generation
-  TheLastGeneration,
-}
+  pub const TheLastGeneration: GameVariant =
+    GameVariant::builtin("TheLastGeneration");
 
-const BASE_CATEGORIES: &[&str] =
-  &["typeface", "map_typeface", "overmap_typeface"];
-const DDA_CATEGORIES: &[&str] = &[
-  "typeface",
-  "map_typeface",
-  "overmap_typeface",
-  "gui_typeface",
-];
+  /// Creates a built-in variant. The ID must be a valid variant ID.
+  const fn builtin(id: &str) -> Self {
+    let id = id.as_bytes();
+    let mut bytes = [0; MAX_VARIANT_ID_LEN];
+    let mut i = 0;
+    while i < id.len() {
+      bytes[i] = id[i];
+      i += 1;
+    }
 
-impl GameVariant {
-  /// Returns a stable string identifier for the variant.
-  pub fn id(&self) -> &'static str {
-    self.into()
+    GameVariant(VariantId {
+      len: id.len() as u8,
+      bytes,
+    })
   }
+}
+
+/// The error returned when a variant ID is malformed, or refers to a
+/// variant that is not registered.
+#[derive(thiserror::Error, Debug)]
+#[error("unknown game variant: {0}")]
+pub struct UnknownGameVariantError(pub String);
+
+/// Returns whether the string is a well-formed variant ID: non-empty, at
+/// most [`MAX_VARIANT_ID_LEN`] long, and made of ASCII letters and digits.
+pub fn is_valid_variant_id(id: &str) -> bool {
+  !id.is_empty()
+    && id.len() <= MAX_VARIANT_ID_LEN
+    && id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+impl FromStr for GameVariant {
+  type Err = UnknownGameVariantError;
 
-  /// Returns the human-readable name of the variant.
-  pub fn name(&self) -> &'static str {
-    match self {
-      GameVariant::DarkDaysAhead => "Dark Days Ahead",
-      GameVariant::BrightNights => "Bright Nights",
-      GameVariant::TheLastGeneration => "The Last Generation",
+  /// Parses a variant ID. Whether the variant is registered is checked
+  /// when its descriptor is looked up.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if !is_valid_variant_id(s) {
+      return Err(UnknownGameVariantError(s.to_string()));
     }
+
+    let mut bytes = [0; MAX_VARIANT_ID_LEN];
+    bytes[..s.len()].copy_from_slice(s.as_bytes());
+    let len = u8::try_from(s.len())
+      .map_err(|_| UnknownGameVariantError(s.to_string()))?;
+
+    Ok(GameVariant(VariantId { len, bytes }))
   }
+}
 
-  /// Determines the `ReleaseType` based on the tag name and prerelease flag,
-  /// which varies depending on the variant's naming conventions.
-  pub fn determine_release_type(
-    &self,
-    tag_name: &str,
-    prerelease: bool,
-  ) -> ReleaseType {
-    match self {
-      GameVariant::DarkDaysAhead => {
-        if !prerelease {
-          ReleaseType::Stable
-        } else if tag_name.contains("experimental") {
-          ReleaseType::Experimental
-        } else {
-          ReleaseType::ReleaseCandidate
-        }
-      }
-      _ => {
-        if prerelease {
-          ReleaseType::Experimental
-        } else {
-          ReleaseType::Stable
-        }
-      }
-    }
+impl fmt::Display for GameVariant {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.id())
   }
+}
 
-  /// Returns the list of typeface categories supported by this variant.
-  pub fn supported_typeface_categories(
+impl fmt::Debug for GameVariant {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("GameVariant").field(&self.id()).finish()
+  }
+}
+
+impl Serialize for GameVariant {
+  fn serialize<S: Serializer>(
     &self,
-  ) -> &'static [&'static str] {
-    match self {
-      GameVariant::DarkDaysAhead => DDA_CATEGORIES,
-      _ => BASE_CATEGORIES,
-    }
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(self.id())
+  }
+}
+
+impl<'de> Deserialize<'de> for GameVariant {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    let id = String::deserialize(deserializer)?;
+    GameVariant::from_str(&id).map_err(serde::de::Error::custom)
+  }
+}
+
+impl GameVariant {
+  /// Returns a stable string identifier for the variant.
+  pub fn id(&self) -> &str {
+    let VariantId { len, bytes } = &self.0;
+    // Only ASCII IDs are ever stored.
+    std::str::from_utf8(&bytes[..usize::from(*len)])
+      .unwrap_or_default()
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_variant_id() {
+    assert_eq!(
+      GameVariant::from_str("BrightNights").unwrap(),
+      GameVariant::BrightNights
+    );
+    assert_eq!(GameVariant::BrightNights.id(), "BrightNights");
+
+    let fork = GameVariant::from_str("BrightNightsFork").unwrap();
+    assert_eq!(fork.to_string(), "BrightNightsFork");
+
+    assert!(GameVariant::from_str("").is_err());
+    assert!(GameVariant::from_str("../escape").is_err());
+    assert!(GameVariant::from_str(&"a".repeat(33)).is_err());
   }
 }
@@ -1,8 +1,9 @@
-use strum::IntoEnumIterator;
-
 use crate::variants::repository::game_variant_order_repository::GameVariantOrderRepository;
 
 use crate::variants::GameVariant;
+use crate::variants::variant_descriptor::{
+  VariantDescriptor, VariantRegistry,
+};
 use ts_rs::TS;
 
 /// Information about a game variant, suitable for display in the UI.
@@ -16,11 +17,15 @@ pub struct GameVariantInfo {
 }
 
 impl GameVariantInfo {
-  /// Creates a new `GameVariantInfo` from a `GameVariant`.
-  pub fn from_variant(variant: GameVariant) -> Self {
+  /// Creates a new `GameVariantInfo` from a `GameVariant` and its
+  /// descriptor.
+  pub fn new(
+    variant: GameVariant,
+    descriptor: &VariantDescriptor,
+  ) -> Self {
     GameVariantInfo {
       id: variant,
-      name: variant.name().to_string(),
+      name: descriptor.name.clone(),
     }
   }
 }
@@ -33,20 +38,21 @@ pub enum GetGameVariantsInfoError {
     Get(#[from] crate::variants::repository::game_variant_order_repository::GameVariantOrderRepositoryError),
 }
 
-/// Retrieves information for all game variants in their preferred display order.
+/// Retrieves information for all registered game variants in their
+/// preferred display order.
 pub async fn get_game_variants_info(
+  registry: &VariantRegistry,
   game_variant_order_repository: &impl GameVariantOrderRepository,
 ) -> Result<Vec<GameVariantInfo>, GetGameVariantsInfoError> {
   let ordered_variants =
     game_variant_order_repository.get_ordered_variants().await?;
 
   let variants_to_display = if ordered_variants.is_empty() {
-    GameVariant::iter().collect::<Vec<_>>()
+    registry.variants()
   } else {
     // When the order table is partially populated, return ordered variants first
-    // followed by unordered variants in default enum order
-    let all_variants: Vec<GameVariant> =
-      GameVariant::iter().collect();
+    // followed by unordered variants in default order
+    let all_variants = registry.variants();
     let ordered_set: std::collections::HashSet<_> =
       ordered_variants.iter().collect();
     let mut unordered_variants: Vec<GameVariant> = all_variants
@@ -55,14 +61,20 @@ pub async fn get_game_variants_info(
       .collect();
 
     // Maintain order: ordered variants first, then unordered in default order
-    let mut result = ordered_variants;
+    let mut result: Vec<GameVariant> = ordered_variants
+      .into_iter()
+      .filter(|v| registry.get(v).is_ok())
+      .collect();
     result.append(&mut unordered_variants);
     result
   };
 
   let result = variants_to_display
     .into_iter()
-    .map(GameVariantInfo::from_variant)
+    .filter_map(|variant| {
+      let descriptor = registry.get(&variant).ok()?;
+      Some(GameVariantInfo::new(variant, &descriptor))
+    })
     .collect();
   Ok(result)
 }
@@ -71,12 +83,11 @@ pub async fn get_game_variants_info(
 mod tests {
   use std::error::Error;
 
-  use strum::IntoEnumIterator;
-
   use crate::infra::testing::test_database::TestDatabase;
   use crate::variants::repository::game_variant_order_repository::GameVariantOrderRepository;
   use crate::variants::repository::sqlite_game_variant_order_repository::SqliteGameVariantOrderRepository;
   use crate::variants::GameVariant;
+  use crate::variants::variant_descriptor::VariantRegistry;
 
   use super::get_game_variants_info;
 
@@ -85,9 +96,13 @@ mod tests {
   -> Result<(), Box<dyn Error + Send + Sync>> {
     let repository = repository(&TestDatabase::builder().build()?);
 
-    let infos = get_game_variants_info(&repository).await?;
+    let infos = get_game_variants_info(
+      &VariantRegistry::default(),
+      &repository,
+    )
+    .await?;
 
-    assert_infos_eq(infos, GameVariant::iter().collect::<Vec<_>>());
+    assert_infos_eq(infos, VariantRegistry::default().variants());
 
     Ok(())
   }
@@ -106,7 +121,11 @@ mod tests {
       ])
       .await?;
 
-    let infos = get_game_variants_info(&repository).await?;
+    let infos = get_game_variants_info(
+      &VariantRegistry::default(),
+      &repository,
+    )
+    .await?;
 
     assert_infos_eq(
       infos,
@@ -133,7 +152,11 @@ mod tests {
       ])
       .await?;
 
-    let infos = get_game_variants_info(&repository).await?;
+    let infos = get_game_variants_info(
+      &VariantRegistry::default(),
+      &repository,
+    )
+    .await?;
 
     assert_infos_eq(
       infos,
@@ -157,11 +180,12 @@ mod tests {
 
     let actual_names = actual
       .iter()
-      .map(|info| info.name.as_str())
+      .map(|info| info.name.clone())
       .collect::<Vec<_>>();
+    let registry = VariantRegistry::default();
     let expected_names = expected_variants
       .iter()
-      .map(GameVariant::name)
+      .map(|variant| registry.get(variant).unwrap().name.clone())
       .collect::<Vec<_>>();
     assert_eq!(actual_names, expected_names);
   }
@@ -1,7 +1,10 @@
+pub mod build_flavor;
 pub mod commands;
 pub mod game_variant;
 pub mod get_game_variants_info;
+pub mod register_game_variant;
 pub mod repository;
+pub mod variant_descriptor;
 
 pub mod update_game_variant_order;
 
@@ -0,0 +1,160 @@
+use crate::variants::GameVariant;
+use crate::variants::repository::variant_descriptor_repository::{
+  VariantDescriptorRepository, VariantDescriptorRepositoryError,
+};
+use crate::variants::variant_descriptor::{
+  RegisterVariantDescriptorError, VariantDescriptor, VariantRegistry,
+};
+
+/// Errors that can occur when registering custom game variants.
+#[derive(thiserror::Error, Debug)]
+pub enum RegisterGameVariantError {
+  /// The descriptor is invalid.
+  #[error("failed to register variant descriptor: {0}")]
+  Descriptor(#[from] RegisterVariantDescriptorError),
+
+  /// An error occurred in the variant descriptor repository.
+  #[error("failed to access variant descriptors: {0}")]
+  Repository(#[from] VariantDescriptorRepositoryError),
+}
+
+/// Registers a custom game variant and stores its descriptor, so that it
+/// persists across restarts. Registering an existing custom variant again
+/// replaces its descriptor.
+///
+/// The descriptor is validated and stored before it's registered, so that
+/// an invalid descriptor is never stored and a registered variant is never
+/// lost on restart.
+pub async fn register_game_variant(
+  descriptor: VariantDescriptor,
+  registry: &VariantRegistry,
+  repository: &impl VariantDescriptorRepository,
+) -> Result<GameVariant, RegisterGameVariantError> {
+  descriptor.validate()?;
+  repository.save_variant_descriptor(&descriptor).await?;
+  let variant = registry.register(descriptor)?;
+
+  Ok(variant)
+}
+
+/// Registers the custom game variants stored in the repository. Invalid
+/// descriptors are skipped, so that they don't keep the others from being
+/// loaded.
+pub async fn load_custom_game_variants(
+  registry: &VariantRegistry,
+  repository: &impl VariantDescriptorRepository,
+) -> Result<(), RegisterGameVariantError> {
+  for descriptor in repository.get_variant_descriptors().await? {
+    let id = descriptor.id.clone();
+    if let Err(e) = registry.register(descriptor) {
+      eprintln!(
+        "Failed to register custom game variant {}: {}",
+        id, e
+      );
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::infra::testing::test_database::TestDatabase;
+  use crate::variants::repository::sqlite_variant_descriptor_repository::SqliteVariantDescriptorRepository;
+  use crate::variants::variant_descriptor::builtin_descriptors;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  fn fork_descriptor() -> VariantDescriptor {
+    let mut descriptor = builtin_descriptors().remove(1);
+    descriptor.id = "BrightNightsFork".to_string();
+    descriptor.name = "Bright Nights Fork".to_string();
+    descriptor.repo = "someone/Cataclysm-BN-Fork".to_string();
+    descriptor
+  }
+
+  #[tokio::test]
+  async fn test_register_game_variant() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteVariantDescriptorRepository::new(db.pool().clone());
+    let registry = VariantRegistry::default();
+
+    let variant =
+      register_game_variant(fork_descriptor(), &registry, &repo)
+        .await?;
+
+    assert_eq!(variant.id(), "BrightNightsFork");
+    assert_eq!(*registry.get(&variant)?, fork_descriptor());
+    assert_eq!(
+      repo.get_variant_descriptors().await?,
+      vec![fork_descriptor()]
+    );
+
+    let restarted_registry = VariantRegistry::default();
+    load_custom_game_variants(&restarted_registry, &repo).await?;
+    assert_eq!(*restarted_registry.get(&variant)?, fork_descriptor());
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_load_custom_game_variants_skips_invalid_descriptors()
+  -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteVariantDescriptorRepository::new(db.pool().clone());
+    let registry = VariantRegistry::default();
+
+    let mut invalid = fork_descriptor();
+    invalid.id = "InvalidFork".to_string();
+    invalid.repo = "no-owner".to_string();
+    repo.save_variant_descriptor(&invalid).await?;
+    repo.save_variant_descriptor(&fork_descriptor()).await?;
+
+    load_custom_game_variants(&registry, &repo).await?;
+
+    assert!(registry.get(&"InvalidFork".parse()?).is_err());
+    assert_eq!(
+      *registry.get(&"BrightNightsFork".parse()?)?,
+      fork_descriptor()
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_register_game_variant_rejects_builtin_variants()
+  -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteVariantDescriptorRepository::new(db.pool().clone());
+    let registry = VariantRegistry::default();
+
+    let result = register_game_variant(
+      builtin_descriptors().remove(0),
+      &registry,
+      &repo,
+    )
+    .await;
+
+    assert!(matches!(
+      result,
+      Err(RegisterGameVariantError::Descriptor(
+        RegisterVariantDescriptorError::BuiltIn(_)
+      ))
+    ));
+    assert!(repo.get_variant_descriptors().await?.is_empty());
+
+    Ok(())
+  }
+}
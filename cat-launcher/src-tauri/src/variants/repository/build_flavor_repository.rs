@@ -0,0 +1,37 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+
+/// Errors that can occur when interacting with the build flavor repository.
+#[derive(thiserror::Error, Debug)]
+pub enum BuildFlavorRepositoryError {
+  /// An error occurred while retrieving the build flavors.
+  #[error("failed to get build flavors: {0}")]
+  Get(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while setting a build flavor.
+  #[error("failed to set build flavor: {0}")]
+  Set(Box<dyn Error + Send + Sync>),
+}
+
+/// A repository for the build flavor selected for each game variant.
+#[async_trait]
+pub trait BuildFlavorRepository: Send + Sync {
+  /// Retrieves the build flavors of all variants that have one selected.
+  async fn get_build_flavors(
+    &self,
+  ) -> Result<
+    Vec<(GameVariant, BuildFlavor)>,
+    BuildFlavorRepositoryError,
+  >;
+
+  /// Sets the build flavor of the given game variant.
+  async fn set_build_flavor(
+    &self,
+    game_variant: &GameVariant,
+    flavor: BuildFlavor,
+  ) -> Result<(), BuildFlavorRepositoryError>;
+}
@@ -1,2 +1,6 @@
+pub mod build_flavor_repository;
 pub mod game_variant_order_repository;
+pub mod sqlite_build_flavor_repository;
 pub mod sqlite_game_variant_order_repository;
+pub mod sqlite_variant_descriptor_repository;
+pub mod variant_descriptor_repository;
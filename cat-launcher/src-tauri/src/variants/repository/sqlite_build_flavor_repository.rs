@@ -0,0 +1,127 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+
+use crate::infra::repository::db_helper::run_db;
+use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::repository::build_flavor_repository::{
+  BuildFlavorRepository, BuildFlavorRepositoryError,
+};
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+/// A SQLite-backed implementation of the `BuildFlavorRepository`.
+#[derive(Clone)]
+pub struct SqliteBuildFlavorRepository {
+  pool: Pool,
+}
+
+impl SqliteBuildFlavorRepository {
+  /// Creates a new `SqliteBuildFlavorRepository` with the given connection pool.
+  pub fn new(pool: Pool) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl BuildFlavorRepository for SqliteBuildFlavorRepository {
+  async fn get_build_flavors(
+    &self,
+  ) -> Result<
+    Vec<(GameVariant, BuildFlavor)>,
+    BuildFlavorRepositoryError,
+  > {
+    let pool = self.pool.clone();
+
+    let rows: Vec<(String, String)> = run_db(pool, move |conn| {
+      let mut stmt = conn
+        .prepare("SELECT game_variant, flavor FROM build_flavors")?;
+      let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+      Ok::<_, rusqlite::Error>(rows)
+    })
+    .await
+    .map_err(BuildFlavorRepositoryError::Get)?;
+
+    // Selections of variants that are no longer registered are skipped.
+    let flavors = rows
+      .iter()
+      .filter_map(|(variant, flavor)| {
+        Some((
+          GameVariant::from_str(variant).ok()?,
+          BuildFlavor::from_str(flavor).ok()?,
+        ))
+      })
+      .collect();
+
+    Ok(flavors)
+  }
+
+  async fn set_build_flavor(
+    &self,
+    game_variant: &GameVariant,
+    flavor: BuildFlavor,
+  ) -> Result<(), BuildFlavorRepositoryError> {
+    let pool = self.pool.clone();
+    let game_variant = game_variant.to_string();
+    let flavor = flavor.to_string();
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO build_flavors (game_variant, flavor) VALUES (?1, ?2)",
+        [game_variant, flavor],
+      )
+    })
+    .await
+    .map_err(BuildFlavorRepositoryError::Set)?;
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::infra::testing::test_database::TestDatabase;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_set_and_get_build_flavors() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo = SqliteBuildFlavorRepository::new(db.pool().clone());
+
+    assert!(repo.get_build_flavors().await?.is_empty());
+
+    repo
+      .set_build_flavor(
+        &GameVariant::DarkDaysAhead,
+        BuildFlavor::Tiles,
+      )
+      .await?;
+    repo
+      .set_build_flavor(
+        &GameVariant::DarkDaysAhead,
+        BuildFlavor::Curses,
+      )
+      .await?;
+
+    assert_eq!(
+      repo.get_build_flavors().await?,
+      vec![(GameVariant::DarkDaysAhead, BuildFlavor::Curses)]
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,158 @@
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+
+use crate::infra::repository::db_helper::run_db;
+use crate::variants::repository::variant_descriptor_repository::{
+  VariantDescriptorRepository, VariantDescriptorRepositoryError,
+};
+use crate::variants::variant_descriptor::VariantDescriptor;
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+/// A SQLite-backed implementation of the `VariantDescriptorRepository`.
+#[derive(Clone)]
+pub struct SqliteVariantDescriptorRepository {
+  pool: Pool,
+}
+
+impl SqliteVariantDescriptorRepository {
+  /// Creates a new `SqliteVariantDescriptorRepository` with the given connection pool.
+  pub fn new(pool: Pool) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl VariantDescriptorRepository
+  for SqliteVariantDescriptorRepository
+{
+  async fn get_variant_descriptors(
+    &self,
+  ) -> Result<Vec<VariantDescriptor>, VariantDescriptorRepositoryError>
+  {
+    let pool = self.pool.clone();
+
+    let rows: Vec<String> = run_db(pool, move |conn| {
+      let mut stmt = conn.prepare(
+        "SELECT descriptor FROM variant_descriptors ORDER BY rowid",
+      )?;
+      let rows = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+      Ok::<_, rusqlite::Error>(rows)
+    })
+    .await
+    .map_err(VariantDescriptorRepositoryError::Get)?;
+
+    // A descriptor that can't be read, e.g. one stored by a newer
+    // version, shouldn't keep the others from being loaded.
+    let descriptors = rows
+      .iter()
+      .filter_map(|row| {
+        serde_json::from_str::<VariantDescriptor>(row)
+          .inspect_err(|e| {
+            eprintln!("Failed to read variant descriptor: {}", e)
+          })
+          .ok()
+      })
+      .collect();
+
+    Ok(descriptors)
+  }
+
+  async fn save_variant_descriptor(
+    &self,
+    descriptor: &VariantDescriptor,
+  ) -> Result<(), VariantDescriptorRepositoryError> {
+    let pool = self.pool.clone();
+    let id = descriptor.id.clone();
+    let json = serde_json::to_string(descriptor).map_err(|e| {
+      VariantDescriptorRepositoryError::Save(Box::new(e))
+    })?;
+
+    run_db(pool, move |mut conn| {
+      let tx = conn.transaction()?;
+      tx.execute(
+        "INSERT OR IGNORE INTO variants (name) VALUES (?1)",
+        [&id],
+      )?;
+      tx.execute(
+        "INSERT INTO variant_descriptors (game_variant, descriptor)
+         VALUES (?1, ?2)
+         ON CONFLICT(game_variant) DO UPDATE SET descriptor = excluded.descriptor",
+        [&id, &json],
+      )?;
+      tx.commit()
+    })
+    .await
+    .map_err(VariantDescriptorRepositoryError::Save)
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+  use crate::infra::testing::test_database::TestDatabase;
+  use crate::variants::variant_descriptor::builtin_descriptors;
+
+  type TestResult<T = ()> =
+    std::result::Result<T, Box<dyn std::error::Error>>;
+
+  #[tokio::test]
+  async fn test_save_and_get_variant_descriptors() -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteVariantDescriptorRepository::new(db.pool().clone());
+
+    assert!(repo.get_variant_descriptors().await?.is_empty());
+
+    let mut fork = builtin_descriptors().remove(0);
+    fork.id = "DarkDaysAheadFork".to_string();
+    fork.repo = "someone/Cataclysm-DDA".to_string();
+    repo.save_variant_descriptor(&fork).await?;
+    assert_eq!(
+      repo.get_variant_descriptors().await?,
+      vec![fork.clone()]
+    );
+
+    fork.name = "Renamed Fork".to_string();
+    repo.save_variant_descriptor(&fork).await?;
+    assert_eq!(repo.get_variant_descriptors().await?, vec![fork]);
+
+    Ok(())
+  }
+  #[tokio::test]
+  async fn test_get_variant_descriptors_skips_unreadable_rows()
+  -> TestResult {
+    let db = TestDatabase::builder().build()?;
+    let repo =
+      SqliteVariantDescriptorRepository::new(db.pool().clone());
+
+    let mut fork = builtin_descriptors().remove(0);
+    fork.id = "DarkDaysAheadFork".to_string();
+    fork.repo = "someone/Cataclysm-DDA".to_string();
+    repo.save_variant_descriptor(&fork).await?;
+
+    let conn = db.pool().get()?;
+    conn.execute(
+      "INSERT INTO variants (name) VALUES ('BrokenFork')",
+      [],
+    )?;
+    conn.execute(
+      "INSERT INTO variant_descriptors (game_variant, descriptor)
+       VALUES ('BrokenFork', '{\"id\": \"BrokenFork\"}')",
+      [],
+    )?;
+
+    assert_eq!(repo.get_variant_descriptors().await?, vec![fork]);
+
+    Ok(())
+  }
+}
@@ -0,0 +1,34 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use crate::variants::variant_descriptor::VariantDescriptor;
+
+/// Errors that can occur when interacting with the variant descriptor
+/// repository.
+#[derive(thiserror::Error, Debug)]
+pub enum VariantDescriptorRepositoryError {
+  /// An error occurred while retrieving the descriptors.
+  #[error("failed to get variant descriptors: {0}")]
+  Get(Box<dyn Error + Send + Sync>),
+
+  /// An error occurred while storing a descriptor.
+  #[error("failed to save variant descriptor: {0}")]
+  Save(Box<dyn Error + Send + Sync>),
+}
+
+/// A repository for the descriptors of user-registered game variants.
+#[async_trait]
+pub trait VariantDescriptorRepository: Send + Sync {
+  /// Retrieves all stored descriptors.
+  async fn get_variant_descriptors(
+    &self,
+  ) -> Result<Vec<VariantDescriptor>, VariantDescriptorRepositoryError>;
+
+  /// Stores a descriptor and adds its variant to the known variants,
+  /// replacing a stored descriptor with the same ID.
+  async fn save_variant_descriptor(
+    &self,
+    descriptor: &VariantDescriptor,
+  ) -> Result<(), VariantDescriptorRepositoryError>;
+}
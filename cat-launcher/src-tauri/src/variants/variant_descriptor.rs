@@ -0,0 +1,930 @@
+use std::path::{Component, Path};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::game_release::game_release::ReleaseType;
+use crate::infra::utils::{Arch, HostSystem, OS};
+use crate::variants::GameVariant;
+use crate::variants::build_flavor::BuildFlavor;
+use crate::variants::game_variant::{
+  UnknownGameVariantError, is_valid_variant_id,
+};
+
+/// Describes a game variant: where its releases are published and how its
+/// releases are laid out.
+///
+/// The built-in variants are described by default descriptors, and users can
+/// register additional ones, e.g. for forks of a built-in variant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VariantDescriptor {
+  /// The stable identifier of the variant, also used in paths.
+  pub id: String,
+  /// The human-readable name of the variant.
+  pub name: String,
+  /// The GitHub repository releases are fetched from, as `owner/name`.
+  pub repo: String,
+  /// Substrings identifying the release asset for each platform and build
+  /// flavor.
+  pub assets: Vec<AssetPattern>,
+  /// The executable filenames of the game on each OS, in order of
+  /// preference.
+  pub executables: Vec<ExecutableNames>,
+  /// Rules mapping a release to its type. The first matching rule wins.
+  pub release_type_rules: Vec<ReleaseTypeRule>,
+  /// Paths of the tips files, relative to the game's resources directory.
+  pub tips_paths: Vec<String>,
+  /// The font categories the game's font config supports.
+  #[serde(default = "base_typeface_categories")]
+  pub typeface_categories: Vec<String>,
+}
+
+/// The substrings identifying the release asset for a platform and build
+/// flavor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AssetPattern {
+  pub os: OS,
+  /// The architecture this pattern is limited to, if any.
+  pub arch: Option<Arch>,
+  /// The build flavor of the matching asset.
+  #[serde(default)]
+  pub flavor: BuildFlavor,
+  /// An asset matches if its name contains any of these, in order of
+  /// preference.
+  pub substrs: Vec<String>,
+  /// An asset doesn't match if its name contains any of these, e.g. to
+  /// tell a build without sounds apart from the one with sounds.
+  #[serde(default)]
+  pub excludes: Vec<String>,
+  /// Architectures that can't run the asset natively, but can through an
+  /// emulation layer, e.g. ARM64 for an x64 build. Such assets are only
+  /// used if emulated builds are allowed.
+  #[serde(default)]
+  pub emulated_archs: Vec<Arch>,
+}
+
+impl AssetPattern {
+  /// Returns whether the asset with the given name is excluded by this
+  /// pattern.
+  pub fn excludes_asset(&self, asset_name: &str) -> bool {
+    self
+      .excludes
+      .iter()
+      .any(|e| asset_name.contains(e.as_str()))
+  }
+
+  /// Returns whether the matching asset runs natively on the given
+  /// platform.
+  fn is_native(&self, host_system: &HostSystem) -> bool {
+    self.os == host_system.os
+      && self.arch.is_none_or(|arch| arch == host_system.arch)
+  }
+
+  /// Returns whether the matching asset runs on the given platform only
+  /// through an emulation layer.
+  fn is_emulated(&self, host_system: &HostSystem) -> bool {
+    self.os == host_system.os
+      && !self.is_native(host_system)
+      && self.emulated_archs.contains(&host_system.arch)
+  }
+}
+
+/// The executable filenames of the game on an OS.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ExecutableNames {
+  pub os: OS,
+  pub filenames: Vec<String>,
+}
+
+/// Maps releases matching all of the given conditions to a release type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ReleaseTypeRule {
+  /// Matches only releases with this prerelease flag, if set.
+  pub prerelease: Option<bool>,
+  /// Matches only releases whose tag contains this, if set.
+  pub tag_contains: Option<String>,
+  pub release_type: ReleaseType,
+}
+
+impl VariantDescriptor {
+  /// Returns the asset patterns matching the given platform: those of
+  /// native builds first, then those of emulated builds if they are
+  /// allowed.
+  fn platform_asset_patterns(
+    &self,
+    host_system: &HostSystem,
+    allow_emulated: bool,
+  ) -> impl Iterator<Item = &AssetPattern> {
+    let native =
+      self.assets.iter().filter(move |p| p.is_native(host_system));
+    let emulated = self
+      .assets
+      .iter()
+      .filter(move |p| allow_emulated && p.is_emulated(host_system));
+    native.chain(emulated)
+  }
+
+  /// Returns the patterns identifying the release asset for the given
+  /// platform and build flavor, in order of preference.
+  pub fn asset_patterns(
+    &self,
+    host_system: &HostSystem,
+    flavor: BuildFlavor,
+    allow_emulated: bool,
+  ) -> impl Iterator<Item = &AssetPattern> {
+    self
+      .platform_asset_patterns(host_system, allow_emulated)
+      .filter(move |p| p.flavor == flavor)
+  }
+
+  /// Returns the substrings identifying the release asset for the given
+  /// platform and build flavor, in order of preference.
+  pub fn asset_substrs(
+    &self,
+    host_system: &HostSystem,
+    flavor: BuildFlavor,
+    allow_emulated: bool,
+  ) -> Vec<&str> {
+    self
+      .asset_patterns(host_system, flavor, allow_emulated)
+      .flat_map(|p| p.substrs.iter().map(String::as_str))
+      .collect()
+  }
+
+  /// Returns the build flavors published for the given platform, in the
+  /// order of the asset patterns.
+  pub fn flavors(
+    &self,
+    host_system: &HostSystem,
+    allow_emulated: bool,
+  ) -> Vec<BuildFlavor> {
+    let mut flavors = Vec::new();
+    let patterns =
+      self.platform_asset_patterns(host_system, allow_emulated);
+    for pattern in patterns {
+      if !flavors.contains(&pattern.flavor) {
+        flavors.push(pattern.flavor);
+      }
+    }
+    flavors
+  }
+
+  /// Returns the executable filenames of the game on the given OS.
+  pub fn executable_filenames(&self, os: &OS) -> Vec<&str> {
+    self
+      .executables
+      .iter()
+      .find(|e| e.os == *os)
+      .map(|e| e.filenames.iter().map(String::as_str).collect())
+      .unwrap_or_default()
+  }
+
+  /// Determines the release type of a release using the first matching
+  /// rule. Without a matching rule, prereleases are experimental and all
+  /// other releases are stable.
+  pub fn release_type(
+    &self,
+    tag_name: &str,
+    prerelease: bool,
+  ) -> ReleaseType {
+    self
+      .release_type_rules
+      .iter()
+      .find(|rule| {
+        rule.prerelease.is_none_or(|p| p == prerelease)
+          && rule
+            .tag_contains
+            .as_deref()
+            .is_none_or(|s| tag_name.contains(s))
+      })
+      .map(|rule| rule.release_type)
+      .unwrap_or(if prerelease {
+        ReleaseType::Experimental
+      } else {
+        ReleaseType::Stable
+      })
+  }
+
+  /// Checks that the descriptor can be registered as a custom variant.
+  pub fn validate(
+    &self,
+  ) -> Result<(), RegisterVariantDescriptorError> {
+    if builtin_descriptors().iter().any(|d| d.id == self.id) {
+      return Err(RegisterVariantDescriptorError::BuiltIn(
+        self.id.clone(),
+      ));
+    }
+
+    if !is_valid_variant_id(&self.id) {
+      return Err(RegisterVariantDescriptorError::InvalidId(
+        self.id.clone(),
+      ));
+    }
+
+    let is_valid_repo = matches!(
+      self.repo.split_once('/'),
+      Some((owner, name))
+        if !owner.is_empty() && !name.is_empty() && !name.contains('/')
+    );
+    if !is_valid_repo {
+      return Err(RegisterVariantDescriptorError::InvalidRepo(
+        self.repo.clone(),
+      ));
+    }
+
+    let filenames =
+      self.executables.iter().flat_map(|e| &e.filenames);
+    for filename in filenames {
+      if !is_plain_relative_path(filename)
+        || Path::new(filename).components().count() != 1
+      {
+        return Err(RegisterVariantDescriptorError::InvalidPath(
+          filename.clone(),
+        ));
+      }
+    }
+
+    for path in &self.tips_paths {
+      if !is_plain_relative_path(path) {
+        return Err(RegisterVariantDescriptorError::InvalidPath(
+          path.clone(),
+        ));
+      }
+    }
+
+    Ok(())
+  }
+}
+
+fn is_plain_relative_path(path: &str) -> bool {
+  !path.is_empty()
+    && Path::new(path)
+      .components()
+      .all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+  values.iter().map(|v| v.to_string()).collect()
+}
+
+fn asset(
+  os: OS,
+  arch: Option<Arch>,
+  flavor: BuildFlavor,
+  substrs: &[&str],
+) -> AssetPattern {
+  AssetPattern {
+    os,
+    arch,
+    flavor,
+    substrs: strings(substrs),
+    excludes: Vec::new(),
+    emulated_archs: Vec::new(),
+  }
+}
+
+/// Returns a pattern for an x64 build, which ARM64 machines can run through
+/// an emulation layer. The substrings don't name the architecture, so
+/// ARM64 builds of the same release are excluded.
+fn x64_asset(
+  os: OS,
+  flavor: BuildFlavor,
+  substrs: &[&str],
+) -> AssetPattern {
+  AssetPattern {
+    excludes: strings(&["arm64", "aarch64"]),
+    emulated_archs: vec![Arch::ARM64],
+    ..asset(os, Some(Arch::X64), flavor, substrs)
+  }
+}
+
+/// Returns a pattern for a native ARM64 build.
+fn arm64_asset(
+  os: OS,
+  flavor: BuildFlavor,
+  substrs: &[&str],
+) -> AssetPattern {
+  asset(os, Some(Arch::ARM64), flavor, substrs)
+}
+
+/// Returns the pattern for a build without sounds whose name is a prefix
+/// of the name of the build with sounds.
+fn silent(pattern: AssetPattern) -> AssetPattern {
+  let mut excludes = pattern.excludes;
+  excludes.push("sounds".to_string());
+  AssetPattern {
+    flavor: BuildFlavor::Tiles,
+    excludes,
+    ..pattern
+  }
+}
+
+fn executables(windows: &[&str]) -> Vec<ExecutableNames> {
+  vec![
+    ExecutableNames {
+      os: OS::Windows,
+      filenames: strings(windows),
+    },
+    ExecutableNames {
+      os: OS::Linux,
+      filenames: strings(&["cataclysm-launcher"]),
+    },
+    ExecutableNames {
+      os: OS::Mac,
+      filenames: strings(&["Cataclysm.sh"]),
+    },
+  ]
+}
+
+fn prerelease_rule(release_type: ReleaseType) -> ReleaseTypeRule {
+  ReleaseTypeRule {
+    prerelease: Some(true),
+    tag_contains: None,
+    release_type,
+  }
+}
+
+const HINTS_PATH: &str = "data/json/npcs/hints.json";
+
+/// Returns the font categories supported by all variants.
+fn base_typeface_categories() -> Vec<String> {
+  strings(&["typeface", "map_typeface", "overmap_typeface"])
+}
+
+/// Returns the default descriptors of the built-in variants.
+pub fn builtin_descriptors() -> Vec<VariantDescriptor> {
+  vec![
+    VariantDescriptor {
+      id: "DarkDaysAhead".to_string(),
+      name: "Dark Days Ahead".to_string(),
+      repo: "CleverRaven/Cataclysm-DDA".to_string(),
+      assets: vec![
+        x64_asset(
+          OS::Windows,
+          BuildFlavor::TilesWithSounds,
+          &["windows-with-graphics-and-sounds"],
+        ),
+        silent(x64_asset(
+          OS::Windows,
+          BuildFlavor::Tiles,
+          &["windows-with-graphics"],
+        )),
+        x64_asset(
+          OS::Windows,
+          BuildFlavor::Curses,
+          &["windows-terminal-only"],
+        ),
+        // Releases without a graphical macOS build fall back to the
+        // terminal one.
+        asset(
+          OS::Mac,
+          None,
+          BuildFlavor::TilesWithSounds,
+          &["osx-with-graphics", "osx-terminal-only"],
+        ),
+        asset(
+          OS::Mac,
+          None,
+          BuildFlavor::Curses,
+          &["osx-terminal-only"],
+        ),
+        x64_asset(
+          OS::Linux,
+          BuildFlavor::TilesWithSounds,
+          &["linux-with-graphics-and-sounds"],
+        ),
+        silent(x64_asset(
+          OS::Linux,
+          BuildFlavor::Tiles,
+          &["linux-with-graphics"],
+        )),
+        x64_asset(
+          OS::Linux,
+          BuildFlavor::Curses,
+          &["linux-terminal-only"],
+        ),
+        arm64_asset(
+          OS::Linux,
+          BuildFlavor::TilesWithSounds,
+          &["linux-with-graphics-and-sounds-arm64"],
+        ),
+        arm64_asset(
+          OS::Linux,
+          BuildFlavor::Tiles,
+          &["linux-with-graphics-arm64"],
+        ),
+        arm64_asset(
+          OS::Linux,
+          BuildFlavor::Curses,
+          &["linux-terminal-only-arm64"],
+        ),
+      ],
+      executables: executables(&[
+        "cataclysm-tiles.exe",
+        "cataclysm.exe",
+      ]),
+      release_type_rules: vec![
+        ReleaseTypeRule {
+          prerelease: Some(true),
+          tag_contains: Some("experimental".to_string()),
+          release_type: ReleaseType::Experimental,
+        },
+        prerelease_rule(ReleaseType::ReleaseCandidate),
+      ],
+      tips_paths: strings(&["data/core/tips.json", HINTS_PATH]),
+      typeface_categories: strings(&[
+        "typeface",
+        "map_typeface",
+        "overmap_typeface",
+        "gui_typeface",
+      ]),
+    },
+    VariantDescriptor {
+      id: "BrightNights".to_string(),
+      name: "Bright Nights".to_string(),
+      repo: "cataclysmbnteam/Cataclysm-BN".to_string(),
+      assets: vec![
+        x64_asset(
+          OS::Windows,
+          BuildFlavor::TilesWithSounds,
+          &["windows-tiles"],
+        ),
+        asset(
+          OS::Mac,
+          Some(Arch::ARM64),
+          BuildFlavor::TilesWithSounds,
+          &["osx-tiles-arm"],
+        ),
+        x64_asset(
+          OS::Mac,
+          BuildFlavor::TilesWithSounds,
+          &["osx-tiles-x64"],
+        ),
+        x64_asset(
+          OS::Linux,
+          BuildFlavor::TilesWithSounds,
+          &["linux-tiles"],
+        ),
+        x64_asset(OS::Linux, BuildFlavor::Curses, &["linux-curses"]),
+        arm64_asset(
+          OS::Linux,
+          BuildFlavor::TilesWithSounds,
+          &["linux-tiles-arm64"],
+        ),
+        arm64_asset(
+          OS::Linux,
+          BuildFlavor::Curses,
+          &["linux-curses-arm64"],
+        ),
+      ],
+      executables: executables(&[
+        "cataclysm-bn-tiles.exe",
+        "cataclysm-bn.exe",
+      ]),
+      release_type_rules: vec![prerelease_rule(
+        ReleaseType::Experimental,
+      )],
+      tips_paths: strings(&["data/raw/tips.json", HINTS_PATH]),
+      typeface_categories: base_typeface_categories(),
+    },
+    VariantDescriptor {
+      id: "TheLastGeneration".to_string(),
+      name: "The Last Generation".to_string(),
+      repo: "Cataclysm-TLG/Cataclysm-TLG".to_string(),
+      assets: vec![
+        x64_asset(
+          OS::Windows,
+          BuildFlavor::TilesWithSounds,
+          &["windows-tiles-sounds-x64-msvc"],
+        ),
+        x64_asset(
+          OS::Windows,
+          BuildFlavor::Tiles,
+          &["windows-tiles-x64-msvc"],
+        ),
+        x64_asset(
+          OS::Windows,
+          BuildFlavor::Curses,
+          &["windows-curses-x64-msvc"],
+        ),
+        asset(
+          OS::Mac,
+          None,
+          BuildFlavor::TilesWithSounds,
+          &["osx-tiles-universal"],
+        ),
+        asset(
+          OS::Mac,
+          None,
+          BuildFlavor::Curses,
+          &["osx-curses-universal"],
+        ),
+        x64_asset(
+          OS::Linux,
+          BuildFlavor::TilesWithSounds,
+          &["linux-tiles-sounds"],
+        ),
+        silent(x64_asset(
+          OS::Linux,
+          BuildFlavor::Tiles,
+          &["linux-tiles"],
+        )),
+        x64_asset(OS::Linux, BuildFlavor::Curses, &["linux-curses"]),
+        arm64_asset(
+          OS::Linux,
+          BuildFlavor::TilesWithSounds,
+          &["linux-tiles-sounds-arm64"],
+        ),
+        silent(arm64_asset(
+          OS::Linux,
+          BuildFlavor::Tiles,
+          &["linux-tiles-arm64"],
+        )),
+        arm64_asset(
+          OS::Linux,
+          BuildFlavor::Curses,
+          &["linux-curses-arm64"],
+        ),
+      ],
+      executables: executables(&[
+        "cataclysm-tlg-tiles.exe",
+        "cataclysm-tiles.exe",
+        "cataclysm-tlg.exe",
+      ]),
+      release_type_rules: vec![prerelease_rule(
+        ReleaseType::Experimental,
+      )],
+      tips_paths: strings(&["data/core/tips.json", HINTS_PATH]),
+      typeface_categories: base_typeface_categories(),
+    },
+  ]
+}
+
+/// Errors that can occur when registering a variant descriptor.
+#[derive(thiserror::Error, Debug)]
+pub enum RegisterVariantDescriptorError {
+  /// The ID is empty, too long, or contains characters other than ASCII
+  /// letters and digits.
+  #[error("invalid variant ID: {0}")]
+  InvalidId(String),
+
+  /// The ID belongs to a built-in variant.
+  #[error("built-in variant cannot be replaced: {0}")]
+  BuiltIn(String),
+
+  /// The repository is not of the form `owner/name`.
+  #[error("invalid GitHub repository: {0}")]
+  InvalidRepo(String),
+
+  /// A file path is absolute or leaves its base directory.
+  #[error("invalid path: {0}")]
+  InvalidPath(String),
+}
+
+/// The registered variant descriptors, in registration order.
+pub struct VariantRegistry {
+  descriptors: RwLock<Vec<Arc<VariantDescriptor>>>,
+}
+
+impl Default for VariantRegistry {
+  /// Creates a registry containing only the built-in variants.
+  fn default() -> Self {
+    let descriptors =
+      builtin_descriptors().into_iter().map(Arc::new).collect();
+    Self {
+      descriptors: RwLock::new(descriptors),
+    }
+  }
+}
+
+impl VariantRegistry {
+  /// Returns all registered descriptors, built-in ones first.
+  pub fn descriptors(&self) -> Vec<Arc<VariantDescriptor>> {
+    self
+      .descriptors
+      .read()
+      .unwrap_or_else(PoisonError::into_inner)
+      .clone()
+  }
+
+  /// Returns all registered variants, built-in ones first.
+  pub fn variants(&self) -> Vec<GameVariant> {
+    self
+      .descriptors
+      .read()
+      .unwrap_or_else(PoisonError::into_inner)
+      .iter()
+      .filter_map(|d| d.id.parse().ok())
+      .collect()
+  }
+
+  /// Returns the descriptor of a registered variant.
+  pub fn get(
+    &self,
+    variant: &GameVariant,
+  ) -> Result<Arc<VariantDescriptor>, UnknownGameVariantError> {
+    self
+      .descriptors
+      .read()
+      .unwrap_or_else(PoisonError::into_inner)
+      .iter()
+      .find(|d| d.id == variant.id())
+      .cloned()
+      .ok_or_else(|| UnknownGameVariantError(variant.to_string()))
+  }
+
+  /// Validates and registers a descriptor, replacing a previously
+  /// registered custom descriptor with the same ID.
+  pub fn register(
+    &self,
+    descriptor: VariantDescriptor,
+  ) -> Result<GameVariant, RegisterVariantDescriptorError> {
+    descriptor.validate()?;
+    let variant = descriptor.id.parse().map_err(|_| {
+      RegisterVariantDescriptorError::InvalidId(descriptor.id.clone())
+    })?;
+
+    let descriptor = Arc::new(descriptor);
+    let mut descriptors = self
+      .descriptors
+      .write()
+      .unwrap_or_else(PoisonError::into_inner);
+    match descriptors.iter_mut().find(|d| d.id == descriptor.id) {
+      Some(existing) => *existing = descriptor,
+      None => descriptors.push(descriptor),
+    }
+
+    Ok(variant)
+  }
+}
+
+#[cfg(test)]
+#[allow(
+  clippy::panic_in_result_fn,
+  clippy::indexing_slicing,
+  clippy::expect_used,
+  clippy::io_other_error,
+  clippy::unwrap_used
+)]
+mod tests {
+  use super::*;
+
+  fn fork_descriptor(id: &str) -> VariantDescriptor {
+    VariantDescriptor {
+      id: id.to_string(),
+      name: "Fork".to_string(),
+      repo: "someone/Cataclysm-Fork".to_string(),
+      assets: vec![asset(
+        OS::Linux,
+        Some(Arch::X64),
+        BuildFlavor::TilesWithSounds,
+        &["linux-x64"],
+      )],
+      executables: executables(&["cataclysm-fork.exe"]),
+      release_type_rules: vec![ReleaseTypeRule {
+        prerelease: None,
+        tag_contains: Some("nightly".to_string()),
+        release_type: ReleaseType::Experimental,
+      }],
+      tips_paths: strings(&["data/core/tips.json"]),
+      typeface_categories: base_typeface_categories(),
+    }
+  }
+
+  #[test]
+  fn test_release_type_rules() {
+    let dda = &builtin_descriptors()[0];
+    assert_eq!(dda.release_type("0.H", false), ReleaseType::Stable);
+    assert_eq!(
+      dda.release_type("cdda-experimental-2024", true),
+      ReleaseType::Experimental
+    );
+    assert_eq!(
+      dda.release_type("0.H-RC1", true),
+      ReleaseType::ReleaseCandidate
+    );
+
+    let fork = fork_descriptor("Fork");
+    assert_eq!(
+      fork.release_type("nightly-1", false),
+      ReleaseType::Experimental
+    );
+    assert_eq!(fork.release_type("v1", false), ReleaseType::Stable);
+    assert_eq!(
+      fork.release_type("v2", true),
+      ReleaseType::Experimental
+    );
+  }
+
+  #[test]
+  fn test_asset_substrs_respect_arch() {
+    let fork = fork_descriptor("Fork");
+    let linux_x64 = HostSystem {
+      os: OS::Linux,
+      arch: Arch::X64,
+    };
+    let linux_arm = HostSystem {
+      os: OS::Linux,
+      arch: Arch::ARM64,
+    };
+
+    let flavor = BuildFlavor::TilesWithSounds;
+    assert_eq!(
+      fork.asset_substrs(&linux_x64, flavor, false),
+      vec!["linux-x64"]
+    );
+    assert!(fork.asset_substrs(&linux_arm, flavor, false).is_empty());
+    // The fork declares no emulated architectures.
+    assert!(fork.asset_substrs(&linux_arm, flavor, true).is_empty());
+  }
+
+  #[test]
+  fn test_emulated_assets_are_opt_in() {
+    let bn = &builtin_descriptors()[1];
+    let windows_arm = HostSystem {
+      os: OS::Windows,
+      arch: Arch::ARM64,
+    };
+    let linux_arm = HostSystem {
+      os: OS::Linux,
+      arch: Arch::ARM64,
+    };
+    let mac_arm = HostSystem {
+      os: OS::Mac,
+      arch: Arch::ARM64,
+    };
+
+    let flavor = BuildFlavor::TilesWithSounds;
+    assert!(bn.flavors(&windows_arm, false).is_empty());
+    assert!(bn.asset_substrs(&windows_arm, flavor, false).is_empty());
+    assert_eq!(
+      bn.flavors(&windows_arm, true),
+      vec![BuildFlavor::TilesWithSounds]
+    );
+    assert_eq!(
+      bn.asset_substrs(&windows_arm, flavor, true),
+      vec!["windows-tiles"]
+    );
+
+    // Native builds are preferred over emulated ones.
+    assert_eq!(
+      bn.asset_substrs(&linux_arm, flavor, false),
+      vec!["linux-tiles-arm64"]
+    );
+    assert_eq!(
+      bn.asset_substrs(&linux_arm, flavor, true),
+      vec!["linux-tiles-arm64", "linux-tiles"]
+    );
+    assert_eq!(
+      bn.asset_substrs(&mac_arm, flavor, true),
+      vec!["osx-tiles-arm", "osx-tiles-x64"]
+    );
+  }
+
+  #[test]
+  fn test_dda_mac_falls_back_to_terminal_build() {
+    let dda = &builtin_descriptors()[0];
+    let mac = HostSystem {
+      os: OS::Mac,
+      arch: Arch::ARM64,
+    };
+
+    assert_eq!(
+      dda.asset_substrs(&mac, BuildFlavor::TilesWithSounds, false),
+      vec!["osx-with-graphics", "osx-terminal-only"]
+    );
+    assert_eq!(
+      dda.asset_substrs(&mac, BuildFlavor::Curses, false),
+      vec!["osx-terminal-only"]
+    );
+  }
+
+  #[test]
+  fn test_asset_patterns_per_flavor() {
+    let tlg = &builtin_descriptors()[2];
+    let linux = HostSystem {
+      os: OS::Linux,
+      arch: Arch::X64,
+    };
+
+    assert_eq!(
+      tlg.flavors(&linux, false),
+      vec![
+        BuildFlavor::TilesWithSounds,
+        BuildFlavor::Tiles,
+        BuildFlavor::Curses
+      ]
+    );
+    assert_eq!(
+      tlg.asset_substrs(&linux, BuildFlavor::Curses, false),
+      vec!["linux-curses"]
+    );
+
+    let tiles = tlg
+      .asset_patterns(&linux, BuildFlavor::Tiles, false)
+      .next()
+      .unwrap();
+    assert!(
+      tiles
+        .excludes_asset("cataclysmtlg-linux-tiles-sounds-x64.tar.gz")
+    );
+    assert!(
+      !tiles.excludes_asset("cataclysmtlg-linux-tiles-x64.tar.gz")
+    );
+  }
+
+  #[test]
+  fn test_typeface_categories_default_to_base_categories() {
+    let dda = &builtin_descriptors()[0];
+    assert!(dda.typeface_categories.contains(&"gui_typeface".into()));
+
+    let mut json =
+      serde_json::to_value(fork_descriptor("Fork")).unwrap();
+    json.as_object_mut().unwrap().remove("typeface_categories");
+    let fork: VariantDescriptor =
+      serde_json::from_value(json).unwrap();
+    assert_eq!(fork.typeface_categories, base_typeface_categories());
+  }
+
+  #[test]
+  fn test_register_descriptor() {
+    let registry = VariantRegistry::default();
+
+    let variant = registry.register(fork_descriptor("Fork")).unwrap();
+    assert_eq!(variant.id(), "Fork");
+    assert_eq!(
+      *registry.get(&variant).unwrap(),
+      fork_descriptor("Fork")
+    );
+    assert!(
+      VariantRegistry::default().get(&variant).is_err(),
+      "registries should not share descriptors"
+    );
+
+    let mut renamed = fork_descriptor("Fork");
+    renamed.name = "Renamed".to_string();
+    registry.register(renamed).unwrap();
+    assert_eq!(registry.get(&variant).unwrap().name, "Renamed");
+
+    let ids = registry
+      .descriptors()
+      .iter()
+      .map(|d| d.id.clone())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      ids,
+      vec![
+        "DarkDaysAhead",
+        "BrightNights",
+        "TheLastGeneration",
+        "Fork"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_register_descriptor_rejects_invalid_descriptors() {
+    let registry = VariantRegistry::default();
+
+    assert!(matches!(
+      registry.register(fork_descriptor("BrightNights")),
+      Err(RegisterVariantDescriptorError::BuiltIn(_))
+    ));
+    assert!(matches!(
+      registry.register(fork_descriptor("../escape")),
+      Err(RegisterVariantDescriptorError::InvalidId(_))
+    ));
+    assert!(matches!(
+      registry.register(fork_descriptor(&"Fork".repeat(9))),
+      Err(RegisterVariantDescriptorError::InvalidId(_))
+    ));
+
+    let mut descriptor = fork_descriptor("InvalidRepoFork");
+    descriptor.repo = "no-owner".to_string();
+    assert!(matches!(
+      registry.register(descriptor),
+      Err(RegisterVariantDescriptorError::InvalidRepo(_))
+    ));
+
+    let mut descriptor = fork_descriptor("InvalidTipsFork");
+    descriptor.tips_paths = strings(&["../../etc/passwd"]);
+    assert!(matches!(
+      registry.register(descriptor),
+      Err(RegisterVariantDescriptorError::InvalidPath(_))
+    ));
+
+    let mut descriptor = fork_descriptor("InvalidExecutableFork");
+    descriptor.executables = executables(&["bin/cataclysm.exe"]);
+    assert!(matches!(
+      registry.register(descriptor),
+      Err(RegisterVariantDescriptorError::InvalidPath(_))
+    ));
+
+    assert_eq!(registry.descriptors().len(), 3);
+  }
+}
@@ -1,4 +1,5 @@
 import { Progress } from "@/components/ui/progress";
+import type { InstallationPhase } from "@/generated-types/InstallationPhase";
 import { formatBytes } from "@/lib/utils";
 
 /**
@@ -7,14 +8,19 @@ import { formatBytes } from "@/lib/utils";
  * @public
  */
 interface DownloadProgressProps {
-  /** The number of bytes already downloaded. */
+  /** The number of bytes already downloaded or extracted. */
   downloaded: number;
-  /** The total number of bytes to download. If 0, the progress is considered indeterminate. */
+  /** The total number of bytes to download or extract. If 0, the progress is considered indeterminate. */
   total: number;
+  /** Whether the archive is being downloaded or extracted. Defaults to downloading. */
+  phase?: InstallationPhase;
+  /** Details to show while extracting, e.g. how many entries were extracted. */
+  message?: string | null;
 }
 
 /**
- * A component that displays the progress of a file download.
+ * A component that displays the progress of a file download, or of the
+ * extraction that follows it.
  * Handles both determinate and indeterminate (unknown total size) progress states.
  *
  * @param props - The properties for the download progress component.
@@ -25,12 +31,21 @@ interface DownloadProgressProps {
 export function DownloadProgress({
   downloaded,
   total,
+  phase = "Downloading",
+  message,
 }: DownloadProgressProps) {
   // For some downloads, the total size is not known.
   const isIndeterminate = total === 0 && downloaded > 0;
 
   const progress = total > 0 ? (downloaded * 100) / total : 0;
 
+  const label =
+    phase === "Extracting" ? "Extracting..." : "Downloading...";
+  const details = [
+    message,
+    isIndeterminate ? formatBytes(downloaded).join(" ") : null,
+  ].filter(Boolean);
+
   return (
     <Progress
       className={
@@ -40,9 +55,7 @@ export function DownloadProgress({
       }
       value={isIndeterminate ? 0 : progress}
     >
-      {isIndeterminate
-        ? `Downloading... ${formatBytes(downloaded).join(" ")}`
-        : "Downloading..."}
+      {details.length > 0 ? `${label} ${details.join(", ")}` : label}
     </Progress>
   );
 }
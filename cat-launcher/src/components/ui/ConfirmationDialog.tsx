@@ -22,6 +22,8 @@ interface ConfirmationDialogProps {
   onOpenChange: (_open: boolean) => void;
   /** Callback function executed when the user confirms the action. */
   onConfirm: () => void;
+  /** Optional callback executed when the user presses the cancel button. */
+  onCancel?: () => void;
   /** The title of the confirmation dialog. */
   title: string;
   /** A description providing more context about the action being confirmed. */
@@ -45,6 +47,7 @@ export function ConfirmationDialog({
   open,
   onOpenChange,
   onConfirm,
+  onCancel,
   title,
   description,
   confirmText = "Confirm",
@@ -61,7 +64,9 @@ export function ConfirmationDialog({
         {children}
         <DialogFooter>
           <DialogClose asChild>
-            <Button variant="outline">{cancelText}</Button>
+            <Button variant="outline" onClick={onCancel}>
+              {cancelText}
+            </Button>
           </DialogClose>
           <DialogClose asChild>
             <Button onClick={onConfirm}>{confirmText}</Button>
@@ -34,8 +34,8 @@ type InstallationFunction<T> = (
  * @param id - The unique identifier of the component being installed.
  * @param installationFunction - The function that performs the installation and reports progress.
  * @param onSuccess - Optional callback executed upon successful installation.
- * @param onError - Optional callback executed upon installation error.
- * @returns An object containing the install function, installation status, and download progress.
+ * @param onError - Optional callback executed upon installation error, with the ID that failed to install.
+ * @returns An object containing the install functions, installation status, and download progress.
  */
 export function useInstallAndMonitor<T>(
   type: InstallationType,
@@ -43,7 +43,7 @@ export function useInstallAndMonitor<T>(
   id: string | undefined,
   installationFunction: InstallationFunction<T>,
   onSuccess?: (id: string) => void,
-  onError?: (error: Error) => void,
+  onError?: (error: Error, id: string) => void,
 ) {
   const dispatch = useAppDispatch();
 
@@ -52,9 +52,11 @@ export function useInstallAndMonitor<T>(
       return null;
     }
 
-    return state.installationProgress.downloadProgressByVariant[type][
-      variant
-    ][id];
+    return (
+      state.installationProgress.downloadProgressByVariant[type][
+        variant
+      ]?.[id] ?? null
+    );
   });
 
   const installationProgressStatus = useAppSelector((state) => {
@@ -62,9 +64,11 @@ export function useInstallAndMonitor<T>(
       return null;
     }
 
-    return state.installationProgress.installationStatusByVariant[
-      type
-    ][variant][id];
+    return (
+      state.installationProgress.installationStatusByVariant[type][
+        variant
+      ]?.[id] ?? null
+    );
   });
 
   const {
@@ -89,6 +93,7 @@ export function useInstallAndMonitor<T>(
 
   const {
     mutate,
+    mutateAsync,
     isPending: isInstalling,
     reset,
   } = useMutation({
@@ -104,8 +109,10 @@ export function useInstallAndMonitor<T>(
           variant,
           id: itemId,
           progress: {
+            phase: "Downloading",
             bytes_downloaded: 0,
             total_bytes: 0,
+            message: null,
           },
         }),
       );
@@ -126,9 +133,9 @@ export function useInstallAndMonitor<T>(
         }),
       );
     },
-    onError: (e) => {
+    onError: (e, itemId) => {
       if (onError) {
-        onError(e);
+        onError(e, itemId);
       }
     },
   });
@@ -139,6 +146,7 @@ export function useInstallAndMonitor<T>(
 
   return {
     install: mutate,
+    installAsync: mutateAsync,
     isInstalling,
     installationProgressStatus,
     downloadProgress,
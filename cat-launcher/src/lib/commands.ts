@@ -2,24 +2,40 @@ import { Channel, invoke } from "@tauri-apps/api/core";
 import { emit, listen } from "@tauri-apps/api/event";
 
 import type { BackupEntry } from "@/generated-types/BackupEntry";
+import type { BuildFlavor } from "@/generated-types/BuildFlavor";
+import type { BuildFlavors } from "@/generated-types/BuildFlavors";
+import type { ConnectivityState } from "@/generated-types/ConnectivityState";
+import type { DownloadMirror } from "@/generated-types/DownloadMirror";
+import type { DownloadPriority } from "@/generated-types/DownloadPriority";
 import type { DownloadProgress } from "@/generated-types/DownloadProgress";
 import type { GameEvent } from "@/generated-types/GameEvent";
 import type { GameRelease } from "@/generated-types/GameRelease";
 import type { GameReleaseStatus } from "@/generated-types/GameReleaseStatus";
 import type { GameVariant } from "@/generated-types/GameVariant";
 import type { GameVariantInfo } from "@/generated-types/GameVariantInfo";
+import type { GarbageCollectionReport } from "@/generated-types/GarbageCollectionReport";
+import type { GitHubRateLimit } from "@/generated-types/GitHubRateLimit";
+import type { InstalledRelease } from "@/generated-types/InstalledRelease";
 import type { LastModActivity } from "@/generated-types/LastModActivity";
 import type { ManualBackupEntry } from "@/generated-types/ManualBackupEntry";
 import type { ModInstallationStatus } from "@/generated-types/ModInstallationStatus";
 import type { ModsUpdatePayload } from "@/generated-types/ModsUpdatePayload";
+import type { QueuedDownload } from "@/generated-types/QueuedDownload";
+import type { ReleaseChangelog } from "@/generated-types/ReleaseChangelog";
+import type { ReleaseRetentionPolicy } from "@/generated-types/ReleaseRetentionPolicy";
+import type { ReleaseUpdate } from "@/generated-types/ReleaseUpdate";
+import type { ReleaseUpdatePolicy } from "@/generated-types/ReleaseUpdatePolicy";
 import type { ReleasesUpdatePayload } from "@/generated-types/ReleasesUpdatePayload";
 import type { Soundpack } from "@/generated-types/Soundpack";
 import type { SoundpackInstallationStatus } from "@/generated-types/SoundpackInstallationStatus";
+import type { StorageUsageProgress } from "@/generated-types/StorageUsageProgress";
 import type { Theme } from "@/generated-types/Theme";
 import type { ThemePreference } from "@/generated-types/ThemePreference";
 import type { Tileset } from "@/generated-types/Tileset";
 import type { TilesetInstallationStatus } from "@/generated-types/TilesetInstallationStatus";
 import type { UpdateStatus } from "@/generated-types/UpdateStatus";
+import type { VariantDescriptor } from "@/generated-types/VariantDescriptor";
+import type { VariantStorageUsage } from "@/generated-types/VariantStorageUsage";
 
 /**
  * Listens for a request to quit the application.
@@ -52,6 +68,39 @@ export async function listenToReleasesUpdate(
   );
 }
 
+/**
+ * Listens for newer releases found by the update check, whether they were
+ * only announced or already installed.
+ *
+ * @param onUpdate - Callback function that receives the {@link ReleaseUpdate}.
+ * @returns A promise that resolves to an unlisten function.
+ */
+export async function listenToReleaseUpdate(
+  onUpdate: (update: ReleaseUpdate) => void,
+) {
+  return await listen<ReleaseUpdate>("release-update", (event) => {
+    onUpdate(event.payload);
+  });
+}
+
+/**
+ * Listens for changes of the launcher's connectivity state, e.g. when the
+ * network becomes unreachable or offline mode is toggled.
+ *
+ * @param onChange - Callback function that receives the new {@link ConnectivityState}.
+ * @returns A promise that resolves to an unlisten function.
+ */
+export async function listenToConnectivityChanged(
+  onChange: (state: ConnectivityState) => void,
+) {
+  return await listen<ConnectivityState>(
+    "connectivity-changed",
+    (event) => {
+      onChange(event.payload);
+    },
+  );
+}
+
 /**
  * Listens for updates to the available mods.
  *
@@ -128,6 +177,173 @@ export async function fetchGameVariantsInfo(): Promise<
   return response;
 }
 
+/**
+ * Lists the descriptors of all game variants, built-in ones first.
+ *
+ * @returns A promise that resolves to an array of {@link VariantDescriptor}.
+ */
+export async function listVariantDescriptors(): Promise<
+  VariantDescriptor[]
+> {
+  const response = await invoke<VariantDescriptor[]>(
+    "list_variant_descriptors",
+  );
+  return response;
+}
+
+/**
+ * Registers a custom game variant, e.g. a fork of a built-in variant.
+ * Registering an existing custom variant again replaces its descriptor.
+ *
+ * @param descriptor - The descriptor of the variant.
+ * @returns A promise that resolves to the {@link GameVariantInfo} of the registered variant.
+ */
+export async function registerGameVariant(
+  descriptor: VariantDescriptor,
+): Promise<GameVariantInfo> {
+  const response = await invoke<GameVariantInfo>(
+    "register_game_variant",
+    { descriptor },
+  );
+  return response;
+}
+
+/**
+ * Gets the build flavor (e.g. tiles or curses) selected for a game variant,
+ * along with the flavors available on this platform.
+ *
+ * @param variant - The game variant.
+ * @returns A promise that resolves to the {@link BuildFlavors} of the variant.
+ */
+export async function getBuildFlavors(
+  variant: GameVariant,
+): Promise<BuildFlavors> {
+  const response = await invoke<BuildFlavors>("get_build_flavors", {
+    variant,
+  });
+  return response;
+}
+
+/**
+ * Selects the build flavor releases of a game variant are installed and
+ * launched in. Releases installed in another flavor have to be installed
+ * again before they can be played.
+ *
+ * @param variant - The game variant.
+ * @param flavor - The build flavor to select.
+ */
+export async function setBuildFlavor(
+  variant: GameVariant,
+  flavor: BuildFlavor,
+): Promise<void> {
+  await invoke("set_build_flavor", { variant, flavor });
+}
+
+/**
+ * Gets whether builds that run through an emulation layer, e.g. x64 builds
+ * on an ARM64 machine, may be used for releases without a native build.
+ *
+ * @returns A promise that resolves to whether emulated builds are allowed.
+ */
+export async function getEmulatedBuildsAllowed(): Promise<boolean> {
+  return await invoke<boolean>("get_emulated_builds_allowed");
+}
+
+/**
+ * Allows or disallows builds that run through an emulation layer for
+ * releases without a native build.
+ *
+ * @param allowed - Whether emulated builds should be allowed.
+ */
+export async function setEmulatedBuildsAllowed(
+  allowed: boolean,
+): Promise<void> {
+  await invoke("set_emulated_builds_allowed", { allowed });
+}
+
+/**
+ * Lists the mirrors release assets are downloaded from.
+ *
+ * @returns A promise that resolves to the download mirrors.
+ */
+export async function listDownloadMirrors(): Promise<DownloadMirror[]> {
+  return await invoke<DownloadMirror[]>("list_download_mirrors");
+}
+
+/**
+ * Adds a mirror to download release assets from.
+ *
+ * @param urlTemplate - The URL of an asset on the mirror, in which `{repo}`,
+ * `{tag}` and `{asset}` are replaced. Must contain `{asset}`.
+ * @returns A promise that resolves to the added mirror.
+ */
+export async function addDownloadMirror(
+  urlTemplate: string,
+): Promise<DownloadMirror> {
+  return await invoke<DownloadMirror>("add_download_mirror", {
+    urlTemplate,
+  });
+}
+
+/**
+ * Removes a download mirror.
+ *
+ * @param id - The ID of the mirror to remove.
+ */
+export async function removeDownloadMirror(id: number): Promise<void> {
+  await invoke("remove_download_mirror", { id });
+}
+
+/**
+ * Lists the game, mod, tileset and soundpack downloads that are queued,
+ * running or paused, in the order they start in.
+ *
+ * @returns A promise that resolves to the downloads in the queue.
+ */
+export async function listDownloads(): Promise<QueuedDownload[]> {
+  return await invoke<QueuedDownload[]>("list_downloads");
+}
+
+/**
+ * Pauses a download. It continues where it left off once resumed.
+ *
+ * @param id - The ID of the download.
+ */
+export async function pauseDownload(id: number): Promise<void> {
+  await invoke("pause_download", { id });
+}
+
+/**
+ * Puts a paused download back into the queue.
+ *
+ * @param id - The ID of the download.
+ */
+export async function resumeDownload(id: number): Promise<void> {
+  await invoke("resume_download", { id });
+}
+
+/**
+ * Cancels a download. The installation it belongs to fails.
+ *
+ * @param id - The ID of the download.
+ */
+export async function cancelDownload(id: number): Promise<void> {
+  await invoke("cancel_download", { id });
+}
+
+/**
+ * Changes the priority of a download that is waiting for a free slot.
+ *
+ * @param id - The ID of the download.
+ * @param priority - The new priority.
+ */
+export async function setDownloadPriority(
+  id: number,
+  priority: DownloadPriority,
+): Promise<void> {
+  await invoke("set_download_priority", { id, priority });
+}
+
 /**
  * Deletes a backup entry by its unique identifier.
  *
@@ -354,12 +570,33 @@ export async function fetchReleaseNotes(
   return response;
 }
 
+/**
+ * Gets the release notes of every release between two versions of a game variant.
+ *
+ * @param variant - The game variant.
+ * @param fromVersion - One end of the range, e.g. the installed version.
+ * @param toVersion - The other end of the range, e.g. the version to update to.
+ * @returns A promise that resolves to the {@link ReleaseChangelog}, with releases listed newest first.
+ */
+export async function getReleaseChangelog(
+  variant: GameVariant,
+  fromVersion: string,
+  toVersion: string,
+): Promise<ReleaseChangelog> {
+  const response = await invoke<ReleaseChangelog>("get_release_changelog", {
+    variant,
+    fromVersion,
+    toVersion,
+  });
+  return response;
+}
+
 /**
  * Installs a specific release for a game variant, with progress tracking.
  *
  * @param releaseId - The unique identifier of the release to install.
  * @param variant - The game variant.
- * @param onDownloadProgress - Callback function for download progress updates.
+ * @param onDownloadProgress - Callback function for download and extraction progress updates.
  * @returns A promise that resolves to the installed {@link GameRelease}.
  */
 export async function installReleaseForVariant(
@@ -381,6 +618,34 @@ export async function installReleaseForVariant(
   return response;
 }
 
+/**
+ * Cancels the installations of a game variant that are in progress, whether
+ * started by the user or by an automatic update. A cancelled installation
+ * fails once it has removed what it had downloaded and extracted so far.
+ *
+ * @param variant - The game variant.
+ * @returns A promise that resolves to whether any installation was running.
+ */
+export async function cancelInstall(variant: GameVariant): Promise<boolean> {
+  return await invoke<boolean>("cancel_install", { variant });
+}
+
+/**
+ * Checks for a newer release on the variant's update channel without
+ * installing it. Meant to be called before launching, so that the user can
+ * choose whether to install the newer release first.
+ *
+ * @param variant - The game variant.
+ * @returns A promise that resolves to the {@link ReleaseUpdate}, or null if there is none.
+ */
+export async function checkReleaseUpdate(
+  variant: GameVariant,
+): Promise<ReleaseUpdate | null> {
+  return await invoke<ReleaseUpdate | null>("check_release_update", {
+    variant,
+  });
+}
+
 /**
  * Launches the game with the specified release and optional world.
  *
@@ -400,6 +665,30 @@ export async function launchGame(
   });
 }
 
+/**
+ * Installs a release of a game variant from a local archive, e.g. a build
+ * shared on a LAN drive. Supports .zip, .7z, .rar, .dmg and compressed
+ * tarballs.
+ *
+ * @param variant - The game variant.
+ * @param archivePath - The path of the archive.
+ * @param version - The version label of the release. Inferred from the archive's name if omitted.
+ * @returns A promise that resolves to the sideloaded {@link GameRelease}.
+ */
+export async function sideloadRelease(
+  variant: GameVariant,
+  archivePath: string,
+  version?: string,
+): Promise<GameRelease> {
+  const response = await invoke<GameRelease>("sideload_release", {
+    variant,
+    archivePath,
+    version: version ?? null,
+  });
+
+  return response;
+}
+
 /**
  * Gets the installation status of a specific release for a game variant.
  *
@@ -422,6 +711,207 @@ export async function getInstallationStatus(
   return response;
 }
 
+/**
+ * Lists the installed releases of a game variant along with their size on disk.
+ *
+ * @param variant - The game variant.
+ * @returns A promise that resolves to an array of {@link InstalledRelease}, newest first.
+ */
+export async function listInstalledReleases(
+  variant: GameVariant,
+): Promise<InstalledRelease[]> {
+  const response = await invoke<InstalledRelease[]>(
+    "list_installed_releases",
+    {
+      variant,
+    },
+  );
+
+  return response;
+}
+
+/**
+ * Uninstalls a release of a game variant. The active release cannot be uninstalled.
+ *
+ * @param variant - The game variant.
+ * @param releaseId - The unique identifier of the release to uninstall.
+ */
+export async function uninstallRelease(
+  variant: GameVariant,
+  releaseId: string,
+): Promise<void> {
+  await invoke("uninstall_release", {
+    variant,
+    releaseId,
+  });
+}
+
+/**
+ * Reports the disk space used by each game variant, broken down by category:
+ * installed releases, downloads, saves, mods, tilesets, soundpacks and backups.
+ *
+ * @param onProgress - Optional callback that receives every item as soon as it
+ * has been measured, for showing progress while large directories are walked.
+ * @returns A promise that resolves to the {@link VariantStorageUsage} of every variant.
+ */
+export async function getStorageUsageReport(
+  onProgress?: (progress: StorageUsageProgress) => void,
+): Promise<VariantStorageUsage[]> {
+  if (!onProgress) {
+    return await invoke<VariantStorageUsage[]>("get_storage_usage_report");
+  }
+
+  const channel = new Channel<StorageUsageProgress>();
+  channel.onmessage = onProgress;
+
+  return await invoke<VariantStorageUsage[]>("stream_storage_usage_report", {
+    onProgress: channel,
+  });
+}
+
+/**
+ * Removes what failed installations and backups left behind: downloaded
+ * release archives, temporary mod, tileset and soundpack installation
+ * directories, backup records without an archive and backup archives without
+ * a record. Anything that may still be in use is kept.
+ *
+ * @returns A promise that resolves to a {@link GarbageCollectionReport} of what was removed.
+ */
+export async function runGarbageCollection(): Promise<GarbageCollectionReport> {
+  return await invoke<GarbageCollectionReport>("run_garbage_collection");
+}
+
+/**
+ * Gets whether older releases of a game variant are pruned after installing a new one.
+ *
+ * @param variant - The game variant.
+ * @returns A promise that resolves to the {@link ReleaseRetentionPolicy}.
+ */
+export async function getReleaseRetentionPolicy(
+  variant: GameVariant,
+): Promise<ReleaseRetentionPolicy> {
+  const response = await invoke<ReleaseRetentionPolicy>(
+    "get_release_retention_policy",
+    {
+      variant,
+    },
+  );
+
+  return response;
+}
+
+/**
+ * Sets whether older releases of a game variant are pruned after installing a new one.
+ *
+ * @param variant - The game variant.
+ * @param policy - The retention policy to apply.
+ */
+export async function setReleaseRetentionPolicy(
+  variant: GameVariant,
+  policy: ReleaseRetentionPolicy,
+): Promise<void> {
+  await invoke("set_release_retention_policy", {
+    variant,
+    policy,
+  });
+}
+
+/**
+ * Gets whether and how a game variant is updated to newer releases.
+ *
+ * @param variant - The game variant.
+ * @returns A promise that resolves to the {@link ReleaseUpdatePolicy}.
+ */
+export async function getReleaseUpdatePolicy(
+  variant: GameVariant,
+): Promise<ReleaseUpdatePolicy> {
+  const response = await invoke<ReleaseUpdatePolicy>(
+    "get_release_update_policy",
+    {
+      variant,
+    },
+  );
+
+  return response;
+}
+
+/**
+ * Sets whether and how a game variant is updated to newer releases.
+ *
+ * @param variant - The game variant.
+ * @param policy - The update policy to apply.
+ */
+export async function setReleaseUpdatePolicy(
+  variant: GameVariant,
+  policy: ReleaseUpdatePolicy,
+): Promise<void> {
+  await invoke("set_release_update_policy", {
+    variant,
+    policy,
+  });
+}
+
+/**
+ * Validates and stores a GitHub token, which raises the GitHub API rate limit.
+ * The token is only sent to api.github.com.
+ *
+ * @param token - The GitHub personal access token.
+ * @returns A promise that resolves to the {@link GitHubRateLimit} of the token.
+ */
+export async function setGitHubToken(
+  token: string,
+): Promise<GitHubRateLimit> {
+  const response = await invoke<GitHubRateLimit>("set_github_token", {
+    token,
+  });
+
+  return response;
+}
+
+/**
+ * Validates the stored GitHub token.
+ *
+ * @returns A promise that resolves to the {@link GitHubRateLimit} of the token.
+ */
+export async function validateGitHubToken(): Promise<GitHubRateLimit> {
+  const response = await invoke<GitHubRateLimit>(
+    "validate_github_token",
+  );
+
+  return response;
+}
+
+/**
+ * Removes the stored GitHub token.
+ */
+export async function clearGitHubToken(): Promise<void> {
+  await invoke("clear_github_token");
+}
+
+/**
+ * Gets whether the launcher is online, can't reach the network, or is in offline mode.
+ *
+ * @returns A promise that resolves to the current {@link ConnectivityState}.
+ */
+export async function getConnectivityState(): Promise<ConnectivityState> {
+  return await invoke<ConnectivityState>("get_connectivity_state");
+}
+
+/**
+ * Enables or disables offline mode. While it is enabled, no network requests
+ * are made and only cached or installed content is shown.
+ *
+ * @param enabled - Whether offline mode should be enabled.
+ * @returns A promise that resolves to the resulting {@link ConnectivityState}.
+ */
+export async function setOfflineMode(
+  enabled: boolean,
+): Promise<ConnectivityState> {
+  return await invoke<ConnectivityState>("set_offline_mode", {
+    enabled,
+  });
+}
+
 /**
  * Gets the name of the last played world for a specific variant.
  *
@@ -492,7 +982,7 @@ export async function triggerFetchModsForVariant(
  *
  * @param modId - The unique identifier of the mod to install.
  * @param variant - The game variant.
- * @param onDownloadProgress - Callback function for download progress updates.
+ * @param onDownloadProgress - Callback function for download and extraction progress updates.
  */
 export async function installThirdPartyMod(
   modId: string,
@@ -592,7 +1082,7 @@ export async function listAllTilesets(
  *
  * @param tilesetId - The unique identifier of the tileset to install.
  * @param variant - The game variant.
- * @param onDownloadProgress - Callback function for download progress updates.
+ * @param onDownloadProgress - Callback function for download and extraction progress updates.
  */
 export async function installThirdPartyTileset(
   tilesetId: string,
@@ -671,7 +1161,7 @@ export async function listAllSoundpacks(
  *
  * @param soundpackId - The unique identifier of the soundpack to install.
  * @param variant - The game variant.
- * @param onDownloadProgress - Callback function for download progress updates.
+ * @param onDownloadProgress - Callback function for download and extraction progress updates.
  */
 export async function installThirdPartySoundpack(
   soundpackId: string,
@@ -1,4 +1,5 @@
 import type { DownloadProgress } from "@/generated-types/DownloadProgress";
+import type { InstallationPhase } from "@/generated-types/InstallationPhase";
 
 /**
  * A serializable version of the {@link DownloadProgress} interface.
@@ -6,13 +7,21 @@ import type { DownloadProgress } from "@/generated-types/DownloadProgress";
  */
 export interface SerializableDownloadProgress {
   /**
-   * The number of bytes downloaded so far.
+   * Whether the archive is being downloaded or extracted.
+   */
+  phase: InstallationPhase;
+  /**
+   * The number of bytes downloaded or extracted so far.
    */
   bytes_downloaded: number;
   /**
-   * The total number of bytes to download.
+   * The total number of bytes to download or extract.
    */
   total_bytes: number;
+  /**
+   * While extracting, how many entries were extracted.
+   */
+  message: string | null;
 }
 
 /**
@@ -25,7 +34,9 @@ export function toSerializableDownloadProgress(
   progress: DownloadProgress,
 ): SerializableDownloadProgress {
   return {
+    phase: progress.phase,
     bytes_downloaded: Number(progress.bytes_downloaded),
     total_bytes: Number(progress.total_bytes),
+    message: progress.message,
   };
 }
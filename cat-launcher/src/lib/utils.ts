@@ -30,6 +30,8 @@ export function getVariantLabel(variant: GameVariant): string {
       return "Bright Nights";
     case "TheLastGeneration":
       return "The Last Generation";
+    default:
+      return variant;
   }
 }
 
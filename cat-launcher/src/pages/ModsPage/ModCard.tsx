@@ -151,11 +151,14 @@ export default function ModCard({ variant, mod }: ModCardProps) {
       </CardContent>
       <CardFooter className="flex flex-col gap-4 items-stretch">
         {isThirdParty ? (
-          modInstallationProgress === "Downloading" &&
+          (modInstallationProgress === "Downloading" ||
+            modInstallationProgress === "Extracting") &&
           modDownloadProgress ? (
             <DownloadProgress
               downloaded={modDownloadProgress.bytes_downloaded}
               total={modDownloadProgress.total_bytes}
+              phase={modDownloadProgress.phase}
+              message={modDownloadProgress.message}
             />
           ) : isInstalled ? (
             <Button
@@ -174,9 +177,11 @@ export default function ModCard({ variant, mod }: ModCardProps) {
             >
               {modInstallationProgress === "Installing"
                 ? "Installing..."
-                : modInstallationProgress === "Downloading"
-                  ? "Downloading..."
-                  : "Install"}
+                : modInstallationProgress === "Extracting"
+                  ? "Extracting..."
+                  : modInstallationProgress === "Downloading"
+                    ? "Downloading..."
+                    : "Install"}
             </Button>
           )
         ) : (
@@ -56,7 +56,10 @@ export function useMods(
       );
 
       if (payload.variant === variant) {
-        if (payload.status === "Success") {
+        if (
+          payload.status === "Success" ||
+          payload.status === "Offline"
+        ) {
           setFetchStatus("success");
         } else if (payload.status === "Error") {
           setFetchStatus("error");
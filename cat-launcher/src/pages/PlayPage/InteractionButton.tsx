@@ -1,6 +1,9 @@
+import { useState } from "react";
+
 import { DownloadProgress } from "@/components/DownloadProgress";
 import { DropdownButton } from "@/components/DropdownButton";
 import { Button } from "@/components/ui/button";
+import { ConfirmationDialog } from "@/components/ui/ConfirmationDialog";
 import {
   Tooltip,
   TooltipContent,
@@ -8,6 +11,7 @@ import {
 } from "@/components/ui/tooltip";
 import type { GameReleaseStatus } from "@/generated-types/GameReleaseStatus";
 import type { GameVariant } from "@/generated-types/GameVariant";
+import type { ReleaseUpdate } from "@/generated-types/ReleaseUpdate";
 import { toastCL } from "@/lib/utils";
 import { useAppSelector } from "@/store/hooks";
 import { InstallationProgressStatus } from "@/store/installationProgressSlice";
@@ -15,6 +19,7 @@ import {
   useInstallAndMonitorRelease,
   useInstallationStatus,
   usePlayGame,
+  useReleaseUpdateCheck,
   useResumeLastWorld,
   useUpgradeInfo,
 } from "./hooks";
@@ -22,7 +27,8 @@ import {
 /**
  * Component that renders the primary interaction buttons for a game variant.
  * Handles Play, Install, Resume, and Upgrade actions based on the current
- * installation and running status of the selected release.
+ * installation and running status of the selected release. Before the active
+ * release is launched, the user is offered to install a newer release first.
  *
  * @param props - Component properties.
  * @returns A React element containing the interaction buttons.
@@ -38,8 +44,12 @@ export default function InteractionButton({
   const isThisVariantRunning = currentlyPlaying === variant;
   const isAnyVariantRunning = currentlyPlaying !== null;
 
-  const { install, installationProgressStatus, downloadProgress } =
-    useInstallAndMonitorRelease(variant, selectedReleaseId);
+  const {
+    install,
+    installAsync,
+    installationProgressStatus,
+    downloadProgress,
+  } = useInstallAndMonitorRelease(variant, selectedReleaseId);
 
   const { latestReleaseId, upgradeOptions, shouldAllowUpgrading } =
     useUpgradeInfo(
@@ -67,8 +77,45 @@ export default function InteractionButton({
       toastCL("error", "Failed to get last played world.", e);
     },
   });
+  const { checkForUpdate, isCheckingForUpdate } =
+    useReleaseUpdateCheck(variant);
+  const [pendingUpdate, setPendingUpdate] = useState<{
+    update: ReleaseUpdate;
+    launch: (releaseId: string) => void;
+  } | null>(null);
+
   const isStartingGame =
-    isStartingGameFromPlay || isStartingGameFromResume;
+    isStartingGameFromPlay ||
+    isStartingGameFromResume ||
+    isCheckingForUpdate;
+
+  const launchOrOfferUpdate = async (
+    releaseId: string | undefined,
+    launch: (releaseId: string | undefined) => void,
+  ) => {
+    const update = releaseId ? await checkForUpdate(releaseId) : null;
+    if (update) {
+      setPendingUpdate({ update, launch });
+    } else {
+      launch(releaseId);
+    }
+  };
+
+  const installUpdateAndLaunch = async () => {
+    if (!pendingUpdate) {
+      return;
+    }
+
+    const { update, launch } = pendingUpdate;
+    setSelectedReleaseId(update.version);
+    try {
+      await installAsync(update.version);
+    } catch {
+      // The installation error is already reported.
+      return;
+    }
+    launch(update.version);
+  };
 
   const actionButtonLabel = getActionButtonLabel(
     selectedReleaseId,
@@ -83,17 +130,23 @@ export default function InteractionButton({
     installationStatus === "Unknown" ||
     installationStatus === "NotAvailable" ||
     installationProgressStatus === "Downloading" ||
+    installationProgressStatus === "Extracting" ||
     installationProgressStatus === "Installing" ||
     // Only one variant should be running at a time.
     // Disable button if any variant is already running.
     isAnyVariantRunning ||
     isStartingGame;
 
-  if (installationProgressStatus === "Downloading") {
+  if (
+    installationProgressStatus === "Downloading" ||
+    installationProgressStatus === "Extracting"
+  ) {
     return (
       <DownloadProgress
         downloaded={downloadProgress?.bytes_downloaded ?? 0}
         total={downloadProgress?.total_bytes ?? 0}
+        phase={downloadProgress?.phase}
+        message={downloadProgress?.message}
       />
     );
   }
@@ -104,7 +157,7 @@ export default function InteractionButton({
         className="grow"
         onClick={() =>
           installationStatus === "ReadyToPlay"
-            ? play(selectedReleaseId)
+            ? launchOrOfferUpdate(selectedReleaseId, play)
             : selectedReleaseId && install(selectedReleaseId)
         }
         disabled={isActionButtonDisabled}
@@ -114,7 +167,7 @@ export default function InteractionButton({
       {selectedReleaseId && installationStatus === "ReadyToPlay" && (
         <Button
           className="grow"
-          onClick={() => resume(selectedReleaseId)}
+          onClick={() => launchOrOfferUpdate(selectedReleaseId, resume)}
           disabled={isActionButtonDisabled || !lastPlayedWorld}
         >
           Resume
@@ -136,9 +189,43 @@ export default function InteractionButton({
           Upgrade
         </DropdownButton>
       )}
+      {pendingUpdate && (
+        <ConfirmationDialog
+          open
+          onOpenChange={(open) => {
+            if (!open) {
+              setPendingUpdate(null);
+            }
+          }}
+          onConfirm={installUpdateAndLaunch}
+          onCancel={() =>
+            pendingUpdate.launch(pendingUpdate.update.previous_version)
+          }
+          title="Update Available"
+          description={`${pendingUpdate.update.version} is available. Do you want to install it before playing?`}
+          confirmText="Update and Play"
+          cancelText={`Play ${pendingUpdate.update.previous_version}`}
+        />
+      )}
     </div>
   );
 
+  if (installationStatus === "Corrupted") {
+    return (
+      <Tooltip>
+        <TooltipTrigger asChild>
+          <span className="w-full">{button}</span>
+        </TooltipTrigger>
+        <TooltipContent>
+          <p>
+            The last download of this release was corrupted. It will be
+            downloaded again.
+          </p>
+        </TooltipContent>
+      </Tooltip>
+    );
+  }
+
   if (installationStatus === "NotAvailable") {
     return (
       <Tooltip>
@@ -197,6 +284,10 @@ function getActionButtonLabel(
     return "Downloading...";
   }
 
+  if (installationProgressStatus === "Extracting") {
+    return "Extracting...";
+  }
+
   if (installationProgressStatus === "Installing") {
     return "Installing...";
   }
@@ -213,5 +304,9 @@ function getActionButtonLabel(
     return "Not Available";
   }
 
+  if (installationStatus === "Corrupted") {
+    return "Retry Download";
+  }
+
   return "Install";
 }
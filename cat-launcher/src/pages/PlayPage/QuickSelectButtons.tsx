@@ -1,6 +1,9 @@
 import { Button } from "@/components/ui/button";
 import type { GameVariant } from "@/generated-types/GameVariant";
-import { QUICK_SELECT_BUTTONS } from "./constants";
+import {
+  DEFAULT_QUICK_SELECT_BUTTONS,
+  QUICK_SELECT_BUTTONS,
+} from "./constants";
 import type { QuickSelectKey } from "./hooks/useReleaseNotesRange";
 
 /**
@@ -29,7 +32,9 @@ export default function QuickSelectButtons({
 }: QuickSelectButtonsProps) {
   return (
     <div className="grid grid-cols-2 gap-2 mt-1">
-      {QUICK_SELECT_BUTTONS[variant].map((btn) => {
+      {(
+        QUICK_SELECT_BUTTONS[variant] ?? DEFAULT_QUICK_SELECT_BUTTONS
+      ).map((btn) => {
         const version = targetVersions[btn.key];
         return (
           <Button
@@ -120,7 +120,11 @@ export default function ReleaseDropdown({
     (items: ComboboxItem[]) => {
       const installingOrDownloadingRelease = items.find((item) => {
         const status = installationStatusByVersion?.[item.value];
-        return status === "Downloading" || status === "Installing";
+        return (
+          status === "Downloading" ||
+          status === "Extracting" ||
+          status === "Installing"
+        );
       });
 
       if (installingOrDownloadingRelease) {
@@ -154,7 +158,10 @@ export default function ReleaseDropdown({
   const isInstalling = Object.values(
     installationStatusByVersion ?? {},
   ).some(
-    (status) => status === "Downloading" || status === "Installing",
+    (status) =>
+      status === "Downloading" ||
+      status === "Extracting" ||
+      status === "Installing",
   );
 
   const placeholderText = isReleasesLoading
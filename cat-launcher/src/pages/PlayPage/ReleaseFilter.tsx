@@ -68,6 +68,8 @@ function getFilters(variant: GameVariant): Filter[] {
       return [stableFilter];
     case "BrightNights":
       return [stableFilter, experimentalFilter];
+    default:
+      return [stableFilter, experimentalFilter, releaseCandidateFilter];
   }
 }
 
@@ -104,7 +106,10 @@ export default function ReleaseFilter({
       return false;
     }
     return Object.values(installationStatuses).some(
-      (status) => status === "Downloading" || status === "Installing",
+      (status) =>
+        status === "Downloading" ||
+        status === "Extracting" ||
+        status === "Installing",
     );
   }, [installationStatuses]);
 
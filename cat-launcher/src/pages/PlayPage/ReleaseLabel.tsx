@@ -45,6 +45,9 @@ function getShortReleaseName(
       }
       return version;
     }
+    default: {
+      return version;
+    }
   }
 }
 
@@ -5,9 +5,8 @@ import type { QuickSelectKey } from "./hooks/useReleaseNotesRange";
  * Defines the quick select buttons available for each game variant.
  * Each button has a label for display and a key used to identify the target version.
  */
-export const QUICK_SELECT_BUTTONS: Record<
-  GameVariant,
-  { label: string; key: QuickSelectKey }[]
+export const QUICK_SELECT_BUTTONS: Partial<
+  Record<GameVariant, { label: string; key: QuickSelectKey }[]>
 > = {
   DarkDaysAhead: [
     { label: "Active", key: "Active" },
@@ -25,3 +24,15 @@ export const QUICK_SELECT_BUTTONS: Record<
     { label: "Latest", key: "Latest" },
   ],
 };
+
+/**
+ * The quick select buttons for custom game variants.
+ */
+export const DEFAULT_QUICK_SELECT_BUTTONS: {
+  label: string;
+  key: QuickSelectKey;
+}[] = [
+  { label: "Active", key: "Active" },
+  { label: "Latest Stable", key: "Stable" },
+  { label: "Latest Experimental", key: "Experimental" },
+];
@@ -15,3 +15,4 @@ export * from "./usePlayTime";
 export * from "./useUpgradeInfo";
 export * from "./useReleaseNotesRange";
 export * from "./useActiveRelease";
+export * from "./useReleaseUpdateCheck";
@@ -24,6 +24,7 @@ export function useInstallAndMonitorRelease(
 
   const {
     install,
+    installAsync,
     isInstalling,
     installationProgressStatus,
     downloadProgress,
@@ -57,13 +58,19 @@ export function useInstallAndMonitorRelease(
         queryKey: queryKeys.tips(variant),
       });
     },
-    (e) => {
+    (e, releaseId) => {
       toastCL("error", "Failed to install release.", e);
+
+      // A corrupted download is reported through the installation status.
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.installationStatus(variant, releaseId),
+      });
     },
   );
 
   return {
     install,
+    installAsync,
     isInstalling,
     installationProgressStatus,
     downloadProgress,
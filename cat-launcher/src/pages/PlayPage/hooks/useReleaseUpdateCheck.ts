@@ -0,0 +1,36 @@
+import { useMutation, useQueryClient } from "@tanstack/react-query";
+
+import type { GameVariant } from "@/generated-types/GameVariant";
+import type { ReleaseUpdate } from "@/generated-types/ReleaseUpdate";
+import { checkReleaseUpdate } from "@/lib/commands";
+import { queryKeys } from "@/lib/queryKeys";
+
+/**
+ * Custom hook to check for a newer release before the active release is
+ * launched, so that the user can be offered to install it first.
+ *
+ * @param variant - The game variant.
+ * @returns An object containing the check function and a loading state.
+ */
+export function useReleaseUpdateCheck(variant: GameVariant) {
+  const queryClient = useQueryClient();
+
+  const { mutateAsync: checkForUpdate, isPending: isCheckingForUpdate } =
+    useMutation({
+      mutationFn: async (
+        releaseId: string,
+      ): Promise<ReleaseUpdate | null> => {
+        const activeRelease = queryClient.getQueryData<string | null>(
+          queryKeys.activeRelease(variant),
+        );
+        if (releaseId !== activeRelease) {
+          return null;
+        }
+
+        // A failed update check shouldn't keep the game from launching.
+        return await checkReleaseUpdate(variant).catch(() => null);
+      },
+    });
+
+  return { checkForUpdate, isCheckingForUpdate };
+}
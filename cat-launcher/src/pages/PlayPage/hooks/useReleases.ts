@@ -31,7 +31,8 @@ export type ReleaseFetchStatus =
  * @param variant - The game variant to fetch releases for.
  * @param onReleasesLoadError - Optional callback triggered if releases fail to load from cache.
  * @param onReleasesTriggerError - Optional callback triggered if the fetch request fails.
- * @returns An object containing the list of releases and a loading state.
+ * @returns An object containing the list of releases, a loading state, and,
+ * if GitHub rate limited the last fetch, when fresh releases will be available.
  */
 export function useReleases(
   variant: GameVariant,
@@ -43,6 +44,8 @@ export function useReleases(
   const onReleasesTriggerErrorRef = useRef(onReleasesTriggerError);
   const [fetchStatus, setFetchStatus] =
     useState<ReleaseFetchStatus>("idle");
+  const [rateLimitedUntil, setRateLimitedUntil] =
+    useState<Date | null>(null);
 
   useEffect(() => {
     onReleasesLoadErrorRef.current = onReleasesLoadError;
@@ -76,6 +79,20 @@ export function useReleases(
 
         if (payload.status === "Success") {
           setFetchStatus("success");
+          setRateLimitedUntil(null);
+        } else if (
+          typeof payload.status === "object" &&
+          "RateLimited" in payload.status
+        ) {
+          // Cached and default releases were still delivered.
+          setFetchStatus("success");
+          setRateLimitedUntil(
+            new Date(payload.status.RateLimited.reset_at),
+          );
+        } else if (payload.status === "Offline") {
+          // Offline mode only delivers cached and default releases.
+          setFetchStatus("success");
+          setRateLimitedUntil(null);
         } else if (payload.status === "Error") {
           setFetchStatus("error");
         } else if (payload.status === "Fetching") {
@@ -92,6 +109,7 @@ export function useReleases(
 
     return () => {
       setFetchStatus("idle");
+      setRateLimitedUntil(null);
       cleanup();
     };
   }, [variant, queryClient]);
@@ -144,5 +162,6 @@ export function useReleases(
     isLoading:
       (fetchStatus === "loading" || isReleasesTriggerLoading) &&
       releases.length === 0,
+    rateLimitedUntil,
   };
 }
@@ -108,11 +108,14 @@ export default function SoundpackCard({
       </CardHeader>
       <CardFooter className="flex flex-col gap-4 items-stretch">
         {isThirdParty ? (
-          soundpackInstallationProgress === "Downloading" &&
+          (soundpackInstallationProgress === "Downloading" ||
+            soundpackInstallationProgress === "Extracting") &&
           soundpackDownloadProgress ? (
             <DownloadProgress
               downloaded={soundpackDownloadProgress.bytes_downloaded}
               total={soundpackDownloadProgress.total_bytes}
+              phase={soundpackDownloadProgress.phase}
+              message={soundpackDownloadProgress.message}
             />
           ) : isInstalled ? (
             <Button
@@ -133,9 +136,11 @@ export default function SoundpackCard({
             >
               {soundpackInstallationProgress === "Installing"
                 ? "Installing..."
-                : soundpackInstallationProgress === "Downloading"
-                  ? "Downloading..."
-                  : "Install"}
+                : soundpackInstallationProgress === "Extracting"
+                  ? "Extracting..."
+                  : soundpackInstallationProgress === "Downloading"
+                    ? "Downloading..."
+                    : "Install"}
             </Button>
           )
         ) : (
@@ -105,11 +105,14 @@ export default function TilesetCard({
       </CardHeader>
       <CardFooter className="flex flex-col gap-4 items-stretch">
         {isThirdParty ? (
-          tilesetInstallationProgress === "Downloading" &&
+          (tilesetInstallationProgress === "Downloading" ||
+            tilesetInstallationProgress === "Extracting") &&
           tilesetDownloadProgress ? (
             <DownloadProgress
               downloaded={tilesetDownloadProgress.bytes_downloaded}
               total={tilesetDownloadProgress.total_bytes}
+              phase={tilesetDownloadProgress.phase}
+              message={tilesetDownloadProgress.message}
             />
           ) : isInstalled ? (
             <Button
@@ -128,9 +131,11 @@ export default function TilesetCard({
             >
               {tilesetInstallationProgress === "Installing"
                 ? "Installing..."
-                : tilesetInstallationProgress === "Downloading"
-                  ? "Downloading..."
-                  : "Install"}
+                : tilesetInstallationProgress === "Extracting"
+                  ? "Extracting..."
+                  : tilesetInstallationProgress === "Downloading"
+                    ? "Downloading..."
+                    : "Install"}
             </Button>
           )
         ) : (
@@ -13,6 +13,7 @@ type InstallationType = "release" | "mod" | "soundpack" | "tileset";
  */
 export type InstallationProgressStatus =
   | "Downloading"
+  | "Extracting"
   | "Installing"
   | "Success"
   | "Error";
@@ -26,9 +27,11 @@ interface InstallationProgressState {
    */
   installationStatusByVariant: Record<
     InstallationType,
-    Record<
-      GameVariant,
-      Record<string, InstallationProgressStatus | null>
+    Partial<
+      Record<
+        GameVariant,
+        Record<string, InstallationProgressStatus | null>
+      >
     >
   >;
   /**
@@ -36,9 +39,11 @@ interface InstallationProgressState {
    */
   downloadProgressByVariant: Record<
     InstallationType,
-    Record<
-      GameVariant,
-      Record<string, SerializableDownloadProgress | null>
+    Partial<
+      Record<
+        GameVariant,
+        Record<string, SerializableDownloadProgress | null>
+      >
     >
   >;
 }
@@ -116,19 +121,23 @@ export const installationProgressSlice = createSlice({
       }>,
     ) => {
       const { type, variant, id, progress } = action.payload;
-      state.downloadProgressByVariant[type][variant][id] = progress;
+      // Custom variants have no entry until their first installation.
+      const downloadProgress = (state.downloadProgressByVariant[type][
+        variant
+      ] ??= {});
+      const installationStatus = (state.installationStatusByVariant[
+        type
+      ][variant] ??= {});
+      downloadProgress[id] = progress;
 
-      const { bytes_downloaded, total_bytes } = progress;
+      const { phase, bytes_downloaded, total_bytes } = progress;
 
-      if (total_bytes === 0) {
-        state.installationStatusByVariant[type][variant][id] =
-          "Downloading";
-      } else if (bytes_downloaded === total_bytes) {
-        state.installationStatusByVariant[type][variant][id] =
-          "Installing";
+      if (total_bytes !== 0 && bytes_downloaded === total_bytes) {
+        installationStatus[id] = "Installing";
+      } else if (phase === "Extracting") {
+        installationStatus[id] = "Extracting";
       } else {
-        state.installationStatusByVariant[type][variant][id] =
-          "Downloading";
+        installationStatus[id] = "Downloading";
       }
     },
 
@@ -148,8 +157,16 @@ export const installationProgressSlice = createSlice({
     ) => {
       const { variant, id, type } = action.payload;
 
-      state.installationStatusByVariant[type][variant][id] = null;
-      state.downloadProgressByVariant[type][variant][id] = null;
+      const installationStatus =
+        state.installationStatusByVariant[type][variant];
+      if (installationStatus) {
+        installationStatus[id] = null;
+      }
+      const downloadProgress =
+        state.downloadProgressByVariant[type][variant];
+      if (downloadProgress) {
+        downloadProgress[id] = null;
+      }
     },
   },
 });
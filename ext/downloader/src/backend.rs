@@ -10,28 +10,94 @@ use http::StatusCode;
 use rand::seq::IndexedRandom;
 
 use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 
 fn select_url(urls: &[String]) -> String {
     assert!(!urls.is_empty());
     urls.choose(&mut rand::rng()).unwrap().clone()
 }
 
+fn part_file_name(file_name: &Path) -> PathBuf {
+    let mut part = file_name.as_os_str().to_os_string();
+    part.push(".part");
+    PathBuf::from(part)
+}
+
+fn validator_file_name(file_name: &Path) -> PathBuf {
+    let mut validator = file_name.as_os_str().to_os_string();
+    validator.push(".part.validator");
+    PathBuf::from(validator)
+}
+
+/// Pick the value to send as `If-Range` when resuming this response's content.
+///
+/// Weak `ETag`s are not allowed in `If-Range`, so fall back to `Last-Modified`.
+fn response_validator<R: Response>(response: &R) -> Option<String> {
+    response
+        .etag()
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| response.last_modified())
+}
+
 async fn download_url<C: crate::HttpClient>(
     client: C,
     url: String,
     writer: &mut std::io::BufWriter<std::fs::File>,
-    progress: &mut crate::Progress,
+    validator_file: &Path,
+    progress: &crate::Progress,
     message: &str,
 ) -> u16 {
-    if let Ok(mut response) = client.get(&url).await {
-        let total = response.content_length();
-        let mut current: u64 = 0;
-        writer.seek(SeekFrom::Start(current)).unwrap_or(0);
-        let _ = writer.get_mut().set_len(0);
+    let offset = if writer.flush().is_ok() {
+        writer.get_ref().metadata().map_or(0, |m| m.len())
+    } else {
+        0
+    };
+    let validator = std::fs::read_to_string(validator_file).ok();
+
+    let response = match validator {
+        Some(ref v) if offset > 0 => client.get_range(&url, offset, v).await,
+        _ => client.get(&url).await,
+    };
+
+    if let Ok(mut response) = response {
+        let status = response.status();
+        if !StatusCode::from_u16(status).is_ok_and(|sc| sc.is_success()) {
+            // Keep the partial file for another attempt, unless the server
+            // says the range can't be served from it anymore.
+            if status == StatusCode::RANGE_NOT_SATISFIABLE.as_u16() {
+                let _ = std::fs::remove_file(validator_file);
+            }
+            progress.set_message(&format!("{message} - {status}"));
+            return status;
+        }
+
+        let mut current: u64 = if response.is_partial_content() {
+            offset
+        } else {
+            0
+        };
+        let total = response.content_length().map(|len| len + current);
+
+        if writer.seek(SeekFrom::Start(current)).is_err()
+            || writer.get_mut().set_len(current).is_err()
+        {
+            return StatusCode::INTERNAL_SERVER_ERROR.as_u16();
+        }
+
+        if current == 0 {
+            // The partial file now holds (the start of) this response's content,
+            // so only resume it later if the server can confirm it is unchanged.
+            let stored = response_validator(&response)
+                .is_some_and(|v| std::fs::write(validator_file, v).is_ok());
+            if !stored {
+                let _ = std::fs::remove_file(validator_file);
+            }
+        }
 
         progress.setup(total, message);
+        progress.progress(current);
 
-        let mut result = response.status();
+        let mut result = status;
 
         loop {
             match response.chunk().await {
@@ -77,15 +143,20 @@ async fn download<C: crate::HttpClient>(
     let mut urls = std::mem::take(&mut download.urls);
     assert!(!urls.is_empty());
 
-    let mut progress = download.progress.expect("This has been set!").clone();
+    let progress = download.progress.expect("This has been set!").clone();
 
     let mut download_successful = false;
 
+    // Content is written to a `.part` file next to the target, which is kept
+    // on failure so that a later attempt can resume from it.
+    let part_file = part_file_name(&summary.file_name);
+    let validator_file = validator_file_name(&summary.file_name);
+
     if let Ok(file) = std::fs::OpenOptions::new()
         .write(true)
         .create(true)
-        .truncate(true)
-        .open(&summary.file_name)
+        .truncate(false)
+        .open(&part_file)
     {
         let mut writer = std::io::BufWriter::new(file);
 
@@ -107,27 +178,31 @@ async fn download<C: crate::HttpClient>(
                 client.clone(),
                 url.clone(),
                 &mut writer,
-                &mut progress,
+                &validator_file,
+                &progress,
                 &message,
             )
             .await;
 
             summary.status.push((url.clone(), s));
 
-            if StatusCode::from_u16(s).is_ok_and(|sc| sc.is_server_error()) {
-                urls = urls
-                    .iter()
-                    .filter_map(|u| if u == &url { Some(u.clone()) } else { None })
-                    .collect();
-                if urls.is_empty() {
-                    break;
-                }
-            }
-
             if StatusCode::from_u16(s).is_ok_and(|sc| sc.is_success()) {
                 download_successful = true;
                 break;
             }
+
+            // Retry with the other mirrors, if any, rather than one that
+            // just failed. The last one left is retried as is.
+            if urls.len() > 1 {
+                urls.retain(|u| u != &url);
+            }
+        }
+
+        drop(writer);
+
+        if download_successful {
+            download_successful = std::fs::rename(&part_file, &summary.file_name).is_ok();
+            let _ = std::fs::remove_file(&validator_file);
         }
     }
 
@@ -141,7 +216,7 @@ async fn download<C: crate::HttpClient>(
 
 /// Run the provided list of `downloads`, using the provided `client`
 pub(crate) fn run<C: crate::HttpClient>(
-    client: &mut C,
+    client: &C,
     downloads: Vec<Download>,
     retries: u16,
     parallel_requests: u16,
@@ -161,21 +236,272 @@ pub(crate) fn run<C: crate::HttpClient>(
 }
 
 pub(crate) async fn async_run<C: crate::HttpClient>(
-    client: &mut C,
+    client: &C,
     downloads: Vec<Download>,
     retries: u16,
     parallel_requests: u16,
 ) -> Vec<Result<DownloadSummary>> {
     let cl = client.clone();
 
-    let result = tokio::spawn(async move {
-        stream::iter(downloads)
-            .map(move |d| download(cl.clone(), d, retries))
-            .buffer_unordered(parallel_requests as usize)
-            .collect::<Vec<Result<DownloadSummary>>>()
-            .await
-    })
-    .await;
+    // Run on the caller's task rather than a spawned one, so that dropping
+    // the future stops the downloads. The `.part` files are kept, so they
+    // can be resumed later.
+    stream::iter(downloads)
+        .map(move |d| download(cl.clone(), d, retries))
+        .buffer_unordered(parallel_requests as usize)
+        .collect::<Vec<Result<DownloadSummary>>>()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::downloader::Builder;
+
+    use std::collections::VecDeque;
+    use std::convert::TryFrom;
+    use std::sync::{Arc, Mutex};
+
+    const URL: &str = "https://example.com/file.bin";
+
+    /// How the first response ends once it has sent the content up to its
+    /// cut-off point.
+    #[derive(Clone, Copy)]
+    enum Cutoff {
+        /// The connection fails.
+        Fail,
+        /// No more content arrives.
+        Stall,
+    }
+
+    struct Server {
+        content: Vec<u8>,
+        etag: String,
+        cutoff: Option<(usize, Cutoff)>,
+        /// The offset and `If-Range` validator of every request.
+        requests: Vec<(u64, Option<String>)>,
+        /// How many bytes of content were sent.
+        sent: usize,
+    }
+
+    /// A client for a server that honours `If-Range` like a real one does.
+    #[derive(Clone)]
+    struct FakeClient(Arc<Mutex<Server>>);
+
+    impl FakeClient {
+        fn new(content: &[u8], etag: &str, cutoff: Option<(usize, Cutoff)>) -> Self {
+            Self(Arc::new(Mutex::new(Server {
+                content: content.to_vec(),
+                etag: etag.to_owned(),
+                cutoff,
+                requests: Vec::new(),
+                sent: 0,
+            })))
+        }
+
+        fn change_content(&self, content: &[u8], etag: &str) {
+            let mut server = self.0.lock().unwrap();
+            server.content = content.to_vec();
+            server.etag = etag.to_owned();
+        }
+
+        /// Returns the requests made so far and how many bytes were sent.
+        fn history(&self) -> (Vec<(u64, Option<String>)>, usize) {
+            let server = self.0.lock().unwrap();
+            (server.requests.clone(), server.sent)
+        }
+
+        fn respond(&self, offset: u64, if_range: Option<&str>) -> FakeResponse {
+            let mut server = self.0.lock().unwrap();
+            server.requests.push((offset, if_range.map(str::to_owned)));
+
+            let resume = if_range == Some(server.etag.as_str());
+            let start = if resume {
+                usize::try_from(offset).unwrap()
+            } else {
+                0
+            };
+            let (end, cutoff) = match server.cutoff.take() {
+                Some((end, cutoff)) => (end, Some(cutoff)),
+                None => (server.content.len(), None),
+            };
 
-    result.unwrap()
+            FakeResponse {
+                status: if resume { 206 } else { 200 },
+                etag: server.etag.clone(),
+                content_length: (server.content.len() - start) as u64,
+                chunks: server.content[start..end]
+                    .chunks(4)
+                    .map(<[u8]>::to_vec)
+                    .collect(),
+                cutoff,
+                server: self.0.clone(),
+            }
+        }
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("connection reset")]
+    struct FakeError;
+
+    impl crate::HttpClient for FakeClient {
+        type Error = FakeError;
+        type Response = FakeResponse;
+
+        async fn get(&self, _url: &str) -> std::result::Result<FakeResponse, FakeError> {
+            Ok(self.respond(0, None))
+        }
+
+        async fn get_range(
+            &self,
+            _url: &str,
+            offset: u64,
+            if_range: &str,
+        ) -> std::result::Result<FakeResponse, FakeError> {
+            Ok(self.respond(offset, Some(if_range)))
+        }
+    }
+
+    struct FakeResponse {
+        status: u16,
+        etag: String,
+        content_length: u64,
+        chunks: VecDeque<Vec<u8>>,
+        cutoff: Option<Cutoff>,
+        server: Arc<Mutex<Server>>,
+    }
+
+    impl Response for FakeResponse {
+        type Error = FakeError;
+        type Bytes = Vec<u8>;
+
+        fn status(&self) -> u16 {
+            self.status
+        }
+
+        fn content_length(&self) -> Option<u64> {
+            Some(self.content_length)
+        }
+
+        fn etag(&self) -> Option<String> {
+            Some(self.etag.clone())
+        }
+
+        async fn chunk(&mut self) -> std::result::Result<Option<Vec<u8>>, FakeError> {
+            if let Some(chunk) = self.chunks.pop_front() {
+                self.server.lock().unwrap().sent += chunk.len();
+                return Ok(Some(chunk));
+            }
+
+            match self.cutoff {
+                Some(Cutoff::Fail) => Err(FakeError),
+                Some(Cutoff::Stall) => std::future::pending().await,
+                None => Ok(None),
+            }
+        }
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("downloader-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn download_once(dir: &Path, client: &FakeClient) -> Result<DownloadSummary> {
+        let mut downloader = Builder::default()
+            .download_folder(dir)
+            .retries(1)
+            .build_with_client(client.clone())
+            .unwrap();
+
+        runtime()
+            .block_on(downloader.async_download(&[Download::new(URL)]))
+            .unwrap()
+            .pop()
+            .unwrap()
+    }
+
+    #[test]
+    fn resumes_partial_download() {
+        let dir = temp_dir("resume");
+        let content = b"0123456789abcdefghij";
+        let client = FakeClient::new(content, "\"v1\"", Some((8, Cutoff::Fail)));
+
+        assert!(download_once(&dir, &client).is_err());
+        assert_eq!(
+            std::fs::read(dir.join("file.bin.part")).unwrap(),
+            &content[..8]
+        );
+
+        assert!(download_once(&dir, &client).is_ok());
+        assert_eq!(std::fs::read(dir.join("file.bin")).unwrap(), content);
+        assert!(!dir.join("file.bin.part").exists());
+        assert!(!dir.join("file.bin.part.validator").exists());
+
+        let (requests, sent) = client.history();
+        assert_eq!(requests, vec![(0, None), (8, Some("\"v1\"".to_owned()))]);
+        assert_eq!(sent, content.len());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn restarts_download_when_content_changed() {
+        let dir = temp_dir("restart");
+        let client = FakeClient::new(b"0123456789abcdefghij", "\"v1\"", Some((8, Cutoff::Fail)));
+
+        assert!(download_once(&dir, &client).is_err());
+
+        let changed = b"changed content";
+        client.change_content(changed, "\"v2\"");
+        assert!(download_once(&dir, &client).is_ok());
+        assert_eq!(std::fs::read(dir.join("file.bin")).unwrap(), changed);
+
+        let (requests, sent) = client.history();
+        assert_eq!(requests, vec![(0, None), (8, Some("\"v1\"".to_owned()))]);
+        assert_eq!(sent, 8 + changed.len());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resumes_cancelled_download_without_refetching() {
+        let dir = temp_dir("cancel");
+        let content = b"0123456789abcdefghij";
+        let client = FakeClient::new(content, "\"v1\"", Some((8, Cutoff::Stall)));
+
+        let mut downloader = Builder::default()
+            .download_folder(&dir)
+            .retries(1)
+            .build_with_client(client.clone())
+            .unwrap();
+        runtime().block_on(async {
+            // Dropping the download cancels it.
+            let downloads = [Download::new(URL)];
+            let download = downloader.async_download(&downloads);
+            let timeout = std::time::Duration::from_millis(100);
+            assert!(tokio::time::timeout(timeout, download).await.is_err());
+        });
+        assert_eq!(
+            std::fs::read(dir.join("file.bin.part")).unwrap(),
+            &content[..8]
+        );
+
+        assert!(download_once(&dir, &client).is_ok());
+        assert_eq!(std::fs::read(dir.join("file.bin")).unwrap(), content);
+
+        let (requests, sent) = client.history();
+        assert_eq!(requests, vec![(0, None), (8, Some("\"v1\"".to_owned()))]);
+        assert_eq!(sent, content.len());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -28,8 +28,8 @@ fn file_name_from_url(url: &str) -> std::path::PathBuf {
     };
 
     url.path_segments()
-        .map_or_else(std::path::PathBuf::new, |f| {
-            std::path::PathBuf::from(f.last().unwrap_or(""))
+        .map_or_else(std::path::PathBuf::new, |mut f| {
+            std::path::PathBuf::from(f.next_back().unwrap_or(""))
         })
 }
 
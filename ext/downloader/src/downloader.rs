@@ -58,11 +58,10 @@ fn validate_downloads(
             )));
         }
 
-        let progress = if d.progress.is_none() {
-            factory.create_reporter()
-        } else {
-            d.progress.as_ref().expect("Was Some just now...").clone()
-        };
+        let progress = d
+            .progress
+            .as_ref()
+            .map_or_else(|| factory.create_reporter(), Clone::clone);
 
         result.push(Download {
             urls,
@@ -101,7 +100,7 @@ impl<C: crate::HttpClient> Downloader<C> {
         }
 
         Ok(crate::backend::run(
-            &mut self.client,
+            &self.client,
             to_process,
             self.retries,
             self.parallel_requests,
@@ -124,7 +123,7 @@ impl<C: crate::HttpClient> Downloader<C> {
         }
 
         let result = crate::backend::async_run(
-            &mut self.client,
+            &self.client,
             to_process,
             self.retries,
             self.parallel_requests,
@@ -12,6 +12,25 @@ pub trait HttpClient: Send + Sync + Clone + 'static {
 
     /// Send a GET request to the specified URL.
     fn get(&self, url: &str) -> impl Future<Output = Result<Self::Response, Self::Error>> + Send;
+
+    /// Send a GET request for the content of `url` starting at byte `offset`.
+    ///
+    /// `if_range` is a validator (an `ETag` or `Last-Modified` value) returned
+    /// by an earlier response for this URL. It is meant to be sent as the
+    /// `If-Range` header so that the server only honours the range while the
+    /// resource is unchanged, and sends the full content otherwise.
+    ///
+    /// The default implementation ignores the range and requests the full
+    /// content, which means downloads are never resumed.
+    fn get_range(
+        &self,
+        url: &str,
+        offset: u64,
+        if_range: &str,
+    ) -> impl Future<Output = Result<Self::Response, Self::Error>> + Send {
+        let _ = (offset, if_range);
+        self.get(url)
+    }
 }
 
 /// A trait representing an HTTP response.
@@ -26,8 +45,26 @@ pub trait Response: Send {
     fn status(&self) -> u16;
 
     /// Get the content length of the response, if available.
+    ///
+    /// For partial content this is the length of the returned range only.
     fn content_length(&self) -> Option<u64>;
 
+    /// Whether the response only contains the requested range of the
+    /// resource (`206 Partial Content`).
+    fn is_partial_content(&self) -> bool {
+        self.status() == 206
+    }
+
+    /// Get the `ETag` header of the response, if available.
+    fn etag(&self) -> Option<String> {
+        None
+    }
+
+    /// Get the `Last-Modified` header of the response, if available.
+    fn last_modified(&self) -> Option<String> {
+        None
+    }
+
     /// Get the next chunk of the response body.
     ///
     /// Returns `Ok(Some(bytes))` if a chunk was successfully read.
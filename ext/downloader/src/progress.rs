@@ -10,6 +10,15 @@
 /// A Progress reporter to use for the `Download`
 pub type Progress = std::sync::Arc<dyn Reporter>;
 
+/// What the progress reported to a `Reporter` is of
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Downloading a file
+    Download,
+    /// Extracting a downloaded file
+    Extract,
+}
+
 // ----------------------------------------------------------------------
 // - Traits:
 // ----------------------------------------------------------------------
@@ -18,6 +27,14 @@ pub type Progress = std::sync::Arc<dyn Reporter>;
 pub trait Reporter: Send + Sync {
     /// Setup a TUI element for the next progress
     fn setup(&self, max_progress: Option<u64>, message: &str);
+    /// Setup a TUI element for the next progress of the given `phase`
+    ///
+    /// Downloads are set up with `setup`. Reporters that don't tell phases
+    /// apart need not implement this, it defaults to `setup`.
+    fn setup_phase(&self, phase: Phase, max_progress: Option<u64>, message: &str) {
+        let _ = phase;
+        self.setup(max_progress, message);
+    }
     /// Report progress
     fn progress(&self, current: u64);
     /// Report progress